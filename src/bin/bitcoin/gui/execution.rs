@@ -38,12 +38,13 @@ fn spawn_backend_handler<N: Notifier + 'static>(
     thread::spawn(move || {
         let mut load_system = LoadSystem::new(save_config.clone(), logger.clone());
 
-        let input_handler = InputHandlerGUI::new(rx_from_front, notifier.clone(), logger.clone());
+        let input_handler = InputHandlerGUI::new(rx_from_front, notifier.clone(), logger.clone())
+            .with_network(connection_config.network);
 
         backend::backend(
             mode_config,
             connection_config,
-            download_config,
+            (download_config, save_config),
             &mut load_system,
             input_handler,
             notifier,
@@ -13,14 +13,20 @@ use cargosos_bitcoin::{
         block_chain::BlockChain,
         hash::{HashType, HASH_TYPE_SIZE},
     },
+    connections::network::Network,
     logs::logger_sender::LoggerSender,
     node_structure::connection_id::ConnectionId,
     notifications::{notification::Notification, notifier::Notifier},
-    wallet_structure::{private_key::PrivateKey, public_key::PublicKey, wallet::Wallet},
+    wallet_structure::{
+        contact::Contact, output_record::OutputRecord, private_key::PrivateKey,
+        public_key::PublicKey, transaction_record::TransactionRecord, wallet::Wallet,
+    },
 };
 
 use gtk::{
-    glib, prelude::*, Builder, Button, ComboBoxText, Entry, Image, Label, ProgressBar, SpinButton,
+    glib, prelude::*, Builder, Button, ButtonsType, CellRendererText, CellRendererToggle,
+    ComboBoxText, DialogFlags, Entry, FileChooserAction, FileChooserDialog, Image, Label,
+    MessageDialog, MessageType, ProgressBar, ResponseType, SpinButton, TreeIter, TreeSelection,
     TreeStore, Window,
 };
 
@@ -39,9 +45,10 @@ pub fn create_account<N: Notifier>(
     account_name: &str,
     private_key_string: &str,
     public_key_string: &str,
+    network: Network,
     notifier: N,
 ) -> Result<(), ErrorUI> {
-    let private_key = match PrivateKey::try_from(private_key_string) {
+    let private_key = match PrivateKey::from_string(private_key_string, network) {
         Ok(private_key) => private_key,
         Err(_) => {
             notifier.notify(Notification::InvalidPrivateKeyEnter);
@@ -58,7 +65,14 @@ pub fn create_account<N: Notifier>(
     };
 
     let mut wallet = reference::get_reference(&wallet)?;
-    account::create_account(&mut wallet, account_name, private_key, public_key, notifier)
+    account::create_account(
+        &mut wallet,
+        account_name,
+        private_key,
+        public_key,
+        network,
+        notifier,
+    )
 }
 
 /// This function sets up the main window
@@ -210,6 +224,9 @@ fn login_combo_box(builder: &Builder, tx_to_back: Sender<SignalToBack>) -> Resul
         if let Err(error) = tx_to_back.send(SignalToBack::GetAccountTransactions) {
             println!("Error sending get account transactions signal: {}", error);
         };
+        if let Err(error) = tx_to_back.send(SignalToBack::GetAccountOutputs) {
+            println!("Error sending get account outputs signal: {}", error);
+        };
     });
     Ok(())
 }
@@ -637,6 +654,113 @@ fn clear_send_transaction_contents(builder: &Builder) {
     fee_spin_button.set_value(0.0);
 }
 
+/// Function that sets up the button that exports the history of transactions to a file
+fn login_export_transactions_button(
+    builder: &Builder,
+    tx_to_back: Sender<SignalToBack>,
+) -> Result<(), ErrorUI> {
+    let export_transactions_button: Button = match builder.object("ExportTransactionsButton") {
+        Some(button) => button,
+        None => {
+            return Err(ErrorUI::MissingElement(
+                "ExportTransactionsButton".to_string(),
+            ))
+        }
+    };
+
+    export_transactions_button.connect_clicked(move |_| {
+        let file_chooser = FileChooserDialog::with_buttons(
+            Some("Export history (.csv or .json)"),
+            None::<&Window>,
+            FileChooserAction::Save,
+            &[
+                ("Cancel", ResponseType::Cancel),
+                ("Save", ResponseType::Accept),
+            ],
+        );
+        file_chooser.set_do_overwrite_confirmation(true);
+        file_chooser.set_current_name("transactions.csv");
+
+        let tx_to_back = tx_to_back.clone();
+        file_chooser.connect_response(move |file_chooser, response| {
+            if response == ResponseType::Accept {
+                if let Some(path) = file_chooser.filename() {
+                    let path = path.to_string_lossy().to_string();
+                    if tx_to_back
+                        .send(SignalToBack::ExportTransactions(path))
+                        .is_err()
+                    {
+                        println!("Error sending signal to back");
+                    }
+                }
+            }
+            file_chooser.close();
+        });
+        file_chooser.show_all();
+    });
+
+    Ok(())
+}
+
+/// Function that sets up the edition of the labels in the history of transactions
+fn login_transaction_labels(
+    builder: &Builder,
+    tx_to_back: Sender<SignalToBack>,
+) -> Result<(), ErrorUI> {
+    let transactions_tree_store: TreeStore = match builder.object("TransactionTreeStore") {
+        Some(tree_store) => tree_store,
+        None => return Err(ErrorUI::MissingElement("TransactionTreeStore".to_string())),
+    };
+    let transaction_label_renderer: CellRendererText =
+        match builder.object("TransactionLabelRenderer") {
+            Some(renderer) => renderer,
+            None => {
+                return Err(ErrorUI::MissingElement(
+                    "TransactionLabelRenderer".to_string(),
+                ))
+            }
+        };
+
+    transaction_label_renderer.connect_edited(move |_, path, label| {
+        let tree_iter = match transactions_tree_store.iter(&path) {
+            Some(tree_iter) => tree_iter,
+            None => return,
+        };
+        let transaction_id = match transactions_tree_store.value(&tree_iter, 2).get::<String>() {
+            Ok(transaction_id) => transaction_id,
+            Err(_) => return,
+        };
+
+        transactions_tree_store.set_value(&tree_iter, 6, &glib::Value::from(label));
+        if tx_to_back
+            .send(SignalToBack::LabelTransaction(
+                transaction_id,
+                label.to_string(),
+            ))
+            .is_err()
+        {
+            println!("Error sending signal to back");
+        }
+    });
+
+    Ok(())
+}
+
+/// Function that shows the path of the file where the transactions were exported
+fn show_transactions_exported_notification(path: String) {
+    let message_dialog = MessageDialog::new(
+        None::<&Window>,
+        DialogFlags::MODAL,
+        MessageType::Info,
+        ButtonsType::Ok,
+        &format!("Transactions exported to\n{path}"),
+    );
+    message_dialog.connect_response(|message_dialog, _| {
+        message_dialog.close();
+    });
+    message_dialog.show_all();
+}
+
 /// Function that sets up the send transaction page
 fn login_send_page(builder: &Builder, tx_to_back: Sender<SignalToBack>) -> Result<(), ErrorUI> {
     let transaction_clear_all_button: Button = match builder.object("TransactionClearAllButton") {
@@ -685,6 +809,7 @@ fn login_send_page(builder: &Builder, tx_to_back: Sender<SignalToBack>) -> Resul
             bitcoin_address_entry.text().to_string(),
             amount_spin_button.value(),
             fee_spin_button.value(),
+            take_selected_outputs(&cloned_builder),
         ));
         bitcoin_address_entry.set_text("");
         amount_spin_button.set_value(0.0);
@@ -694,6 +819,252 @@ fn login_send_page(builder: &Builder, tx_to_back: Sender<SignalToBack>) -> Resul
     Ok(())
 }
 
+/// Function that returns the outputs marked to be spent in the coins page and unmarks them
+fn take_selected_outputs(builder: &Builder) -> Vec<String> {
+    let outputs_tree_store: TreeStore = match builder.object("OutputsTreeStore") {
+        Some(tree_store) => tree_store,
+        None => {
+            println!("Error: Missing element OutputsTreeStore");
+            return Vec::new();
+        }
+    };
+
+    let mut selected_outputs = Vec::new();
+    let tree_iter = match outputs_tree_store.iter_first() {
+        Some(tree_iter) => tree_iter,
+        None => return selected_outputs,
+    };
+    loop {
+        if let Ok(true) = outputs_tree_store.value(&tree_iter, 0).get::<bool>() {
+            if let Ok(outpoint) = outputs_tree_store.value(&tree_iter, 1).get::<String>() {
+                selected_outputs.push(outpoint);
+            }
+            outputs_tree_store.set_value(&tree_iter, 0, &glib::Value::from(false));
+        }
+        if !outputs_tree_store.iter_next(&tree_iter) {
+            break;
+        }
+    }
+
+    selected_outputs
+}
+
+/// Function that sets up the page to choose the outputs to spend and to freeze them
+fn login_coins_page(builder: &Builder, tx_to_back: Sender<SignalToBack>) -> Result<(), ErrorUI> {
+    let outputs_tree_store: TreeStore = match builder.object("OutputsTreeStore") {
+        Some(tree_store) => tree_store,
+        None => return Err(ErrorUI::MissingElement("OutputsTreeStore".to_string())),
+    };
+    let output_selected_renderer: CellRendererToggle =
+        match builder.object("OutputSelectedRenderer") {
+            Some(renderer) => renderer,
+            None => {
+                return Err(ErrorUI::MissingElement(
+                    "OutputSelectedRenderer".to_string(),
+                ))
+            }
+        };
+    let output_frozen_renderer: CellRendererToggle = match builder.object("OutputFrozenRenderer") {
+        Some(renderer) => renderer,
+        None => return Err(ErrorUI::MissingElement("OutputFrozenRenderer".to_string())),
+    };
+
+    let cloned_tree_store = outputs_tree_store.clone();
+    output_selected_renderer.connect_toggled(move |_, path| {
+        let tree_iter = match cloned_tree_store.iter(&path) {
+            Some(tree_iter) => tree_iter,
+            None => return,
+        };
+        let selected = cloned_tree_store
+            .value(&tree_iter, 0)
+            .get::<bool>()
+            .unwrap_or(false);
+        cloned_tree_store.set_value(&tree_iter, 0, &glib::Value::from(!selected));
+    });
+
+    output_frozen_renderer.connect_toggled(move |_, path| {
+        let tree_iter = match outputs_tree_store.iter(&path) {
+            Some(tree_iter) => tree_iter,
+            None => return,
+        };
+        let outpoint = match outputs_tree_store.value(&tree_iter, 1).get::<String>() {
+            Ok(outpoint) => outpoint,
+            Err(_) => return,
+        };
+        let frozen = outputs_tree_store
+            .value(&tree_iter, 5)
+            .get::<bool>()
+            .unwrap_or(false);
+
+        outputs_tree_store.set_value(&tree_iter, 5, &glib::Value::from(!frozen));
+        if tx_to_back
+            .send(SignalToBack::FreezeOutput(outpoint, !frozen))
+            .is_err()
+        {
+            println!("Error sending signal to back");
+        }
+    });
+
+    Ok(())
+}
+
+/// Function that updates the tree view with the unspent outputs of the current account, keeping the ones marked to be spent
+fn show_outputs_in_tree_view(builder: &Builder, outputs: Vec<OutputRecord>) -> Result<(), ErrorUI> {
+    let outputs_tree_store: TreeStore = match builder.object("OutputsTreeStore") {
+        Some(tree_store) => tree_store,
+        None => return Err(ErrorUI::MissingElement("OutputsTreeStore".to_string())),
+    };
+
+    let selected_outputs = take_selected_outputs(builder);
+    outputs_tree_store.clear();
+
+    for output in outputs.iter() {
+        let outpoint = output.outpoint.to_string();
+        let label = match &output.label {
+            Some(label) => label.clone(),
+            None => "".to_string(),
+        };
+
+        let tree_iter = outputs_tree_store.append(None);
+        outputs_tree_store.set_value(
+            &tree_iter,
+            0,
+            &glib::Value::from(selected_outputs.contains(&outpoint)),
+        );
+        outputs_tree_store.set_value(&tree_iter, 1, &glib::Value::from(outpoint));
+        outputs_tree_store.set_value(
+            &tree_iter,
+            2,
+            &glib::Value::from(output.get_value_in_tbtc().to_string()),
+        );
+        outputs_tree_store.set_value(
+            &tree_iter,
+            3,
+            &glib::Value::from(output.confirmations.to_string()),
+        );
+        outputs_tree_store.set_value(&tree_iter, 4, &glib::Value::from(label));
+        outputs_tree_store.set_value(&tree_iter, 5, &glib::Value::from(output.frozen));
+    }
+
+    Ok(())
+}
+
+/// Function that sets up the page to add, edit and remove contacts of the address book
+fn login_contacts_page(builder: &Builder, tx_to_back: Sender<SignalToBack>) -> Result<(), ErrorUI> {
+    let contact_name_entry: Entry = match builder.object("ContactNameEntry") {
+        Some(entry) => entry,
+        None => return Err(ErrorUI::MissingElement("ContactNameEntry".to_string())),
+    };
+    let contact_address_entry: Entry = match builder.object("ContactAddressEntry") {
+        Some(entry) => entry,
+        None => return Err(ErrorUI::MissingElement("ContactAddressEntry".to_string())),
+    };
+    let contact_note_entry: Entry = match builder.object("ContactNoteEntry") {
+        Some(entry) => entry,
+        None => return Err(ErrorUI::MissingElement("ContactNoteEntry".to_string())),
+    };
+
+    let contacts_tree_selection: TreeSelection = match builder.object("ContactsTreeSelection") {
+        Some(selection) => selection,
+        None => return Err(ErrorUI::MissingElement("ContactsTreeSelection".to_string())),
+    };
+    let (name_entry, address_entry, note_entry) = (
+        contact_name_entry.clone(),
+        contact_address_entry.clone(),
+        contact_note_entry.clone(),
+    );
+    contacts_tree_selection.connect_changed(move |selection| {
+        if let Some((model, tree_iter)) = selection.selected() {
+            for (column, entry) in [(0, &name_entry), (1, &address_entry), (2, &note_entry)] {
+                if let Ok(text) = model.value(&tree_iter, column).get::<String>() {
+                    entry.set_text(&text);
+                }
+            }
+        }
+    });
+
+    let save_contact_button: Button = match builder.object("SaveContactButton") {
+        Some(button) => button,
+        None => return Err(ErrorUI::MissingElement("SaveContactButton".to_string())),
+    };
+    let cloned_tx_to_back = tx_to_back.clone();
+    let (name_entry, address_entry, note_entry) = (
+        contact_name_entry.clone(),
+        contact_address_entry.clone(),
+        contact_note_entry.clone(),
+    );
+    save_contact_button.connect_clicked(move |_| {
+        if cloned_tx_to_back
+            .send(SignalToBack::SaveContact(
+                name_entry.text().to_string(),
+                address_entry.text().to_string(),
+                note_entry.text().to_string(),
+            ))
+            .is_err()
+        {
+            println!("Error sending signal to back");
+        }
+        name_entry.set_text("");
+        address_entry.set_text("");
+        note_entry.set_text("");
+    });
+
+    let remove_contact_button: Button = match builder.object("RemoveContactButton") {
+        Some(button) => button,
+        None => return Err(ErrorUI::MissingElement("RemoveContactButton".to_string())),
+    };
+    let cloned_tx_to_back = tx_to_back.clone();
+    remove_contact_button.connect_clicked(move |_| {
+        if cloned_tx_to_back
+            .send(SignalToBack::RemoveContact(
+                contact_name_entry.text().to_string(),
+            ))
+            .is_err()
+        {
+            println!("Error sending signal to back");
+        }
+        contact_name_entry.set_text("");
+        contact_address_entry.set_text("");
+        contact_note_entry.set_text("");
+    });
+
+    if tx_to_back.send(SignalToBack::GetAddressBook).is_err() {
+        return Err(ErrorUI::FailedSignalToFront(
+            "Failed to request the address book".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Function that updates the tree view with the contacts of the address book
+fn show_contacts_in_tree_view(builder: &Builder, contacts: Vec<Contact>) -> Result<(), ErrorUI> {
+    let contacts_tree_store: TreeStore = match builder.object("ContactsTreeStore") {
+        Some(tree_store) => tree_store,
+        None => return Err(ErrorUI::MissingElement("ContactsTreeStore".to_string())),
+    };
+
+    contacts_tree_store.clear();
+
+    for contact in contacts.iter() {
+        let note = match &contact.note {
+            Some(note) => note.clone(),
+            None => "".to_string(),
+        };
+
+        let tree_iter = contacts_tree_store.append(None);
+        contacts_tree_store.set_value(&tree_iter, 0, &glib::Value::from(&contact.name));
+        contacts_tree_store.set_value(
+            &tree_iter,
+            1,
+            &glib::Value::from(contact.address.to_string()),
+        );
+        contacts_tree_store.set_value(&tree_iter, 2, &glib::Value::from(note));
+    }
+
+    Ok(())
+}
+
 /// Function that takes a timestamp and turns it into a string of the date
 fn from_timestamp_to_string(timestamp: &u32) -> Result<String, ErrorUI> {
     let naive = match NaiveDateTime::from_timestamp_opt(*timestamp as i64, 0) {
@@ -713,10 +1084,10 @@ fn from_vector_to_string(vector: &[u8; 32]) -> String {
     string
 }
 
-/// Function that updates the tree vies with the transactions of the current account
+/// Function that updates the tree vies with the history of transactions of the current account
 fn show_transactions_in_tree_view(
     builder: &Builder,
-    transaction_information: Vec<(u32, [u8; 32], i64)>,
+    records: Vec<TransactionRecord>,
 ) -> Result<(), ErrorUI> {
     let transactions_tree_store: TreeStore = match builder.object("TransactionTreeStore") {
         Some(list_store) => list_store,
@@ -725,20 +1096,43 @@ fn show_transactions_in_tree_view(
 
     transactions_tree_store.clear();
 
-    for (timestamp, label, amount) in transaction_information.iter().rev() {
+    for record in records.iter().rev() {
         let tree_iter = transactions_tree_store.append(None);
         transactions_tree_store.set_value(
             &tree_iter,
             0,
-            &glib::Value::from(from_timestamp_to_string(timestamp)?),
+            &glib::Value::from(from_timestamp_to_string(&record.timestamp)?),
+        );
+        transactions_tree_store.set_value(
+            &tree_iter,
+            1,
+            &glib::Value::from(record.direction.to_string()),
         );
-        transactions_tree_store.set_value(&tree_iter, 1, &glib::Value::from("Mined".to_string()));
         transactions_tree_store.set_value(
             &tree_iter,
             2,
-            &glib::Value::from(from_vector_to_string(label)),
+            &glib::Value::from(from_vector_to_string(&record.transaction_id)),
         );
-        transactions_tree_store.set_value(&tree_iter, 3, &glib::Value::from(amount.to_string()));
+        transactions_tree_store.set_value(
+            &tree_iter,
+            3,
+            &glib::Value::from(record.get_net_amount_in_tbtc().to_string()),
+        );
+        transactions_tree_store.set_value(
+            &tree_iter,
+            4,
+            &glib::Value::from(record.get_fee_in_tbtc().to_string()),
+        );
+        transactions_tree_store.set_value(
+            &tree_iter,
+            5,
+            &glib::Value::from(record.confirmations.to_string()),
+        );
+        let label = match &record.label {
+            Some(label) => label.clone(),
+            None => "".to_string(),
+        };
+        transactions_tree_store.set_value(&tree_iter, 6, &glib::Value::from(label));
     }
     Ok(())
 }
@@ -768,6 +1162,75 @@ fn show_connections_in_tree_view(
     Ok(())
 }
 
+/// Function that finds the row of a connection in the tree view with the connections
+fn find_connection_in_tree_view(
+    connections_tree_store: &TreeStore,
+    connection: &ConnectionId,
+) -> Option<TreeIter> {
+    let connection_type = connection.connection_type.to_string();
+    let ip_address = connection.address.ip().to_string();
+    let port = connection.address.port().to_string();
+
+    let tree_iter = connections_tree_store.iter_first()?;
+    loop {
+        let is_connection = [connection_type.clone(), ip_address.clone(), port.clone()]
+            .iter()
+            .enumerate()
+            .all(|(column, value)| {
+                connections_tree_store
+                    .value(&tree_iter, column as i32)
+                    .get::<String>()
+                    .is_ok_and(|column_value| column_value == *value)
+            });
+
+        if is_connection {
+            return Some(tree_iter);
+        }
+        if !connections_tree_store.iter_next(&tree_iter) {
+            return None;
+        }
+    }
+}
+
+/// Function that removes a lost connection from the tree view with the connections
+fn remove_connection_from_tree_view(
+    builder: &Builder,
+    connection: ConnectionId,
+) -> Result<(), ErrorUI> {
+    let connections_tree_store: TreeStore = match builder.object("ConnectionsTreeStore") {
+        Some(list_store) => list_store,
+        None => return Err(ErrorUI::MissingElement("ConnectionsTreeStore".to_string())),
+    };
+
+    if let Some(tree_iter) = find_connection_in_tree_view(&connections_tree_store, &connection) {
+        connections_tree_store.remove(&tree_iter);
+    }
+
+    Ok(())
+}
+
+/// Function that shows the latency of a connection in the tree view with the connections
+fn show_latency_in_tree_view(
+    builder: &Builder,
+    connection: ConnectionId,
+    latency: u128,
+) -> Result<(), ErrorUI> {
+    let connections_tree_store: TreeStore = match builder.object("ConnectionsTreeStore") {
+        Some(list_store) => list_store,
+        None => return Err(ErrorUI::MissingElement("ConnectionsTreeStore".to_string())),
+    };
+
+    if let Some(tree_iter) = find_connection_in_tree_view(&connections_tree_store, &connection) {
+        connections_tree_store.set_value(
+            &tree_iter,
+            3,
+            &glib::Value::from(format!("{latency} ms")),
+        );
+    }
+
+    Ok(())
+}
+
 /// This functions sets up the behaviour of the GUI when it receives a signal from the backend
 fn spawn_local_handler(
     builder: &Builder,
@@ -849,16 +1312,30 @@ fn spawn_local_handler(
                     );
                 };
             }
-            SignalToFront::AccountTransactions(transaction_information) => {
-                if let Err(error) =
-                    show_transactions_in_tree_view(&cloned_builder, transaction_information)
-                {
+            SignalToFront::AccountTransactions(records) => {
+                if let Err(error) = show_transactions_in_tree_view(&cloned_builder, records) {
                     println!(
                         "Error showing transactions in tree view, with error {:?}",
                         error
                     );
                 };
             }
+            SignalToFront::AccountOutputs(outputs) => {
+                if let Err(error) = show_outputs_in_tree_view(&cloned_builder, outputs) {
+                    println!("Error showing outputs in tree view, with error {:?}", error);
+                };
+            }
+            SignalToFront::LoadContacts(contacts) => {
+                if let Err(error) = show_contacts_in_tree_view(&cloned_builder, contacts) {
+                    println!(
+                        "Error showing contacts in tree view, with error {:?}",
+                        error
+                    );
+                };
+            }
+            SignalToFront::TransactionsExported(path) => {
+                show_transactions_exported_notification(path);
+            }
             SignalToFront::SuccessfullySentTransaction(tx_id) => {
                 if let Err(error) = show_new_transaction_sent_notification(&cloned_builder, tx_id) {
                     println!(
@@ -872,6 +1349,7 @@ fn spawn_local_handler(
                     || tx_to_back
                         .send(SignalToBack::GetAccountTransactions)
                         .is_err()
+                    || tx_to_back.send(SignalToBack::GetAccountOutputs).is_err()
                 {
                     println!("Error sending signal to back");
                 };
@@ -925,6 +1403,23 @@ fn spawn_local_handler(
                     );
                 };
             }
+            SignalToFront::UpdateLatency(connection, latency) => {
+                if let Err(error) = show_latency_in_tree_view(&cloned_builder, connection, latency)
+                {
+                    println!(
+                        "Error showing the latency in tree view, with error {:?}",
+                        error
+                    );
+                };
+            }
+            SignalToFront::RemoveConnection(connection) => {
+                if let Err(error) = remove_connection_from_tree_view(&cloned_builder, connection) {
+                    println!(
+                        "Error removing connection from tree view, with error {:?}",
+                        error
+                    );
+                };
+            }
             SignalToFront::ErrorInMerkleProof(error) => {
                 if let Err(error) = show_merkle_error_window(&cloned_builder, error) {
                     println!(
@@ -970,6 +1465,14 @@ pub fn build_ui(
 
     login_registration_window(&builder, application, tx_to_back.clone())?;
 
+    login_export_transactions_button(&builder, tx_to_back.clone())?;
+
+    login_transaction_labels(&builder, tx_to_back.clone())?;
+
+    login_contacts_page(&builder, tx_to_back.clone())?;
+
+    login_coins_page(&builder, tx_to_back.clone())?;
+
     login_combo_box(&builder, tx_to_back)?;
 
     login_transaction_error_window(&builder)?;
@@ -2,18 +2,26 @@ use super::{frontend, signal_to_back::SignalToBack};
 
 use crate::{
     process::{
-        reference::{get_reference, MutArc},
+        reference::{get_optional_reference, get_reference, MutArc},
         transaction,
     },
-    ui::{account, error_ui::ErrorUI, input_handler::InputHandler},
+    ui::{
+        account, address_book, coin_control, error_ui::ErrorUI, export_history, from_hexa,
+        input_handler::InputHandler, label,
+    },
 };
 
 use cargosos_bitcoin::{
-    block_structure::{block_chain::BlockChain, utxo_set::UTXOSet},
-    logs::logger_sender::LoggerSender,
+    block_structure::{
+        block_chain::BlockChain, filter_chain::FilterChain, hash::HASH_TYPE_SIZE, utxo_set::UTXOSet,
+    },
+    connections::network::Network,
+    logs::{level::Level, logger_sender::LoggerSender},
     node_structure::broadcasting::Broadcasting,
     notifications::{notification::Notification, notifier::Notifier},
-    wallet_structure::{address::Address, wallet::Wallet},
+    wallet_structure::{
+        address_book::AddressBook, transaction_history::TransactionHistory, wallet::Wallet,
+    },
 };
 
 use std::{
@@ -28,6 +36,7 @@ where
     rx_from_front: Receiver<SignalToBack>,
     notifier: N,
     logger: LoggerSender,
+    network: Network,
 }
 
 impl<N: Notifier> InputHandlerGUI<N> {
@@ -36,8 +45,14 @@ impl<N: Notifier> InputHandlerGUI<N> {
             rx_from_front,
             notifier,
             logger,
+            network: Network::default(),
         }
     }
+
+    /// Changes the network of the accounts created
+    pub fn with_network(self, network: Network) -> Self {
+        Self { network, ..self }
+    }
 }
 
 impl<RW, N> InputHandler<RW> for InputHandlerGUI<N>
@@ -49,14 +64,19 @@ where
         &self,
         broadcasting: MutArc<Broadcasting<RW>>,
         wallet: MutArc<Wallet>,
+        address_book: MutArc<AddressBook>,
         utxo_set: MutArc<UTXOSet>,
         block_chain: MutArc<BlockChain>,
+        history_data: (MutArc<TransactionHistory>, Option<MutArc<FilterChain>>),
     ) -> Result<(), ErrorUI> {
+        let (transaction_history, filter_chain) = history_data;
         for rx in &self.rx_from_front {
             let mut wallet_reference = get_reference(&wallet)?;
+            let mut address_book_reference = get_reference(&address_book)?;
             let mut utxo_set_reference = get_reference(&utxo_set)?;
             let mut broadcasting_reference = get_reference(&broadcasting)?;
             let block_chain_reference = get_reference(&block_chain)?;
+            let mut transaction_history_reference = get_reference(&transaction_history)?;
 
             match rx {
                 SignalToBack::GetAccountBalance => {
@@ -73,12 +93,25 @@ where
                         self.notifier.clone(),
                     )?;
                 }
-                SignalToBack::CreateTransaction(address_string, amount, fee) => {
-                    let address = match Address::new(&address_string) {
-                        Ok(address) => address,
-                        Err(_) => {
-                            self.notifier.notify(Notification::InvalidAddressEnter);
-                            return Ok(());
+                SignalToBack::CreateTransaction(address_string, amount, fee, selected_outputs) => {
+                    let address =
+                        match address_book_reference.get_address(&address_string, self.network) {
+                            Ok(address) => address,
+                            Err(_) => {
+                                self.notifier.notify(Notification::InvalidAddressEnter);
+                                return Ok(());
+                            }
+                        };
+
+                    let outpoints = match selected_outputs
+                        .iter()
+                        .map(|outpoint| label::from_outpoint_string(outpoint))
+                        .collect()
+                    {
+                        Ok(outpoints) => outpoints,
+                        Err(error) => {
+                            let _ = self.logger.log_data(Level::ERROR, error);
+                            continue;
                         }
                     };
 
@@ -86,7 +119,7 @@ where
                         &mut broadcasting_reference,
                         &wallet_reference,
                         &mut utxo_set_reference,
-                        address,
+                        (address, outpoints),
                         (amount, fee),
                         self.notifier.clone(),
                         self.logger.clone(),
@@ -98,16 +131,90 @@ where
                         &name,
                         &private_key,
                         &public_key,
+                        self.network,
                         self.notifier.clone(),
                     )?;
                 }
                 SignalToBack::GetAccountTransactions => {
+                    let filter_chain_reference = get_optional_reference(&filter_chain)?;
                     account::give_account_transactions(
                         &wallet_reference,
-                        &block_chain_reference,
+                        (&block_chain_reference, filter_chain_reference.as_deref()),
+                        &mut transaction_history_reference,
+                        self.notifier.clone(),
+                        self.logger.clone(),
+                    )?;
+                }
+                SignalToBack::ExportTransactions(path) => {
+                    let filter_chain_reference = get_optional_reference(&filter_chain)?;
+                    export_history::export_account_transactions(
+                        &wallet_reference,
+                        (&block_chain_reference, filter_chain_reference.as_deref()),
+                        &mut transaction_history_reference,
+                        &path,
+                        self.notifier.clone(),
+                        self.logger.clone(),
+                    )?;
+                }
+                SignalToBack::GetAccountOutputs => {
+                    coin_control::give_account_outputs(
+                        &wallet_reference,
+                        &utxo_set_reference,
+                        &transaction_history_reference,
+                        self.notifier.clone(),
+                    );
+                }
+                SignalToBack::FreezeOutput(outpoint, frozen) => {
+                    match label::from_outpoint_string(&outpoint) {
+                        Ok(outpoint) => coin_control::change_output_frozen(
+                            &mut wallet_reference,
+                            outpoint,
+                            frozen,
+                            self.notifier.clone(),
+                            self.logger.clone(),
+                        ),
+                        Err(error) => {
+                            let _ = self.logger.log_data(Level::ERROR, error);
+                        }
+                    }
+                }
+                SignalToBack::LabelTransaction(transaction_id, transaction_label) => {
+                    match from_hexa::from::<HASH_TYPE_SIZE>(&transaction_id) {
+                        Ok(transaction_id) => label::label_transaction(
+                            &mut wallet_reference,
+                            transaction_id,
+                            &transaction_label,
+                            self.notifier.clone(),
+                            self.logger.clone(),
+                        ),
+                        Err(error) => {
+                            let _ = self.logger.log_data(Level::ERROR, error);
+                        }
+                    }
+                }
+                SignalToBack::GetAddressBook => {
+                    address_book::give_contacts(&address_book_reference, self.notifier.clone());
+                }
+                SignalToBack::SaveContact(name, address, note) => {
+                    address_book::save_contact(
+                        &mut address_book_reference,
+                        &name,
+                        &address,
+                        &note,
+                        self.network,
+                        self.notifier.clone(),
+                        self.logger.clone(),
+                    )?;
+                    address_book::give_contacts(&address_book_reference, self.notifier.clone());
+                }
+                SignalToBack::RemoveContact(name) => {
+                    address_book::remove_contact(
+                        &mut address_book_reference,
+                        &name,
                         self.notifier.clone(),
                         self.logger.clone(),
                     )?;
+                    address_book::give_contacts(&address_book_reference, self.notifier.clone());
                 }
                 SignalToBack::RequestMerkleProof(block_hash, transaction_id) => {
                     frontend::request_merkle_proof(
@@ -1,10 +1,8 @@
 use super::signal_to_front::SignalToFront;
 
 use cargosos_bitcoin::{
-    block_structure::transaction::Transaction,
     logs::logger_sender::LoggerSender,
     notifications::{notification::Notification, notifier::Notifier},
-    wallet_structure::account::Account,
 };
 
 use gtk::glib::Sender;
@@ -48,6 +46,34 @@ impl Notifier for NotifierGUI {
                         .log_error("Error updating connection".to_string());
                 }
             }
+            Notification::UpdatedPeerLatency(connection_id, latency) => {
+                if self
+                    .tx_to_front
+                    .send(SignalToFront::UpdateLatency(
+                        connection_id,
+                        latency.as_millis(),
+                    ))
+                    .is_err()
+                {
+                    let _ = self
+                        .logger
+                        .log_error("Error updating the latency of a connection".to_string());
+                }
+            }
+            Notification::PeerBanned(socket_address, misbehavior) => {
+                println!("Peer {socket_address} banned because of: {misbehavior}")
+            }
+            Notification::ConnectionLost(connection_id) => {
+                if self
+                    .tx_to_front
+                    .send(SignalToFront::RemoveConnection(connection_id))
+                    .is_err()
+                {
+                    let _ = self
+                        .logger
+                        .log_error("Error removing connection".to_string());
+                }
+            }
             Notification::TransactionOfAccountReceived(accounts, _) => {
                 if self.tx_to_front.send(SignalToFront::Update).is_err()
                     || self
@@ -135,11 +161,10 @@ impl Notifier for NotifierGUI {
                         .log_error("Failed to send error signal to front".to_string());
                 }
             }
-            Notification::AccountTransactions(account, transactions) => {
-                let transactions = get_account_transactions_information(&account, transactions);
+            Notification::AccountTransactions(_, records) => {
                 if self
                     .tx_to_front
-                    .send(SignalToFront::AccountTransactions(transactions))
+                    .send(SignalToFront::AccountTransactions(records))
                     .is_err()
                 {
                     let _ = self.logger.log_error(
@@ -213,6 +238,122 @@ impl Notifier for NotifierGUI {
                     );
                 }
             }
+            Notification::AccountOutputs(account, outputs) => {
+                let _ = self.logger.log_wallet(format!(
+                    "The account {} has {} unspent outputs",
+                    account.account_name,
+                    outputs.len()
+                ));
+                if self
+                    .tx_to_front
+                    .send(SignalToFront::AccountOutputs(outputs))
+                    .is_err()
+                {
+                    let _ = self
+                        .logger
+                        .log_error("Failed to send outputs to front".to_string());
+                }
+            }
+            Notification::TransactionLabeled(_, label) => {
+                let _ = self
+                    .logger
+                    .log_wallet(format!("Transaction labeled as: {label}"));
+                if self.tx_to_front.send(SignalToFront::Update).is_err() {
+                    let _ = self
+                        .logger
+                        .log_error("Failed to send update signal to front".to_string());
+                }
+            }
+            Notification::OutputLabeled(outpoint, label) => {
+                let _ = self
+                    .logger
+                    .log_wallet(format!("Output {outpoint} labeled as: {label}"));
+            }
+            Notification::OutputFrozen(outpoint) => {
+                let _ = self.logger.log_wallet(format!("Output {outpoint} frozen"));
+                if self.tx_to_front.send(SignalToFront::Update).is_err() {
+                    let _ = self
+                        .logger
+                        .log_error("Failed to send update signal to front".to_string());
+                }
+            }
+            Notification::OutputUnfrozen(outpoint) => {
+                let _ = self
+                    .logger
+                    .log_wallet(format!("Output {outpoint} unfrozen"));
+                if self.tx_to_front.send(SignalToFront::Update).is_err() {
+                    let _ = self
+                        .logger
+                        .log_error("Failed to send update signal to front".to_string());
+                }
+            }
+            Notification::AddressBookContacts(contacts) => {
+                if self
+                    .tx_to_front
+                    .send(SignalToFront::LoadContacts(contacts))
+                    .is_err()
+                {
+                    let _ = self
+                        .logger
+                        .log_error("Failed to send contacts to front".to_string());
+                }
+            }
+            Notification::SavedContact(contact) => {
+                let _ = self
+                    .logger
+                    .log_wallet(format!("Contact {} saved", contact.name));
+            }
+            Notification::RemovedContact(contact) => {
+                let _ = self
+                    .logger
+                    .log_wallet(format!("Contact {} removed", contact.name));
+            }
+            Notification::ContactAlreadyExists(name) => {
+                let message = format!("There is already a contact named {name}");
+                if self
+                    .tx_to_front
+                    .send(SignalToFront::ErrorInTransaction(message))
+                    .is_err()
+                {
+                    let _ = self
+                        .logger
+                        .log_error("Failed to send error signal to front".to_string());
+                }
+            }
+            Notification::ContactNotFound(name) => {
+                let message = format!("There is no contact named {name}");
+                if self
+                    .tx_to_front
+                    .send(SignalToFront::ErrorInTransaction(message))
+                    .is_err()
+                {
+                    let _ = self
+                        .logger
+                        .log_error("Failed to send error signal to front".to_string());
+                }
+            }
+            Notification::SuccessfullyExportedTransactions(_, path) => {
+                if self
+                    .tx_to_front
+                    .send(SignalToFront::TransactionsExported(path))
+                    .is_err()
+                {
+                    let _ = self
+                        .logger
+                        .log_error("Failed to send exported transactions to front".to_string());
+                }
+            }
+            Notification::ProblemExportingTransactions(error) => {
+                if self
+                    .tx_to_front
+                    .send(SignalToFront::ErrorInTransaction(error))
+                    .is_err()
+                {
+                    let _ = self
+                        .logger
+                        .log_error("Failed to send error signal to front".to_string());
+                }
+            }
             Notification::SuccessfullySentTransaction(transaction) => {
                 if self
                     .tx_to_front
@@ -227,6 +368,19 @@ impl Notifier for NotifierGUI {
                 }
                 println!("Transaction sent: {transaction}", transaction = transaction);
             }
+            Notification::TransactionRejected(transaction, code, reason) => {
+                if self
+                    .tx_to_front
+                    .send(SignalToFront::ErrorInTransaction(format!(
+                        "The transaction {transaction} was rejected ({code}): {reason}"
+                    )))
+                    .is_err()
+                {
+                    let _ = self
+                        .logger
+                        .log_error("Failed to send error signal to front".to_string());
+                }
+            }
             Notification::HeadersReceived(headers) => {
                 println!("Received {headers} headers");
             }
@@ -292,27 +446,3 @@ impl Notifier for NotifierGUI {
         }
     }
 }
-
-/// Return the information of the transactions of an account
-fn get_account_transactions_information(
-    account: &Account,
-    transactions: Vec<Transaction>,
-) -> Vec<(u32, [u8; 32], i64)> {
-    transactions
-        .iter()
-        .filter_map(|transaction| {
-            let timestamp = transaction.time;
-            let label = match transaction.get_tx_id() {
-                Ok(txid) => txid,
-                Err(_) => return None,
-            };
-            let mut amount: i64 = 0;
-            for utxo in transaction.tx_out.clone() {
-                if account.verify_transaction_output_ownership(&utxo) {
-                    amount += utxo.value;
-                }
-            }
-            Some((timestamp, label, amount))
-        })
-        .collect()
-}
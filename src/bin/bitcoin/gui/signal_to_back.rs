@@ -3,8 +3,8 @@ pub enum SignalToBack {
     /// Signal to obtain the balance of an account.
     GetAccountBalance,
 
-    /// Signal to create a transaction.
-    CreateTransaction(String, f64, f64),
+    /// Signal to create a transaction, spending the selected outputs or choosing them automatically if there are none.
+    CreateTransaction(String, f64, f64, Vec<String>),
 
     /// Signal to change the selected account.
     ChangeSelectedAccount(String),
@@ -15,6 +15,25 @@ pub enum SignalToBack {
     /// Signal to get the transactions of an account.
     GetAccountTransactions,
 
+    /// Signal to export the transactions of the selected account to a file.
+    ExportTransactions(String),
+
+    /// Signal to get the unspent outputs of an account.
+    GetAccountOutputs,
+    /// Signal to freeze or unfreeze an output.
+    FreezeOutput(String, bool),
+    /// Signal to change the label of a transaction.
+    LabelTransaction(String, String),
+
+    /// Signal to get the contacts of the address book.
+    GetAddressBook,
+
+    /// Signal to add a contact to the address book, or update it if it already exists.
+    SaveContact(String, String, String),
+
+    /// Signal to remove a contact from the address book.
+    RemoveContact(String),
+
     /// Signal requesting the merkle proof of a transaction.
     RequestMerkleProof(String, String),
 
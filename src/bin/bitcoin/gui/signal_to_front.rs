@@ -1,5 +1,9 @@
 use cargosos_bitcoin::{
-    block_structure::hash::HashType, node_structure::connection_id::ConnectionId,
+    block_structure::hash::HashType,
+    node_structure::connection_id::ConnectionId,
+    wallet_structure::{
+        contact::Contact, output_record::OutputRecord, transaction_record::TransactionRecord,
+    },
 };
 
 /// This enum represents the signal that the back sends to the front.
@@ -25,8 +29,16 @@ pub enum SignalToFront {
     /// Signal to notify that we received a transaction from one of our accounts in a block.
     BlockWithUnconfirmedTransactionReceived(String, String),
 
-    /// Signal to transmit the information of the transactions of an account.
-    AccountTransactions(Vec<(u32, HashType, i64)>),
+    /// Signal to transmit the history of the transactions of an account.
+    AccountTransactions(Vec<TransactionRecord>),
+    /// Signal to transmit the unspent outputs of an account.
+    AccountOutputs(Vec<OutputRecord>),
+
+    /// Signal to transmit the contacts of the address book.
+    LoadContacts(Vec<Contact>),
+
+    /// Signal to transmit the path of the file where the transactions were exported.
+    TransactionsExported(String),
 
     /// Signal to transmit the success in sending a transaction.
     SuccessfullySentTransaction(String),
@@ -46,6 +58,12 @@ pub enum SignalToFront {
     /// Signal to notify to that we have to update the current connections
     UpdateConnection(ConnectionId),
 
+    /// Signal to notify that a connection was lost and has to be removed from the current connections
+    RemoveConnection(ConnectionId),
+
+    /// Signal to notify the latency, in milliseconds, of a connection
+    UpdateLatency(ConnectionId, u128),
+
     /// Signal to notify that we have to update the front.
     Update,
 }
@@ -122,7 +122,7 @@ fn main() -> Result<(), ErrorExecution> {
             tui::execution::program_execution(
                 mode_config,
                 connection_config,
-                download_config,
+                (download_config, save_config.clone()),
                 &mut load_system,
                 logger.clone(),
             )?
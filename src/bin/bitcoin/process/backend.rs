@@ -8,19 +8,24 @@ use crate::{
 };
 
 use cargosos_bitcoin::{
-    block_structure::{block_chain::BlockChain, utxo_set::UTXOSet},
+    block_structure::{
+        block::Block, block_chain::BlockChain, block_header::BlockHeader,
+        filter_chain::FilterChain, utxo_set::UTXOSet,
+    },
     concurrency::stop::Stop,
     configurations::{
         connection_config::ConnectionConfig, download_config::DownloadConfig,
-        mode_config::ModeConfig,
+        mode_config::ModeConfig, save_config::SaveConfig, spv_config::DEFAULT_FALSE_POSITIVE_RATE,
     },
+    connections::{network::Network, supported_services::SupportedServices},
     logs::{level::Level, logger_sender::LoggerSender},
     node_structure::{
+        address_manager::AddressManager, ban_list::BanList, bloom_filter::BloomFilter,
         broadcasting::Broadcasting, connection_event::ConnectionEvent, connection_id::ConnectionId,
-        message_response::MessageResponse,
+        message_response::MessageResponse, process_connection::SenderPotential,
     },
     notifications::{notification::Notification, notifier::Notifier},
-    wallet_structure::wallet::Wallet,
+    wallet_structure::{transaction_history::TransactionHistory, wallet::Wallet},
 };
 
 use std::{
@@ -28,6 +33,7 @@ use std::{
     sync::mpsc::{channel, Receiver},
     sync::{Arc, Mutex},
     thread::JoinHandle,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 type HandlePeer = JoinHandle<Result<(), ErrorProcess>>;
@@ -44,7 +50,7 @@ type HandlePeer = JoinHandle<Result<(), ErrorProcess>>;
 pub fn backend<N, I>(
     mode_config: ModeConfig,
     connection_config: ConnectionConfig,
-    download_config: DownloadConfig,
+    sync_config: (DownloadConfig, SaveConfig),
     load_system: &mut LoadSystem,
     input_handler: I,
     notifier: N,
@@ -57,10 +63,13 @@ where
     let (sender_confirm_connection, receiver_confirm_connection) =
         channel::<(TcpStream, ConnectionId)>();
 
+    let ban_list = Arc::new(Mutex::new(load_system.get_ban_list()?));
+
     let (handle_process_connection, sender_potential_connections) =
         connection::create_process_connection(
             connection_config.clone(),
             sender_confirm_connection,
+            ban_list.clone(),
             notifier.clone(),
             logger.clone(),
         );
@@ -73,60 +82,136 @@ where
 
     let wallet = Arc::new(Mutex::new(wallet));
 
-    let block_chain = load_system.get_block_chain()?;
+    let address_book = Arc::new(Mutex::new(load_system.get_address_book()?));
+
+    let address_manager = Arc::new(Mutex::new(load_system.get_address_manager()?));
+
+    let block_chain = get_block_chain(load_system, connection_config.network)?;
+    let block_chain = match sync_config.0.prune_depth {
+        Some(prune_depth) => block_chain.with_prune_depth(prune_depth),
+        None => block_chain,
+    };
 
     let utxo_set = Arc::new(Mutex::new(download::get_utxo_set(
         &block_chain,
         logger.clone(),
     )));
 
+    let filter_chain = match serves_compact_filters(&mode_config, &connection_config) {
+        true => get_filter_chain(load_system, &block_chain, logger.clone()),
+        false => None,
+    };
+
+    let mut transaction_history =
+        get_transaction_history(load_system, &block_chain, connection_config.network)?;
+    for account in reference::get_reference(&wallet)?.get_accounts().iter() {
+        transaction_history.track_account(account, &block_chain, filter_chain.as_ref());
+    }
+
+    let transaction_history = Arc::new(Mutex::new(transaction_history));
+    let filter_chain = filter_chain.map(|filter_chain| Arc::new(Mutex::new(filter_chain)));
+
     let block_chain = Arc::new(Mutex::new(block_chain));
 
     notifier.notify(Notification::NotifyBlockchainIsReady);
 
     let (sender_response, receiver_response) = channel::<MessageResponse>();
 
+    let peer_count_max = match &mode_config {
+        ModeConfig::Server(server_config) => server_config.peer_count_max,
+        ModeConfig::Client(_) => 0,
+        ModeConfig::Spv(spv_config) => spv_config.peer_count_max,
+    };
+
+    let spv_filter = match &mode_config {
+        ModeConfig::Spv(_) => Some(get_bloom_filter(&wallet, &utxo_set)?),
+        _ => None,
+    };
+
     let (handle_peers, broadcasting) = broadcasting(
         (wallet.clone(), utxo_set.clone(), block_chain.clone()),
+        transaction_history.clone(),
+        (
+            address_manager.clone(),
+            ban_list.clone(),
+            sender_potential_connections.clone(),
+            peer_count_max,
+        ),
         receiver_response,
         notifier.clone(),
         logger.clone(),
     );
 
+    let network = connection_config.network;
     let handle_confirmed_connection = connection::update_from_connection(
         receiver_confirm_connection,
         sender_response,
-        (broadcasting.clone(), block_chain.clone(), utxo_set.clone()),
-        (connection_config, download_config),
+        (
+            broadcasting.clone(),
+            block_chain.clone(),
+            utxo_set.clone(),
+            filter_chain.clone(),
+            transaction_history.clone(),
+        ),
+        (address_manager.clone(), ban_list.clone()),
+        (connection_config, sync_config.0, sync_config.1, spv_filter),
         notifier.clone(),
         logger.clone(),
     );
 
     connection::establish_connection_to_peers(
         mode_config.clone(),
+        network,
+        &mut *reference::get_reference(&address_manager)?,
         sender_potential_connections.clone(),
         logger.clone(),
     )?;
 
     let (sender_stop, receiver_stop) = channel::<Stop>();
+    let (sender_stop_maintainer, receiver_stop_maintainer) = channel::<Stop>();
 
-    let posible_handle = match mode_config {
+    let posible_handle = match mode_config.clone() {
         ModeConfig::Server(server_config) => connection::establish_connection_with_clients(
             server_config,
+            network,
             receiver_stop,
             sender_potential_connections.clone(),
             logger.clone(),
         ),
-        ModeConfig::Client(_) => None,
+        ModeConfig::Client(_) | ModeConfig::Spv(_) => None,
     };
 
+    let handle_maintainer = connection::maintain_connections(
+        mode_config,
+        network,
+        (broadcasting.clone(), address_manager.clone()),
+        receiver_stop_maintainer,
+        sender_potential_connections.clone(),
+        notifier.clone(),
+        logger.clone(),
+    );
+
     input_handler.handle_input(
         broadcasting.clone(),
         wallet.clone(),
+        address_book.clone(),
         utxo_set,
         block_chain.clone(),
+        (transaction_history.clone(), filter_chain.clone()),
     )?;
 
+    if sender_stop_maintainer.send(Stop::Stop).is_err() {
+        let _ = logger.log_data(
+            Level::ERROR,
+            ErrorUI::ErrorFromPeer("Fail to stop the connection maintainer".to_string()),
+        );
+    } else if handle_maintainer.join().is_err() {
+        let _ = logger.log_data(
+            Level::ERROR,
+            ErrorUI::ErrorFromPeer("Fail to close the connection maintainer".to_string()),
+        );
+    }
+
     if let Some(handle) = posible_handle {
         if sender_stop.send(Stop::Stop).is_err() {
             let _ = logger.log_data(
@@ -182,9 +267,18 @@ where
         );
     }
 
+    let filter_chain = match filter_chain {
+        Some(filter_chain) => Some(reference::get_inner(filter_chain)?),
+        None => None,
+    };
+
     Ok(SaveSystem::new(
-        reference::get_inner(block_chain)?,
+        (reference::get_inner(block_chain)?, filter_chain),
         reference::get_inner(wallet)?,
+        reference::get_inner(address_book)?,
+        reference::get_inner(address_manager)?,
+        reference::get_inner(ban_list)?,
+        reference::get_inner(transaction_history)?,
         logger,
     ))
 }
@@ -192,6 +286,13 @@ where
 /// Broadcasting blocks and transactions from and to the given peers
 fn broadcasting<N: Notifier + 'static>(
     data: (MutArc<Wallet>, MutArc<UTXOSet>, MutArc<BlockChain>),
+    transaction_history: MutArc<TransactionHistory>,
+    peer_data: (
+        MutArc<AddressManager>,
+        MutArc<BanList>,
+        SenderPotential,
+        usize,
+    ),
     receiver_response: Receiver<MessageResponse>,
     notifier: N,
     logger: LoggerSender,
@@ -206,12 +307,121 @@ fn broadcasting<N: Notifier + 'static>(
     let handle = broadcasting::handle_peers(
         receiver_response,
         broadcasting.clone(),
-        wallet,
-        utxo_set,
-        block_chain,
+        (wallet, transaction_history),
+        (utxo_set, block_chain),
+        peer_data,
         notifier,
         logger,
     );
 
     (handle, broadcasting)
 }
+
+/// Gets the block chain saved, following the checkpoints and the difficulty adjustment of the network. It starts again from the genesis block
+/// of the network if the saved one is from another network
+///
+/// ### Error
+///  * `ErrorProcess:FailThread`: It will appear when a thread panics and fails
+///  * `ErrorProcess:AlreadyLoaded`: It will appear when try to get a value that is already loaded
+///  * `ErrorBlock::CouldNotHash`: It will appear when the block chain of the network could not be created
+fn get_block_chain(
+    load_system: &mut LoadSystem,
+    network: Network,
+) -> Result<BlockChain, ErrorExecution> {
+    let block_chain = load_system.get_block_chain()?;
+
+    let genesis_block = Block::new(BlockHeader::generate_genesis_block_header(network));
+    let block_chain = match genesis_block.header.get_hash256d() {
+        Ok(genesis_hash) if block_chain.get_block_with_hash(&genesis_hash).is_some() => block_chain,
+        _ => BlockChain::new(genesis_block)?,
+    };
+
+    Ok(block_chain
+        .with_checkpoints(network.get_checkpoints())
+        .with_network(network))
+}
+
+/// Gets the bloom filter matching the transactions of the accounts of the wallet and the ones spending their outputs
+///
+/// ### Error
+///  * `ErrorUI::CannotUnwrapArc`: It will appear when we try to unwrap an Arc
+fn get_bloom_filter(
+    wallet: &MutArc<Wallet>,
+    utxo_set: &MutArc<UTXOSet>,
+) -> Result<BloomFilter, ErrorExecution> {
+    let wallet = reference::get_reference(wallet)?;
+    let outpoints = wallet.get_outpoints(&*reference::get_reference(utxo_set)?);
+
+    Ok(BloomFilter::from_elements(
+        &wallet.get_script_data(),
+        &outpoints,
+        DEFAULT_FALSE_POSITIVE_RATE,
+        generate_tweak(),
+    ))
+}
+
+/// Returns true if the compact filters of the blocks are served to the peers, which needs the full blocks
+fn serves_compact_filters(mode_config: &ModeConfig, connection_config: &ConnectionConfig) -> bool {
+    match mode_config {
+        ModeConfig::Spv(_) => false,
+        ModeConfig::Server(_) | ModeConfig::Client(_) => connection_config
+            .services
+            .elements
+            .contains(&SupportedServices::NodeCompactFilters),
+    }
+}
+
+/// Gets the compact filters of the blocks saved, updated with the blocks of the block chain. They are created again
+/// if the saved ones don't follow the block chain. Returns None if they could not be loaded nor created, so the node
+/// keeps working without serving them
+fn get_filter_chain(
+    load_system: &mut LoadSystem,
+    block_chain: &BlockChain,
+    logger: LoggerSender,
+) -> Option<FilterChain> {
+    let mut filter_chain = match load_system.get_filter_chain() {
+        Ok(filter_chain) if filter_chain.follows(block_chain) => filter_chain,
+        Ok(_) => FilterChain::default(),
+        Err(error) => {
+            let _ = logger.log_data(Level::ERROR, error);
+            FilterChain::default()
+        }
+    };
+
+    match filter_chain.update(block_chain) {
+        Ok(()) => Some(filter_chain),
+        Err(error) => {
+            let _ = logger.log_data(Level::ERROR, error);
+            None
+        }
+    }
+}
+
+/// Generates the tweak of the bloom filter, so it's different each time the program runs
+fn generate_tweak() -> u32 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.subsec_nanos(),
+        Err(_) => 0,
+    }
+}
+
+/// Gets the transaction history saved, updated to the height of the block chain. It starts a new one if the saved
+/// one is from another network
+///
+/// ### Error
+///  * `ErrorProcess:FailThread`: It will appear when a thread panics and fails
+///  * `ErrorProcess:AlreadyLoaded`: It will appear when try to get a value that is already loaded
+fn get_transaction_history(
+    load_system: &mut LoadSystem,
+    block_chain: &BlockChain,
+    network: Network,
+) -> Result<TransactionHistory, ErrorExecution> {
+    let mut transaction_history = load_system.get_transaction_history()?;
+
+    if transaction_history.get_network() != network {
+        return Ok(TransactionHistory::new(&[], block_chain, None, network));
+    }
+
+    transaction_history.update_tip_height(block_chain);
+    Ok(transaction_history)
+}
@@ -1,43 +1,53 @@
 use super::{
+    connection, download,
     error_process::ErrorProcess,
     reference::{get_reference, MutArc},
 };
 
 use cargosos_bitcoin::{
     block_structure::{
-        block::Block, block_chain::BlockChain, error_block::ErrorBlock, transaction::Transaction,
-        utxo_set::UTXOSet,
+        block::Block, block_chain::BlockChain, error_block::ErrorBlock, filter_chain::FilterChain,
+        miner::Miner, transaction::Transaction, utxo_set::UTXOSet,
     },
     logs::logger_sender::LoggerSender,
+    messages::{command_name::CommandName, reject_code::RejectCode, reject_message::RejectMessage},
     node_structure::{
-        broadcasting::Broadcasting, connection_id::ConnectionId, message_response::MessageResponse,
-        message_to_peer::MessageToPeer, peer_manager::PeerManager,
+        address_manager::AddressManager, ban_list::BanList, broadcasting::Broadcasting,
+        connection_id::ConnectionId, message_response::MessageResponse,
+        message_to_peer::MessageToPeer, misbehavior::Misbehavior, peer_manager::PeerManager,
+        process_connection::SenderPotential,
     },
     notifications::{notification::Notification, notifier::Notifier},
-    wallet_structure::wallet::Wallet,
+    wallet_structure::{transaction_history::TransactionHistory, wallet::Wallet},
 };
 
 use std::{
     io::{Read, Write},
     sync::mpsc::{channel, Receiver, Sender},
     thread::{self, JoinHandle},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-/// Gives the broadcasting the peers to broadcast the blocks and transactions
+/// Gives the broadcasting the peers to broadcast the blocks and transactions. The chain data has the utxo set, whose
+/// pending transactions are used to rebuild compact blocks, the block chain and the compact block filters if they are served. The peer config has the magic numbers, the ping timeout, if bloom filters are served and if the blocks are requested filtered
 pub fn add_peer_to_broadcasting<N: Notifier + 'static, RW: Read + Write + Send + 'static>(
     broadcasting: &mut Broadcasting<RW>,
     connection: (RW, ConnectionId),
     sender_response: Sender<MessageResponse>,
-    blockchain: MutArc<BlockChain>,
-    magic_numbers: [u8; 4],
+    chain_data: (
+        MutArc<UTXOSet>,
+        MutArc<BlockChain>,
+        Option<MutArc<FilterChain>>,
+    ),
+    peer_config: ([u8; 4], u64, bool, bool),
     notifier: N,
     logger: LoggerSender,
 ) {
     let peer_manager = create_peer_manager(
         connection,
         sender_response,
-        blockchain,
-        magic_numbers,
+        chain_data,
+        peer_config,
         notifier,
         logger.clone(),
     );
@@ -53,13 +63,19 @@ pub fn add_peer_to_broadcasting<N: Notifier + 'static, RW: Read + Write + Send +
 fn create_peer_manager<N: Notifier + 'static, RW: Read + Write + Send + 'static>(
     connection: (RW, ConnectionId),
     sender_response: Sender<MessageResponse>,
-    blockchain: MutArc<BlockChain>,
-    magic_numbers: [u8; 4],
+    chain_data: (
+        MutArc<UTXOSet>,
+        MutArc<BlockChain>,
+        Option<MutArc<FilterChain>>,
+    ),
+    peer_config: ([u8; 4], u64, bool, bool),
     notifier: N,
     logger: LoggerSender,
 ) -> PeerManager<RW, N> {
     let _ = logger.log_node("Creating new Peer manager".to_string());
-    PeerManager::<RW, N>::new(
+    let (utxo_set, blockchain, filter_chain) = chain_data;
+    let (magic_numbers, ping_timeout, serves_bloom_filters, filtered_blocks) = peer_config;
+    let peer_manager = PeerManager::<RW, N>::new(
         connection.1,
         connection.0,
         sender_response,
@@ -68,15 +84,30 @@ fn create_peer_manager<N: Notifier + 'static, RW: Read + Write + Send + 'static>
         notifier,
         logger,
     )
+    .with_ping_timeout(ping_timeout)
+    .with_mempool(utxo_set)
+    .with_bloom_filters(serves_bloom_filters)
+    .with_filtered_blocks(filtered_blocks);
+
+    match filter_chain {
+        Some(filter_chain) => peer_manager.with_filter_chain(filter_chain),
+        None => peer_manager,
+    }
 }
 
-/// Create a thread for handling the blocks and transactions received
+/// Create a thread for handling the blocks, transactions and addresses of potential peers received, punishing the peers that misbehave.
+/// The peer data has the address manager, the ban list, where to send the potential peers and the maximum amount of peers
 pub fn handle_peers<RW, N>(
     receiver_broadcasting: Receiver<MessageResponse>,
     broadcasting: MutArc<Broadcasting<RW>>,
-    wallet: MutArc<Wallet>,
-    utxo_set: MutArc<UTXOSet>,
-    block_chain: MutArc<BlockChain>,
+    wallet_data: (MutArc<Wallet>, MutArc<TransactionHistory>),
+    chain_data: (MutArc<UTXOSet>, MutArc<BlockChain>),
+    peer_data: (
+        MutArc<AddressManager>,
+        MutArc<BanList>,
+        SenderPotential,
+        usize,
+    ),
     notifier: N,
     logger: LoggerSender,
 ) -> JoinHandle<Result<(), ErrorProcess>>
@@ -84,33 +115,76 @@ where
     RW: Read + Write + Send + 'static,
     N: Notifier + 'static,
 {
+    let (wallet, transaction_history) = wallet_data;
+    let (utxo_set, block_chain) = chain_data;
+    let (address_manager, ban_list, sender_potential_connections, peer_count_max) = peer_data;
     thread::spawn(move || {
         for message in receiver_broadcasting {
             let mut broadcasting_reference = get_reference(&broadcasting)?;
 
             match message {
                 MessageResponse::Block(block, from) => {
-                    receive_block(
+                    if !block.proof_of_inclusion() {
+                        broadcasting_reference.send_reject(
+                            RejectMessage::new(
+                                CommandName::Block,
+                                RejectCode::Invalid,
+                                "bad-txnmrklroot",
+                                block.header.get_hash256d().ok(),
+                            ),
+                            &from,
+                        );
+                        if connection::punish_peer(
+                            &ban_list,
+                            from,
+                            Misbehavior::InvalidBlock,
+                            notifier.clone(),
+                            logger.clone(),
+                        ) {
+                            broadcasting_reference.close_connection(&from);
+                        }
+                        continue;
+                    }
+
+                    if let Some(reject) = receive_block(
                         &utxo_set,
-                        &wallet,
+                        (&wallet, &transaction_history),
                         &block_chain,
                         block.clone(),
                         notifier.clone(),
-                    )?;
+                        &logger,
+                    )? {
+                        broadcasting_reference.send_reject(reject, &from);
+                        continue;
+                    }
 
                     if broadcasting_reference.broadcast_block(block, from).is_err() {
                         let _ = logger.log_node("Error broadcasting block".to_string());
                         return Err(ErrorProcess::ErrorReading);
                     }
                 }
+                MessageResponse::FilteredBlock(block, from) => {
+                    if let Some(reject) = receive_block(
+                        &utxo_set,
+                        (&wallet, &transaction_history),
+                        &block_chain,
+                        block,
+                        notifier.clone(),
+                        &logger,
+                    )? {
+                        broadcasting_reference.send_reject(reject, &from);
+                    }
+                }
                 MessageResponse::Transaction(transaction, from) => {
-                    receive_transaction(
+                    if !receive_transaction(
                         &wallet,
                         transaction.clone(),
                         &utxo_set,
                         logger.clone(),
                         notifier.clone(),
-                    )?;
+                    )? {
+                        continue;
+                    }
 
                     if broadcasting_reference
                         .broadcast_transaction(transaction, from)
@@ -120,6 +194,41 @@ where
                         return Err(ErrorProcess::ErrorReading);
                     }
                 }
+                MessageResponse::Addresses(addresses, _) => {
+                    let mut address_manager = get_reference(&address_manager)?;
+                    address_manager.add_network_addresses(&addresses);
+
+                    connection::request_potential_peers(
+                        &mut address_manager,
+                        &broadcasting_reference.get_connections(),
+                        peer_count_max,
+                        &sender_potential_connections,
+                        logger.clone(),
+                    );
+                }
+                MessageResponse::AddressesV2(addresses, _) => {
+                    let mut address_manager = get_reference(&address_manager)?;
+                    address_manager.add_network_addresses_v2(&addresses);
+
+                    connection::request_potential_peers(
+                        &mut address_manager,
+                        &broadcasting_reference.get_connections(),
+                        peer_count_max,
+                        &sender_potential_connections,
+                        logger.clone(),
+                    );
+                }
+                MessageResponse::Misbehavior(misbehavior, from) => {
+                    if connection::punish_peer(
+                        &ban_list,
+                        from,
+                        misbehavior,
+                        notifier.clone(),
+                        logger.clone(),
+                    ) {
+                        broadcasting_reference.close_connection(&from);
+                    }
+                }
             }
         }
 
@@ -127,7 +236,81 @@ where
     })
 }
 
-/// Manage receiving a transaction by updating the list of transactions seen so far if the transaction is from the selected account
+/// Mines the next block of the regtest chain with the pending transactions, paying to the selected account of the wallet.
+/// The block is added as if it was received from a peer, and then it's sent to all the peers
+///
+/// ### Error
+///  * `ErrorProcess::CannotUnwrapArc`: It will appear when we try to unwrap an Arc
+///  * `ErrorProcess::ErrorWriting`: It will appear when the block could not be mined or added to the block chain
+///  * `ErrorProcess::ErrorFromPeer`: It will appear when the block could not be sent to the peers
+pub fn mine_block<N, RW>(
+    broadcasting: &MutArc<Broadcasting<RW>>,
+    wallet_data: (&MutArc<Wallet>, &MutArc<TransactionHistory>),
+    chain_data: (&MutArc<UTXOSet>, &MutArc<BlockChain>),
+    notifier: N,
+    logger: &LoggerSender,
+) -> Result<(), ErrorProcess>
+where
+    RW: Read + Write + Send + 'static,
+    N: Notifier,
+{
+    let (utxo_set, block_chain) = chain_data;
+
+    let pk_script = match get_reference(wallet_data.0)?.get_selected_account() {
+        Some(account) => account.address.generate_script_pubkey_p2pkh(),
+        None => {
+            let _ = logger.log_wallet("No account selected cannot mine a block".to_string());
+            notifier.notify(Notification::AccountNotSelected);
+            return Ok(());
+        }
+    };
+
+    let time = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as u32,
+        Err(_) => 0,
+    };
+
+    let mined_block = {
+        let utxo_set = get_reference(utxo_set)?;
+        let block_chain = get_reference(block_chain)?;
+        Miner::new(pk_script).mine_block(&block_chain, &utxo_set, time)
+    };
+
+    let block = match mined_block {
+        Ok(block) => block,
+        Err(error) => {
+            let _ = logger.log_node(format!("Error mining a block: {error:?}"));
+            return Err(ErrorProcess::ErrorWriting);
+        }
+    };
+
+    let _ = logger.log_node(format!(
+        "Mined a block with {} transactions",
+        block.transactions.len()
+    ));
+
+    if let Some(reject) = receive_block(
+        utxo_set,
+        wallet_data,
+        block_chain,
+        block.clone(),
+        notifier,
+        logger,
+    )? {
+        let _ = logger.log_node(format!("Mined block refused: {}", reject.reason));
+        return Err(ErrorProcess::ErrorWriting);
+    }
+
+    match get_reference(broadcasting)?.send_block(block) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(ErrorProcess::ErrorFromPeer(
+            "While sending mined block".to_string(),
+        )),
+    }
+}
+
+/// Manage receiving a transaction by updating the list of transactions seen so far if the transaction is from the selected account.
+/// Returns false if the transaction was already seen, so it's not relayed again
 ///
 /// ### Error
 ///  * `ErrorUI::CannotUnwrapArc`: It will appear when we try to unwrap an Arc
@@ -137,14 +320,14 @@ fn receive_transaction<N: Notifier>(
     utxo_set: &MutArc<UTXOSet>,
     logger: LoggerSender,
     notifier: N,
-) -> Result<(), ErrorProcess> {
+) -> Result<bool, ErrorProcess> {
     let mut utxo_set = get_reference(utxo_set)?;
 
     if utxo_set.is_transaction_pending(&transaction) {
         let _ = logger.log_wallet(format!(
             "Transaction {transaction} is already in the list of transactions seen so far",
         ));
-        return Ok(());
+        return Ok(false);
     }
 
     let mut involved_accounts = Vec::new();
@@ -165,23 +348,48 @@ fn receive_transaction<N: Notifier>(
     }
 
     utxo_set.append_pending_transaction(transaction);
-    Ok(())
+    Ok(true)
 }
 
-/// Manage receiving a block by updating the block chain and the utxo set
+/// Manage receiving a block by updating the block chain, the utxo set and the transaction history, pruning the old blocks
+/// after it. Returns the reject message for the peer if the block doesn't follow the checkpoints or the difficulty adjustment
 ///
 /// ### Error
 ///  * `ErrorUI::CannotUnwrapArc`: It will appear when we try to unwrap an Arc
 ///  * `ErrorUI::ErrorWriting`: It will appear when writing to the block chain
 fn receive_block<N: Notifier>(
     utxo_set: &MutArc<UTXOSet>,
-    wallet: &MutArc<Wallet>,
+    wallet_data: (&MutArc<Wallet>, &MutArc<TransactionHistory>),
     block_chain: &MutArc<BlockChain>,
     block: Block,
     notifier: N,
-) -> Result<(), ErrorProcess> {
+    logger: &LoggerSender,
+) -> Result<Option<RejectMessage>, ErrorProcess> {
     let mut utxo_set = get_reference(utxo_set)?;
-    let wallet = get_reference(wallet)?;
+    let wallet = get_reference(wallet_data.0)?;
+    let mut block_chain = get_reference(block_chain)?;
+    let mut transaction_history = get_reference(wallet_data.1)?;
+
+    match block_chain.append_block(block.clone()) {
+        Ok(_) | Err(ErrorBlock::TransactionAlreadyInBlock) => {}
+        Err(ErrorBlock::CheckpointMismatch) => {
+            return Ok(Some(RejectMessage::new(
+                CommandName::Block,
+                RejectCode::Checkpoint,
+                "checkpoint-mismatch",
+                block.header.get_hash256d().ok(),
+            )))
+        }
+        Err(ErrorBlock::DifficultyMismatch) => {
+            return Ok(Some(RejectMessage::new(
+                CommandName::Block,
+                RejectCode::Invalid,
+                "bad-diffbits",
+                block.header.get_hash256d().ok(),
+            )))
+        }
+        _ => return Err(ErrorProcess::ErrorWriting),
+    }
 
     for transaction in utxo_set.pending_transactions() {
         if block.transactions.contains(transaction)
@@ -199,10 +407,11 @@ fn receive_block<N: Notifier>(
 
     utxo_set.update_utxo_with_block(&block);
 
-    notifier.notify(Notification::NewBlockAddedToTheBlockchain(block.clone()));
+    download::update_transaction_history(&mut transaction_history, &block_chain, &block);
 
-    match get_reference(block_chain)?.append_block(block) {
-        Ok(_) | Err(ErrorBlock::TransactionAlreadyInBlock) => Ok(()),
-        _ => Err(ErrorProcess::ErrorWriting),
-    }
+    download::prune_block_chain(&mut block_chain, logger);
+
+    notifier.notify(Notification::NewBlockAddedToTheBlockchain(block));
+
+    Ok(None)
 }
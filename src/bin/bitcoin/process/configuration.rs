@@ -8,6 +8,7 @@ use cargosos_bitcoin::configurations::{
     parsable::{parse_structure, Parsable},
     save_config::SaveConfig,
     server_config::ServerConfig,
+    spv_config::SpvConfig,
     ui_config::UIConfig,
 };
 
@@ -29,6 +30,7 @@ const SAVE_CONFIG: &str = "Save";
 const UI_CONFIG: &str = "UI";
 const UI_SERVER: &str = "Server";
 const UI_CLIENT: &str = "Client";
+const UI_SPV: &str = "SPV";
 
 /// Represents all the configuration needed to run the program
 #[derive(Debug, Clone)]
@@ -58,17 +60,29 @@ impl Configuration {
 
         let possible_server_config = Option::<ServerConfig>::parse(UI_SERVER, &map)?;
         let possible_client_config = Option::<ClientConfig>::parse(UI_CLIENT, &map)?;
+        let possible_spv_config = Option::<SpvConfig>::parse(UI_SPV, &map)?;
 
-        let mode_config = match (possible_server_config, possible_client_config) {
-            (None, Some(client_config)) => ModeConfig::Client(client_config),
-            (Some(server_config), _) => ModeConfig::Server(server_config),
+        let mode_config = match (
+            possible_server_config,
+            possible_client_config,
+            possible_spv_config,
+        ) {
+            (None, None, Some(spv_config)) => ModeConfig::Spv(spv_config),
+            (None, Some(client_config), _) => ModeConfig::Client(client_config),
+            (Some(server_config), _, _) => ModeConfig::Server(server_config),
             _ => return Err(ErrorConfiguration::ErrorIncompleteConfiguration),
         };
 
+        let mut connection_config = ConnectionConfig::parse(CONNECTION_CONFIG, &map)?;
+        let download_config = DownloadConfig::parse(DOWNLOAD_CONFIG, &map)?;
+        if download_config.prune_depth.is_some() {
+            connection_config.services = connection_config.services.with_network_limited();
+        }
+
         Ok(Configuration {
             log_config: LogConfig::parse(LOGS_CONFIG, &map)?,
-            connection_config: ConnectionConfig::parse(CONNECTION_CONFIG, &map)?,
-            download_config: DownloadConfig::parse(DOWNLOAD_CONFIG, &map)?,
+            connection_config,
+            download_config,
             save_config: SaveConfig::parse(SAVE_CONFIG, &map)?,
             ui_config: UIConfig::parse(UI_CONFIG, &map)?,
             mode_config,
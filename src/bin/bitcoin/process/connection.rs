@@ -1,53 +1,91 @@
 use super::{
     broadcasting, download,
+    error_process::ErrorProcess,
     reference::{get_reference, MutArc},
 };
 
 use crate::error_execution::ErrorExecution;
 
 use cargosos_bitcoin::{
-    block_structure::{block_chain::BlockChain, utxo_set::UTXOSet},
+    block_structure::{block_chain::BlockChain, filter_chain::FilterChain, utxo_set::UTXOSet},
     concurrency::{listener::Listener, stop::Stop},
     configurations::{
         connection_config::ConnectionConfig, download_config::DownloadConfig,
-        mode_config::ModeConfig, server_config::ServerConfig,
+        mode_config::ModeConfig, save_config::SaveConfig, server_config::ServerConfig,
+    },
+    connections::{
+        dns_seeder::DNSSeeder, error_connection::ErrorConnection, network::Network,
+        supported_services::SupportedServices,
     },
-    connections::error_connection::ErrorConnection,
     logs::logger_sender::LoggerSender,
     node_structure::{
+        address_manager::AddressManager,
+        ban_list::BanList,
+        bloom_filter::BloomFilter,
         broadcasting::Broadcasting,
         connection_event::ConnectionEvent,
         connection_id::ConnectionId,
         connection_type::ConnectionType,
         error_node::ErrorNode,
         message_response::MessageResponse,
+        misbehavior::Misbehavior,
         process_connection::{ProcessConnection, SenderPotential},
     },
-    notifications::notifier::Notifier,
+    notifications::{notification::Notification, notifier::Notifier},
+    wallet_structure::transaction_history::TransactionHistory,
 };
 
+use chrono::Utc;
+
 use std::{
     net::{IpAddr, SocketAddr, TcpListener, TcpStream},
-    sync::mpsc::{channel, Receiver, Sender},
+    sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender},
     thread::{self, JoinHandle},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-/// Get the peers from the dns seeder
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(10);
+const SEEDING_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const GATHERING_INTERVAL: Duration = Duration::from_secs(2);
+const HANDSHAKE_WINDOW: Duration = Duration::from_secs(60);
+
+/// The broadcasting to the peers and the data updated with the blocks they send
+type ConnectionData = (
+    MutArc<Broadcasting<TcpStream>>,
+    MutArc<BlockChain>,
+    MutArc<UTXOSet>,
+    Option<MutArc<FilterChain>>,
+    MutArc<TransactionHistory>,
+);
+
+/// Get the peers from the dns seeders, skipping the ones that can't be reached
 ///
 /// ### Error
-///  * `ErrorConnection::ErrorInvalidIPOrPortNumber`: It will appear if the IP or the port number its not valid
+///  * `ErrorConnection::ErrorInvalidIPOrPortNumber`: It will appear if none of the dns seeders gave peers because the IP or the port number its not valid
 pub fn get_potential_peers(
-    server_config: ServerConfig,
+    dns_seeders: &[DNSSeeder],
     logger: LoggerSender,
 ) -> Result<Vec<SocketAddr>, ErrorConnection> {
     let _ = logger.log_connection("Getting potential peers with dns seeder".to_string());
 
-    let potential_peers = server_config.dns_seeder.discover_peers()?;
-
-    let peer_count_max = std::cmp::min(server_config.peer_count_max, potential_peers.len());
+    let mut potential_peers: Vec<SocketAddr> = Vec::new();
+    let mut last_error: Option<ErrorConnection> = None;
+    for dns_seeder in dns_seeders {
+        match dns_seeder.discover_peers() {
+            Ok(peers) => potential_peers.extend(peers),
+            Err(error) => {
+                let _ = logger.log_connection(format!(
+                    "Could not get peers from the dns seeder {:?}, with error: {:?}",
+                    dns_seeder, error
+                ));
+                last_error = Some(error);
+            }
+        }
+    }
 
-    let potential_peers = potential_peers[0..peer_count_max].to_vec();
+    if let (true, Some(error)) = (potential_peers.is_empty(), last_error) {
+        return Err(error);
+    }
 
     for potential_peer in &potential_peers {
         let _ = logger.log_connection(format!("Potential peer: {:?}", potential_peer));
@@ -56,10 +94,238 @@ pub fn get_potential_peers(
     Ok(potential_peers)
 }
 
+/// Sends the addresses of potential peers, taken from the address manager, to connect until reaching the maximum amount of peers.
+/// The attempts that may still be in their handshake count as peers, so they are not requested twice.
+/// Returns the amount of peers that are still missing to reach that maximum
+pub fn request_potential_peers(
+    address_manager: &mut AddressManager,
+    connections: &[ConnectionId],
+    peer_count_max: usize,
+    sender_potential_connections: &SenderPotential,
+    logger: LoggerSender,
+) -> usize {
+    let peer_count = connections
+        .iter()
+        .filter(|connection| connection.connection_type == ConnectionType::Peer)
+        .count();
+
+    let connected_addresses: Vec<SocketAddr> = connections
+        .iter()
+        .map(|connection| connection.address)
+        .collect();
+
+    let now = Utc::now().timestamp() as u32;
+    let pending_count = address_manager.count_pending_attempts(
+        &connected_addresses,
+        now,
+        HANDSHAKE_WINDOW.as_secs() as u32,
+    );
+
+    let amount = peer_count_max.saturating_sub(peer_count + pending_count);
+    if amount == 0 {
+        return 0;
+    }
+
+    let potential_sockets = address_manager.select_addresses(amount, &connected_addresses, now);
+    let missing = amount - potential_sockets.len();
+
+    for potential_socket in potential_sockets {
+        let _ = logger.log_connection(format!("Potential peer: {:?}", potential_socket));
+        address_manager.mark_attempt(&potential_socket, now);
+
+        if sender_potential_connections
+            .send(ConnectionEvent::PotentialPeer(potential_socket))
+            .is_err()
+        {
+            let _ = logger.log_connection("Could not send potential connection".to_string());
+        }
+    }
+
+    missing
+}
+
+/// Sends the address of the node the client works with to connect to it, unless it's already connected
+/// or an attempt to connect to it may still be in its handshake
+fn request_client_peer(
+    address_manager: &mut AddressManager,
+    connections: &[ConnectionId],
+    client_peer: SocketAddr,
+    sender_potential_connections: &SenderPotential,
+    logger: LoggerSender,
+) {
+    let now = Utc::now().timestamp() as u32;
+    if connections
+        .iter()
+        .any(|connection| connection.address == client_peer)
+        || address_manager.is_attempt_pending(&client_peer, now, HANDSHAKE_WINDOW.as_secs() as u32)
+    {
+        return;
+    }
+
+    let _ = logger.log_connection(format!("Potential peer: {:?}", client_peer));
+    address_manager.add_address(client_peer, now);
+    address_manager.mark_attempt(&client_peer, now);
+
+    if sender_potential_connections
+        .send(ConnectionEvent::PotentialPeer(client_peer))
+        .is_err()
+    {
+        let _ = logger.log_connection("Could not send potential connection".to_string());
+    }
+}
+
+/// Creates a thread that periodically removes the connections that finished and requests new potential peers
+/// to keep the maximum amount of peers of the mode. When the address manager runs out of addresses, the dns seeders
+/// are asked again. As a client, the node it works with is dialed again when its connection is lost
+pub fn maintain_connections<N: Notifier + Send + 'static>(
+    mode_config: ModeConfig,
+    network: Network,
+    data: (MutArc<Broadcasting<TcpStream>>, MutArc<AddressManager>),
+    receiver_stop: Receiver<Stop>,
+    sender_potential_connections: SenderPotential,
+    notifier: N,
+    logger: LoggerSender,
+) -> JoinHandle<()> {
+    let (broadcasting, address_manager) = data;
+    let (dns_seeders, peer_count_max, client_peer) = match mode_config {
+        ModeConfig::Server(server_config) => (
+            server_config.get_dns_seeders(network),
+            server_config.peer_count_max,
+            None,
+        ),
+        ModeConfig::Spv(spv_config) => (
+            spv_config.get_dns_seeders(network),
+            spv_config.peer_count_max,
+            None,
+        ),
+        ModeConfig::Client(client_config) => (
+            Vec::new(),
+            0,
+            Some(SocketAddr::new(
+                IpAddr::V4(client_config.address),
+                client_config.get_port(network),
+            )),
+        ),
+    };
+
+    thread::spawn(move || {
+        let mut last_seeding = Instant::now();
+
+        while let Err(RecvTimeoutError::Timeout) = receiver_stop.recv_timeout(MAINTENANCE_INTERVAL)
+        {
+            let connections = match get_reference(&broadcasting) {
+                Ok(mut broadcasting_reference) => {
+                    for connection in broadcasting_reference.remove_finished_connections() {
+                        let _ = logger.log_connection(format!("Connection lost: {connection}"));
+                        notifier.notify(Notification::ConnectionLost(connection));
+                    }
+                    broadcasting_reference.get_connections()
+                }
+                Err(error) => {
+                    let _ = logger.log_connection(format!("Error: {:?}", error));
+                    continue;
+                }
+            };
+
+            if let Some(client_peer) = client_peer {
+                match get_reference(&address_manager) {
+                    Ok(mut address_manager) => request_client_peer(
+                        &mut address_manager,
+                        &connections,
+                        client_peer,
+                        &sender_potential_connections,
+                        logger.clone(),
+                    ),
+                    Err(error) => {
+                        let _ = logger.log_connection(format!("Error: {:?}", error));
+                    }
+                }
+                continue;
+            }
+
+            let missing = match get_reference(&address_manager) {
+                Ok(mut address_manager) => request_potential_peers(
+                    &mut address_manager,
+                    &connections,
+                    peer_count_max,
+                    &sender_potential_connections,
+                    logger.clone(),
+                ),
+                Err(error) => {
+                    let _ = logger.log_connection(format!("Error: {:?}", error));
+                    continue;
+                }
+            };
+
+            if missing == 0 || last_seeding.elapsed() < SEEDING_INTERVAL {
+                continue;
+            }
+            last_seeding = Instant::now();
+
+            let potential_peers = match get_potential_peers(&dns_seeders, logger.clone()) {
+                Ok(potential_peers) => potential_peers,
+                Err(error) => {
+                    let _ = logger.log_connection(format!(
+                        "Could not get peers from the dns seeder, with error: {:?}",
+                        error
+                    ));
+                    continue;
+                }
+            };
+
+            if let Ok(mut address_manager) = get_reference(&address_manager) {
+                let now = Utc::now().timestamp() as u32;
+                for potential_peer in potential_peers {
+                    address_manager.add_address(potential_peer, now);
+                }
+
+                request_potential_peers(
+                    &mut address_manager,
+                    &connections,
+                    peer_count_max,
+                    &sender_potential_connections,
+                    logger.clone(),
+                );
+            }
+        }
+    })
+}
+
+/// Adds the misbehavior to the score of the connection, banning its address if the score gets too high.
+/// Returns true if the address was banned and false otherwise
+pub fn punish_peer<N: Notifier>(
+    ban_list: &MutArc<BanList>,
+    connection: ConnectionId,
+    misbehavior: Misbehavior,
+    notifier: N,
+    logger: LoggerSender,
+) -> bool {
+    let _ = logger.log_connection(format!("Peer {connection} misbehaved: {misbehavior}"));
+
+    let mut ban_list = match get_reference(ban_list) {
+        Ok(ban_list) => ban_list,
+        Err(error) => {
+            let _ = logger.log_connection(format!("Error: {:?}", error));
+            return false;
+        }
+    };
+
+    let now = Utc::now().timestamp() as u32;
+    ban_list.remove_expired(now);
+    if !ban_list.add_misbehavior(connection, misbehavior, now) {
+        return false;
+    }
+
+    let _ = logger.log_connection(format!("Banning peer {connection}"));
+    notifier.notify(Notification::PeerBanned(connection.address, misbehavior));
+    true
+}
+
 /// Crates the thread to manega the potential connections to establish a connection via a handshake
 pub fn create_process_connection<N: Notifier + Send + 'static>(
     connection_config: ConnectionConfig,
     sender_confirm_connection: Sender<(TcpStream, ConnectionId)>,
+    ban_list: MutArc<BanList>,
     notifier: N,
     logger: LoggerSender,
 ) -> (JoinHandle<Result<(), ErrorNode>>, SenderPotential) {
@@ -70,6 +336,7 @@ pub fn create_process_connection<N: Notifier + Send + 'static>(
         connection_config,
         sender_confirm_connection,
         receiver_potential_connections,
+        ban_list,
         notifier,
         logger,
     );
@@ -79,116 +346,244 @@ pub fn create_process_connection<N: Notifier + Send + 'static>(
     (handle, sender_potential_connections)
 }
 
-/// Creates a thread to manage the confirmed connections and update the block chain if the connection is a peer
+/// Creates a thread to manage the confirmed connections and update the block chain if the connection is a peer.
+/// The peers that send invalid information while updating the block chain are punished. With the bloom filter of the
+/// SPV mode, the peers only send the blocks filtered with it, and the filters are not served to others
 pub fn update_from_connection<N: Notifier + Send + 'static>(
     receiver_confirm_connection: Receiver<(TcpStream, ConnectionId)>,
     sender_response: Sender<MessageResponse>,
-    data: (
-        MutArc<Broadcasting<TcpStream>>,
-        MutArc<BlockChain>,
-        MutArc<UTXOSet>,
+    data: ConnectionData,
+    peer_data: (MutArc<AddressManager>, MutArc<BanList>),
+    config: (
+        ConnectionConfig,
+        DownloadConfig,
+        SaveConfig,
+        Option<BloomFilter>,
     ),
-    config: (ConnectionConfig, DownloadConfig),
     notifier: N,
     logger: LoggerSender,
 ) -> JoinHandle<()> {
     let broadcasting = data.0;
     let block_chain = data.1;
     let utxo_set = data.2;
+    let transaction_history = data.4;
+    let filter_chain = data.3;
+    let filtered_blocks = config.3.is_some();
+    let (address_manager, ban_list) = peer_data;
 
-    let magic_numbers = config.0.magic_numbers;
+    let serves_bloom_filters = config
+        .0
+        .services
+        .elements
+        .contains(&SupportedServices::NodeBloom)
+        && !filtered_blocks;
+    let peer_config = (
+        config.0.magic_numbers,
+        config.0.ping_timeout,
+        serves_bloom_filters,
+        filtered_blocks,
+    );
 
     thread::spawn(move || {
-        for (stream, connection_id) in receiver_confirm_connection {
-            let (stream, connection_id) = match connection_id.connection_type {
-                ConnectionType::Peer => {
-                    match download::update_block_chain_with_peer(
-                        (stream, connection_id),
-                        block_chain.clone(),
-                        utxo_set.clone(),
-                        config.clone(),
-                        notifier.clone(),
-                        logger.clone(),
-                    ) {
-                        Ok(connection) => connection,
-                        Err(error) => {
-                            let _ = logger.log_connection(format!(
-                                "Error while updating the block chain: {:?}",
-                                error
-                            ));
-                            continue;
+        while let Ok(first_connection) = receiver_confirm_connection.recv() {
+            let connections = gather_connections(first_connection, &receiver_confirm_connection);
+
+            let (peers, mut ready_connections): (Vec<_>, Vec<_>) =
+                connections.into_iter().partition(|(_, connection_id)| {
+                    connection_id.connection_type == ConnectionType::Peer
+                });
+
+            if !peers.is_empty() {
+                match get_reference(&address_manager) {
+                    Ok(mut address_manager) => {
+                        for (_, connection_id) in peers.iter() {
+                            address_manager
+                                .mark_good(connection_id.address, Utc::now().timestamp() as u32);
                         }
                     }
+                    Err(error) => {
+                        let _ = logger.log_connection(format!("Error: {:?}", error));
+                    }
                 }
-                ConnectionType::Client => (stream, connection_id),
-            };
 
-            let mut broadcasting_reference = match get_reference(&broadcasting) {
-                Ok(broadcasting_reference) => broadcasting_reference,
-                Err(error) => {
-                    let _ = logger.log_connection(format!("Error: {:?}", error));
-                    continue;
+                match download::update_block_chain_with_peers(
+                    peers,
+                    block_chain.clone(),
+                    utxo_set.clone(),
+                    transaction_history.clone(),
+                    config.clone(),
+                    notifier.clone(),
+                    logger.clone(),
+                ) {
+                    Ok((finished_connections, failed_connections)) => {
+                        ready_connections.extend(finished_connections);
+                        for (connection_id, error) in failed_connections {
+                            match error {
+                                ErrorProcess::PeerMisbehaved(misbehavior) => {
+                                    punish_peer(
+                                        &ban_list,
+                                        connection_id,
+                                        misbehavior,
+                                        notifier.clone(),
+                                        logger.clone(),
+                                    );
+                                }
+                                error => {
+                                    let _ = logger.log_connection(format!(
+                                        "Error while updating the block chain with peer {connection_id}: {:?}",
+                                        error
+                                    ));
+                                }
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        let _ = logger.log_connection(format!(
+                            "Error while updating the block chain: {:?}",
+                            error
+                        ));
+                    }
                 }
-            };
-
-            if stream
-                .set_read_timeout(Some(Duration::from_secs(1)))
-                .is_err()
-            {
-                let _ = logger.log_connection("Could not set timeout".to_string());
-                continue;
-            };
+            }
 
-            broadcasting::add_peer_to_broadcasting(
-                &mut broadcasting_reference,
-                (stream, connection_id),
-                sender_response.clone(),
-                block_chain.clone(),
-                magic_numbers,
-                notifier.clone(),
-                logger.clone(),
-            );
+            for connection in ready_connections {
+                add_connection_to_broadcasting(
+                    &broadcasting,
+                    connection,
+                    sender_response.clone(),
+                    (utxo_set.clone(), block_chain.clone(), filter_chain.clone()),
+                    peer_config,
+                    notifier.clone(),
+                    logger.clone(),
+                );
+            }
         }
     })
 }
 
-/// Establish the connection with the peers and the clients
+/// Gathers the connections confirmed shortly after the given one, so the block chain can be
+/// downloaded from all of them at the same time
+fn gather_connections(
+    first_connection: (TcpStream, ConnectionId),
+    receiver_confirm_connection: &Receiver<(TcpStream, ConnectionId)>,
+) -> Vec<(TcpStream, ConnectionId)> {
+    let mut connections = vec![first_connection];
+    let deadline = Instant::now() + GATHERING_INTERVAL;
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match receiver_confirm_connection.recv_timeout(remaining) {
+            Ok(connection) => connections.push(connection),
+            Err(_) => break,
+        }
+    }
+
+    connections
+}
+
+/// Adds the connection to the broadcasting, so it starts to receive and send new information
+fn add_connection_to_broadcasting<N: Notifier + Send + 'static>(
+    broadcasting: &MutArc<Broadcasting<TcpStream>>,
+    connection: (TcpStream, ConnectionId),
+    sender_response: Sender<MessageResponse>,
+    chain_data: (
+        MutArc<UTXOSet>,
+        MutArc<BlockChain>,
+        Option<MutArc<FilterChain>>,
+    ),
+    peer_config: ([u8; 4], u64, bool, bool),
+    notifier: N,
+    logger: LoggerSender,
+) {
+    let mut broadcasting_reference = match get_reference(broadcasting) {
+        Ok(broadcasting_reference) => broadcasting_reference,
+        Err(error) => {
+            let _ = logger.log_connection(format!("Error: {:?}", error));
+            return;
+        }
+    };
+
+    if connection
+        .0
+        .set_read_timeout(Some(Duration::from_secs(1)))
+        .is_err()
+    {
+        let _ = logger.log_connection("Could not set timeout".to_string());
+        return;
+    };
+
+    broadcasting::add_peer_to_broadcasting(
+        &mut broadcasting_reference,
+        connection,
+        sender_response,
+        chain_data,
+        peer_config,
+        notifier,
+        logger,
+    );
+}
+
+/// Establish the connection with the peers and the clients. As a server or in the SPV mode the peers are taken
+/// from the address manager, after adding the ones given by the dns seeders of the network
 pub fn establish_connection_to_peers(
     mode_config: ModeConfig,
+    network: Network,
+    address_manager: &mut AddressManager,
     sender_potential_connections: Sender<ConnectionEvent>,
     logger: LoggerSender,
 ) -> Result<(), ErrorExecution> {
-    let potential_sockets = match mode_config {
-        ModeConfig::Server(server_config) => get_potential_peers(server_config, logger.clone())?,
+    let (dns_seeders, peer_count_max) = match mode_config {
+        ModeConfig::Server(server_config) => (
+            server_config.get_dns_seeders(network),
+            server_config.peer_count_max,
+        ),
+        ModeConfig::Spv(spv_config) => (
+            spv_config.get_dns_seeders(network),
+            spv_config.peer_count_max,
+        ),
         ModeConfig::Client(client_config) => {
-            vec![SocketAddr::new(
+            let client_peer = SocketAddr::new(
                 IpAddr::V4(client_config.address),
-                client_config.port,
-            )]
+                client_config.get_port(network),
+            );
+
+            request_client_peer(
+                address_manager,
+                &[],
+                client_peer,
+                &sender_potential_connections,
+                logger,
+            );
+            return Ok(());
         }
     };
 
-    for potential_socket in potential_sockets {
-        if sender_potential_connections
-            .send(ConnectionEvent::PotentialPeer(potential_socket))
-            .is_err()
-        {
-            let _ = logger.log_connection("Could not send potential connection".to_string());
-        }
+    let now = Utc::now().timestamp() as u32;
+    for potential_peer in get_potential_peers(&dns_seeders, logger.clone())? {
+        address_manager.add_address(potential_peer, now);
     }
 
+    request_potential_peers(
+        address_manager,
+        &[],
+        peer_count_max,
+        &sender_potential_connections,
+        logger,
+    );
+
     Ok(())
 }
 
 pub fn establish_connection_with_clients(
     server_config: ServerConfig,
+    network: Network,
     receiver_stop: Receiver<Stop>,
     sender_potential_connections: Sender<ConnectionEvent>,
     logger: LoggerSender,
 ) -> Option<JoinHandle<()>> {
     let mut listener = match TcpListener::bind(SocketAddr::new(
         IpAddr::V4(server_config.address),
-        server_config.own_port,
+        server_config.get_own_port(network),
     )) {
         Ok(listener) => listener,
         Err(_) => {
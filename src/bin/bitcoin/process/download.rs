@@ -1,44 +1,64 @@
 use super::{
     error_process::ErrorProcess,
     reference::{get_reference, MutArc},
+    sync_progress::SyncProgress,
 };
 
 use cargosos_bitcoin::{
     block_structure::{block::Block, block_chain::BlockChain, hash::HashType, utxo_set::UTXOSet},
-    configurations::{connection_config::ConnectionConfig, download_config::DownloadConfig},
+    configurations::{
+        connection_config::ConnectionConfig, download_config::DownloadConfig,
+        save_config::SaveConfig,
+    },
     connections::ibd_methods::IBDMethod,
     logs::logger_sender::LoggerSender,
     node_structure::{
-        block_download::BlockDownload, connection_id::ConnectionId, error_node::ErrorNode,
+        block_download::BlockDownload, bloom_filter::BloomFilter, connection_id::ConnectionId,
+        download_scheduler::DownloadScheduler, error_node::ErrorNode,
         initial_headers_download::InitialHeaderDownload,
+        merkle_block_download::MerkleBlockDownload, misbehavior::Misbehavior,
     },
     notifications::{notification::Notification, notifier::Notifier},
+    wallet_structure::transaction_history::TransactionHistory,
 };
 
 use std::{
+    collections::VecDeque,
     fmt::Debug,
     io::{Read, Write},
+    sync::{Arc, Mutex},
     thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
 
+const DOWNLOAD_WINDOW: usize = 16;
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+const LOADING_INTERVAL: Duration = Duration::from_millis(100);
+const FILTERED_BLOCKS_CHUNK: usize = 500;
+
+type PeerDownloadHandle<RW> = JoinHandle<(Result<(), ErrorNode>, (RW, ConnectionId))>;
+
+/// The connections that finished the download and the ones that failed with their error
+pub type DownloadResult<RW> = (Vec<(RW, ConnectionId)>, Vec<(ConnectionId, ErrorProcess)>);
+
 /// Given the peers connection, updates the blockchain with the new blocks of the respected peers.
-/// The approch is to get the headers first and then the blocks.
+/// The approch is to get the headers first and then the blocks, downloading them from all the peers at the same time.
+/// The progress is saved while the sync goes on, so it can be resumed from the last point. With a bloom filter,
+/// only the merkle blocks with the transactions matching it are downloaded instead of the full blocks.
+/// Returns the connections that finished the download and the ones that failed with their error
 ///
 /// ### Error
-///  * `ErrorMessage::InSerialization`: It will appear when the serialization of the message fails or the SHA(SHA(header)) fails
-///  * `ErrorNode::NodeNotResponding`: It will appear when
-///  * `ErrorNode::WhileValidating`: It will appear when
-///  * `ErrorBlock::CouldNotUpdate`: It will appear when the block is not in the blockchain.
 ///  * `ErrorProcess::FailThread`: It will appear when the thread fails
-fn headers_first<N: Notifier + 'static, RW: Read + Write + Send + Debug + 'static>(
-    connection: (RW, ConnectionId),
-    block_chain: &mut BlockChain,
-    utxo_set: &mut UTXOSet,
-    connection_config: ConnectionConfig,
-    download_config: DownloadConfig,
+fn headers_first<N: Notifier + Send + 'static, RW: Read + Write + Send + Debug + 'static>(
+    connections: Vec<(RW, ConnectionId)>,
+    chain_data: (&mut BlockChain, &mut UTXOSet, &mut TransactionHistory),
+    config: (ConnectionConfig, DownloadConfig, Option<BloomFilter>),
+    sync_progress: &mut SyncProgress,
     notifier: N,
     logger: LoggerSender,
-) -> Result<(RW, ConnectionId), ErrorProcess> {
+) -> Result<DownloadResult<RW>, ErrorProcess> {
+    let (block_chain, utxo_set, transaction_history) = chain_data;
+    let (connection_config, download_config, bloom_filter) = config;
     let header_download = InitialHeaderDownload::new(
         connection_config.p2p_protocol_version,
         connection_config.magic_numbers,
@@ -49,42 +69,198 @@ fn headers_first<N: Notifier + 'static, RW: Read + Write + Send + Debug + 'stati
 
     let _ = logger.log_connection("Getting initial download headers first".to_string());
 
-    let (mut peer_stream, id) = connection;
-
-    let _ = logger.log_connection(format!("Connecting to peer: {}", id));
-
-    get_peer_header(
-        &mut peer_stream,
-        &header_download,
-        block_chain,
-        notifier.clone(),
-        &logger,
-    )?;
+    let mut synced_connections: Vec<(RW, ConnectionId)> = Vec::new();
+    let mut failed_connections: Vec<(ConnectionId, ErrorProcess)> = Vec::new();
+    for (mut peer_stream, id) in connections {
+        let _ = logger.log_connection(format!("Connecting to peer: {}", id));
+
+        match get_peer_header(
+            &mut peer_stream,
+            &header_download,
+            block_chain,
+            sync_progress,
+            notifier.clone(),
+            &logger,
+        ) {
+            Ok(()) => synced_connections.push((peer_stream, id)),
+            Err(error) => failed_connections.push((id, error)),
+        }
+    }
 
-    let mut list_of_blocks: Vec<Block> = Vec::new();
+    let mut hashes: Vec<HashType> = Vec::new();
     for block in block_chain.get_blocks_after_timestamp(download_config.timestamp) {
-        if block.transactions.len() as u64 == 0 {
-            list_of_blocks.push(block);
+        if block.transactions.len() as u64 != 0 {
+            continue;
+        }
+
+        if let Ok(header_hash) = block.header.get_hash256d() {
+            if !block_chain.is_pruned(&header_hash) {
+                hashes.push(header_hash);
+            }
         }
     }
 
-    let peer_download_handle = get_blocks(
-        peer_stream,
-        block_download,
-        list_of_blocks,
-        notifier.clone(),
-        logger.clone(),
-    );
+    if let Some(bloom_filter) = bloom_filter {
+        let merkle_block_download =
+            MerkleBlockDownload::new(connection_config.magic_numbers, logger.clone());
 
-    let stream = updating_block_chain(
-        block_chain,
-        utxo_set,
-        peer_download_handle,
+        let (finished_connections, failed_filtered) = get_filtered_blocks(
+            synced_connections,
+            hashes,
+            (block_chain, utxo_set, transaction_history),
+            (merkle_block_download, bloom_filter),
+            sync_progress,
+            notifier,
+            logger,
+        );
+        failed_connections.extend(failed_filtered);
+        sync_progress.save(block_chain);
+
+        return Ok((finished_connections, failed_connections));
+    }
+
+    let mut scheduler = DownloadScheduler::new(hashes, DOWNLOAD_WINDOW, STALL_TIMEOUT);
+    for (_, id) in synced_connections.iter() {
+        scheduler.add_peer(*id);
+    }
+    let scheduler = Arc::new(Mutex::new(scheduler));
+
+    let peer_download_handles: Vec<PeerDownloadHandle<RW>> = synced_connections
+        .into_iter()
+        .map(|connection| {
+            get_blocks(
+                connection,
+                block_download.clone(),
+                scheduler.clone(),
+                logger.clone(),
+            )
+        })
+        .collect();
+
+    updating_block_chain(
+        (block_chain, utxo_set, transaction_history),
+        &scheduler,
+        sync_progress,
         notifier,
         logger.clone(),
     )?;
+    sync_progress.save(block_chain);
+
+    let mut finished_connections: Vec<(RW, ConnectionId)> = Vec::new();
+    for peer_download_handle in peer_download_handles {
+        let (result, (peer_stream, id)) = match peer_download_handle.join() {
+            Ok(download) => download,
+            Err(_) => return Err(ErrorProcess::FailThread),
+        };
 
-    Ok((stream, id))
+        match result {
+            Ok(()) => finished_connections.push((peer_stream, id)),
+            Err(error) => failed_connections.push((id, get_download_error(error))),
+        }
+    }
+
+    Ok((finished_connections, failed_connections))
+}
+
+/// Returns the error of a peer that failed while downloading blocks, punishing the invalid or malformed ones
+fn get_download_error(error: ErrorNode) -> ErrorProcess {
+    match error {
+        ErrorNode::WhileValidating(_) => ErrorProcess::PeerMisbehaved(Misbehavior::InvalidBlock),
+        ErrorNode::WhileDeserializing(_) => {
+            ErrorProcess::PeerMisbehaved(Misbehavior::MalformedMessage)
+        }
+        error => ErrorProcess::ErrorFromPeer(format!("Cannot get blocks, we get {:?}", error)),
+    }
+}
+
+/// It loads the bloom filter in the peers and downloads the merkle blocks of the given hashes from them, in chunks
+/// and one peer after the other. The blocks only have the matched transactions, and are used to update the block chain.
+/// Returns the connections that finished the download and the ones that failed with their error
+fn get_filtered_blocks<N: Notifier, RW: Read + Write>(
+    connections: Vec<(RW, ConnectionId)>,
+    hashes: Vec<HashType>,
+    chain_data: (&mut BlockChain, &mut UTXOSet, &mut TransactionHistory),
+    download: (MerkleBlockDownload, BloomFilter),
+    sync_progress: &mut SyncProgress,
+    notifier: N,
+    logger: LoggerSender,
+) -> DownloadResult<RW> {
+    let (block_chain, utxo_set, transaction_history) = chain_data;
+    let (merkle_block_download, bloom_filter) = download;
+
+    let mut peers: VecDeque<(RW, ConnectionId)> = VecDeque::new();
+    let mut failed_connections: Vec<(ConnectionId, ErrorProcess)> = Vec::new();
+    for (mut peer_stream, id) in connections {
+        match merkle_block_download.load_filter(&mut peer_stream, bloom_filter.clone()) {
+            Ok(()) => peers.push_back((peer_stream, id)),
+            Err(error) => failed_connections.push((
+                id,
+                ErrorProcess::ErrorFromPeer(format!("Cannot load the filter, we get {:?}", error)),
+            )),
+        }
+    }
+
+    let total = hashes.len() as u32;
+    let mut loaded_blocks: u32 = 0;
+
+    'chunks: for chunk in hashes.chunks(FILTERED_BLOCKS_CHUNK) {
+        let blocks = loop {
+            let (mut peer_stream, id) = match peers.pop_front() {
+                Some(peer) => peer,
+                None => {
+                    let _ = logger.log_connection(format!(
+                        "No peers left to download from, loaded [{loaded_blocks}] of [{total}] merkle blocks"
+                    ));
+                    break 'chunks;
+                }
+            };
+
+            match merkle_block_download.get_filtered_blocks(&mut peer_stream, chunk.to_vec()) {
+                Ok(blocks) => {
+                    peers.push_back((peer_stream, id));
+                    break blocks;
+                }
+                Err(error) => {
+                    let _ = logger.log_connection(format!(
+                        "Cannot get merkle blocks from peer {id}, we get {:?}",
+                        error
+                    ));
+                    failed_connections.push((id, get_download_error(error)));
+                }
+            }
+        };
+
+        for block in blocks {
+            loaded_blocks += 1;
+            if block_chain.update_block(block.clone()).is_err() {
+                continue;
+            }
+
+            utxo_set.update_utxo_with_block(&block);
+            update_transaction_history(transaction_history, block_chain, &block);
+        }
+
+        notifier.notify(Notification::ProgressDownloadingBlocks(
+            loaded_blocks,
+            total,
+        ));
+        notifier.notify(Notification::ProgressUpdatingBlockchain(
+            loaded_blocks,
+            total,
+        ));
+        prune_block_chain(block_chain, &logger);
+        sync_progress.block_chain_updated(block_chain);
+    }
+
+    if loaded_blocks == 0 {
+        notifier.notify(Notification::ProgressUpdatingBlockchain(1, 1));
+    }
+
+    let _ = logger.log_connection(format!(
+        "Loading [{loaded_blocks}] merkle blocks to blockchain"
+    ));
+
+    (peers.into_iter().collect(), failed_connections)
 }
 
 /// It updates the blockchain with a specific peer headers until it reach the last header
@@ -93,10 +269,12 @@ fn headers_first<N: Notifier + 'static, RW: Read + Write + Send + Debug + 'stati
 ///  * `ErrorMessage::InSerialization`: It will appear when the serialization of the message fails or the SHA(SHA(header)) fails
 ///  * `ErrorNode::NodeNotResponding`: It will appear when no message is received from the node
 ///  * `ErrorNode::WhileValidating`: It will appear when a given header does not pass the proof of work to be added to the blockchain
+///  * `ErrorProcess::PeerMisbehaved`: It will appear when the peer sends invalid headers or a malformed message
 fn get_peer_header<N: Notifier, RW: Read + Write>(
     peer_stream: &mut RW,
     header_download: &InitialHeaderDownload,
     block_chain: &mut BlockChain,
+    sync_progress: &mut SyncProgress,
     notifier: N,
     logger: &LoggerSender,
 ) -> Result<(), ErrorProcess> {
@@ -108,6 +286,12 @@ fn get_peer_header<N: Notifier, RW: Read + Write>(
             }
             Ok(count) => count,
             Err(ErrorNode::WhileSerializing(_)) => return Err(ErrorProcess::ErrorWriting),
+            Err(ErrorNode::WhileValidating(_)) => {
+                return Err(ErrorProcess::PeerMisbehaved(Misbehavior::InvalidHeaders))
+            }
+            Err(ErrorNode::WhileDeserializing(_)) => {
+                return Err(ErrorProcess::PeerMisbehaved(Misbehavior::MalformedMessage))
+            }
             Err(_) => {
                 return Err(ErrorProcess::ErrorFromPeer(
                     "Proof of work failed".to_string(),
@@ -121,75 +305,82 @@ fn get_peer_header<N: Notifier, RW: Read + Write>(
         if header_count == 0 {
             break;
         }
+
+        sync_progress.block_chain_updated(block_chain);
     }
 
     Ok(())
 }
 
-/// It gets the blocks from a specific peer in a thread
-fn get_blocks<N: Notifier + 'static, RW: Read + Write + Send + 'static>(
-    mut peer_stream: RW,
+/// It gets the blocks given by the scheduler from a specific peer in a thread
+fn get_blocks<RW: Read + Write + Send + 'static>(
+    connection: (RW, ConnectionId),
     block_download: BlockDownload,
-    list_of_blocks: Vec<Block>,
-    notifier: N,
+    scheduler: MutArc<DownloadScheduler>,
     logger: LoggerSender,
-) -> JoinHandle<(Vec<Block>, RW)> {
+) -> PeerDownloadHandle<RW> {
     thread::spawn(move || {
-        let mut headers: Vec<HashType> = Vec::new();
-
-        for block in list_of_blocks {
-            let header_hash = match block.header.get_hash256d() {
-                Ok(header_hash) => header_hash,
-                Err(_) => continue,
-            };
-
-            headers.push(header_hash);
+        let (mut peer_stream, id) = connection;
+
+        let result = block_download.get_data_from_scheduler(&mut peer_stream, id, &scheduler);
+        if let Err(error) = &result {
+            let _ = logger.log_connection(format!(
+                "Cannot get blocks from peer {id}, we get {:?}",
+                error
+            ));
         }
 
-        match block_download.get_data(&mut peer_stream, headers, notifier) {
-            Ok(blocks) => (blocks, peer_stream),
-            Err(error) => {
-                let _ = logger.log_connection(format!("Cannot get block, we get {:?}", error));
-                (vec![], peer_stream)
-            }
-        }
+        (result, (peer_stream, id))
     })
 }
 
-/// Updates the blockchain with the IBD with the specific peer
+/// Updates the blockchain with the IBD, downloading the blocks from all the given peers. With a bloom filter
+/// only the transactions matching it are downloaded, as done in the SPV mode. The transaction history of the
+/// wallet is updated with the blocks as they are added.
+/// Returns the connections that finished the download and the ones that failed with their error
 ///
 /// ### Error
-///  * `ErrorMessage::InSerialization`: It will appear when the serialization of the message fails or the SHA(SHA(header)) fails
-///  * `ErrorNode::NodeNotResponding`: It will appear when
-///  * `ErrorNode::WhileValidating`: It will appear when
-///  * `ErrorBlock::CouldNotUpdate`: It will appear when the block is not in the blockchain.
 ///  * `ErrorProcess::FailThread`: It will appear when the thread fails
-pub fn update_block_chain_with_peer<
-    N: Notifier + 'static,
+///  * `ErrorProcess::CannotGetInner`: It will appear when the block chain, the UTXO set or the transaction history could not be locked
+pub fn update_block_chain_with_peers<
+    N: Notifier + Send + 'static,
     RW: Read + Write + Send + Debug + 'static,
 >(
-    connection: (RW, ConnectionId),
+    connections: Vec<(RW, ConnectionId)>,
     block_chain: MutArc<BlockChain>,
     utxo_set: MutArc<UTXOSet>,
-    config: (ConnectionConfig, DownloadConfig),
+    transaction_history: MutArc<TransactionHistory>,
+    config: (
+        ConnectionConfig,
+        DownloadConfig,
+        SaveConfig,
+        Option<BloomFilter>,
+    ),
     notifier: N,
     logger: LoggerSender,
-) -> Result<(RW, ConnectionId), ErrorProcess> {
-    let connection_config = config.0;
-    let download_config = config.1;
+) -> Result<DownloadResult<RW>, ErrorProcess> {
+    let (connection_config, download_config, save_config, bloom_filter) = config;
+    let mut sync_progress = SyncProgress::new(save_config.write_block_chain, logger.clone());
 
     let mut block_chain_reference = get_reference(&block_chain)?;
     let mut utxo_set_reference = get_reference(&utxo_set)?;
+    let mut transaction_history_reference = get_reference(&transaction_history)?;
 
-    let _ = logger.log_connection("Getting block chain".to_string());
+    let _ = logger.log_connection(format!(
+        "Getting block chain from {} peers",
+        connections.len()
+    ));
 
     Ok(match connection_config.ibd_method {
         IBDMethod::HeaderFirst => headers_first(
-            connection,
-            &mut block_chain_reference,
-            &mut utxo_set_reference,
-            connection_config,
-            download_config,
+            connections,
+            (
+                &mut block_chain_reference,
+                &mut utxo_set_reference,
+                &mut transaction_history_reference,
+            ),
+            (connection_config, download_config, bloom_filter),
+            &mut sync_progress,
             notifier,
             logger,
         )?,
@@ -207,60 +398,127 @@ pub fn get_utxo_set(block_chain: &BlockChain, logger: LoggerSender) -> UTXOSet {
     utxo_set
 }
 
-/// Updates the blockchain of the thread of a peer
+/// Updates the blockchain with the blocks downloaded by the peers, in order, while the download goes on.
+/// It stops when all the blocks were loaded or there are no more peers to download from
 ///
 /// ### Error
-///  * `ErrorBlock::CouldNotUpdate`: It will appear when the block is not in the blockchain.
-///  * `ErrorExecution::FailThread`: It will appear when the thread fails
-fn updating_block_chain<N: Notifier, RW: Read + Write + Send>(
-    block_chain: &mut BlockChain,
-    utxo_set: &mut UTXOSet,
-    peer_download_handle: JoinHandle<(Vec<Block>, RW)>,
+///  * `ErrorProcess::CannotGetInner`: It will appear when the scheduler could not be locked
+fn updating_block_chain<N: Notifier>(
+    chain_data: (&mut BlockChain, &mut UTXOSet, &mut TransactionHistory),
+    scheduler: &MutArc<DownloadScheduler>,
+    sync_progress: &mut SyncProgress,
     notifier: N,
     logger: LoggerSender,
-) -> Result<RW, ErrorProcess> {
-    let _ = logger.log_connection("Finish downloading, loading to blockchain".to_string());
-    match peer_download_handle.join() {
-        Ok((blocks, peer_stream)) => {
-            let total_blocks = blocks.len() as u32;
-            let _ = logger.log_connection(format!("Loading {total_blocks} blocks to blockchain"));
-
-            for (i, block) in blocks.iter().enumerate() {
-                if block_chain.update_block(block.clone()).is_err() {
-                    continue;
-                }
+) -> Result<(), ErrorProcess> {
+    let (block_chain, utxo_set, transaction_history) = chain_data;
+    let mut loaded_blocks: u32 = 0;
+    let mut last_received: usize = 0;
 
-                utxo_set.update_utxo_with_block(block);
+    loop {
+        let (blocks, (received, total), finished, without_peers) = {
+            let mut scheduler = get_reference(scheduler)?;
 
-                if i % 50 == 0 {
-                    let _ = logger.log_connection(format!("Loading [{i}] blocks to blockchain",));
-                    notifier.notify(Notification::ProgressUpdatingBlockchain(
-                        i as u32,
-                        total_blocks,
-                    ));
-                }
+            for peer in scheduler.reassign_stalled(Instant::now()) {
+                let _ = logger.log_connection(format!("Peer {peer} stalled the download"));
+            }
+
+            (
+                scheduler.take_ready_blocks(),
+                scheduler.get_progress(),
+                scheduler.is_finished(),
+                scheduler.peer_count() == 0,
+            )
+        };
+
+        if received != last_received {
+            last_received = received;
+            notifier.notify(Notification::ProgressDownloadingBlocks(
+                received as u32,
+                total as u32,
+            ));
+        }
+
+        let updated = !blocks.is_empty();
+        for block in blocks {
+            loaded_blocks += 1;
+            if block_chain.update_block(block.clone()).is_err() {
+                continue;
             }
 
-            if total_blocks == 0 {
-                notifier.notify(Notification::ProgressUpdatingBlockchain(1, 1));
-            } else {
+            utxo_set.update_utxo_with_block(&block);
+            update_transaction_history(transaction_history, block_chain, &block);
+
+            if loaded_blocks.is_multiple_of(50) {
+                let _ = logger
+                    .log_connection(format!("Loading [{loaded_blocks}] blocks to blockchain"));
                 notifier.notify(Notification::ProgressUpdatingBlockchain(
-                    total_blocks,
-                    total_blocks,
+                    loaded_blocks,
+                    total as u32,
                 ));
             }
+        }
+
+        if updated {
+            prune_block_chain(block_chain, &logger);
+            sync_progress.block_chain_updated(block_chain);
+        }
+
+        if finished {
+            break;
+        }
+
+        if without_peers {
+            let _ = logger.log_connection(format!(
+                "No peers left to download from, loaded [{loaded_blocks}] of [{total}] blocks"
+            ));
+            break;
+        }
+
+        thread::sleep(LOADING_INTERVAL);
+    }
 
-            let _ =
-                logger.log_connection(format!("Loading [{}] blocks to blockchain", blocks.len()));
+    if loaded_blocks == 0 {
+        notifier.notify(Notification::ProgressUpdatingBlockchain(1, 1));
+    } else {
+        notifier.notify(Notification::ProgressUpdatingBlockchain(
+            loaded_blocks,
+            loaded_blocks,
+        ));
+    }
 
-            Ok(peer_stream)
+    let _ = logger.log_connection(format!("Loading [{loaded_blocks}] blocks to blockchain"));
+
+    Ok(())
+}
+
+/// Records in the transaction history the transactions of the wallet in a block added to the block chain
+pub fn update_transaction_history(
+    transaction_history: &mut TransactionHistory,
+    block_chain: &BlockChain,
+    block: &Block,
+) {
+    if let Ok(header_hash) = block.header.get_hash256d() {
+        if let Some(height) = block_chain.get_height_of_block(&header_hash) {
+            transaction_history.update_with_block(block, height);
+        }
+    }
+}
+
+/// Prunes the blocks already loaded to the UTXO set that are deeper than the prune depth of the block chain
+pub fn prune_block_chain(block_chain: &mut BlockChain, logger: &LoggerSender) {
+    match block_chain.prune() {
+        Ok(0) => {}
+        Ok(pruned_count) => {
+            let _ = logger.log_connection(format!("Pruned [{pruned_count}] blocks"));
+        }
+        Err(error) => {
+            let _ = logger.log_connection(format!("Could not prune the blocks: {:?}", error));
         }
-        _ => Err(ErrorProcess::FailThread),
     }
 }
 
 /// Given the peers connection, updates the blockchain with the new blocks of the respected peers.
 /// The approch is to get the entire block.
-fn blocks_first<RW: Read + Write + Send>() -> (RW, ConnectionId) {
+fn blocks_first<RW: Read + Write + Send>() -> DownloadResult<RW> {
     todo!()
 }
@@ -1,4 +1,7 @@
-use cargosos_bitcoin::serialization::error_serialization::ErrorSerialization;
+use cargosos_bitcoin::{
+    node_structure::misbehavior::Misbehavior,
+    serialization::error_serialization::ErrorSerialization,
+};
 
 use std::convert::From;
 
@@ -23,6 +26,9 @@ pub enum ErrorProcess {
     /// It will appear when a conextion with a peer fails
     ErrorFromPeer(String),
 
+    /// It will appear when a peer sends invalid or malformed information
+    PeerMisbehaved(Misbehavior),
+
     /// It will appear when can't create the default value
     CannotCreateDefault,
 
@@ -1,11 +1,14 @@
 use super::error_process::ErrorProcess;
 
 use cargosos_bitcoin::{
-    block_structure::block_chain::BlockChain,
+    block_structure::{block_chain::BlockChain, filter_chain::FilterChain},
     configurations::{save_config::SaveConfig, try_default::TryDefault},
     logs::logger_sender::LoggerSender,
+    node_structure::{address_manager::AddressManager, ban_list::BanList},
     serialization::deserializable_internal_order::DeserializableInternalOrder,
-    wallet_structure::wallet::Wallet,
+    wallet_structure::{
+        address_book::AddressBook, transaction_history::TransactionHistory, wallet::Wallet,
+    },
 };
 
 use std::{
@@ -20,11 +23,22 @@ type Handle<T> = Option<JoinHandle<T>>;
 
 const BLOCKCHAIN_FILE: &str = "Blockchain";
 const WALLET_FILE: &str = "Wallet";
+const ADDRESS_BOOK_FILE: &str = "Address book";
+const PEER_ADDRESSES_FILE: &str = "Peer addresses";
+const BAN_LIST_FILE: &str = "Ban list";
+const TRANSACTION_HISTORY_FILE: &str = "Transaction history";
+const FILTER_CHAIN_FILE: &str = "Filter chain";
 
 /// Represents the elements to load from files
 pub struct LoadSystem {
     block_chain: Handle<Result<BlockChain, ErrorProcess>>,
     wallet: Handle<Result<Wallet, ErrorProcess>>,
+    address_book: Handle<Result<AddressBook, ErrorProcess>>,
+    address_manager: Handle<Result<AddressManager, ErrorProcess>>,
+    ban_list: Handle<Result<BanList, ErrorProcess>>,
+    transaction_history: Handle<Result<TransactionHistory, ErrorProcess>>,
+    filter_chain_path: Option<Option<String>>,
+    logger: LoggerSender,
 }
 
 impl LoadSystem {
@@ -38,8 +52,30 @@ impl LoadSystem {
             wallet: Some(Self::load_value(
                 WALLET_FILE.to_string(),
                 save_config.read_wallet,
-                logger,
+                logger.clone(),
+            )),
+            address_book: Some(Self::load_value(
+                ADDRESS_BOOK_FILE.to_string(),
+                save_config.read_address_book,
+                logger.clone(),
+            )),
+            address_manager: Some(Self::load_value(
+                PEER_ADDRESSES_FILE.to_string(),
+                save_config.read_peer_addresses,
+                logger.clone(),
             )),
+            ban_list: Some(Self::load_value(
+                BAN_LIST_FILE.to_string(),
+                save_config.read_ban_list,
+                logger.clone(),
+            )),
+            transaction_history: Some(Self::load_value(
+                TRANSACTION_HISTORY_FILE.to_string(),
+                save_config.read_transaction_history,
+                logger.clone(),
+            )),
+            filter_chain_path: Some(save_config.read_filter_chain),
+            logger,
         }
     }
 
@@ -83,6 +119,108 @@ impl LoadSystem {
         Err(ErrorProcess::AlreadyLoaded)
     }
 
+    /// Get the address book from a file, if already loaded it will return the value immediately.
+    /// In the case of the file not existing, it will return the default value.
+    ///
+    /// ### Error
+    ///  * `ErrorProcess:FailThread`: It will appear when a thread panics and fails
+    ///  * `ErrorProcess:CannotCreateDefault`: It will appear when can't create the default value
+    ///  * `ErrorProcess:AlreadyLoaded`: It will appear when try to get a value that is already loaded
+    pub fn get_address_book(&mut self) -> Result<AddressBook, ErrorProcess> {
+        let address_book_handle = self.address_book.take();
+
+        if let Some(address_book_handle) = address_book_handle {
+            return match address_book_handle.join() {
+                Ok(address_book) => address_book,
+                _ => Err(ErrorProcess::FailThread),
+            };
+        }
+
+        Err(ErrorProcess::AlreadyLoaded)
+    }
+
+    /// Get the addresses of the potential peers from a file, if already loaded it will return the value immediately.
+    /// In the case of the file not existing, it will return the default value.
+    ///
+    /// ### Error
+    ///  * `ErrorProcess:FailThread`: It will appear when a thread panics and fails
+    ///  * `ErrorProcess:CannotCreateDefault`: It will appear when can't create the default value
+    ///  * `ErrorProcess:AlreadyLoaded`: It will appear when try to get a value that is already loaded
+    pub fn get_address_manager(&mut self) -> Result<AddressManager, ErrorProcess> {
+        let address_manager_handle = self.address_manager.take();
+
+        if let Some(address_manager_handle) = address_manager_handle {
+            return match address_manager_handle.join() {
+                Ok(address_manager) => address_manager,
+                _ => Err(ErrorProcess::FailThread),
+            };
+        }
+
+        Err(ErrorProcess::AlreadyLoaded)
+    }
+
+    /// Get the banned addresses from a file, if already loaded it will return the value immediately.
+    /// In the case of the file not existing, it will return the default value.
+    ///
+    /// ### Error
+    ///  * `ErrorProcess:FailThread`: It will appear when a thread panics and fails
+    ///  * `ErrorProcess:CannotCreateDefault`: It will appear when can't create the default value
+    ///  * `ErrorProcess:AlreadyLoaded`: It will appear when try to get a value that is already loaded
+    pub fn get_ban_list(&mut self) -> Result<BanList, ErrorProcess> {
+        let ban_list_handle = self.ban_list.take();
+
+        if let Some(ban_list_handle) = ban_list_handle {
+            return match ban_list_handle.join() {
+                Ok(ban_list) => ban_list,
+                _ => Err(ErrorProcess::FailThread),
+            };
+        }
+
+        Err(ErrorProcess::AlreadyLoaded)
+    }
+
+    /// Get the transaction history of the wallet from a file, if already loaded it will return the value immediately.
+    /// In the case of the file not existing, it will return the default value.
+    ///
+    /// ### Error
+    ///  * `ErrorProcess:FailThread`: It will appear when a thread panics and fails
+    ///  * `ErrorProcess:CannotCreateDefault`: It will appear when can't create the default value
+    ///  * `ErrorProcess:AlreadyLoaded`: It will appear when try to get a value that is already loaded
+    pub fn get_transaction_history(&mut self) -> Result<TransactionHistory, ErrorProcess> {
+        let transaction_history_handle = self.transaction_history.take();
+
+        if let Some(transaction_history_handle) = transaction_history_handle {
+            return match transaction_history_handle.join() {
+                Ok(transaction_history) => transaction_history,
+                _ => Err(ErrorProcess::FailThread),
+            };
+        }
+
+        Err(ErrorProcess::AlreadyLoaded)
+    }
+
+    /// Get the compact filters of the blocks from a file. Unlike the other values, they are only read when asked for,
+    /// because they are only needed to serve them to the peers.
+    /// In the case of the file not existing, it will return the default value.
+    ///
+    /// ### Error
+    ///  * `ErrorProcess:FailThread`: It will appear when a thread panics and fails
+    ///  * `ErrorProcess:CannotCreateDefault`: It will appear when can't create the default value
+    ///  * `ErrorProcess:AlreadyLoaded`: It will appear when try to get a value that is already loaded
+    pub fn get_filter_chain(&mut self) -> Result<FilterChain, ErrorProcess> {
+        if let Some(path) = self.filter_chain_path.take() {
+            let filter_chain_handle =
+                Self::load_value(FILTER_CHAIN_FILE.to_string(), path, self.logger.clone());
+
+            return match filter_chain_handle.join() {
+                Ok(filter_chain) => filter_chain,
+                _ => Err(ErrorProcess::FailThread),
+            };
+        }
+
+        Err(ErrorProcess::AlreadyLoaded)
+    }
+
     /// Creates a thread to load a deserializable from a file, if the file does not exist or fail to read it will return the default value.
     ///
     /// ### Error
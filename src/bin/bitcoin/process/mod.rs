@@ -3,6 +3,7 @@ pub mod backend;
 pub mod broadcasting;
 pub mod connection;
 pub mod download;
+pub mod sync_progress;
 pub mod transaction;
 
 pub mod configuration;
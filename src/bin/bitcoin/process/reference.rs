@@ -29,3 +29,16 @@ pub fn get_reference<T>(reference: &MutArc<T>) -> Result<MutexGuard<'_, T>, Erro
         Err(_) => Err(ErrorProcess::CannotUnwrapArc),
     }
 }
+
+/// Get a mutable guard to use the value inside the Arc<Mutex<T>>, if there is one
+///
+/// ### Error
+///  * `ErrorUI::CannotUnwrapArc`: It will appear when we try to unwrap an Arc
+pub fn get_optional_reference<T>(
+    reference: &Option<MutArc<T>>,
+) -> Result<Option<MutexGuard<'_, T>>, ErrorProcess> {
+    match reference {
+        Some(reference) => Ok(Some(get_reference(reference)?)),
+        None => Ok(None),
+    }
+}
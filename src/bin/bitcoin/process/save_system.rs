@@ -1,34 +1,64 @@
 use crate::{error_execution::ErrorExecution, error_initialization::ErrorInitialization};
 
 use cargosos_bitcoin::{
-    block_structure::block_chain::BlockChain, configurations::save_config::SaveConfig,
+    block_structure::{block_chain::BlockChain, filter_chain::FilterChain},
+    configurations::save_config::SaveConfig,
     logs::logger_sender::LoggerSender,
+    node_structure::{address_manager::AddressManager, ban_list::BanList},
     serialization::serializable_internal_order::SerializableInternalOrder,
-    wallet_structure::wallet::Wallet,
+    wallet_structure::{
+        address_book::AddressBook, transaction_history::TransactionHistory, wallet::Wallet,
+    },
 };
 
 use std::fs::OpenOptions;
 
 const BLOCKCHAIN_FILE: &str = "Blockchain";
 const WALLET_FILE: &str = "Wallet";
+const ADDRESS_BOOK_FILE: &str = "Address book";
+const PEER_ADDRESSES_FILE: &str = "Peer addresses";
+const BAN_LIST_FILE: &str = "Ban list";
+const TRANSACTION_HISTORY_FILE: &str = "Transaction history";
+const FILTER_CHAIN_FILE: &str = "Filter chain";
 
 /// Represents the elements to save to files
 pub struct SaveSystem {
     block_chain: BlockChain,
+    filter_chain: Option<FilterChain>,
     wallet: Wallet,
+    address_book: AddressBook,
+    address_manager: AddressManager,
+    ban_list: BanList,
+    transaction_history: TransactionHistory,
     logger: LoggerSender,
 }
 
 impl SaveSystem {
-    pub fn new(block_chain: BlockChain, wallet: Wallet, logger: LoggerSender) -> SaveSystem {
+    pub fn new(
+        chains: (BlockChain, Option<FilterChain>),
+        wallet: Wallet,
+        address_book: AddressBook,
+        address_manager: AddressManager,
+        ban_list: BanList,
+        transaction_history: TransactionHistory,
+        logger: LoggerSender,
+    ) -> SaveSystem {
+        let (block_chain, filter_chain) = chains;
         SaveSystem {
             block_chain,
+            filter_chain,
             wallet,
+            address_book,
+            address_manager,
+            ban_list,
+            transaction_history,
             logger,
         }
     }
 
-    /// Saves the block chain and a wallet to there respective files if given
+    /// Saves the block chain, the compact filters of the blocks if they were used, the wallet, the address book,
+    /// the addresses of the potential peers, the banned addresses and the transaction history to there respective
+    /// files if given
     ///
     /// ### Error
     ///  * `ErrorInitialization::ValueFileDoesntExist`: It will appear when the file could not be created
@@ -41,10 +71,47 @@ impl SaveSystem {
             self.logger.clone(),
         )?;
 
+        if let Some(filter_chain) = self.filter_chain {
+            Self::save_value(
+                filter_chain,
+                FILTER_CHAIN_FILE,
+                save_config.write_filter_chain,
+                self.logger.clone(),
+            )?;
+        }
+
         Self::save_value(
             self.wallet,
             WALLET_FILE,
             save_config.write_wallet,
+            self.logger.clone(),
+        )?;
+
+        Self::save_value(
+            self.address_book,
+            ADDRESS_BOOK_FILE,
+            save_config.write_address_book,
+            self.logger.clone(),
+        )?;
+
+        Self::save_value(
+            self.address_manager,
+            PEER_ADDRESSES_FILE,
+            save_config.write_peer_addresses,
+            self.logger.clone(),
+        )?;
+
+        Self::save_value(
+            self.ban_list,
+            BAN_LIST_FILE,
+            save_config.write_ban_list,
+            self.logger.clone(),
+        )?;
+
+        Self::save_value(
+            self.transaction_history,
+            TRANSACTION_HISTORY_FILE,
+            save_config.write_transaction_history,
             self.logger,
         )?;
 
@@ -0,0 +1,105 @@
+use cargosos_bitcoin::{
+    block_structure::block_chain::BlockChain, logs::logger_sender::LoggerSender,
+    serialization::serializable_internal_order::SerializableInternalOrder,
+};
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{BufWriter, Write},
+    time::{Duration, Instant},
+};
+
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+const PARTIAL_EXTENSION: &str = "partial";
+
+/// It saves the block chain while the initial sync goes on, so the headers validated and the blocks
+/// downloaded are not lost if the program is closed. The next time the sync resumes from the saved block chain
+pub struct SyncProgress {
+    path: Option<String>,
+    last_save: Instant,
+    unsaved_changes: bool,
+    logger: LoggerSender,
+}
+
+impl SyncProgress {
+    pub fn new(path: Option<String>, logger: LoggerSender) -> Self {
+        SyncProgress {
+            path,
+            last_save: Instant::now(),
+            unsaved_changes: false,
+            logger,
+        }
+    }
+
+    /// Registers that the block chain changed, saving it if enough time passed since the last save
+    pub fn block_chain_updated(&mut self, block_chain: &BlockChain) {
+        self.unsaved_changes = true;
+
+        if self.last_save.elapsed() >= SAVE_INTERVAL {
+            self.save(block_chain);
+        }
+    }
+
+    /// Saves the block chain if it changed since the last save. It's first written to a partial
+    /// file and then renamed, so a save interrupted halfway doesn't corrupt the previous one
+    pub fn save(&mut self, block_chain: &BlockChain) {
+        if !self.unsaved_changes {
+            return;
+        }
+
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return,
+        };
+
+        self.last_save = Instant::now();
+        self.unsaved_changes = false;
+
+        let partial_path = format!("{path}.{PARTIAL_EXTENSION}");
+        let file = match OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&partial_path)
+        {
+            Ok(file) => file,
+            Err(error) => {
+                let _ = self.logger.log_file(format!(
+                    "Could not save the sync progress to {partial_path}: {:?}",
+                    error
+                ));
+                return;
+            }
+        };
+
+        let mut writer = BufWriter::new(file);
+        if let Err(error) = block_chain.io_serialize(&mut writer) {
+            let _ = self
+                .logger
+                .log_file(format!("Could not save the sync progress: {:?}", error));
+            return;
+        }
+
+        if writer.flush().is_err() {
+            let _ = self
+                .logger
+                .log_file("Could not write the sync progress to file".to_string());
+            return;
+        }
+
+        match fs::rename(&partial_path, &path) {
+            Ok(()) => {
+                let _ = self.logger.log_file(format!(
+                    "Sync progress saved at height {}",
+                    block_chain.get_height()
+                ));
+            }
+            Err(error) => {
+                let _ = self.logger.log_file(format!(
+                    "Could not save the sync progress to {path}: {:?}",
+                    error
+                ));
+            }
+        }
+    }
+}
@@ -4,8 +4,8 @@ use crate::ui::error_ui::ErrorUI;
 
 use cargosos_bitcoin::{
     block_structure::{
-        block_chain::BlockChain, hash::HashType, merkle_tree::MerkleTree, transaction::Transaction,
-        utxo_set::UTXOSet,
+        block_chain::BlockChain, hash::HashType, merkle_tree::MerkleTree, outpoint::Outpoint,
+        transaction::Transaction, utxo_set::UTXOSet,
     },
     logs::logger_sender::LoggerSender,
     node_structure::{broadcasting::Broadcasting, error_node::ErrorNode},
@@ -22,24 +22,46 @@ pub fn fron_tbtc_to_satoshi(tbtc: f64) -> i64 {
     (tbtc * 100_000_000.0) as i64
 }
 
-/// Creates a transaction given the user user_input
+/// Creates a transaction given the user user_input. If there are no selected outputs, they are chosen
+/// automatically without spending the frozen outputs of the wallet
 ///
 /// ### Error
 ///  * `ErrorUI::ErrorInTransaction`: It will appear when the user does not have enough funds to make the transaction or the transaction is not valid
 fn create_transaction(
     utxo_set: &UTXOSet,
+    wallet: &Wallet,
     account: &Account,
     logger: LoggerSender,
     address: &Address,
-    amount: f64,
-    fee: f64,
+    amount_fee: (f64, f64),
+    selected_outputs: &[Outpoint],
 ) -> Result<Transaction, ErrorProcess> {
-    match account.create_transaction(
-        address.clone(),
-        fron_tbtc_to_satoshi(amount),
-        fron_tbtc_to_satoshi(fee),
-        utxo_set,
-    ) {
+    let amount = fron_tbtc_to_satoshi(amount_fee.0);
+    let fee = fron_tbtc_to_satoshi(amount_fee.1);
+
+    let transaction = if selected_outputs.is_empty() {
+        account.create_transaction(
+            address.clone(),
+            amount,
+            fee,
+            utxo_set,
+            wallet.get_frozen_outputs(),
+        )
+    } else {
+        let _ = logger.log_wallet(format!(
+            "Creating a transaction spending {} selected outputs",
+            selected_outputs.len()
+        ));
+        account.create_transaction_with_selected_outputs(
+            address.clone(),
+            amount,
+            fee,
+            utxo_set,
+            selected_outputs,
+        )
+    };
+
+    match transaction {
         Ok(transaction) => Ok(transaction),
         Err(ErrorWallet::NotEnoughFunds(error_string)) => {
             let _ = logger.log_wallet(format!(
@@ -58,7 +80,8 @@ fn create_transaction(
     }
 }
 
-/// Broadcast the transaction created by the user to the peers from the selected account in the wallet
+/// Broadcast the transaction created by the user to the peers from the selected account in the wallet.
+/// The destination has the address and the outputs chosen to be spent, empty to choose them automatically
 ///
 /// ### Error
 ///  * `ErrorUI::FailedSignalToFront`: It will appear when the sender fails
@@ -68,13 +91,12 @@ pub fn sending_transaction<N: Notifier, RW: Read + Write + Send + 'static>(
     broadcasting: &mut Broadcasting<RW>,
     wallet: &Wallet,
     utxo_set: &mut UTXOSet,
-    address: Address,
+    destination: (Address, Vec<Outpoint>),
     amount_fee: (f64, f64),
     notifier: N,
     logger: LoggerSender,
 ) -> Result<(), ErrorUI> {
-    let amount = amount_fee.0;
-    let fee = amount_fee.1;
+    let (address, selected_outputs) = destination;
 
     let account = match wallet.get_selected_account() {
         Some(account) => account,
@@ -85,14 +107,21 @@ pub fn sending_transaction<N: Notifier, RW: Read + Write + Send + 'static>(
         }
     };
 
-    let transaction =
-        match create_transaction(utxo_set, account, logger.clone(), &address, amount, fee) {
-            Ok(transaction) => transaction,
-            Err(error) => {
-                notifier.notify(Notification::NotEnoughFunds);
-                return Err(error.into());
-            }
-        };
+    let transaction = match create_transaction(
+        utxo_set,
+        wallet,
+        account,
+        logger.clone(),
+        &address,
+        amount_fee,
+        &selected_outputs,
+    ) {
+        Ok(transaction) => transaction,
+        Err(error) => {
+            notifier.notify(Notification::NotEnoughFunds);
+            return Err(error.into());
+        }
+    };
 
     let _ = logger.log_transaction("Sending transaction".to_string());
     utxo_set.append_pending_transaction(transaction.clone());
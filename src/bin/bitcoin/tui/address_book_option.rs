@@ -0,0 +1,85 @@
+use crate::ui::error_ui::ErrorUI;
+
+use std::{
+    convert::{From, TryFrom},
+    fmt::Display,
+};
+
+const SHOW_CONTACTS: char = '1';
+const ADD_CONTACT: char = '2';
+const EDIT_CONTACT: char = '3';
+const REMOVE_CONTACT: char = '4';
+const BACK: char = '0';
+
+/// The options for the user in the address book menu
+#[derive(Debug, Clone, Copy)]
+pub enum AddressBookOption {
+    ShowContacts,
+    AddContact,
+    EditContact,
+    RemoveContact,
+    Back,
+}
+
+impl AddressBookOption {
+    pub fn print_all() {
+        let options: &[AddressBookOption] = &[
+            AddressBookOption::ShowContacts,
+            AddressBookOption::AddContact,
+            AddressBookOption::EditContact,
+            AddressBookOption::RemoveContact,
+            AddressBookOption::Back,
+        ];
+
+        let mut message = "".to_string();
+        for option in options {
+            let option_id: char = (*option).into();
+            message.push_str(&format!("\n{option} [{option_id}]"));
+        }
+        println!("{message}")
+    }
+}
+
+impl Display for AddressBookOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressBookOption::ShowContacts => write!(f, "Show contacts"),
+            AddressBookOption::AddContact => write!(f, "Add contact"),
+            AddressBookOption::EditContact => write!(f, "Edit contact"),
+            AddressBookOption::RemoveContact => write!(f, "Remove contact"),
+            AddressBookOption::Back => write!(f, "Back"),
+        }
+    }
+}
+
+impl From<AddressBookOption> for char {
+    fn from(value: AddressBookOption) -> Self {
+        match value {
+            AddressBookOption::ShowContacts => SHOW_CONTACTS,
+            AddressBookOption::AddContact => ADD_CONTACT,
+            AddressBookOption::EditContact => EDIT_CONTACT,
+            AddressBookOption::RemoveContact => REMOVE_CONTACT,
+            AddressBookOption::Back => BACK,
+        }
+    }
+}
+
+impl TryFrom<&str> for AddressBookOption {
+    type Error = ErrorUI;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value: char = match value.chars().next() {
+            Some(value) => value,
+            _ => return Err(ErrorUI::InvalidMenuOption),
+        };
+
+        match value {
+            SHOW_CONTACTS => Ok(AddressBookOption::ShowContacts),
+            ADD_CONTACT => Ok(AddressBookOption::AddContact),
+            EDIT_CONTACT => Ok(AddressBookOption::EditContact),
+            REMOVE_CONTACT => Ok(AddressBookOption::RemoveContact),
+            BACK => Ok(AddressBookOption::Back),
+            _ => Err(ErrorUI::InvalidMenuOption),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::ui::error_ui::ErrorUI;
+
+use std::{
+    convert::{From, TryFrom},
+    fmt::Display,
+};
+
+const SHOW_OUTPUTS: char = '1';
+const FREEZE_OUTPUT: char = '2';
+const UNFREEZE_OUTPUT: char = '3';
+const BACK: char = '0';
+
+/// The options for the user in the coin control menu
+#[derive(Debug, Clone, Copy)]
+pub enum CoinControlOption {
+    ShowOutputs,
+    FreezeOutput,
+    UnfreezeOutput,
+    Back,
+}
+
+impl CoinControlOption {
+    pub fn print_all() {
+        let options: &[CoinControlOption] = &[
+            CoinControlOption::ShowOutputs,
+            CoinControlOption::FreezeOutput,
+            CoinControlOption::UnfreezeOutput,
+            CoinControlOption::Back,
+        ];
+
+        let mut message = "".to_string();
+        for option in options {
+            let option_id: char = (*option).into();
+            message.push_str(&format!("\n{option} [{option_id}]"));
+        }
+        println!("{message}")
+    }
+}
+
+impl Display for CoinControlOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoinControlOption::ShowOutputs => write!(f, "Show outputs"),
+            CoinControlOption::FreezeOutput => write!(f, "Freeze output"),
+            CoinControlOption::UnfreezeOutput => write!(f, "Unfreeze output"),
+            CoinControlOption::Back => write!(f, "Back"),
+        }
+    }
+}
+
+impl From<CoinControlOption> for char {
+    fn from(value: CoinControlOption) -> Self {
+        match value {
+            CoinControlOption::ShowOutputs => SHOW_OUTPUTS,
+            CoinControlOption::FreezeOutput => FREEZE_OUTPUT,
+            CoinControlOption::UnfreezeOutput => UNFREEZE_OUTPUT,
+            CoinControlOption::Back => BACK,
+        }
+    }
+}
+
+impl TryFrom<&str> for CoinControlOption {
+    type Error = ErrorUI;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value: char = match value.chars().next() {
+            Some(value) => value,
+            _ => return Err(ErrorUI::InvalidMenuOption),
+        };
+
+        match value {
+            SHOW_OUTPUTS => Ok(CoinControlOption::ShowOutputs),
+            FREEZE_OUTPUT => Ok(CoinControlOption::FreezeOutput),
+            UNFREEZE_OUTPUT => Ok(CoinControlOption::UnfreezeOutput),
+            BACK => Ok(CoinControlOption::Back),
+            _ => Err(ErrorUI::InvalidMenuOption),
+        }
+    }
+}
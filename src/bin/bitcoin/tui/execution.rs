@@ -9,7 +9,7 @@ use cargosos_bitcoin::{
     block_structure::block_chain::BlockChain,
     configurations::{
         connection_config::ConnectionConfig, download_config::DownloadConfig,
-        mode_config::ModeConfig,
+        mode_config::ModeConfig, save_config::SaveConfig,
     },
     logs::logger_sender::LoggerSender,
 };
@@ -60,17 +60,18 @@ fn _show_merkle_path(block_chain: &BlockChain, logger: LoggerSender) -> Result<(
 pub fn program_execution(
     mode_config: ModeConfig,
     connection_config: ConnectionConfig,
-    download_config: DownloadConfig,
+    sync_config: (DownloadConfig, SaveConfig),
     load_system: &mut LoadSystem,
     logger: LoggerSender,
 ) -> Result<SaveSystem, ErrorExecution> {
     let notifier = NotifierTUI::new(logger.clone());
-    let input_handler = InputHandlerTUI::new(notifier.clone(), logger.clone());
+    let input_handler = InputHandlerTUI::new(notifier.clone(), logger.clone())
+        .with_network(connection_config.network);
 
     backend::backend(
         mode_config,
         connection_config,
-        download_config,
+        sync_config,
         load_system,
         input_handler,
         notifier,
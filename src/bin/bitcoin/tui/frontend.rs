@@ -1,29 +1,44 @@
-use crate::ui::{account, error_ui::ErrorUI, from_hexa};
+use super::{
+    address_book_option::AddressBookOption, coin_control_option::CoinControlOption,
+    label_option::LabelOption, menu,
+};
+
+use crate::ui::{
+    account, address_book, coin_control, error_ui::ErrorUI, export_history, from_hexa, label,
+};
 
-use crate::process::transaction;
+use crate::process::{broadcasting, reference::MutArc, transaction};
 
 use cargosos_bitcoin::{
     block_structure::utxo_set::UTXOSet,
     block_structure::{
         block_chain::BlockChain,
+        filter_chain::FilterChain,
         hash::{HashType, HASH_TYPE_SIZE},
+        outpoint::Outpoint,
     },
+    connections::network::Network,
     logs::logger_sender::LoggerSender,
     node_structure::broadcasting::Broadcasting,
     notifications::{notification::Notification, notifier::Notifier},
     wallet_structure::{
-        account::Account, address::Address, private_key::PrivateKey, public_key::PublicKey,
+        account::Account, address::Address, address_book::AddressBook, contact::Contact,
+        private_key::PrivateKey, public_key::PublicKey, transaction_history::TransactionHistory,
         wallet::Wallet,
     },
 };
 
 use std::io::{stdin, Read, Write};
 
-/// Get the private key from the terminal
+/// Get the private key from the terminal, in the wallet import format of the network or in hexadecimal
 ///
 /// ### Error
 ///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
-fn get_private_key<N: Notifier>(notifier: N, logger: LoggerSender) -> Result<PrivateKey, ErrorUI> {
+fn get_private_key<N: Notifier>(
+    network: Network,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<PrivateKey, ErrorUI> {
     let mut private_key: String = String::new();
 
     println!("Enter the private key: ");
@@ -32,7 +47,7 @@ fn get_private_key<N: Notifier>(notifier: N, logger: LoggerSender) -> Result<Pri
     }
 
     loop {
-        let _: PrivateKey = match PrivateKey::try_from(private_key.trim()) {
+        let _: PrivateKey = match PrivateKey::from_string(private_key.trim(), network) {
             Ok(result) => {
                 let _ = logger.log_wallet("Valid private key entered".to_string());
                 return Ok(result);
@@ -89,6 +104,7 @@ fn get_public_key<N: Notifier>(notifier: N, logger: LoggerSender) -> Result<Publ
 /// ### Error
 ///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
 pub(super) fn get_address<N: Notifier>(
+    network: Network,
     notifier: N,
     logger: LoggerSender,
 ) -> Result<Address, ErrorUI> {
@@ -100,7 +116,7 @@ pub(super) fn get_address<N: Notifier>(
     }
 
     loop {
-        match Address::new(address.trim()) {
+        match Address::new(address.trim(), network) {
             Ok(result) => {
                 let _ = logger.log_wallet("Valid address entered".to_string());
                 return Ok(result);
@@ -119,6 +135,57 @@ pub(super) fn get_address<N: Notifier>(
     }
 }
 
+/// Get the address from the terminal, it can also be the name of a contact in the address book
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn get_address_or_contact<N: Notifier>(
+    address_book: &AddressBook,
+    network: Network,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<Address, ErrorUI> {
+    let mut address: String = String::new();
+
+    println!("Enter the address or the name of a contact: ");
+    if stdin().read_line(&mut address).is_err() {
+        return Err(ErrorUI::TerminalReadFail);
+    }
+
+    loop {
+        match address_book.get_address(address.trim(), network) {
+            Ok(result) => {
+                let _ = logger.log_wallet("Valid address entered".to_string());
+                return Ok(result);
+            }
+            _ => {
+                notifier.notify(Notification::InvalidAddressEnter);
+
+                address.clear();
+                println!("Error, please enter a valid address or contact name:");
+                if stdin().read_line(&mut address).is_err() {
+                    return Err(ErrorUI::TerminalReadFail);
+                }
+                continue;
+            }
+        };
+    }
+}
+
+/// Get the note of a contact from the terminal
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn get_note() -> Result<String, ErrorUI> {
+    let mut note: String = String::new();
+
+    println!("Enter a note (optional): ");
+    match stdin().read_line(&mut note) {
+        Ok(_) => Ok(note.trim().to_string()),
+        Err(_) => Err(ErrorUI::TerminalReadFail),
+    }
+}
+
 /// Get the account name from the terminal
 ///
 /// ### Error
@@ -133,6 +200,20 @@ fn get_account_name() -> Result<String, ErrorUI> {
     }
 }
 
+/// Get the path of the file to write from the terminal
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn get_file_path() -> Result<String, ErrorUI> {
+    let mut path: String = String::new();
+
+    println!("Enter the path of the file (.csv or .json): ");
+    match stdin().read_line(&mut path) {
+        Ok(_) => Ok(path.trim().to_string()),
+        Err(_) => Err(ErrorUI::TerminalReadFail),
+    }
+}
+
 fn get_hash_id<N: Notifier>(
     hash_type: &str,
     notifier: N,
@@ -188,22 +269,55 @@ pub fn create_merkle_proof_of_inclusion<N: Notifier>(
     Ok(())
 }
 
+/// Exports the history of transactions of the selected account to the file entered by the user
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+pub fn export_transactions<N: Notifier>(
+    wallet: &Wallet,
+    chains: (&BlockChain, Option<&FilterChain>),
+    transaction_history: &mut TransactionHistory,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    let _ = logger.log_wallet("Exporting the transactions".to_string());
+
+    let path = get_file_path()?;
+
+    export_history::export_account_transactions(
+        wallet,
+        chains,
+        transaction_history,
+        &path,
+        notifier,
+        logger,
+    )
+}
+
 /// Creates a new account with the data entered by the user
 ///
 /// ### Error
 ///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
 pub fn create_account<N: Notifier>(
     wallet: &mut Wallet,
+    network: Network,
     notifier: N,
     logger: LoggerSender,
 ) -> Result<(), ErrorUI> {
     let _ = logger.log_wallet("Creating a new account".to_string());
 
-    let private_key = get_private_key(notifier.clone(), logger.clone())?;
+    let private_key = get_private_key(network, notifier.clone(), logger.clone())?;
     let public_key = get_public_key(notifier.clone(), logger)?;
     let account_name = get_account_name()?;
 
-    account::create_account(wallet, &account_name, private_key, public_key, notifier)
+    account::create_account(
+        wallet,
+        &account_name,
+        private_key,
+        public_key,
+        network,
+        notifier,
+    )
 }
 
 /// Delete the selected account selected by the user
@@ -325,6 +439,31 @@ pub fn show_accounts(wallet: &Wallet, logger: LoggerSender) {
     });
 }
 
+/// Mines a block with the pending transactions paying to the selected account, only in the regtest network
+///
+/// ### Error
+///  * `ErrorUI::CannotUnwrapArc`: It will appear when we try to unwrap an Arc
+///  * `ErrorUI::ErrorWriting`: It will appear when the block could not be mined or added to the block chain
+///  * `ErrorUI::ErrorFromPeer`: It will appear when the block could not be sent to the peers
+pub fn mine_block<N: Notifier, RW: Read + Write + Send + 'static>(
+    network: Network,
+    broadcasting: &MutArc<Broadcasting<RW>>,
+    wallet_data: (&MutArc<Wallet>, &MutArc<TransactionHistory>),
+    chain_data: (&MutArc<UTXOSet>, &MutArc<BlockChain>),
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    if network != Network::Regtest {
+        let _ = logger.log_interface("Mining is only available in regtest".to_string());
+        println!("Mining is only available in the regtest network");
+        return Ok(());
+    }
+
+    println!("Mining a block...");
+    broadcasting::mine_block(broadcasting, wallet_data, chain_data, notifier, &logger)?;
+    Ok(())
+}
+
 /// Get the amount for the transaction from the terminal
 ///
 /// ### Error
@@ -392,6 +531,89 @@ fn get_fee(logger: LoggerSender) -> Result<f64, ErrorUI> {
     }
 }
 
+/// Get the positions of the outputs, separated by commas, from the terminal
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn get_output_positions(
+    amount_outputs: usize,
+    logger: LoggerSender,
+) -> Result<Vec<usize>, ErrorUI> {
+    let mut positions: String = String::new();
+
+    println!("Enter the numbers of the outputs to spend, separated by commas: ");
+    if stdin().read_line(&mut positions).is_err() {
+        return Err(ErrorUI::TerminalReadFail);
+    }
+
+    loop {
+        let result: Result<Vec<usize>, _> = positions
+            .trim()
+            .split(',')
+            .map(|position| position.trim().parse::<usize>())
+            .collect();
+
+        match result {
+            Ok(result) if result.iter().all(|position| *position < amount_outputs) => {
+                let _ = logger.log_wallet("Valid outputs entered".to_string());
+                return Ok(result);
+            }
+            _ => {
+                let _ = logger.log_wallet("Invalid outputs entered".to_string());
+
+                positions.clear();
+                println!("Error, please enter valid numbers of outputs:");
+                if stdin().read_line(&mut positions).is_err() {
+                    return Err(ErrorUI::TerminalReadFail);
+                }
+                continue;
+            }
+        }
+    }
+}
+
+/// Asks the user if they want to choose the outputs to spend and returns the ones selected.
+/// If the user does not want to choose them it returns no outputs, so they are chosen automatically
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn select_outputs_to_spend(
+    wallet: &Wallet,
+    utxo_set: &UTXOSet,
+    transaction_history: &TransactionHistory,
+    logger: LoggerSender,
+) -> Result<Vec<Outpoint>, ErrorUI> {
+    let account = match wallet.get_selected_account() {
+        Some(account) => account,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut answer: String = String::new();
+    println!("Do you want to choose the outputs to spend? [y/N]: ");
+    if stdin().read_line(&mut answer).is_err() {
+        return Err(ErrorUI::TerminalReadFail);
+    }
+    if !answer.trim().eq_ignore_ascii_case("y") {
+        return Ok(Vec::new());
+    }
+
+    let outputs = coin_control::get_account_outputs(wallet, utxo_set, transaction_history, account);
+    if outputs.is_empty() {
+        println!("The account does not have outputs to spend");
+        return Ok(Vec::new());
+    }
+
+    for (position, output) in outputs.iter().enumerate() {
+        println!("[{position}] {output}\n");
+    }
+
+    let positions = get_output_positions(outputs.len(), logger)?;
+    Ok(positions
+        .into_iter()
+        .map(|position| outputs[position].outpoint.clone())
+        .collect())
+}
+
 /// Broadcast the transaction created by the user to the peers from the selected account in the wallet
 ///
 /// ### Error
@@ -401,21 +623,346 @@ fn get_fee(logger: LoggerSender) -> Result<f64, ErrorUI> {
 pub fn sending_transaction<N: Notifier, RW: Read + Write + Send + 'static>(
     broadcasting: &mut Broadcasting<RW>,
     wallet: &Wallet,
+    (address_book, network): (&AddressBook, Network),
     utxo_set: &mut UTXOSet,
+    transaction_history: &TransactionHistory,
     notifier: N,
     logger: LoggerSender,
 ) -> Result<(), ErrorUI> {
-    let address = get_address(notifier.clone(), logger.clone())?;
+    let address = get_address_or_contact(address_book, network, notifier.clone(), logger.clone())?;
     let amount = get_amount(logger.clone())?;
     let fee = get_fee(logger.clone())?;
+    let selected_outputs =
+        select_outputs_to_spend(wallet, utxo_set, transaction_history, logger.clone())?;
 
     transaction::sending_transaction(
         broadcasting,
         wallet,
         utxo_set,
-        address,
+        (address, selected_outputs),
         (amount, fee),
         notifier,
         logger,
     )
 }
+
+/// Select a contact from the address book
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn select_contact(address_book: &AddressBook, logger: LoggerSender) -> Result<Contact, ErrorUI> {
+    let _ = logger.log_wallet("Selecting a contact".to_string());
+
+    println!("Possible contacts: ");
+    show_contacts(address_book);
+
+    let mut contact_name: String = String::new();
+
+    println!("Enter the name: ");
+    if stdin().read_line(&mut contact_name).is_err() {
+        return Err(ErrorUI::TerminalReadFail);
+    }
+
+    loop {
+        match address_book.get_contact_with_name(contact_name.trim()) {
+            Some(contact) => {
+                let _ = logger.log_wallet("Valid contact name entered".to_string());
+                return Ok(contact.clone());
+            }
+            None => {
+                let _ = logger.log_wallet("Invalid contact name entered".to_string());
+
+                contact_name.clear();
+                println!("Error, please enter a valid contact name:");
+                if stdin().read_line(&mut contact_name).is_err() {
+                    return Err(ErrorUI::TerminalReadFail);
+                }
+                continue;
+            }
+        };
+    }
+}
+
+/// Show all contacts from the address book
+fn show_contacts(address_book: &AddressBook) {
+    address_book.get_contacts().iter().for_each(|contact| {
+        println!("{contact}\n");
+    });
+}
+
+/// Creates a new contact with the data entered by the user
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn add_contact<N: Notifier>(
+    address_book: &mut AddressBook,
+    network: Network,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    let _ = logger.log_wallet("Creating a new contact".to_string());
+
+    let name = get_account_name()?;
+    let address = get_address(network, notifier.clone(), logger.clone())?;
+    let note = get_note()?;
+
+    address_book::add_contact(
+        address_book,
+        Contact::new(&name, address, Some(note)),
+        notifier,
+        logger,
+    )
+}
+
+/// Changes the address and the note of the contact selected by the user
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn edit_contact<N: Notifier>(
+    address_book: &mut AddressBook,
+    network: Network,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    if address_book.get_contacts().is_empty() {
+        println!("The address book is empty");
+        return Ok(());
+    }
+
+    let contact = select_contact(address_book, logger.clone())?;
+    let address = get_address(network, notifier.clone(), logger.clone())?;
+    let note = get_note()?;
+
+    address_book::update_contact(
+        address_book,
+        Contact::new(&contact.name, address, Some(note)),
+        notifier,
+        logger,
+    )
+}
+
+/// Removes the contact selected by the user
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn remove_contact<N: Notifier>(
+    address_book: &mut AddressBook,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    if address_book.get_contacts().is_empty() {
+        println!("The address book is empty");
+        return Ok(());
+    }
+
+    let contact = select_contact(address_book, logger.clone())?;
+
+    address_book::remove_contact(address_book, &contact.name, notifier, logger)
+}
+
+/// Shows the options of the address book until the user goes back to the main menu
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+pub fn manage_address_book<N: Notifier>(
+    address_book: &mut AddressBook,
+    network: Network,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    loop {
+        match menu::select_address_book_option(logger.clone())? {
+            AddressBookOption::ShowContacts => {
+                address_book::give_contacts(address_book, notifier.clone())
+            }
+            AddressBookOption::AddContact => {
+                add_contact(address_book, network, notifier.clone(), logger.clone())?
+            }
+            AddressBookOption::EditContact => {
+                edit_contact(address_book, network, notifier.clone(), logger.clone())?
+            }
+            AddressBookOption::RemoveContact => {
+                remove_contact(address_book, notifier.clone(), logger.clone())?
+            }
+            AddressBookOption::Back => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the transaction id, in the order that is shown to the user, from the terminal
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn get_transaction_id(logger: LoggerSender) -> Result<HashType, ErrorUI> {
+    let mut transaction_id: String = String::new();
+
+    println!("Enter the transaction id: ");
+    if stdin().read_line(&mut transaction_id).is_err() {
+        return Err(ErrorUI::TerminalReadFail);
+    }
+
+    loop {
+        match label::from_transaction_id_string(transaction_id.trim()) {
+            Ok(result) => {
+                let _ = logger.log_wallet("Valid transaction id entered".to_string());
+                return Ok(result);
+            }
+            Err(error) => {
+                let _ = logger.log_wallet(format!(
+                    "Invalid transaction id entered, with error: {:?}",
+                    error
+                ));
+
+                transaction_id.clear();
+                println!("Error, please enter a valid transaction id:");
+                if stdin().read_line(&mut transaction_id).is_err() {
+                    return Err(ErrorUI::TerminalReadFail);
+                }
+                continue;
+            }
+        };
+    }
+}
+
+/// Get the outpoint, written as `<transaction id>:<index>`, from the terminal
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn get_outpoint(logger: LoggerSender) -> Result<Outpoint, ErrorUI> {
+    let mut outpoint: String = String::new();
+
+    println!("Enter the output (<transaction id>:<index>): ");
+    if stdin().read_line(&mut outpoint).is_err() {
+        return Err(ErrorUI::TerminalReadFail);
+    }
+
+    loop {
+        match label::from_outpoint_string(outpoint.trim()) {
+            Ok(result) => {
+                let _ = logger.log_wallet("Valid output entered".to_string());
+                return Ok(result);
+            }
+            Err(error) => {
+                let _ =
+                    logger.log_wallet(format!("Invalid output entered, with error: {:?}", error));
+
+                outpoint.clear();
+                println!("Error, please enter a valid output:");
+                if stdin().read_line(&mut outpoint).is_err() {
+                    return Err(ErrorUI::TerminalReadFail);
+                }
+                continue;
+            }
+        };
+    }
+}
+
+/// Get the label from the terminal
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn get_label() -> Result<String, ErrorUI> {
+    let mut label: String = String::new();
+
+    println!("Enter the label (empty to remove it): ");
+    match stdin().read_line(&mut label) {
+        Ok(_) => Ok(label.trim().to_string()),
+        Err(_) => Err(ErrorUI::TerminalReadFail),
+    }
+}
+
+/// Shows the options of the labels until the user goes back to the main menu
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+pub fn manage_labels<N: Notifier>(
+    wallet: &mut Wallet,
+    utxo_set: &UTXOSet,
+    transaction_history: &TransactionHistory,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    loop {
+        match menu::select_label_option(logger.clone())? {
+            LabelOption::ShowOutputs => coin_control::give_account_outputs(
+                wallet,
+                utxo_set,
+                transaction_history,
+                notifier.clone(),
+            ),
+            LabelOption::LabelTransaction => {
+                let transaction_id = get_transaction_id(logger.clone())?;
+                let transaction_label = get_label()?;
+                label::label_transaction(
+                    wallet,
+                    transaction_id,
+                    &transaction_label,
+                    notifier.clone(),
+                    logger.clone(),
+                )
+            }
+            LabelOption::LabelOutput => {
+                let outpoint = get_outpoint(logger.clone())?;
+                let output_label = get_label()?;
+                label::label_output(
+                    wallet,
+                    outpoint,
+                    &output_label,
+                    notifier.clone(),
+                    logger.clone(),
+                )
+            }
+            LabelOption::Back => break,
+        }
+    }
+
+    Ok(())
+}
+
+/// Shows the options of the coin control until the user goes back to the main menu
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+pub fn manage_coin_control<N: Notifier>(
+    wallet: &mut Wallet,
+    utxo_set: &UTXOSet,
+    transaction_history: &TransactionHistory,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    loop {
+        match menu::select_coin_control_option(logger.clone())? {
+            CoinControlOption::ShowOutputs => coin_control::give_account_outputs(
+                wallet,
+                utxo_set,
+                transaction_history,
+                notifier.clone(),
+            ),
+            CoinControlOption::FreezeOutput => {
+                let outpoint = get_outpoint(logger.clone())?;
+                coin_control::change_output_frozen(
+                    wallet,
+                    outpoint,
+                    true,
+                    notifier.clone(),
+                    logger.clone(),
+                )
+            }
+            CoinControlOption::UnfreezeOutput => {
+                let outpoint = get_outpoint(logger.clone())?;
+                coin_control::change_output_frozen(
+                    wallet,
+                    outpoint,
+                    false,
+                    notifier.clone(),
+                    logger.clone(),
+                )
+            }
+            CoinControlOption::Back => break,
+        }
+    }
+
+    Ok(())
+}
@@ -1,16 +1,19 @@
 use super::{frontend, menu, menu_option::MenuOption};
 
 use crate::{
-    process::reference::{get_reference, MutArc},
+    process::reference::{get_optional_reference, get_reference, MutArc},
     ui::{account, error_ui::ErrorUI, input_handler::InputHandler},
 };
 
 use cargosos_bitcoin::{
-    block_structure::{block_chain::BlockChain, utxo_set::UTXOSet},
+    block_structure::{block_chain::BlockChain, filter_chain::FilterChain, utxo_set::UTXOSet},
+    connections::network::Network,
     logs::logger_sender::LoggerSender,
     node_structure::broadcasting::Broadcasting,
     notifications::notifier::Notifier,
-    wallet_structure::wallet::Wallet,
+    wallet_structure::{
+        address_book::AddressBook, transaction_history::TransactionHistory, wallet::Wallet,
+    },
 };
 
 use std::io::{Read, Write};
@@ -21,11 +24,21 @@ where
 {
     notifier: N,
     logger: LoggerSender,
+    network: Network,
 }
 
 impl<N: Notifier> InputHandlerTUI<N> {
     pub fn new(notifier: N, logger: LoggerSender) -> Self {
-        Self { notifier, logger }
+        Self {
+            notifier,
+            logger,
+            network: Network::default(),
+        }
+    }
+
+    /// Changes the network of the accounts created, mining blocks is only possible in the regtest network
+    pub fn with_network(self, network: Network) -> Self {
+        Self { network, ..self }
     }
 }
 
@@ -38,15 +51,19 @@ where
         &self,
         broadcasting: MutArc<Broadcasting<RW>>,
         wallet: MutArc<Wallet>,
+        address_book: MutArc<AddressBook>,
         utxo_set: MutArc<UTXOSet>,
         block_chain: MutArc<BlockChain>,
+        history_data: (MutArc<TransactionHistory>, Option<MutArc<FilterChain>>),
     ) -> Result<(), ErrorUI> {
+        let (transaction_history, filter_chain) = history_data;
         loop {
             match menu::select_option(self.logger.clone())? {
                 MenuOption::CreateAccount => {
                     let mut wallet_reference = get_reference(&wallet)?;
                     frontend::create_account(
                         &mut wallet_reference,
+                        self.network,
                         self.notifier.clone(),
                         self.logger.clone(),
                     )?
@@ -65,12 +82,16 @@ where
                 }
                 MenuOption::SendTransaction => {
                     let wallet_reference = get_reference(&wallet)?;
+                    let address_book_reference = get_reference(&address_book)?;
                     let mut utxo_set_reference = get_reference(&utxo_set)?;
                     let mut broadcasting_reference = get_reference(&broadcasting)?;
+                    let transaction_history_reference = get_reference(&transaction_history)?;
                     frontend::sending_transaction(
                         &mut broadcasting_reference,
                         &wallet_reference,
+                        (&address_book_reference, self.network),
                         &mut utxo_set_reference,
+                        &transaction_history_reference,
                         self.notifier.clone(),
                         self.logger.clone(),
                     )?
@@ -91,9 +112,12 @@ where
                 MenuOption::LastTransactions => {
                     let wallet_reference = get_reference(&wallet)?;
                     let blockchain_reference = get_reference(&block_chain)?;
+                    let filter_chain_reference = get_optional_reference(&filter_chain)?;
+                    let mut transaction_history_reference = get_reference(&transaction_history)?;
                     account::give_account_transactions(
                         &wallet_reference,
-                        &blockchain_reference,
+                        (&blockchain_reference, filter_chain_reference.as_deref()),
+                        &mut transaction_history_reference,
                         self.notifier.clone(),
                         self.logger.clone(),
                     )?
@@ -106,6 +130,60 @@ where
                         self.logger.clone(),
                     )?
                 }
+                MenuOption::ExportTransactions => {
+                    let wallet_reference = get_reference(&wallet)?;
+                    let blockchain_reference = get_reference(&block_chain)?;
+                    let filter_chain_reference = get_optional_reference(&filter_chain)?;
+                    let mut transaction_history_reference = get_reference(&transaction_history)?;
+                    frontend::export_transactions(
+                        &wallet_reference,
+                        (&blockchain_reference, filter_chain_reference.as_deref()),
+                        &mut transaction_history_reference,
+                        self.notifier.clone(),
+                        self.logger.clone(),
+                    )?
+                }
+                MenuOption::AddressBook => {
+                    let mut address_book_reference = get_reference(&address_book)?;
+                    frontend::manage_address_book(
+                        &mut address_book_reference,
+                        self.network,
+                        self.notifier.clone(),
+                        self.logger.clone(),
+                    )?
+                }
+                MenuOption::Labels => {
+                    let mut wallet_reference = get_reference(&wallet)?;
+                    let utxo_set_reference = get_reference(&utxo_set)?;
+                    let transaction_history_reference = get_reference(&transaction_history)?;
+                    frontend::manage_labels(
+                        &mut wallet_reference,
+                        &utxo_set_reference,
+                        &transaction_history_reference,
+                        self.notifier.clone(),
+                        self.logger.clone(),
+                    )?
+                }
+                MenuOption::CoinControl => {
+                    let mut wallet_reference = get_reference(&wallet)?;
+                    let utxo_set_reference = get_reference(&utxo_set)?;
+                    let transaction_history_reference = get_reference(&transaction_history)?;
+                    frontend::manage_coin_control(
+                        &mut wallet_reference,
+                        &utxo_set_reference,
+                        &transaction_history_reference,
+                        self.notifier.clone(),
+                        self.logger.clone(),
+                    )?
+                }
+                MenuOption::MineBlock => frontend::mine_block(
+                    self.network,
+                    &broadcasting,
+                    (&wallet, &transaction_history),
+                    (&utxo_set, &block_chain),
+                    self.notifier.clone(),
+                    self.logger.clone(),
+                )?,
                 MenuOption::Exit => break,
             }
         }
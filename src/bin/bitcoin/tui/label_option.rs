@@ -0,0 +1,79 @@
+use crate::ui::error_ui::ErrorUI;
+
+use std::{
+    convert::{From, TryFrom},
+    fmt::Display,
+};
+
+const SHOW_OUTPUTS: char = '1';
+const LABEL_TRANSACTION: char = '2';
+const LABEL_OUTPUT: char = '3';
+const BACK: char = '0';
+
+/// The options for the user in the labels menu
+#[derive(Debug, Clone, Copy)]
+pub enum LabelOption {
+    ShowOutputs,
+    LabelTransaction,
+    LabelOutput,
+    Back,
+}
+
+impl LabelOption {
+    pub fn print_all() {
+        let options: &[LabelOption] = &[
+            LabelOption::ShowOutputs,
+            LabelOption::LabelTransaction,
+            LabelOption::LabelOutput,
+            LabelOption::Back,
+        ];
+
+        let mut message = "".to_string();
+        for option in options {
+            let option_id: char = (*option).into();
+            message.push_str(&format!("\n{option} [{option_id}]"));
+        }
+        println!("{message}")
+    }
+}
+
+impl Display for LabelOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LabelOption::ShowOutputs => write!(f, "Show outputs"),
+            LabelOption::LabelTransaction => write!(f, "Label transaction"),
+            LabelOption::LabelOutput => write!(f, "Label output"),
+            LabelOption::Back => write!(f, "Back"),
+        }
+    }
+}
+
+impl From<LabelOption> for char {
+    fn from(value: LabelOption) -> Self {
+        match value {
+            LabelOption::ShowOutputs => SHOW_OUTPUTS,
+            LabelOption::LabelTransaction => LABEL_TRANSACTION,
+            LabelOption::LabelOutput => LABEL_OUTPUT,
+            LabelOption::Back => BACK,
+        }
+    }
+}
+
+impl TryFrom<&str> for LabelOption {
+    type Error = ErrorUI;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value: char = match value.chars().next() {
+            Some(value) => value,
+            _ => return Err(ErrorUI::InvalidMenuOption),
+        };
+
+        match value {
+            SHOW_OUTPUTS => Ok(LabelOption::ShowOutputs),
+            LABEL_TRANSACTION => Ok(LabelOption::LabelTransaction),
+            LABEL_OUTPUT => Ok(LabelOption::LabelOutput),
+            BACK => Ok(LabelOption::Back),
+            _ => Err(ErrorUI::InvalidMenuOption),
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use super::menu_option::MenuOption;
+use super::{
+    address_book_option::AddressBookOption, coin_control_option::CoinControlOption,
+    label_option::LabelOption, menu_option::MenuOption,
+};
 
 use crate::ui::error_ui::ErrorUI;
 
@@ -12,7 +15,45 @@ use std::io::stdin;
 ///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
 pub fn select_option(logger: LoggerSender) -> Result<MenuOption, ErrorUI> {
     println!("Select an option:");
-    MenuOption::print_all();
+    select_from_options(MenuOption::print_all, logger)
+}
+
+/// Get the option of the address book from the user via terminal
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+pub fn select_address_book_option(logger: LoggerSender) -> Result<AddressBookOption, ErrorUI> {
+    println!("Select an option of the address book:");
+    select_from_options(AddressBookOption::print_all, logger)
+}
+
+/// Get the option of the labels from the user via terminal
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+pub fn select_label_option(logger: LoggerSender) -> Result<LabelOption, ErrorUI> {
+    println!("Select an option of the labels:");
+    select_from_options(LabelOption::print_all, logger)
+}
+
+/// Get the option of the coin control from the user via terminal
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+pub fn select_coin_control_option(logger: LoggerSender) -> Result<CoinControlOption, ErrorUI> {
+    println!("Select an option of the coin control:");
+    select_from_options(CoinControlOption::print_all, logger)
+}
+
+/// Get one of the options shown with `print_all` from the user via terminal
+///
+/// ### Error
+///  * `ErrorUI::TerminalReadFail`: It will appear when the terminal read fails
+fn select_from_options<T>(print_all: fn(), logger: LoggerSender) -> Result<T, ErrorUI>
+where
+    T: for<'a> TryFrom<&'a str, Error = ErrorUI>,
+{
+    print_all();
 
     let mut option: String = String::new();
     if stdin().read_line(&mut option).is_err() {
@@ -20,7 +61,7 @@ pub fn select_option(logger: LoggerSender) -> Result<MenuOption, ErrorUI> {
     }
 
     loop {
-        let _: MenuOption = match MenuOption::try_from(option.trim()) {
+        let _: T = match T::try_from(option.trim()) {
             Ok(result) => return Ok(result),
             Err(error) => {
                 let _ =
@@ -28,7 +69,7 @@ pub fn select_option(logger: LoggerSender) -> Result<MenuOption, ErrorUI> {
 
                 option.clear();
                 println!("Error, please enter a valid option:");
-                MenuOption::print_all();
+                print_all();
                 if stdin().read_line(&mut option).is_err() {
                     return Err(ErrorUI::TerminalReadFail);
                 }
@@ -13,7 +13,12 @@ const SHOW_ACCOUNTS: char = '5';
 const SHOW_BALANCE: char = '6';
 const LAST_TRANSACTIONS: char = '7';
 const MERKLE_PROOF: char = '8';
-const EXIT: char = '9';
+const EXPORT_TRANSACTIONS: char = '9';
+const ADDRESS_BOOK: char = 'a';
+const LABELS: char = 'b';
+const COIN_CONTROL: char = 'c';
+const MINE_BLOCK: char = 'd';
+const EXIT: char = '0';
 
 /// The options for the user in the menu
 #[derive(Debug, Clone, Copy)]
@@ -26,6 +31,11 @@ pub enum MenuOption {
     ShowBalance,
     LastTransactions,
     MerkleProof,
+    ExportTransactions,
+    AddressBook,
+    Labels,
+    CoinControl,
+    MineBlock,
     Exit,
 }
 
@@ -40,6 +50,11 @@ impl MenuOption {
             MenuOption::ShowBalance,
             MenuOption::LastTransactions,
             MenuOption::MerkleProof,
+            MenuOption::ExportTransactions,
+            MenuOption::AddressBook,
+            MenuOption::Labels,
+            MenuOption::CoinControl,
+            MenuOption::MineBlock,
             MenuOption::Exit,
         ];
 
@@ -63,6 +78,11 @@ impl Display for MenuOption {
             MenuOption::ShowBalance => write!(f, "Show balance"),
             MenuOption::LastTransactions => write!(f, "Last transactions"),
             MenuOption::MerkleProof => write!(f, "Merkle proof"),
+            MenuOption::ExportTransactions => write!(f, "Export transactions"),
+            MenuOption::AddressBook => write!(f, "Address book"),
+            MenuOption::Labels => write!(f, "Labels"),
+            MenuOption::CoinControl => write!(f, "Coin control"),
+            MenuOption::MineBlock => write!(f, "Mine block"),
             MenuOption::Exit => write!(f, "Exit"),
         }
     }
@@ -79,6 +99,11 @@ impl From<MenuOption> for char {
             MenuOption::ShowBalance => SHOW_BALANCE,
             MenuOption::LastTransactions => LAST_TRANSACTIONS,
             MenuOption::MerkleProof => MERKLE_PROOF,
+            MenuOption::ExportTransactions => EXPORT_TRANSACTIONS,
+            MenuOption::AddressBook => ADDRESS_BOOK,
+            MenuOption::Labels => LABELS,
+            MenuOption::CoinControl => COIN_CONTROL,
+            MenuOption::MineBlock => MINE_BLOCK,
             MenuOption::Exit => EXIT,
         }
     }
@@ -102,6 +127,11 @@ impl TryFrom<&str> for MenuOption {
             SHOW_BALANCE => Ok(MenuOption::ShowBalance),
             LAST_TRANSACTIONS => Ok(MenuOption::LastTransactions),
             MERKLE_PROOF => Ok(MenuOption::MerkleProof),
+            EXPORT_TRANSACTIONS => Ok(MenuOption::ExportTransactions),
+            ADDRESS_BOOK => Ok(MenuOption::AddressBook),
+            LABELS => Ok(MenuOption::Labels),
+            COIN_CONTROL => Ok(MenuOption::CoinControl),
+            MINE_BLOCK => Ok(MenuOption::MineBlock),
             EXIT => Ok(MenuOption::Exit),
             _ => Err(ErrorUI::InvalidMenuOption),
         }
@@ -1,6 +1,9 @@
 pub mod execution;
 pub mod frontend;
 
+pub mod address_book_option;
+pub mod coin_control_option;
+pub mod label_option;
 pub mod menu;
 pub mod menu_option;
 
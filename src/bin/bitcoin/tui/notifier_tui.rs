@@ -32,6 +32,15 @@ impl Notifier for NotifierTUI {
             Notification::ConnectionUpdated(connection_id) => {
                 println!("Connection updated: {connection_id}");
             }
+            Notification::ConnectionLost(connection_id) => {
+                println!("Connection lost: {connection_id}");
+            }
+            Notification::UpdatedPeerLatency(connection_id, latency) => {
+                println!("Latency of {connection_id}: {} ms", latency.as_millis());
+            }
+            Notification::PeerBanned(socket_address, misbehavior) => {
+                println!("Peer {socket_address} banned because of: {misbehavior}");
+            }
             Notification::TransactionOfAccountReceived(accounts, transaction) => {
                 for account in accounts {
                     show_notification(
@@ -89,10 +98,10 @@ impl Notifier for NotifierTUI {
                 println!("{message}");
                 let _ = self.logger.log_wallet(message);
             }
-            Notification::AccountTransactions(account, transactions) => {
+            Notification::AccountTransactions(account, records) => {
                 let mut message_transaction = "".to_string();
-                for transaction in transactions {
-                    message_transaction.push_str(&format!("{transaction}\n"));
+                for record in records {
+                    message_transaction.push_str(&format!("{record}\n"));
                 }
                 show_notification(
                     &format!("In the account: {account}", account = account.account_name),
@@ -100,6 +109,70 @@ impl Notifier for NotifierTUI {
                     &self.logger,
                 )
             }
+            Notification::AccountOutputs(account, outputs) => {
+                let mut message_outputs = "".to_string();
+                for output in outputs {
+                    message_outputs.push_str(&format!("{output}\n"));
+                }
+                show_notification(
+                    &format!(
+                        "Outputs of the account: {account}",
+                        account = account.account_name
+                    ),
+                    &message_outputs,
+                    &self.logger,
+                )
+            }
+            Notification::TransactionLabeled(_, label) => {
+                let message = format!("Transaction labeled as: {label}");
+                println!("{message}");
+                let _ = self.logger.log_wallet(message);
+            }
+            Notification::OutputLabeled(outpoint, label) => {
+                let message = format!("Output {outpoint} labeled as: {label}");
+                println!("{message}");
+                let _ = self.logger.log_wallet(message);
+            }
+            Notification::OutputFrozen(outpoint) => {
+                let message = format!("Output {outpoint} frozen");
+                println!("{message}");
+                let _ = self.logger.log_wallet(message);
+            }
+            Notification::OutputUnfrozen(outpoint) => {
+                let message = format!("Output {outpoint} unfrozen");
+                println!("{message}");
+                let _ = self.logger.log_wallet(message);
+            }
+            Notification::AddressBookContacts(contacts) => {
+                let mut message_contacts = "".to_string();
+                for contact in contacts {
+                    message_contacts.push_str(&format!("{contact}\n"));
+                }
+                show_notification("Address book", &message_contacts, &self.logger)
+            }
+            Notification::SavedContact(contact) => {
+                let message = format!("Contact {contact} was saved in the address book");
+                println!("{message}");
+                let _ = self.logger.log_wallet(message);
+            }
+            Notification::RemovedContact(contact) => {
+                let message = format!(
+                    "Contact {name} was removed from the address book",
+                    name = contact.name
+                );
+                println!("{message}");
+                let _ = self.logger.log_wallet(message);
+            }
+            Notification::ContactAlreadyExists(name) => {
+                let message = format!("There is already a contact named {name}");
+                println!("{message}");
+                let _ = self.logger.log_wallet(message);
+            }
+            Notification::ContactNotFound(name) => {
+                let message = format!("There is no contact named {name}");
+                println!("{message}");
+                let _ = self.logger.log_wallet(message);
+            }
             Notification::InvalidAddressEnter => {
                 let message = "Invalid address enter".to_string();
                 println!("{message}");
@@ -132,6 +205,30 @@ impl Notifier for NotifierTUI {
                     &self.logger,
                 );
             }
+            Notification::TransactionRejected(transaction, code, reason) => {
+                show_notification(
+                    "Transaction rejected",
+                    &format!("The transaction {transaction} was rejected ({code}): {reason}"),
+                    &self.logger,
+                );
+            }
+            Notification::SuccessfullyExportedTransactions(account, path) => {
+                show_notification(
+                    "Transactions exported",
+                    &format!(
+                        "The transactions of {account} were exported to {path}",
+                        account = account.account_name
+                    ),
+                    &self.logger,
+                );
+            }
+            Notification::ProblemExportingTransactions(error_message) => {
+                show_notification(
+                    "Error while exporting transactions",
+                    &format!("There was an error exporting the transactions\n the error was: {error_message}"),
+                    &self.logger,
+                );
+            }
             Notification::ProgressDownloadingBlocks(blocks_downloaded, total_blocks) => {
                 let percentage_downloaded =
                     (blocks_downloaded as f32 / total_blocks as f32) * 100.0;
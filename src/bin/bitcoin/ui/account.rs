@@ -1,11 +1,14 @@
 use super::error_ui::ErrorUI;
 
 use cargosos_bitcoin::{
-    block_structure::{block_chain::BlockChain, transaction::Transaction, utxo_set::UTXOSet},
+    block_structure::{block_chain::BlockChain, filter_chain::FilterChain, utxo_set::UTXOSet},
+    connections::network::Network,
     logs::logger_sender::LoggerSender,
     notifications::{notification::Notification, notifier::Notifier},
     wallet_structure::{
-        account::Account, private_key::PrivateKey, public_key::PublicKey, wallet::Wallet,
+        account::Account, private_key::PrivateKey, public_key::PublicKey,
+        transaction_history::TransactionHistory, transaction_record::TransactionRecord,
+        wallet::Wallet,
     },
 };
 
@@ -26,20 +29,6 @@ pub fn give_account_balance<N: Notifier>(wallet: &Wallet, utxo_set: &UTXOSet, no
     ));
 }
 
-/// Function that obtains and return the transactions of an account
-fn get_account_transactions(account: &Account, blockchain: &BlockChain) -> Vec<Transaction> {
-    let mut transactions: Vec<Transaction> = Vec::new();
-    let blocks = blockchain.get_all_blocks();
-    for block in blocks {
-        for transaction in block.transactions {
-            if account.verify_transaction_ownership(&transaction) {
-                transactions.push(transaction);
-            }
-        }
-    }
-    transactions
-}
-
 /// Function that changes the selected account of the address
 pub fn change_selected_account<N: Notifier>(
     account_name: String,
@@ -63,9 +52,10 @@ pub fn create_account<N: Notifier>(
     account_name: &str,
     private_key: PrivateKey,
     public_key: PublicKey,
+    network: Network,
     notifier: N,
 ) -> Result<(), ErrorUI> {
-    let account = match Account::from_keys(account_name, private_key, public_key) {
+    let account = match Account::from_keys(account_name, private_key, public_key, network) {
         Ok(account) => account,
         _ => {
             notifier.notify(Notification::AccountCreationFail);
@@ -79,11 +69,12 @@ pub fn create_account<N: Notifier>(
     Ok(())
 }
 
-/// Function that gets the information of the transactions of the selected account
-/// and sends it to the front
+/// Function that gets the history of the transactions of the selected account
+/// and sends it to the front. The filters of the blocks are used to scan the block chain if given
 pub fn give_account_transactions<N: Notifier>(
     wallet: &Wallet,
-    blockchain: &BlockChain,
+    chains: (&BlockChain, Option<&FilterChain>),
+    transaction_history: &mut TransactionHistory,
     notifier: N,
     logger: LoggerSender,
 ) -> Result<(), ErrorUI> {
@@ -96,8 +87,25 @@ pub fn give_account_transactions<N: Notifier>(
         }
     };
 
-    let transactions = get_account_transactions(&account, blockchain);
-    notifier.notify(Notification::AccountTransactions(account, transactions));
+    let (blockchain, filter_chain) = chains;
+    transaction_history.track_account(&account, blockchain, filter_chain);
+    let records = get_labeled_records(wallet, transaction_history, &account);
+    notifier.notify(Notification::AccountTransactions(account, records));
 
     Ok(())
 }
+
+/// Function that gets the history of the transactions of an account with the labels saved in the wallet
+pub fn get_labeled_records(
+    wallet: &Wallet,
+    transaction_history: &TransactionHistory,
+    account: &Account,
+) -> Vec<TransactionRecord> {
+    let mut records = transaction_history.get_account_records(account);
+    for record in records.iter_mut() {
+        record.label = wallet
+            .get_transaction_label(&record.transaction_id)
+            .cloned();
+    }
+    records
+}
@@ -0,0 +1,108 @@
+use super::error_ui::ErrorUI;
+
+use cargosos_bitcoin::{
+    connections::network::Network,
+    logs::logger_sender::LoggerSender,
+    notifications::{notification::Notification, notifier::Notifier},
+    wallet_structure::{address::Address, address_book::AddressBook, contact::Contact},
+};
+
+/// Function that sends the contacts of the address book to the front
+pub fn give_contacts<N: Notifier>(address_book: &AddressBook, notifier: N) {
+    notifier.notify(Notification::AddressBookContacts(
+        address_book.get_contacts().clone(),
+    ));
+}
+
+/// Function that adds a new contact to the address book
+pub fn add_contact<N: Notifier>(
+    address_book: &mut AddressBook,
+    contact: Contact,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    match address_book.add_contact(contact.clone()) {
+        Ok(()) => {
+            let _ = logger.log_wallet(format!("Contact {} added", contact.name));
+            notifier.notify(Notification::SavedContact(contact));
+        }
+        Err(error) => {
+            let _ = logger.log_wallet(format!("Could not add contact, with error: {:?}", error));
+            notifier.notify(Notification::ContactAlreadyExists(contact.name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Function that changes the address and note of an existing contact in the address book
+pub fn update_contact<N: Notifier>(
+    address_book: &mut AddressBook,
+    contact: Contact,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    match address_book.update_contact(contact.clone()) {
+        Ok(()) => {
+            let _ = logger.log_wallet(format!("Contact {} updated", contact.name));
+            notifier.notify(Notification::SavedContact(contact));
+        }
+        Err(error) => {
+            let _ = logger.log_wallet(format!("Could not update contact, with error: {:?}", error));
+            notifier.notify(Notification::ContactNotFound(contact.name));
+        }
+    }
+
+    Ok(())
+}
+
+/// Function that adds the contact to the address book, or updates it if there is already one with the same name
+pub fn save_contact<N: Notifier>(
+    address_book: &mut AddressBook,
+    name: &str,
+    address: &str,
+    note: &str,
+    network: Network,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    let address = match Address::new(address, network) {
+        Ok(address) => address,
+        Err(error) => {
+            let _ = logger.log_wallet(format!(
+                "Invalid address for a contact, with error: {:?}",
+                error
+            ));
+            notifier.notify(Notification::InvalidAddressEnter);
+            return Ok(());
+        }
+    };
+
+    let contact = Contact::new(name, address, Some(note.to_string()));
+
+    match address_book.get_contact_with_name(name) {
+        Some(_) => update_contact(address_book, contact, notifier, logger),
+        None => add_contact(address_book, contact, notifier, logger),
+    }
+}
+
+/// Function that removes the contact with the given name from the address book
+pub fn remove_contact<N: Notifier>(
+    address_book: &mut AddressBook,
+    name: &str,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    match address_book.remove_contact(name) {
+        Ok(contact) => {
+            let _ = logger.log_wallet(format!("Contact {} removed", contact.name));
+            notifier.notify(Notification::RemovedContact(contact));
+        }
+        Err(error) => {
+            let _ = logger.log_wallet(format!("Could not remove contact, with error: {:?}", error));
+            notifier.notify(Notification::ContactNotFound(name.to_string()));
+        }
+    }
+
+    Ok(())
+}
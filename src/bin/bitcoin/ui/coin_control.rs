@@ -0,0 +1,64 @@
+use cargosos_bitcoin::{
+    block_structure::{outpoint::Outpoint, utxo_set::UTXOSet},
+    logs::logger_sender::LoggerSender,
+    notifications::{notification::Notification, notifier::Notifier},
+    wallet_structure::{
+        account::Account, output_record::OutputRecord, transaction_history::TransactionHistory,
+        wallet::Wallet,
+    },
+};
+
+/// Function that obtains the unspent outputs of the account with their confirmations, labels and if they are frozen
+pub fn get_account_outputs(
+    wallet: &Wallet,
+    utxo_set: &UTXOSet,
+    transaction_history: &TransactionHistory,
+    account: &Account,
+) -> Vec<OutputRecord> {
+    utxo_set
+        .get_utxo_list_with_outpoints(Some(&account.address))
+        .into_iter()
+        .map(|(outpoint, output)| OutputRecord {
+            confirmations: transaction_history.get_confirmations(outpoint.get_transaction_id()),
+            frozen: wallet.is_output_frozen(&outpoint),
+            label: wallet.get_output_label(&outpoint).cloned(),
+            outpoint,
+            output,
+        })
+        .collect()
+}
+
+/// Function that obtains the unspent outputs of the selected account and sends them to the front
+pub fn give_account_outputs<N: Notifier>(
+    wallet: &Wallet,
+    utxo_set: &UTXOSet,
+    transaction_history: &TransactionHistory,
+    notifier: N,
+) {
+    let account = match wallet.get_selected_account() {
+        Some(account) => account.clone(),
+        None => return notifier.notify(Notification::AccountNotSelected),
+    };
+
+    let outputs = get_account_outputs(wallet, utxo_set, transaction_history, &account);
+    notifier.notify(Notification::AccountOutputs(account, outputs));
+}
+
+/// Function that freezes or unfreezes an output of the wallet, so it is not selected automatically to be spent
+pub fn change_output_frozen<N: Notifier>(
+    wallet: &mut Wallet,
+    outpoint: Outpoint,
+    frozen: bool,
+    notifier: N,
+    logger: LoggerSender,
+) {
+    if frozen {
+        let _ = logger.log_wallet(format!("Freezing the output {outpoint}"));
+        wallet.freeze_output(outpoint.clone());
+        notifier.notify(Notification::OutputFrozen(outpoint));
+    } else {
+        let _ = logger.log_wallet(format!("Unfreezing the output {outpoint}"));
+        wallet.unfreeze_output(&outpoint);
+        notifier.notify(Notification::OutputUnfrozen(outpoint));
+    }
+}
@@ -68,6 +68,9 @@ impl From<ErrorProcess> for ErrorUI {
             }
             ErrorProcess::FailThread => ErrorUI::FailThread("While processing data".to_string()),
             ErrorProcess::ErrorFromPeer(message) => ErrorUI::ErrorFromPeer(message),
+            ErrorProcess::PeerMisbehaved(misbehavior) => {
+                ErrorUI::ErrorFromPeer(format!("Peer misbehaved: {misbehavior}"))
+            }
             ErrorProcess::CannotCreateDefault => ErrorUI::CannotCreateDefault,
             ErrorProcess::AlreadyLoaded => ErrorUI::AlreadyLoaded,
             ErrorProcess::CannotGetInner => ErrorUI::CannotGetInner,
@@ -0,0 +1,195 @@
+use super::{account, error_ui::ErrorUI};
+
+use cargosos_bitcoin::{
+    block_structure::{block_chain::BlockChain, filter_chain::FilterChain},
+    logs::{level::Level, logger_sender::LoggerSender},
+    notifications::{notification::Notification, notifier::Notifier},
+    wallet_structure::{
+        transaction_history::TransactionHistory, transaction_record::TransactionRecord,
+        wallet::Wallet,
+    },
+};
+
+use chrono::{offset::Utc, TimeZone};
+
+use std::{fs::File, io::Write};
+
+const JSON_EXTENSION: &str = ".json";
+const CSV_HEADER: &str = "date,txid,direction,amount,fee,confirmations,counterparty,label";
+
+/// Function that exports the transaction history of the selected account to the given file.
+/// If the file ends with `.json` it will be written in JSON, otherwise it will be written in CSV.
+/// The filters of the blocks are used to scan the block chain if given
+pub fn export_account_transactions<N: Notifier>(
+    wallet: &Wallet,
+    chains: (&BlockChain, Option<&FilterChain>),
+    transaction_history: &mut TransactionHistory,
+    path: &str,
+    notifier: N,
+    logger: LoggerSender,
+) -> Result<(), ErrorUI> {
+    let account = match wallet.get_selected_account() {
+        Some(account) => account.clone(),
+        None => {
+            let _ = logger.log_wallet("No account selected cannot export transactions".to_string());
+            notifier.notify(Notification::AccountNotSelected);
+            return Ok(());
+        }
+    };
+
+    let (blockchain, filter_chain) = chains;
+    transaction_history.track_account(&account, blockchain, filter_chain);
+    let records = account::get_labeled_records(wallet, transaction_history, &account);
+
+    let content = match path.ends_with(JSON_EXTENSION) {
+        true => to_json(&records),
+        false => to_csv(&records),
+    };
+
+    let mut file = match File::create(path) {
+        Ok(file) => file,
+        Err(error) => {
+            let _ = logger.log_data(
+                Level::ERROR,
+                ErrorUI::ErrorWriting(format!(
+                    "Could not create the file {path}, with error: {:?}",
+                    error
+                )),
+            );
+            notifier.notify(Notification::ProblemExportingTransactions(format!(
+                "Could not create the file {path}"
+            )));
+            return Ok(());
+        }
+    };
+
+    if let Err(error) = file.write_all(content.as_bytes()) {
+        let _ = logger.log_data(
+            Level::ERROR,
+            ErrorUI::ErrorWriting(format!(
+                "Could not write the file {path}, with error: {:?}",
+                error
+            )),
+        );
+        notifier.notify(Notification::ProblemExportingTransactions(format!(
+            "Could not write the file {path}"
+        )));
+        return Ok(());
+    }
+
+    let _ = logger.log_wallet(format!(
+        "Exported {} transactions of {} to {path}",
+        records.len(),
+        account.account_name
+    ));
+    notifier.notify(Notification::SuccessfullyExportedTransactions(
+        account,
+        path.to_string(),
+    ));
+
+    Ok(())
+}
+
+/// Returns the records as a CSV table with a header
+fn to_csv(records: &[TransactionRecord]) -> String {
+    let mut csv = format!("{CSV_HEADER}\n");
+    for record in records {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            from_timestamp_to_string(record.timestamp),
+            record.get_transaction_id_string(),
+            record.direction,
+            record.get_net_amount_in_tbtc(),
+            record.get_fee_in_tbtc(),
+            record.confirmations,
+            get_counterparty(record),
+            to_csv_field(&get_label(record)),
+        ));
+    }
+    csv
+}
+
+/// Returns the records as a JSON array of objects
+fn to_json(records: &[TransactionRecord]) -> String {
+    let entries: Vec<String> = records
+        .iter()
+        .map(|record| {
+            let counterparty = match &record.counterparty {
+                Some(address) => format!("\"{address}\""),
+                None => "null".to_string(),
+            };
+            let label = match &record.label {
+                Some(label) => format!("\"{}\"", to_json_string(label)),
+                None => "null".to_string(),
+            };
+            format!(
+                "  {{\"date\": \"{}\", \"txid\": \"{}\", \"direction\": \"{}\", \"amount\": {}, \"fee\": {}, \"confirmations\": {}, \"counterparty\": {}, \"label\": {}}}",
+                from_timestamp_to_string(record.timestamp),
+                record.get_transaction_id_string(),
+                record.direction,
+                record.get_net_amount_in_tbtc(),
+                record.get_fee_in_tbtc(),
+                record.confirmations,
+                counterparty,
+                label,
+            )
+        })
+        .collect();
+
+    if entries.is_empty() {
+        return "[]\n".to_string();
+    }
+
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+/// Returns the counterparty address, or an empty string if it could not be decoded
+fn get_counterparty(record: &TransactionRecord) -> String {
+    match &record.counterparty {
+        Some(address) => address.to_string(),
+        None => "".to_string(),
+    }
+}
+
+/// Returns the label of the transaction, or an empty string if it does not have one
+fn get_label(record: &TransactionRecord) -> String {
+    match &record.label {
+        Some(label) => label.clone(),
+        None => "".to_string(),
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break
+fn to_csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+/// Escapes the characters that cannot appear inside a JSON string
+fn to_json_string(text: &str) -> String {
+    let mut escaped = String::new();
+    for character in text.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            character if character.is_control() => {
+                escaped.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Turns a timestamp into a date in UTC
+fn from_timestamp_to_string(timestamp: u32) -> String {
+    match Utc.timestamp_opt(timestamp as i64, 0).single() {
+        Some(datetime) => datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => timestamp.to_string(),
+    }
+}
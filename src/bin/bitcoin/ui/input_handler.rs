@@ -3,9 +3,11 @@ use super::error_ui::ErrorUI;
 use crate::process::reference::MutArc;
 
 use cargosos_bitcoin::{
-    block_structure::{block_chain::BlockChain, utxo_set::UTXOSet},
+    block_structure::{block_chain::BlockChain, filter_chain::FilterChain, utxo_set::UTXOSet},
     node_structure::broadcasting::Broadcasting,
-    wallet_structure::wallet::Wallet,
+    wallet_structure::{
+        address_book::AddressBook, transaction_history::TransactionHistory, wallet::Wallet,
+    },
 };
 
 use std::io::{Read, Write};
@@ -18,7 +20,9 @@ where
         &self,
         broadcasting: MutArc<Broadcasting<RW>>,
         wallet: MutArc<Wallet>,
+        address_book: MutArc<AddressBook>,
         utxo_set: MutArc<UTXOSet>,
         block_chain: MutArc<BlockChain>,
+        history_data: (MutArc<TransactionHistory>, Option<MutArc<FilterChain>>),
     ) -> Result<(), ErrorUI>;
 }
@@ -0,0 +1,82 @@
+use super::{error_ui::ErrorUI, from_hexa};
+
+use cargosos_bitcoin::{
+    block_structure::{
+        hash::{HashType, HASH_TYPE_SIZE},
+        outpoint::Outpoint,
+    },
+    logs::logger_sender::LoggerSender,
+    notifications::{notification::Notification, notifier::Notifier},
+    wallet_structure::wallet::Wallet,
+};
+
+/// Function that changes the label of a transaction in the wallet
+pub fn label_transaction<N: Notifier>(
+    wallet: &mut Wallet,
+    transaction_id: HashType,
+    label: &str,
+    notifier: N,
+    logger: LoggerSender,
+) {
+    let _ = logger.log_wallet(format!("Labeling a transaction as: {label}"));
+
+    wallet.set_transaction_label(transaction_id, label);
+    notifier.notify(Notification::TransactionLabeled(
+        transaction_id,
+        label.to_string(),
+    ));
+}
+
+/// Function that changes the label of an output in the wallet
+pub fn label_output<N: Notifier>(
+    wallet: &mut Wallet,
+    outpoint: Outpoint,
+    label: &str,
+    notifier: N,
+    logger: LoggerSender,
+) {
+    let _ = logger.log_wallet(format!("Labeling the output {outpoint} as: {label}"));
+
+    wallet.set_output_label(outpoint.clone(), label);
+    notifier.notify(Notification::OutputLabeled(outpoint, label.to_string()));
+}
+
+/// Turns a transaction id, in the order that is shown to the user, into its internal representation
+///
+/// ### Error
+///  * `ErrorUI::ErrorReading`: It will appear when the text is not a valid transaction id
+pub fn from_transaction_id_string(transaction_id: &str) -> Result<HashType, ErrorUI> {
+    let mut transaction_id = from_hexa::from::<HASH_TYPE_SIZE>(transaction_id)?;
+    transaction_id.reverse();
+    Ok(transaction_id)
+}
+
+/// Turns an outpoint written as `<transaction id>:<index>` into its internal representation
+///
+/// ### Error
+///  * `ErrorUI::ErrorReading`: It will appear when the text is not a valid outpoint
+pub fn from_outpoint_string(outpoint: &str) -> Result<Outpoint, ErrorUI> {
+    let (transaction_id, index) = match outpoint.split_once(':') {
+        Some(parts) => parts,
+        None => {
+            return Err(ErrorUI::ErrorReading(format!(
+                "The outpoint {outpoint} does not have an index"
+            )))
+        }
+    };
+
+    let index = match index.parse::<u32>() {
+        Ok(index) => index,
+        Err(error) => {
+            return Err(ErrorUI::ErrorReading(format!(
+                "Invalid index of the outpoint {outpoint}, with error: {:?}",
+                error
+            )))
+        }
+    };
+
+    Ok(Outpoint::new(
+        from_transaction_id_string(transaction_id)?,
+        index,
+    ))
+}
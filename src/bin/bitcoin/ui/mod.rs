@@ -1,4 +1,8 @@
 pub mod account;
+pub mod address_book;
+pub mod coin_control;
 pub mod error_ui;
+pub mod export_history;
 pub mod from_hexa;
 pub mod input_handler;
+pub mod label;
@@ -1,6 +1,7 @@
 use super::{
-    block::Block, block_header::BlockHeader, error_block::ErrorBlock, hash::HashType,
-    node_chain::NodeChain,
+    block::Block, block_header::BlockHeader, checkpoint::Checkpoint, compact256::Compact256,
+    error_block::ErrorBlock, hash::HashType, node_chain::NodeChain, outpoint::Outpoint,
+    transaction_output::TransactionOutput,
 };
 
 use crate::serialization::{
@@ -11,21 +12,36 @@ use crate::serialization::{
     serializable_little_endian::SerializableLittleEndian,
 };
 
-use crate::configurations::try_default::TryDefault;
+use crate::{
+    configurations::try_default::TryDefault,
+    connections::network::{Network, DIFFICULTY_ADJUSTMENT_INTERVAL, TARGET_SPACING},
+};
 
 use std::{
     cmp,
+    collections::{HashMap, HashSet},
     io::{Read, Write},
 };
 
-/// It's the internal representation of the block chain
+pub const MAX_HEADERS_SIZE: usize = 2000;
+pub const MAX_BLOCKS_SIZE: usize = 500;
+
+/// It's the internal representation of the block chain. The transactions of the pruned blocks are
+/// discarded, keeping only their header and the outputs they left unspent
 #[derive(Debug, Clone, PartialEq)]
 pub struct BlockChain {
     blocks: Vec<NodeChain>,
     last_blocks: Vec<usize>,
+    checkpoints: Vec<Checkpoint>,
+    network: Option<Network>,
+    prune_depth: Option<u64>,
+    pruned_blocks: HashSet<HashType>,
+    pruned_outputs: HashMap<Outpoint, TransactionOutput>,
 }
 
 impl BlockChain {
+    /// Creates a block chain starting from the given block. It doesn't follow any checkpoints nor check the
+    /// difficulty of the headers until they are given
     pub fn new(block: Block) -> Result<Self, ErrorBlock> {
         let first_node: NodeChain = NodeChain::first(block)?;
 
@@ -35,13 +51,113 @@ impl BlockChain {
         Ok(BlockChain {
             blocks,
             last_blocks,
+            checkpoints: Vec::new(),
+            network: None,
+            prune_depth: None,
+            pruned_blocks: HashSet::new(),
+            pruned_outputs: HashMap::new(),
         })
     }
 
+    /// Changes the checkpoints the headers appended to the block chain must follow
+    pub fn with_checkpoints(mut self, checkpoints: Vec<Checkpoint>) -> Self {
+        self.checkpoints = checkpoints;
+        self
+    }
+
+    /// Makes the headers appended to the block chain follow the difficulty adjustment of the network
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// Changes the amount of the latest blocks that keep their transactions when the block chain is pruned
+    pub fn with_prune_depth(mut self, prune_depth: u64) -> Self {
+        self.prune_depth = Some(prune_depth);
+        self
+    }
+
+    /// Discards the transactions of the blocks deeper than the prune depth, from the oldest one, after adding
+    /// the outputs they left unspent to the pruned outputs. It stops at the first block still missing its
+    /// transactions, so the pruned outputs have all the blocks before it. Returns the amount of blocks pruned
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotGetTxId`: It will appear when the id of a transaction could not be created
+    pub fn prune(&mut self) -> Result<u32, ErrorBlock> {
+        let limit_height = match self
+            .prune_depth
+            .and_then(|prune_depth| self.get_height().checked_sub(prune_depth))
+        {
+            Some(limit_height) => limit_height,
+            None => return Ok(0),
+        };
+
+        let mut indexes: Vec<usize> = (0..self.blocks.len())
+            .filter(|index| self.blocks[*index].height <= limit_height)
+            .collect();
+        indexes.sort_by_key(|index| self.blocks[*index].height);
+
+        let mut pruned_count: u32 = 0;
+        let mut downloading = false;
+        for index in indexes {
+            let node = &mut self.blocks[index];
+            if self.pruned_blocks.contains(&node.header_hash) {
+                downloading = true;
+                continue;
+            }
+
+            if node.block.transactions.is_empty() {
+                if downloading {
+                    break;
+                }
+                continue;
+            }
+            downloading = true;
+
+            for transaction in node.block.transactions.iter() {
+                let transaction_id = match transaction.get_tx_id() {
+                    Ok(transaction_id) => transaction_id,
+                    Err(_) => return Err(ErrorBlock::CouldNotGetTxId),
+                };
+
+                for (index_output, output) in transaction.tx_out.iter().enumerate() {
+                    self.pruned_outputs.insert(
+                        Outpoint::new(transaction_id, index_output as u32),
+                        output.clone(),
+                    );
+                }
+            }
+
+            for transaction in node.block.transactions.iter() {
+                for input in transaction.tx_in.iter() {
+                    self.pruned_outputs.remove(&input.previous_output);
+                }
+            }
+
+            node.block.transactions.clear();
+            self.pruned_blocks.insert(node.header_hash);
+            pruned_count += 1;
+        }
+
+        Ok(pruned_count)
+    }
+
+    /// Returns true if the transactions of the block with the given hash were discarded by the pruning
+    pub fn is_pruned(&self, header_hash: &HashType) -> bool {
+        self.pruned_blocks.contains(header_hash)
+    }
+
+    /// Returns the outputs left unspent by the pruned blocks
+    pub fn get_pruned_outputs(&self) -> &HashMap<Outpoint, TransactionOutput> {
+        &self.pruned_outputs
+    }
+
     /// Appends a block header to the block chain
     ///
     /// ### Error
-    ///  * `ErrorBlock::`
+    ///  * `ErrorBlock::ErrorWithProofOfWork`: It will appear when the header doesn't pass the proof of work
+    ///  * `ErrorBlock::CheckpointMismatch`: It will appear when the header doesn't follow the checkpoints
+    ///  * `ErrorBlock::DifficultyMismatch`: It will appear when the header doesn't have the target of its height
     pub fn append_header(&mut self, header: BlockHeader) -> Result<(), ErrorBlock> {
         if header.proof_of_work() {
             self.append_block(Block::new(header))
@@ -53,7 +169,9 @@ impl BlockChain {
     /// Appends a vector of block headers to the block chain
     ///
     /// ### Error
-    ///  * `ErrorBlock::`
+    ///  * `ErrorBlock::ErrorWithProofOfWork`: It will appear when a header doesn't pass the proof of work
+    ///  * `ErrorBlock::CheckpointMismatch`: It will appear when a header doesn't follow the checkpoints
+    ///  * `ErrorBlock::DifficultyMismatch`: It will appear when a header doesn't have the target of its height
     pub fn append_headers(&mut self, headers: Vec<BlockHeader>) -> Result<u32, ErrorBlock> {
         let mut added_headers = 0;
         for header in headers.iter() {
@@ -62,6 +180,8 @@ impl BlockChain {
                 Err(ErrorBlock::ErrorWithProofOfWork) => {
                     return Err(ErrorBlock::ErrorWithProofOfWork)
                 }
+                Err(ErrorBlock::CheckpointMismatch) => return Err(ErrorBlock::CheckpointMismatch),
+                Err(ErrorBlock::DifficultyMismatch) => return Err(ErrorBlock::DifficultyMismatch),
                 _ => break,
             }
         }
@@ -73,6 +193,8 @@ impl BlockChain {
     ///
     /// ### Error
     ///  * `ErrorBlock::TransactionAlreadyInBlock`: It will appear when the Transaction is already in the block
+    ///  * `ErrorBlock::CheckpointMismatch`: It will appear when the block doesn't follow the checkpoints
+    ///  * `ErrorBlock::DifficultyMismatch`: It will appear when the block doesn't have the target of its height
     pub fn append_block(&mut self, block: Block) -> Result<(), ErrorBlock> {
         for (i, index_last_block) in self.last_blocks.clone().iter().enumerate() {
             let mut last_block = self.get_block_at(*index_last_block)?;
@@ -83,6 +205,8 @@ impl BlockChain {
 
             if last_block.is_previous_of(&block) {
                 let node = NodeChain::new(block, *index_last_block, last_block.height)?;
+                self.follows_checkpoints(&node)?;
+                self.follows_difficulty(&node)?;
                 self.blocks.push(node);
 
                 self.last_blocks[i] = self.blocks.len() - 1;
@@ -98,6 +222,8 @@ impl BlockChain {
 
                 if last_block.is_previous_of(&block) {
                     let node = NodeChain::new(block, index_previous_node, last_block.height)?;
+                    self.follows_checkpoints(&node)?;
+                    self.follows_difficulty(&node)?;
                     self.blocks.push(node);
 
                     self.last_blocks.push(self.blocks.len() - 1);
@@ -109,6 +235,109 @@ impl BlockChain {
         Err(ErrorBlock::CouldNotAppendBlock)
     }
 
+    /// Checks that the node has the hash of the checkpoint at its height, and that it doesn't
+    /// start a fork before a checkpoint the block chain already reached
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CheckpointMismatch`: It will appear when the node doesn't follow the checkpoints
+    fn follows_checkpoints(&self, node: &NodeChain) -> Result<(), ErrorBlock> {
+        let height = self.get_height();
+        for checkpoint in self.checkpoints.iter() {
+            if node.height == checkpoint.height && node.header_hash != checkpoint.header_hash {
+                return Err(ErrorBlock::CheckpointMismatch);
+            }
+
+            if node.height < checkpoint.height && height >= checkpoint.height {
+                return Err(ErrorBlock::CheckpointMismatch);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the node has the target the network expects after its previous node. The difficulty is not
+    /// checked when the block chain doesn't have the network or the blocks the target depends on
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::DifficultyMismatch`: It will appear when the node doesn't have the expected target
+    fn follows_difficulty(&self, node: &NodeChain) -> Result<(), ErrorBlock> {
+        let network = match self.network {
+            Some(network) => network,
+            None => return Ok(()),
+        };
+
+        let previous_node = match node
+            .index_previous_node
+            .and_then(|index| self.blocks.get(index))
+        {
+            Some(previous_node) => previous_node,
+            None => return Ok(()),
+        };
+
+        match self.get_next_target(previous_node, node.block.header.time, network) {
+            Some(target) if target != node.block.header.n_bits => {
+                Err(ErrorBlock::DifficultyMismatch)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the target of the block that follows the given node and has the given time. At the start of a
+    /// difficulty adjustment interval it's calculated from the time the interval took, and in the networks that
+    /// allow it, a block coming twice the expected time after the previous one can use the easiest target
+    fn get_next_target(
+        &self,
+        previous_node: &NodeChain,
+        time: u32,
+        network: Network,
+    ) -> Option<Compact256> {
+        let previous_header = &previous_node.block.header;
+        let height = previous_node.height + 1;
+
+        if height.is_multiple_of(DIFFICULTY_ADJUSTMENT_INTERVAL) {
+            let first_node =
+                self.get_ancestor(previous_node, height - DIFFICULTY_ADJUSTMENT_INTERVAL)?;
+            return Some(previous_header.calculate_next_target(&first_node.block.header, network));
+        }
+
+        if !network.allows_min_difficulty_blocks() {
+            return Some(previous_header.n_bits);
+        }
+
+        let pow_limit = Compact256::from(network.get_pow_limit());
+        if time > previous_header.time.saturating_add(TARGET_SPACING * 2) {
+            return Some(pow_limit);
+        }
+
+        let mut node = previous_node;
+        while !node.height.is_multiple_of(DIFFICULTY_ADJUSTMENT_INTERVAL)
+            && node.block.header.n_bits == pow_limit
+        {
+            node = match node
+                .index_previous_node
+                .and_then(|index| self.blocks.get(index))
+            {
+                Some(previous_node) => previous_node,
+                None => break,
+            };
+        }
+
+        Some(node.block.header.n_bits)
+    }
+
+    /// Returns the node at the given height in the branch of the given node, if the block chain has it
+    fn get_ancestor<'a>(&'a self, node: &'a NodeChain, height: u64) -> Option<&'a NodeChain> {
+        let mut node = node;
+        while node.height > height {
+            node = self.blocks.get(node.index_previous_node?)?;
+        }
+
+        match node.height == height {
+            true => Some(node),
+            false => None,
+        }
+    }
+
     /// Updating the information of a block with its header hash
     ///
     /// ### Error
@@ -147,6 +376,53 @@ impl BlockChain {
             .collect()
     }
 
+    /// Get all completed blocks with their height in the block chain
+    pub fn get_all_blocks_with_height(&self) -> Vec<(Block, u64)> {
+        self.blocks
+            .iter()
+            .filter_map(|node| match !node.block.transactions.is_empty() {
+                true => Some((node.block.clone(), node.height)),
+                false => None,
+            })
+            .collect()
+    }
+
+    /// Get the completed blocks from the given height with their height in the block chain, sorted by height
+    pub fn get_blocks_from_height(&self, height: u64) -> Vec<(Block, u64)> {
+        let mut blocks: Vec<(Block, u64)> = self
+            .blocks
+            .iter()
+            .filter(|node| node.height >= height && !node.block.transactions.is_empty())
+            .map(|node| (node.block.clone(), node.height))
+            .collect();
+
+        blocks.sort_by_key(|(_, height)| *height);
+        blocks
+    }
+
+    /// Get the height of the block with the given hash
+    pub fn get_height_of_block(&self, header_hash: &HashType) -> Option<u64> {
+        self.get_node_chain_with_hash(header_hash)
+            .map(|node| node.height)
+    }
+
+    /// Get the height of the highest block at the end of all forks
+    pub fn get_height(&self) -> u64 {
+        self.get_latests_node_chains()
+            .iter()
+            .map(|node| node.height)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the hash of the block at the end of the longest fork
+    pub fn get_tip_hash(&self) -> Option<HashType> {
+        self.get_latests_node_chains()
+            .iter()
+            .max_by_key(|node| node.height)
+            .map(|node| node.header_hash)
+    }
+
     /// Get the block at the end of all forks
     pub fn latest(&self) -> Vec<Block> {
         let mut latest: Vec<Block> = Vec::new();
@@ -304,7 +580,11 @@ impl BlockChain {
         None
     }
 
-    /// Returns the most reacents out of the headers that match the given hashes
+    /// Returns the most reacents out of the headers that match the given hashes. If none of them matches, it's
+    /// the first block of the chain, so the genesis block of the network the chain belongs to
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::NodeChainReferenceNotFound`: It will appear when the block chain doesn't have blocks
     pub fn get_most_recent_hash(&self, hashes: Vec<HashType>) -> Result<HashType, ErrorBlock> {
         let mut nodes: Vec<NodeChain> = Vec::new();
         for hash in hashes.iter() {
@@ -317,39 +597,76 @@ impl BlockChain {
         }
         match nodes.iter().max_by_key(|node| node.height) {
             Some(node) => Ok(node.header_hash),
-            None => match BlockHeader::generate_genesis_block_header().get_hash256d() {
-                Ok(hash) => Ok(hash),
-                Err(_) => Err(ErrorBlock::ErrorHashingBlockHeader),
+            None => match self.blocks.first() {
+                Some(node) => Ok(node.header_hash),
+                None => Err(ErrorBlock::NodeChainReferenceNotFound),
             },
         }
     }
 
     /// Gets a maximum of 2000 headers from the given hash
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::ErrorCleansingBlockChain`: It will appear when the block chain could not be cleansed
     pub fn get_headers_from_header_hash(
         &mut self,
         header_hash: &HashType,
         stop_hash: &HashType,
     ) -> Result<Vec<BlockHeader>, ErrorBlock> {
-        let mut headers: Vec<BlockHeader> = Vec::new();
-        let mut save = false;
+        if self.cleanse_block_chain().is_err() {
+            return Err(ErrorBlock::ErrorCleansingBlockChain);
+        }
+
+        let nodes = self.get_nodes_from_header_hash(header_hash, stop_hash, MAX_HEADERS_SIZE);
+        Ok(nodes.iter().map(|node| node.block.header).collect())
+    }
 
+    /// Gets a maximum of 500 hashes of the blocks after the given hash, stopping before the first
+    /// block whose transactions are not stored
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::ErrorCleansingBlockChain`: It will appear when the block chain could not be cleansed
+    pub fn get_block_hashes_from_header_hash(
+        &mut self,
+        header_hash: &HashType,
+        stop_hash: &HashType,
+    ) -> Result<Vec<HashType>, ErrorBlock> {
         if self.cleanse_block_chain().is_err() {
             return Err(ErrorBlock::ErrorCleansingBlockChain);
         }
 
+        let nodes = self.get_nodes_from_header_hash(header_hash, stop_hash, MAX_BLOCKS_SIZE);
+
+        Ok(nodes
+            .iter()
+            .take_while(|node| !node.block.transactions.is_empty())
+            .map(|node| node.header_hash)
+            .collect())
+    }
+
+    /// Gets the nodes after the given hash, up to the stop hash or the maximum amount
+    fn get_nodes_from_header_hash(
+        &self,
+        header_hash: &HashType,
+        stop_hash: &HashType,
+        max_size: usize,
+    ) -> Vec<&NodeChain> {
+        let mut nodes: Vec<&NodeChain> = Vec::new();
+        let mut save = false;
+
         for node in self.blocks.iter() {
             if node.header_hash == *header_hash {
                 save = true;
                 continue;
             }
             if save {
-                headers.push(node.block.header);
-                if (node.header_hash == *stop_hash) || (headers.len() >= 2000) {
+                nodes.push(node);
+                if (node.header_hash == *stop_hash) || (nodes.len() >= max_size) {
                     break;
                 }
             }
         }
-        Ok(headers)
+        nodes
     }
 
     /// Gets a block with the given hash
@@ -365,7 +682,8 @@ impl TryDefault for BlockChain {
     type Error = ErrorBlock;
 
     fn try_default() -> Result<Self, Self::Error> {
-        let genesis_header: BlockHeader = BlockHeader::generate_genesis_block_header();
+        let genesis_header: BlockHeader =
+            BlockHeader::generate_genesis_block_header(Network::Testnet3);
         let genesis_block: Block = Block::new(genesis_header);
 
         BlockChain::new(genesis_block)
@@ -391,6 +709,17 @@ impl SerializableInternalOrder for BlockChain {
         header.io_serialize(stream)?;
         block_chain.io_serialize(stream)?;
 
+        (self.pruned_blocks.len() as u64).le_serialize(stream)?;
+        for header_hash in self.pruned_blocks.iter() {
+            header_hash.io_serialize(stream)?;
+        }
+
+        (self.pruned_outputs.len() as u64).le_serialize(stream)?;
+        for (outpoint, output) in self.pruned_outputs.iter() {
+            outpoint.io_serialize(stream)?;
+            output.io_serialize(stream)?;
+        }
+
         Ok(())
     }
 }
@@ -410,9 +739,32 @@ impl DeserializableInternalOrder for BlockChain {
             last_blocks.push(u64::le_deserialize(stream)? as usize);
         }
 
+        let mut pruned_blocks: HashSet<HashType> = HashSet::new();
+        let mut pruned_outputs: HashMap<Outpoint, TransactionOutput> = HashMap::new();
+
+        // The block chains saved before the pruning was added end here, without pruned blocks
+        if let Ok(pruned_blocks_count) = u64::le_deserialize(stream) {
+            for _ in 0..pruned_blocks_count {
+                pruned_blocks.insert(HashType::io_deserialize(stream)?);
+            }
+
+            let pruned_outputs_count = u64::le_deserialize(stream)?;
+            for _ in 0..pruned_outputs_count {
+                pruned_outputs.insert(
+                    Outpoint::io_deserialize(stream)?,
+                    TransactionOutput::io_deserialize(stream)?,
+                );
+            }
+        }
+
         Ok(BlockChain {
             blocks: node_chains,
             last_blocks,
+            checkpoints: Vec::new(),
+            network: None,
+            prune_depth: None,
+            pruned_blocks,
+            pruned_outputs,
         })
     }
 }
@@ -688,4 +1040,213 @@ mod tests {
         let most_recent_hash = blockchain.get_most_recent_hash(hashes).unwrap();
         assert_eq!(most_recent_hash, block_6.header.get_hash256d().unwrap());
     }
+
+    #[test]
+    fn test_08_correct_heights_of_blocks() {
+        let block_1 = create_block([0; 32], 0, 1);
+        let block_2 = create_block(block_1.header.get_hash256d().unwrap(), 1, 2);
+        let block_3 = create_block(block_2.header.get_hash256d().unwrap(), 0, 3);
+
+        let mut block_2_with_transactions = block_2.clone();
+        block_2_with_transactions
+            .append_transaction(create_transaction(0, 1))
+            .unwrap();
+
+        let mut blockchain = BlockChain::new(block_1).unwrap();
+        blockchain.append_block(block_2.clone()).unwrap();
+        blockchain.append_block(block_3.clone()).unwrap();
+        blockchain
+            .update_block(block_2_with_transactions.clone())
+            .unwrap();
+
+        assert_eq!(blockchain.get_height(), 2);
+        assert_eq!(
+            blockchain.get_height_of_block(&block_3.header.get_hash256d().unwrap()),
+            Some(2)
+        );
+        assert_eq!(
+            blockchain.get_all_blocks_with_height(),
+            vec![(block_2_with_transactions.clone(), 1)]
+        );
+        assert_eq!(
+            blockchain.get_blocks_from_height(1),
+            vec![(block_2_with_transactions, 1)]
+        );
+        assert!(blockchain.get_blocks_from_height(2).is_empty());
+    }
+
+    #[test]
+    fn test_09_headers_must_match_the_checkpoints() {
+        let block_1 = create_block([0; 32], 0, 1);
+        let block_2 = create_block(block_1.header.get_hash256d().unwrap(), 0, 2);
+        let other_block_2 = create_block(block_1.header.get_hash256d().unwrap(), 0, 3);
+
+        let checkpoint = Checkpoint::new(1, block_2.header.get_hash256d().unwrap());
+        let mut blockchain = BlockChain::new(block_1)
+            .unwrap()
+            .with_checkpoints(vec![checkpoint]);
+
+        assert!(matches!(
+            blockchain.append_block(other_block_2),
+            Err(ErrorBlock::CheckpointMismatch)
+        ));
+        assert!(blockchain.append_block(block_2).is_ok());
+    }
+
+    #[test]
+    fn test_10_history_before_a_checkpoint_cannot_be_rewritten() {
+        let block_1 = create_block([0; 32], 0, 1);
+        let block_2 = create_block(block_1.header.get_hash256d().unwrap(), 0, 2);
+        let block_3 = create_block(block_2.header.get_hash256d().unwrap(), 0, 3);
+        let other_block_2 = create_block(block_1.header.get_hash256d().unwrap(), 0, 4);
+
+        let checkpoint = Checkpoint::new(2, block_3.header.get_hash256d().unwrap());
+        let mut blockchain = BlockChain::new(block_1.clone())
+            .unwrap()
+            .with_checkpoints(vec![checkpoint]);
+
+        blockchain.append_block(other_block_2).unwrap();
+        blockchain.append_block(block_2).unwrap();
+        blockchain.append_block(block_3).unwrap();
+
+        let late_block_2 = create_block(block_1.header.get_hash256d().unwrap(), 0, 5);
+        assert!(matches!(
+            blockchain.append_block(late_block_2),
+            Err(ErrorBlock::CheckpointMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_11_pruned_blocks_keep_their_header_and_unspent_outputs() {
+        let block_1 = create_block([0; 32], 0, 1);
+        let mut block_2 = create_block(block_1.header.get_hash256d().unwrap(), 1, 2);
+        let funding_transaction = create_transaction(2, 0);
+        block_2
+            .append_transaction(funding_transaction.clone())
+            .unwrap();
+
+        let mut block_3 = create_block(block_2.header.get_hash256d().unwrap(), 1, 3);
+        let mut spending_transaction = create_transaction(3, 1);
+        spending_transaction.tx_in[0].previous_output =
+            Outpoint::new(funding_transaction.get_tx_id().unwrap(), 0);
+        block_3
+            .append_transaction(spending_transaction.clone())
+            .unwrap();
+
+        let mut block_4 = create_block(block_3.header.get_hash256d().unwrap(), 1, 4);
+        block_4
+            .append_transaction(create_transaction(4, 2))
+            .unwrap();
+
+        let mut blockchain = BlockChain::new(block_1).unwrap().with_prune_depth(1);
+        blockchain.append_block(block_2.clone()).unwrap();
+        blockchain.append_block(block_3.clone()).unwrap();
+        blockchain.append_block(block_4.clone()).unwrap();
+
+        assert_eq!(2, blockchain.prune().unwrap());
+        assert_eq!(0, blockchain.prune().unwrap());
+
+        let hash_2 = block_2.header.get_hash256d().unwrap();
+        assert!(blockchain.is_pruned(&hash_2));
+        assert!(!blockchain.is_pruned(&block_4.header.get_hash256d().unwrap()));
+        assert!(blockchain
+            .get_block_with_hash(&hash_2)
+            .unwrap()
+            .transactions
+            .is_empty());
+        assert_eq!(vec![block_4], blockchain.get_all_blocks());
+
+        let spending_outpoint = Outpoint::new(spending_transaction.get_tx_id().unwrap(), 0);
+        assert_eq!(1, blockchain.get_pruned_outputs().len());
+        assert!(blockchain
+            .get_pruned_outputs()
+            .contains_key(&spending_outpoint));
+
+        let mut stream: Vec<u8> = Vec::new();
+        blockchain.io_serialize(&mut stream).unwrap();
+        let deserialized = BlockChain::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert!(deserialized.is_pruned(&hash_2));
+        assert_eq!(
+            blockchain.get_pruned_outputs(),
+            deserialized.get_pruned_outputs()
+        );
+    }
+
+    #[test]
+    fn test_12_block_hashes_stop_at_the_blocks_without_transactions() {
+        let block_1 = create_block([0; 32], 0, 1);
+        let mut block_2 = create_block(block_1.header.get_hash256d().unwrap(), 1, 2);
+        block_2
+            .append_transaction(create_transaction(2, 0))
+            .unwrap();
+        let mut block_3 = create_block(block_2.header.get_hash256d().unwrap(), 1, 3);
+        block_3
+            .append_transaction(create_transaction(3, 1))
+            .unwrap();
+        let block_4 = create_block(block_3.header.get_hash256d().unwrap(), 1, 4);
+
+        let mut blockchain = BlockChain::new(block_1.clone()).unwrap();
+        blockchain.append_block(block_2.clone()).unwrap();
+        blockchain.append_block(block_3.clone()).unwrap();
+        blockchain.append_header(block_4.header).unwrap();
+
+        let block_hashes = blockchain
+            .get_block_hashes_from_header_hash(&block_1.header.get_hash256d().unwrap(), &[0; 32])
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                block_2.header.get_hash256d().unwrap(),
+                block_3.header.get_hash256d().unwrap()
+            ],
+            block_hashes
+        );
+    }
+
+    #[test]
+    fn test_13_headers_must_keep_the_target_of_the_network() {
+        let mut block_1 = create_block([0; 32], 0, 1);
+        block_1.header.n_bits = Compact256::from(Network::Mainnet.get_pow_limit());
+        let mut block_2 = create_block(block_1.header.get_hash256d().unwrap(), 0, 2);
+        block_2.header.n_bits = Compact256::from(0x1c00ffff);
+
+        let mut blockchain = BlockChain::new(block_1.clone())
+            .unwrap()
+            .with_network(Network::Mainnet);
+
+        assert!(matches!(
+            blockchain.append_block(block_2.clone()),
+            Err(ErrorBlock::DifficultyMismatch)
+        ));
+
+        block_2.header.n_bits = block_1.header.n_bits;
+        assert!(blockchain.append_block(block_2).is_ok());
+    }
+
+    #[test]
+    fn test_14_late_blocks_can_use_the_easiest_target_in_the_testnet() {
+        let pow_limit = Compact256::from(Network::Testnet3.get_pow_limit());
+        let target = Compact256::from(0x1c00ffff);
+
+        let mut block_1 = create_block([0; 32], 0, 0);
+        block_1.header.n_bits = target;
+        let mut blockchain = BlockChain::new(block_1.clone())
+            .unwrap()
+            .with_network(Network::Testnet3);
+
+        let mut late_block = create_block(block_1.header.get_hash256d().unwrap(), 0, 2000);
+        late_block.header.n_bits = pow_limit;
+        assert!(blockchain.append_block(late_block.clone()).is_ok());
+
+        let mut early_block = create_block(late_block.header.get_hash256d().unwrap(), 0, 2001);
+        early_block.header.n_bits = pow_limit;
+        assert!(matches!(
+            blockchain.append_block(early_block.clone()),
+            Err(ErrorBlock::DifficultyMismatch)
+        ));
+
+        early_block.header.n_bits = target;
+        assert!(blockchain.append_block(early_block).is_ok());
+    }
 }
@@ -0,0 +1,333 @@
+use super::{
+    block::Block,
+    error_block::ErrorBlock,
+    hash::{hash256d, sip_hash24, HashType},
+};
+
+use crate::{
+    messages::compact_size::CompactSize,
+    serialization::{
+        deserializable_little_endian::DeserializableLittleEndian,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::collections::HashSet;
+
+pub const BASIC_FILTER_TYPE: u8 = 0x00;
+
+const BASIC_FILTER_P: u8 = 19;
+const BASIC_FILTER_M: u64 = 784931;
+
+const OP_RETURN: u8 = 0x6a;
+
+/// It's the basic compact filter of a block (BIP158). It's a Golomb-coded set of the scripts of the
+/// outputs created and spent in the block, so a wallet can know if a block may be relevant without
+/// revealing its addresses to the peers
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockFilter {
+    block_hash: HashType,
+    element_count: u64,
+    encoded: Vec<u8>,
+}
+
+impl BlockFilter {
+    /// Creates the filter of the block with the given elements, the block hash must be the one shown to the user
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotHash`: It will appear when the filter could not be encoded
+    pub fn new(block_hash: HashType, elements: &[Vec<u8>]) -> Result<Self, ErrorBlock> {
+        let elements: HashSet<&Vec<u8>> = elements.iter().collect();
+        let element_count = elements.len() as u64;
+
+        let keys = get_keys(&block_hash);
+        let range = element_count * BASIC_FILTER_M;
+        let mut values: Vec<u64> = elements
+            .iter()
+            .map(|element| hash_to_range(keys, range, element))
+            .collect();
+        values.sort_unstable();
+
+        let mut encoded: Vec<u8> = Vec::new();
+        if CompactSize::new(element_count)
+            .le_serialize(&mut encoded)
+            .is_err()
+        {
+            return Err(ErrorBlock::CouldNotHash);
+        }
+
+        let mut bits: Vec<bool> = Vec::new();
+        let mut last_value = 0;
+        for value in values {
+            golomb_encode(&mut bits, value - last_value);
+            last_value = value;
+        }
+
+        for chunk in bits.chunks(8) {
+            let byte = chunk.iter().enumerate().fold(0u8, |byte, (index, bit)| {
+                byte | ((*bit as u8) << (7 - index))
+            });
+            encoded.push(byte);
+        }
+
+        Ok(BlockFilter {
+            block_hash,
+            element_count,
+            encoded,
+        })
+    }
+
+    /// Creates the basic filter of the block, with the scripts of its outputs and the scripts of the
+    /// outputs spent by its inputs
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotHash`: It will appear when the hash of the block could not be calculated
+    pub fn new_basic(block: &Block, spent_scripts: &[Vec<u8>]) -> Result<Self, ErrorBlock> {
+        let block_hash = match block.header.get_hash256d() {
+            Ok(block_hash) => block_hash,
+            Err(_) => return Err(ErrorBlock::CouldNotHash),
+        };
+
+        let mut elements: Vec<Vec<u8>> = block
+            .transactions
+            .iter()
+            .flat_map(|transaction| transaction.tx_out.iter())
+            .map(|output| output.pk_script.clone())
+            .filter(|script| !script.is_empty() && script[0] != OP_RETURN)
+            .collect();
+
+        elements.extend(
+            spent_scripts
+                .iter()
+                .filter(|script| !script.is_empty())
+                .cloned(),
+        );
+
+        Self::new(block_hash, &elements)
+    }
+
+    /// Creates the filter received from a peer, with the bytes as they are sent in the messages
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::InvalidBlockFilter`: It will appear when the bytes don't start with the amount of elements
+    pub fn from_bytes(block_hash: HashType, encoded: Vec<u8>) -> Result<Self, ErrorBlock> {
+        let element_count = match CompactSize::le_deserialize(&mut encoded.as_slice()) {
+            Ok(element_count) => element_count.value,
+            Err(_) => return Err(ErrorBlock::InvalidBlockFilter),
+        };
+
+        Ok(BlockFilter {
+            block_hash,
+            element_count,
+            encoded,
+        })
+    }
+
+    /// Returns the hash of the block of the filter
+    pub fn get_block_hash(&self) -> HashType {
+        self.block_hash
+    }
+
+    /// Returns the bytes of the filter as they are sent in the messages
+    pub fn get_bytes(&self) -> &[u8] {
+        &self.encoded
+    }
+
+    /// Returns the hash of the filter, in the order it's sent in the messages
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotHash`: It will appear when the filter could not be hashed
+    pub fn get_filter_hash(&self) -> Result<HashType, ErrorBlock> {
+        match hash256d(&self.encoded) {
+            Ok(filter_hash) => Ok(filter_hash),
+            Err(_) => Err(ErrorBlock::CouldNotHash),
+        }
+    }
+
+    /// Returns the header of the filter, which commits to the filter and to the header of the previous block
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotHash`: It will appear when the filter could not be hashed
+    pub fn get_header(&self, previous_header: &HashType) -> Result<HashType, ErrorBlock> {
+        let mut buffer = self.get_filter_hash()?.to_vec();
+        buffer.extend_from_slice(previous_header);
+
+        match hash256d(&buffer) {
+            Ok(header) => Ok(header),
+            Err(_) => Err(ErrorBlock::CouldNotHash),
+        }
+    }
+
+    /// Returns true if any of the scripts may be in the block, and false if none of them is
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::InvalidBlockFilter`: It will appear when the filter ends before all its elements are read
+    pub fn matches_any(&self, scripts: &[Vec<u8>]) -> Result<bool, ErrorBlock> {
+        if self.element_count == 0 || scripts.is_empty() {
+            return Ok(false);
+        }
+
+        let keys = get_keys(&self.block_hash);
+        let range = self.element_count * BASIC_FILTER_M;
+        let mut queries: Vec<u64> = scripts
+            .iter()
+            .map(|script| hash_to_range(keys, range, script))
+            .collect();
+        queries.sort_unstable();
+
+        let mut bit_stream = self.encoded.as_slice();
+        if CompactSize::le_deserialize(&mut bit_stream).is_err() {
+            return Err(ErrorBlock::InvalidBlockFilter);
+        }
+
+        let mut position = (self.encoded.len() - bit_stream.len()) * 8;
+        let mut value = 0;
+        let mut queries = queries.iter().peekable();
+
+        for _ in 0..self.element_count {
+            value += golomb_decode(&self.encoded, &mut position)?;
+
+            while let Some(query) = queries.peek() {
+                if **query == value {
+                    return Ok(true);
+                }
+                if **query > value {
+                    break;
+                }
+                queries.next();
+            }
+
+            if queries.peek().is_none() {
+                return Ok(false);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+/// Returns the keys of the SipHash used by the filter, taken from the block hash in the order of the messages
+fn get_keys(block_hash: &HashType) -> (u64, u64) {
+    let mut internal_hash = *block_hash;
+    internal_hash.reverse();
+
+    let mut first_key = [0; 8];
+    let mut second_key = [0; 8];
+    first_key.copy_from_slice(&internal_hash[0..8]);
+    second_key.copy_from_slice(&internal_hash[8..16]);
+
+    (
+        u64::from_le_bytes(first_key),
+        u64::from_le_bytes(second_key),
+    )
+}
+
+/// Maps the element uniformly to the range [0, range)
+fn hash_to_range(keys: (u64, u64), range: u64, element: &[u8]) -> u64 {
+    ((sip_hash24(keys, element) as u128 * range as u128) >> 64) as u64
+}
+
+/// Writes the value with Golomb-Rice coding: the quotient in unary and the remainder in P bits
+fn golomb_encode(bits: &mut Vec<bool>, value: u64) {
+    let quotient = value >> BASIC_FILTER_P;
+    bits.extend((0..quotient).map(|_| true));
+    bits.push(false);
+    bits.extend((0..BASIC_FILTER_P).rev().map(|bit| (value >> bit) & 1 == 1));
+}
+
+/// Reads a value written with Golomb-Rice coding, starting from the given bit
+///
+/// ### Error
+///  * `ErrorBlock::InvalidBlockFilter`: It will appear when there are not enough bits
+fn golomb_decode(encoded: &[u8], position: &mut usize) -> Result<u64, ErrorBlock> {
+    let mut quotient: u64 = 0;
+    while read_bit(encoded, position)? {
+        quotient += 1;
+    }
+
+    let mut remainder: u64 = 0;
+    for _ in 0..BASIC_FILTER_P {
+        remainder = (remainder << 1) | read_bit(encoded, position)? as u64;
+    }
+
+    Ok((quotient << BASIC_FILTER_P) | remainder)
+}
+
+/// Reads the bit in the position, from the most significant bit of each byte, and advances the position
+fn read_bit(encoded: &[u8], position: &mut usize) -> Result<bool, ErrorBlock> {
+    let byte = match encoded.get(*position / 8) {
+        Some(byte) => *byte,
+        None => return Err(ErrorBlock::InvalidBlockFilter),
+    };
+
+    let bit = (byte >> (7 - *position % 8)) & 1 == 1;
+    *position += 1;
+    Ok(bit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::block_structure::block_header::BlockHeader;
+    use crate::connections::network::Network;
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_01_basic_filter_of_the_genesis_block() {
+        let block_hash = BlockHeader::generate_genesis_block_header(Network::Testnet3)
+            .get_hash256d()
+            .unwrap();
+        let coinbase_script = from_hex(
+            "4104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac",
+        );
+
+        let block_filter =
+            BlockFilter::new(block_hash, std::slice::from_ref(&coinbase_script)).unwrap();
+
+        assert_eq!(from_hex("019dfca8"), block_filter.get_bytes());
+
+        let mut header = block_filter.get_header(&[0; 32]).unwrap();
+        header.reverse();
+        assert_eq!(
+            from_hex("21584579b7eb08997773e5aeff3a7f932700042d0ed2a6129012b7d7ae81b750"),
+            header
+        );
+
+        assert!(block_filter.matches_any(&[coinbase_script]).unwrap());
+        assert!(!block_filter.matches_any(&[vec![0x51]]).unwrap());
+    }
+
+    #[test]
+    fn test_02_filter_matches_every_element_it_has() {
+        let elements: Vec<Vec<u8>> = (0..50u8).map(|index| vec![index; 25]).collect();
+        let block_filter = BlockFilter::new([7; 32], &elements).unwrap();
+
+        for element in elements.iter() {
+            assert!(block_filter
+                .matches_any(std::slice::from_ref(element))
+                .unwrap());
+        }
+
+        let received_filter =
+            BlockFilter::from_bytes([7; 32], block_filter.get_bytes().to_vec()).unwrap();
+        assert!(received_filter
+            .matches_any(&[vec![200; 25], elements[31].clone()])
+            .unwrap());
+        assert!(!received_filter.matches_any(&[vec![200; 25]]).unwrap());
+    }
+
+    #[test]
+    fn test_03_empty_filter_matches_nothing() {
+        let block_filter = BlockFilter::new([7; 32], &[]).unwrap();
+
+        assert_eq!(vec![0x00], block_filter.get_bytes());
+        assert!(!block_filter.matches_any(&[vec![1, 2, 3]]).unwrap());
+    }
+}
@@ -7,6 +7,7 @@ use super::{
 };
 
 use crate::{
+    connections::network::{Network, TARGET_TIMESPAN},
     messages::compact_size::CompactSize,
     serialization::{
         deserializable_big_endian::DeserializableBigEndian,
@@ -22,13 +23,6 @@ use std::io::{Read, Write};
 
 const GENESIS_BLOCK_VERSION: BlockVersion = BlockVersion::version(1);
 const GENESIS_PREVIOUS_BLOCK_HEADER_HASH: HashType = [0; 32];
-const GENESIS_MERKLE_ROOT_HASH: HashType = [
-    0x3b, 0xa3, 0xed, 0xfd, 0x7a, 0x7b, 0x12, 0xb2, 0x7a, 0xc7, 0x2c, 0x3e, 0x67, 0x76, 0x8f, 0x61,
-    0x7f, 0xc8, 0x1b, 0xc3, 0x88, 0x8a, 0x51, 0x32, 0x3a, 0x9f, 0xb8, 0xaa, 0x4b, 0x1e, 0x5e, 0x4a,
-];
-const GENESIS_TIME: u32 = 0x4d49e5da;
-const GENESIS_N_BITS: u32 = 0x1d00ffff;
-const GENESIS_NONCE: u32 = 0x18aea41a;
 const GENESIS_TRANSACTION_COUNT: u64 = 0;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -63,15 +57,15 @@ impl BlockHeader {
         }
     }
 
-    /// Generates the genesis block header
-    pub fn generate_genesis_block_header() -> Self {
+    /// Generates the genesis block header of the given network
+    pub fn generate_genesis_block_header(network: Network) -> Self {
         BlockHeader::new(
             GENESIS_BLOCK_VERSION,
             GENESIS_PREVIOUS_BLOCK_HEADER_HASH,
-            GENESIS_MERKLE_ROOT_HASH,
-            GENESIS_TIME,
-            Compact256::from(GENESIS_N_BITS),
-            GENESIS_NONCE,
+            network.get_genesis_merkle_root_hash(),
+            network.get_genesis_time(),
+            Compact256::from(network.get_pow_limit()),
+            network.get_genesis_nonce(),
             CompactSize::new(GENESIS_TRANSACTION_COUNT),
         )
     }
@@ -91,6 +85,35 @@ impl BlockHeader {
         self.n_bits > compact_hash
     }
 
+    /// Returns the target of the block that follows this one when it starts a difficulty adjustment interval,
+    /// given the first header of the interval this one ends
+    pub fn calculate_next_target(
+        &self,
+        first_header: &BlockHeader,
+        network: Network,
+    ) -> Compact256 {
+        if !network.retargets() {
+            return self.n_bits;
+        }
+
+        let actual_timespan = self
+            .time
+            .saturating_sub(first_header.time)
+            .clamp(TARGET_TIMESPAN / 4, TARGET_TIMESPAN * 4);
+
+        // The testnet4 scales the target of the first block, so the blocks with the easiest target don't change it
+        let target = match network {
+            Network::Testnet4 => first_header.n_bits,
+            _ => self.n_bits,
+        };
+
+        target.scale(
+            actual_timespan,
+            TARGET_TIMESPAN,
+            Compact256::from(network.get_pow_limit()),
+        )
+    }
+
     /// Verifies that the merkle root hash is correct
     pub fn proof_of_inclusion(&self, transactions: &[Transaction]) -> bool {
         let merkle_tree: MerkleTree = match MerkleTree::new(transactions) {
@@ -109,13 +132,7 @@ impl BlockHeader {
     ///  * `ErrorSerialization::ErrorInSerialization`: It will appear when there is an error in the serialization
     pub fn get_hash256d(&self) -> Result<HashType, ErrorSerialization> {
         let mut buffer = vec![];
-
-        self.version.le_serialize(&mut buffer)?;
-        self.previous_block_header_hash.le_serialize(&mut buffer)?;
-        self.merkle_root_hash.be_serialize(&mut buffer)?;
-        self.time.le_serialize(&mut buffer)?;
-        self.n_bits.le_serialize(&mut buffer)?;
-        self.nonce.le_serialize(&mut buffer)?;
+        self.io_serialize_without_count(&mut buffer)?;
 
         let buffer = {
             let mut temp: Vec<u8> = Vec::new();
@@ -138,24 +155,31 @@ impl BlockHeader {
 
         Ok(buffer)
     }
-}
 
-impl SerializableInternalOrder for BlockHeader {
-    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+    /// Serializes the 80 bytes of the header that are hashed and sent in the messages with a single header,
+    /// without the transaction count
+    ///
+    /// ### Error
+    ///  * `ErrorSerialization::ErrorInSerialization`: It will appear when there is an error in the serialization
+    pub fn io_serialize_without_count(
+        &self,
+        stream: &mut dyn Write,
+    ) -> Result<(), ErrorSerialization> {
         self.version.le_serialize(stream)?;
         self.previous_block_header_hash.le_serialize(stream)?;
         self.merkle_root_hash.be_serialize(stream)?;
         self.time.le_serialize(stream)?;
         self.n_bits.le_serialize(stream)?;
         self.nonce.le_serialize(stream)?;
-        self.transaction_count.le_serialize(stream)?;
 
         Ok(())
     }
-}
 
-impl DeserializableInternalOrder for BlockHeader {
-    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+    /// Deserializes the 80 bytes of the header without the transaction count, leaving it in zero
+    ///
+    /// ### Error
+    ///  * `ErrorSerialization::ErrorInDeserialization`: It will appear when there is an error in the deserialization
+    pub fn io_deserialize_without_count(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
         Ok(BlockHeader {
             version: BlockVersion::le_deserialize(stream)?,
             previous_block_header_hash: HashType::le_deserialize(stream)?,
@@ -163,18 +187,36 @@ impl DeserializableInternalOrder for BlockHeader {
             time: u32::le_deserialize(stream)?,
             n_bits: Compact256::le_deserialize(stream)?,
             nonce: u32::le_deserialize(stream)?,
-            transaction_count: CompactSize::le_deserialize(stream)?,
+            transaction_count: CompactSize::new(0),
         })
     }
 }
 
+impl SerializableInternalOrder for BlockHeader {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.io_serialize_without_count(stream)?;
+        self.transaction_count.le_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for BlockHeader {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let mut header = BlockHeader::io_deserialize_without_count(stream)?;
+        header.transaction_count = CompactSize::le_deserialize(stream)?;
+
+        Ok(header)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_01_correct_genesis_block_header() {
-        let genesis_block_header = BlockHeader::generate_genesis_block_header();
+        let genesis_block_header = BlockHeader::generate_genesis_block_header(Network::Testnet3);
 
         assert_eq!(genesis_block_header.version, GENESIS_BLOCK_VERSION);
         assert_eq!(
@@ -183,11 +225,11 @@ mod tests {
         );
         assert_eq!(
             genesis_block_header.merkle_root_hash,
-            GENESIS_MERKLE_ROOT_HASH
+            Network::Testnet3.get_genesis_merkle_root_hash()
         );
-        assert_eq!(genesis_block_header.time, GENESIS_TIME);
-        assert_eq!(u32::from(genesis_block_header.n_bits), GENESIS_N_BITS);
-        assert_eq!(genesis_block_header.nonce, GENESIS_NONCE);
+        assert_eq!(genesis_block_header.time, 0x4d49e5da);
+        assert_eq!(u32::from(genesis_block_header.n_bits), 0x1d00ffff);
+        assert_eq!(genesis_block_header.nonce, 0x18aea41a);
         assert_eq!(
             genesis_block_header.transaction_count,
             CompactSize::new(GENESIS_TRANSACTION_COUNT)
@@ -196,7 +238,7 @@ mod tests {
 
     #[test]
     fn test_02_correct_header_serialization() {
-        let genesis_block_header = BlockHeader::generate_genesis_block_header();
+        let genesis_block_header = BlockHeader::generate_genesis_block_header(Network::Testnet3);
 
         let mut buffer = vec![];
 
@@ -240,7 +282,7 @@ mod tests {
 
     #[test]
     fn test_05_correct_hash_of_header() {
-        let genesis_block_header = BlockHeader::generate_genesis_block_header();
+        let genesis_block_header = BlockHeader::generate_genesis_block_header(Network::Testnet3);
         let mut genesis_hash = [
             0x43, 0x49, 0x7f, 0xd7, 0xf8, 0x26, 0x95, 0x71, 0x08, 0xf4, 0xa3, 0x0f, 0xd9, 0xce,
             0xc3, 0xae, 0xba, 0x79, 0x97, 0x20, 0x84, 0xe9, 0x0e, 0xad, 0x01, 0xea, 0x33, 0x09,
@@ -249,4 +291,49 @@ mod tests {
         genesis_hash.reverse();
         assert_eq!(genesis_block_header.get_hash256d().unwrap(), genesis_hash);
     }
+
+    #[test]
+    fn test_06_correct_regtest_genesis_block_header() {
+        let regtest_block_header = BlockHeader::generate_genesis_block_header(Network::Regtest);
+        let mut regtest_hash = [
+            0x06, 0x22, 0x6e, 0x46, 0x11, 0x1a, 0x0b, 0x59, 0xca, 0xaf, 0x12, 0x60, 0x43, 0xeb,
+            0x5b, 0xbf, 0x28, 0xc3, 0x4f, 0x3a, 0x5e, 0x33, 0x2a, 0x1f, 0xc7, 0xb2, 0xb7, 0x3c,
+            0xf1, 0x88, 0x91, 0x0f,
+        ];
+        regtest_hash.reverse();
+
+        assert_eq!(regtest_block_header.get_hash256d().unwrap(), regtest_hash);
+        assert!(regtest_block_header.proof_of_work());
+    }
+
+    #[test]
+    fn test_07_next_target_follows_the_time_of_the_interval() {
+        let mut first_header = BlockHeader::generate_genesis_block_header(Network::Mainnet);
+        first_header.time = 1261130161;
+        let mut last_header = first_header;
+        last_header.time = 1262152739;
+
+        assert_eq!(
+            Compact256::from(0x1d00d86a),
+            last_header.calculate_next_target(&first_header, Network::Mainnet)
+        );
+
+        last_header.time = first_header.time + TARGET_TIMESPAN * 8;
+        assert_eq!(
+            Compact256::from(0x1d00ffff),
+            last_header.calculate_next_target(&first_header, Network::Mainnet)
+        );
+    }
+
+    #[test]
+    fn test_08_regtest_does_not_retarget() {
+        let first_header = BlockHeader::generate_genesis_block_header(Network::Regtest);
+        let mut last_header = first_header;
+        last_header.time = first_header.time + 1;
+
+        assert_eq!(
+            first_header.n_bits,
+            last_header.calculate_next_target(&first_header, Network::Regtest)
+        );
+    }
 }
@@ -0,0 +1,78 @@
+use super::hash::HashType;
+
+/// The checkpoints of the testnet3, the header hashes are in the order they are shown in a block explorer
+pub const TESTNET_CHECKPOINTS: [Checkpoint; 2] = [
+    Checkpoint::new(
+        0,
+        [
+            0x00, 0x00, 0x00, 0x00, 0x09, 0x33, 0xea, 0x01, 0xad, 0x0e, 0xe9, 0x84, 0x20, 0x97,
+            0x79, 0xba, 0xae, 0xc3, 0xce, 0xd9, 0x0f, 0xa3, 0xf4, 0x08, 0x71, 0x95, 0x26, 0xf8,
+            0xd7, 0x7f, 0x49, 0x43,
+        ],
+    ),
+    Checkpoint::new(
+        546,
+        [
+            0x00, 0x00, 0x00, 0x00, 0x2a, 0x93, 0x6c, 0xa7, 0x63, 0x90, 0x4c, 0x3c, 0x35, 0xfc,
+            0xe2, 0xf3, 0x55, 0x6c, 0x55, 0x9c, 0x02, 0x14, 0x34, 0x5d, 0x31, 0xb1, 0xbc, 0xeb,
+            0xf7, 0x6a, 0xcb, 0x70,
+        ],
+    ),
+];
+
+/// The checkpoints of the mainnet, the header hashes are in the order they are shown in a block explorer
+pub const MAINNET_CHECKPOINTS: [Checkpoint; 2] = [
+    Checkpoint::new(
+        0,
+        [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0xd6, 0x68, 0x9c, 0x08, 0x5a, 0xe1, 0x65, 0x83,
+            0x1e, 0x93, 0x4f, 0xf7, 0x63, 0xae, 0x46, 0xa2, 0xa6, 0xc1, 0x72, 0xb3, 0xf1, 0xb6,
+            0x0a, 0x8c, 0xe2, 0x6f,
+        ],
+    ),
+    Checkpoint::new(
+        11111,
+        [
+            0x00, 0x00, 0x00, 0x00, 0x69, 0xe2, 0x44, 0xf7, 0x3d, 0x78, 0xe8, 0xfd, 0x29, 0xba,
+            0x2f, 0xd2, 0xed, 0x61, 0x8b, 0xd6, 0xfa, 0x2e, 0xe9, 0x25, 0x59, 0xf5, 0x42, 0xfd,
+            0xb2, 0x6e, 0x7c, 0x1d,
+        ],
+    ),
+];
+
+/// The checkpoints of the testnet4, the header hashes are in the order they are shown in a block explorer
+pub const TESTNET4_CHECKPOINTS: [Checkpoint; 1] = [Checkpoint::new(
+    0,
+    [
+        0x00, 0x00, 0x00, 0x00, 0xda, 0x84, 0xf2, 0xba, 0xfb, 0xbc, 0x53, 0xde, 0xe2, 0x5a, 0x72,
+        0xae, 0x50, 0x7f, 0xf4, 0x91, 0x4b, 0x86, 0x7c, 0x56, 0x5b, 0xe3, 0x50, 0xb0, 0xda, 0x8b,
+        0xf0, 0x43,
+    ],
+)];
+
+/// The checkpoints of the default signet, the header hashes are in the order they are shown in a block explorer
+pub const SIGNET_CHECKPOINTS: [Checkpoint; 1] = [Checkpoint::new(
+    0,
+    [
+        0x00, 0x00, 0x00, 0x08, 0x81, 0x98, 0x73, 0xe9, 0x25, 0x42, 0x2c, 0x1f, 0xf0, 0xf9, 0x9f,
+        0x7c, 0xc9, 0xbb, 0xb2, 0x32, 0xaf, 0x63, 0xa0, 0x77, 0xa4, 0x80, 0xa3, 0x63, 0x3b, 0xee,
+        0x1e, 0xf6,
+    ],
+)];
+
+/// It's a block known to be at a given height of the block chain. The headers at that height must
+/// have its hash, so the history before it can't be rewritten
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Checkpoint {
+    pub height: u64,
+    pub header_hash: HashType,
+}
+
+impl Checkpoint {
+    pub const fn new(height: u64, header_hash: HashType) -> Self {
+        Checkpoint {
+            height,
+            header_hash,
+        }
+    }
+}
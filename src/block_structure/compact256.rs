@@ -14,6 +14,15 @@ use std::{
 const BYTES_OF_SIGNIFICAND: u8 = 3;
 const MAX_EXPONENT: u8 = 0x1F;
 
+/// The amount of 32 bits words in a number of 256 bits
+const WORDS_OF_TARGET: usize = 8;
+
+/// The bit of the mantissa that marks a negative number, so it's never set when encoding
+const SIGN_BIT: u32 = 0x00800000;
+
+/// It represents the number of 256 bits of a target as words, from the least significant one
+type TargetWords = [u32; WORDS_OF_TARGET];
+
 /// It represents a number of 256 bits with 4 bytes
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Compact256 {
@@ -21,6 +30,82 @@ pub struct Compact256 {
     pub exponent: u8,
 }
 
+impl Compact256 {
+    /// Returns the target multiplied by the numerator and divided by the denominator, as the difficulty
+    /// adjustment does. If the result is easier than the limit, it returns the limit
+    pub fn scale(&self, numerator: u32, denominator: u32, limit: Compact256) -> Compact256 {
+        let mut words = self.to_words();
+
+        let mut carry: u64 = 0;
+        for word in words.iter_mut() {
+            let product = (*word as u64) * (numerator as u64) + carry;
+            *word = product as u32;
+            carry = product >> 32;
+        }
+
+        let overflows = carry != 0;
+        let mut remainder: u64 = 0;
+        for word in words.iter_mut().rev() {
+            let dividend = (remainder << 32) | (*word as u64);
+            *word = (dividend / denominator as u64) as u32;
+            remainder = dividend % denominator as u64;
+        }
+
+        let limit_words = limit.to_words();
+        if overflows || words.iter().rev().cmp(limit_words.iter().rev()) == Ordering::Greater {
+            return limit;
+        }
+
+        Compact256::from_words(&words)
+    }
+
+    /// Expands the compact form into the number of 256 bits it represents
+    fn to_words(self) -> TargetWords {
+        let compact: u32 = self.into();
+        let mut words: TargetWords = [0; WORDS_OF_TARGET];
+
+        let mantissa = (compact & 0x007fffff) as u64;
+        let exponent = self.exponent as i64;
+        let shift = 8 * (exponent - BYTES_OF_SIGNIFICAND as i64);
+        for bit in 0..64 {
+            let position = bit + shift;
+            if mantissa & (1 << bit) == 0 || !(0..256).contains(&position) {
+                continue;
+            }
+            words[position as usize / 32] |= 1 << (position % 32);
+        }
+
+        words
+    }
+
+    /// Encodes the number of 256 bits in its compact form, keeping its three most significant bytes
+    fn from_words(words: &TargetWords) -> Compact256 {
+        let bits = match words.iter().rposition(|word| *word != 0) {
+            Some(index) => index * 32 + (32 - words[index].leading_zeros() as usize),
+            None => 0,
+        };
+        let mut exponent = bits.div_ceil(8);
+
+        let mut mantissa: u32 = 0;
+        for byte in 0..BYTES_OF_SIGNIFICAND as usize {
+            let position = (exponent + byte) as i64 - BYTES_OF_SIGNIFICAND as i64;
+            if position < 0 {
+                continue;
+            }
+            let position = position as usize;
+            let value = (words[position / 4] >> (8 * (position % 4))) & 0xff;
+            mantissa |= value << (8 * byte);
+        }
+
+        if mantissa & SIGN_BIT != 0 {
+            mantissa >>= 8;
+            exponent += 1;
+        }
+
+        Compact256::from(((exponent as u32) << 24) | mantissa)
+    }
+}
+
 impl From<u32> for Compact256 {
     fn from(value: u32) -> Self {
         let values: [u8; 4] = value.to_be_bytes();
@@ -140,4 +225,31 @@ mod tests {
         let actual_compact256 = Compact256::from(0x1F43497F);
         assert_eq!(hash_compact256, actual_compact256);
     }
+
+    #[test]
+    fn test_04_correct_compact256_scale() {
+        let target = Compact256::from(0x1d00ffff);
+        let limit = Compact256::from(0x1d00ffff);
+
+        assert_eq!(
+            Compact256::from(0x1d00d86a),
+            target.scale(1022578, 1209600, limit)
+        );
+        assert_eq!(target, target.scale(1, 1, limit));
+    }
+
+    #[test]
+    fn test_05_compact256_scale_does_not_go_above_the_limit() {
+        let limit = Compact256::from(0x207fffff);
+
+        assert_eq!(limit, limit.scale(4, 1, limit));
+        assert_eq!(
+            Compact256::from(0x1d00ffff),
+            Compact256::from(0x1d00ffff).scale(4, 1, Compact256::from(0x1d00ffff))
+        );
+        assert_eq!(
+            Compact256::from(0x1d03fffc),
+            Compact256::from(0x1d00ffff).scale(4, 1, limit)
+        );
+    }
 }
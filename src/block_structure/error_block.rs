@@ -42,4 +42,22 @@ pub enum ErrorBlock {
 
     /// It will appear when there is an error disposing of branches
     ErrorCleansingBlockChain,
+
+    /// It will appear when a header doesn't match a checkpoint or tries to rewrite the history before one
+    CheckpointMismatch,
+
+    /// It will appear when a header doesn't have the target the difficulty adjustment expects at its height
+    DifficultyMismatch,
+
+    /// It will appear when a partial merkle tree doesn't have the hashes and flags of a valid tree
+    InvalidPartialMerkleTree,
+
+    /// It will appear when the root of a partial merkle tree is not the merkle root of the header
+    MerkleRootMismatch,
+
+    /// It will appear when a compact block filter ends before all its elements are read
+    InvalidBlockFilter,
+
+    /// It will appear when no nonce gives a header hash under the target of the block
+    CouldNotMine,
 }
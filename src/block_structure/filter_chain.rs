@@ -0,0 +1,593 @@
+use super::{
+    block::Block, block_chain::BlockChain, block_filter::BlockFilter, error_block::ErrorBlock,
+    hash::HashType, outpoint::Outpoint,
+};
+
+use crate::{
+    configurations::try_default::TryDefault,
+    messages::compact_size::CompactSize,
+    serialization::{
+        deserializable_internal_order::DeserializableInternalOrder,
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization,
+        serializable_internal_order::SerializableInternalOrder,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Read, Write},
+};
+
+pub const MAX_GET_CFILTERS_SIZE: u64 = 1000;
+pub const MAX_GET_CFHEADERS_SIZE: u64 = 2000;
+pub const CFCHECKPT_INTERVAL: u64 = 1000;
+
+const FIRST_PREVIOUS_HEADER: HashType = [0; 32];
+
+/// It's the chain of the basic filters of the blocks (BIP157), with the header of each filter committing
+/// to the ones before it. The chain starts at the first block downloaded, and only follows one branch.
+/// The filters of the blocks that spend outputs created before the chain lack the scripts of those outputs,
+/// so they are not served, and the headers are only served when the chain starts at the genesis block
+#[derive(Debug, Clone, Default)]
+pub struct FilterChain {
+    first_height: u64,
+    filters: Vec<BlockFilter>,
+    headers: Vec<HashType>,
+    positions: HashMap<HashType, usize>,
+    spendable_scripts: HashMap<Outpoint, Vec<u8>>,
+    incomplete_filters: HashSet<HashType>,
+}
+
+impl FilterChain {
+    /// Creates the filters of the blocks in the block chain
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotHash`: It will appear when a block or filter could not be hashed
+    pub fn from_block_chain(block_chain: &BlockChain) -> Result<Self, ErrorBlock> {
+        let mut filter_chain = FilterChain::default();
+        filter_chain.update(block_chain)?;
+
+        Ok(filter_chain)
+    }
+
+    /// Adds the filters of the blocks of the block chain that are after the last filter
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotHash`: It will appear when a block or filter could not be hashed
+    pub fn update(&mut self, block_chain: &BlockChain) -> Result<(), ErrorBlock> {
+        let next_height = match self.get_tip_height() {
+            Some(tip_height) => tip_height + 1,
+            None => 0,
+        };
+
+        for (block, height) in block_chain.get_blocks_from_height(next_height) {
+            self.add_block(&block, height)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds the filter of the block if it's the next one of the chain. Returns false if the block was not added
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotHash`: It will appear when the block or its filter could not be hashed
+    pub fn add_block(&mut self, block: &Block, height: u64) -> Result<bool, ErrorBlock> {
+        if let (Some(tip_height), Some(last_filter)) = (self.get_tip_height(), self.filters.last())
+        {
+            if height != tip_height + 1
+                || block.header.previous_block_header_hash != last_filter.get_block_hash()
+            {
+                return Ok(false);
+            }
+        }
+
+        let mut spent_scripts: Vec<Vec<u8>> = Vec::new();
+        let mut is_complete = true;
+        for (position, transaction) in block.transactions.iter().enumerate() {
+            for input in transaction.tx_in.iter() {
+                match self.spendable_scripts.remove(&input.previous_output) {
+                    Some(script) => spent_scripts.push(script),
+                    None => is_complete &= position == 0,
+                }
+            }
+
+            let transaction_id = match transaction.get_tx_id() {
+                Ok(transaction_id) => transaction_id,
+                Err(_) => return Err(ErrorBlock::CouldNotGetTxId),
+            };
+
+            for (index, output) in transaction.tx_out.iter().enumerate() {
+                self.spendable_scripts.insert(
+                    Outpoint::new(transaction_id, index as u32),
+                    output.pk_script.clone(),
+                );
+            }
+        }
+
+        let filter = BlockFilter::new_basic(block, &spent_scripts)?;
+        let previous_header = match self.headers.last() {
+            Some(previous_header) => *previous_header,
+            None => {
+                self.first_height = height;
+                FIRST_PREVIOUS_HEADER
+            }
+        };
+
+        if !is_complete {
+            self.incomplete_filters.insert(filter.get_block_hash());
+        }
+
+        self.headers.push(filter.get_header(&previous_header)?);
+        self.positions
+            .insert(filter.get_block_hash(), self.filters.len());
+        self.filters.push(filter);
+
+        Ok(true)
+    }
+
+    /// Returns the height of the last block with a filter
+    pub fn get_tip_height(&self) -> Option<u64> {
+        match self.filters.is_empty() {
+            true => None,
+            false => Some(self.first_height + self.filters.len() as u64 - 1),
+        }
+    }
+
+    /// Returns true if the last filter is of a block of the block chain at the same height, so the chain
+    /// can be updated with the blocks after it. An empty chain follows every block chain
+    pub fn follows(&self, block_chain: &BlockChain) -> bool {
+        match (self.filters.last(), self.get_tip_height()) {
+            (Some(last_filter), Some(tip_height)) => {
+                block_chain.get_height_of_block(&last_filter.get_block_hash()) == Some(tip_height)
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns true if the chain starts at the genesis block and every filter has the scripts of the
+    /// outputs spent, so its headers are the same ones other nodes have
+    pub fn is_complete(&self) -> bool {
+        self.first_height == 0 && self.incomplete_filters.is_empty()
+    }
+
+    /// Returns the filter of the block with the given hash
+    pub fn get_filter(&self, block_hash: &HashType) -> Option<&BlockFilter> {
+        self.positions
+            .get(block_hash)
+            .map(|position| &self.filters[*position])
+    }
+
+    /// Returns false if the filter of the block surely has none of the scripts. Returns true if it may have
+    /// any of them, or if the block doesn't have a filter with all the scripts spent to tell
+    pub fn may_match(&self, block_hash: &HashType, scripts: &[Vec<u8>]) -> bool {
+        if self.incomplete_filters.contains(block_hash) {
+            return true;
+        }
+
+        match self.get_filter(block_hash) {
+            Some(filter) => filter.matches_any(scripts).unwrap_or(true),
+            None => true,
+        }
+    }
+
+    /// Returns the filters from the start height to the block with the stop hash. Returns None if the range
+    /// is not in the chain, it's bigger than the one allowed or it has a filter without all the scripts spent
+    pub fn get_filters(&self, start_height: u64, stop_hash: &HashType) -> Option<Vec<BlockFilter>> {
+        let (start, stop) = self.get_range(start_height, stop_hash, MAX_GET_CFILTERS_SIZE)?;
+        let filters = &self.filters[start..=stop];
+
+        if filters
+            .iter()
+            .any(|filter| self.incomplete_filters.contains(&filter.get_block_hash()))
+        {
+            return None;
+        }
+
+        Some(filters.to_vec())
+    }
+
+    /// Returns the header of the filter before the start height and the hashes of the filters from the start height
+    /// to the block with the stop hash. Returns None if the range is not in the chain, it's bigger than the one allowed
+    /// or the chain is not complete
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotHash`: It will appear when a filter could not be hashed
+    pub fn get_filter_hashes(
+        &self,
+        start_height: u64,
+        stop_hash: &HashType,
+    ) -> Result<Option<(HashType, Vec<HashType>)>, ErrorBlock> {
+        if !self.is_complete() {
+            return Ok(None);
+        }
+
+        let (start, stop) = match self.get_range(start_height, stop_hash, MAX_GET_CFHEADERS_SIZE) {
+            Some(range) => range,
+            None => return Ok(None),
+        };
+
+        let previous_header = match start {
+            0 => FIRST_PREVIOUS_HEADER,
+            start => self.headers[start - 1],
+        };
+
+        let mut filter_hashes: Vec<HashType> = Vec::new();
+        for filter in self.filters[start..=stop].iter() {
+            filter_hashes.push(filter.get_filter_hash()?);
+        }
+
+        Ok(Some((previous_header, filter_hashes)))
+    }
+
+    /// Returns the headers of the filters at every checkpoint interval up to the block with the stop hash.
+    /// Returns None if the block is not in the chain or the chain is not complete
+    pub fn get_checkpoints(&self, stop_hash: &HashType) -> Option<Vec<HashType>> {
+        if !self.is_complete() {
+            return None;
+        }
+
+        let stop = *self.positions.get(stop_hash)?;
+
+        let checkpoints = (1..=stop as u64 / CFCHECKPT_INTERVAL)
+            .map(|checkpoint| self.headers[(checkpoint * CFCHECKPT_INTERVAL) as usize])
+            .collect();
+
+        Some(checkpoints)
+    }
+
+    /// Returns the positions of the filters from the start height to the block with the stop hash
+    fn get_range(
+        &self,
+        start_height: u64,
+        stop_hash: &HashType,
+        max_size: u64,
+    ) -> Option<(usize, usize)> {
+        let stop = *self.positions.get(stop_hash)?;
+        let start = start_height.checked_sub(self.first_height)? as usize;
+
+        if start > stop || (stop - start) as u64 >= max_size {
+            return None;
+        }
+
+        Some((start, stop))
+    }
+}
+
+impl TryDefault for FilterChain {
+    type Error = ErrorBlock;
+
+    fn try_default() -> Result<Self, Self::Error> {
+        Ok(FilterChain::default())
+    }
+}
+
+impl SerializableInternalOrder for FilterChain {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.first_height.le_serialize(stream)?;
+
+        (self.filters.len() as u64).le_serialize(stream)?;
+        for (filter, header) in self.filters.iter().zip(self.headers.iter()) {
+            filter.get_block_hash().io_serialize(stream)?;
+            serialize_bytes(filter.get_bytes(), stream)?;
+            header.io_serialize(stream)?;
+        }
+
+        (self.spendable_scripts.len() as u64).le_serialize(stream)?;
+        for (outpoint, script) in self.spendable_scripts.iter() {
+            outpoint.io_serialize(stream)?;
+            serialize_bytes(script, stream)?;
+        }
+
+        (self.incomplete_filters.len() as u64).le_serialize(stream)?;
+        for block_hash in self.incomplete_filters.iter() {
+            block_hash.io_serialize(stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for FilterChain {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let first_height = u64::le_deserialize(stream)?;
+
+        let filters_len = u64::le_deserialize(stream)?;
+        let mut filters: Vec<BlockFilter> = Vec::new();
+        let mut headers: Vec<HashType> = Vec::new();
+        let mut positions: HashMap<HashType, usize> = HashMap::new();
+        for position in 0..filters_len as usize {
+            let block_hash = HashType::io_deserialize(stream)?;
+            let filter = match BlockFilter::from_bytes(block_hash, deserialize_bytes(stream)?) {
+                Ok(filter) => filter,
+                Err(error) => {
+                    return Err(ErrorSerialization::ErrorInDeserialization(format!(
+                        "Error while deserializing the filter of a block: {:?}",
+                        error
+                    )))
+                }
+            };
+
+            positions.insert(block_hash, position);
+            filters.push(filter);
+            headers.push(HashType::io_deserialize(stream)?);
+        }
+
+        let spendable_scripts_len = u64::le_deserialize(stream)?;
+        let mut spendable_scripts: HashMap<Outpoint, Vec<u8>> = HashMap::new();
+        for _ in 0..spendable_scripts_len {
+            let outpoint = Outpoint::io_deserialize(stream)?;
+            spendable_scripts.insert(outpoint, deserialize_bytes(stream)?);
+        }
+
+        let incomplete_filters_len = u64::le_deserialize(stream)?;
+        let mut incomplete_filters: HashSet<HashType> = HashSet::new();
+        for _ in 0..incomplete_filters_len {
+            incomplete_filters.insert(HashType::io_deserialize(stream)?);
+        }
+
+        Ok(FilterChain {
+            first_height,
+            filters,
+            headers,
+            positions,
+            spendable_scripts,
+            incomplete_filters,
+        })
+    }
+}
+
+/// Writes the bytes with their length before them
+fn serialize_bytes(bytes: &[u8], stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+    CompactSize::new(bytes.len() as u64).le_serialize(stream)?;
+    bytes.io_serialize(stream)
+}
+
+/// Reads bytes with their length before them
+fn deserialize_bytes(stream: &mut dyn Read) -> Result<Vec<u8>, ErrorSerialization> {
+    let size = CompactSize::le_deserialize(stream)?.value as usize;
+    let mut bytes: Vec<u8> = vec![0; size];
+    if stream.read_exact(&mut bytes).is_err() {
+        return Err(ErrorSerialization::ErrorWhileReading);
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        block_structure::{
+            block_header::BlockHeader, block_version::BlockVersion, compact256::Compact256,
+            transaction::Transaction, transaction_input::TransactionInput,
+            transaction_output::TransactionOutput,
+        },
+        messages::compact_size::CompactSize,
+    };
+
+    fn create_block(previous_hash: HashType, transaction: Transaction) -> Block {
+        let mut block = Block::new(BlockHeader::new(
+            BlockVersion::version(1),
+            previous_hash,
+            [0; 32],
+            0,
+            Compact256::from(u32::MAX),
+            0,
+            CompactSize::new(0),
+        ));
+        block.append_transaction(transaction).unwrap();
+        block
+    }
+
+    fn create_transaction(previous_output: Outpoint, pk_script: Vec<u8>) -> Transaction {
+        Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(previous_output, vec![1, 2, 3], 24)],
+            tx_out: vec![TransactionOutput {
+                value: 10,
+                pk_script,
+            }],
+            time: 0,
+        }
+    }
+
+    #[test]
+    fn test_01_filters_include_the_scripts_spent() {
+        let funding_transaction = create_transaction(Outpoint::new([1; 32], 0), vec![0x51; 25]);
+        let first_block = create_block([0; 32], funding_transaction.clone());
+        let first_hash = first_block.header.get_hash256d().unwrap();
+
+        let spending_transaction = create_transaction(
+            Outpoint::new(funding_transaction.get_tx_id().unwrap(), 0),
+            vec![0x52; 25],
+        );
+        let second_block = create_block(first_hash, spending_transaction);
+        let second_hash = second_block.header.get_hash256d().unwrap();
+
+        let mut filter_chain = FilterChain::default();
+        assert!(filter_chain.add_block(&first_block, 10).unwrap());
+        assert!(filter_chain.add_block(&second_block, 11).unwrap());
+        assert!(!filter_chain.add_block(&second_block, 11).unwrap());
+
+        assert_eq!(Some(11), filter_chain.get_tip_height());
+        assert!(filter_chain
+            .get_filter(&second_hash)
+            .unwrap()
+            .matches_any(&[vec![0x51; 25]])
+            .unwrap());
+    }
+
+    #[test]
+    fn test_02_filter_headers_commit_to_the_previous_ones() {
+        let first_block = create_block(
+            [0; 32],
+            create_transaction(Outpoint::new([1; 32], 0), vec![0x51; 25]),
+        );
+        let first_hash = first_block.header.get_hash256d().unwrap();
+        let second_block = create_block(
+            first_hash,
+            create_transaction(Outpoint::new([2; 32], 0), vec![0x52; 25]),
+        );
+        let second_hash = second_block.header.get_hash256d().unwrap();
+
+        let mut filter_chain = FilterChain::default();
+        filter_chain.add_block(&first_block, 0).unwrap();
+        filter_chain.add_block(&second_block, 1).unwrap();
+        assert!(filter_chain.is_complete());
+
+        let (previous_header, filter_hashes) = filter_chain
+            .get_filter_hashes(1, &second_hash)
+            .unwrap()
+            .unwrap();
+
+        let first_filter = filter_chain.get_filter(&first_hash).unwrap();
+        assert_eq!(first_filter.get_header(&[0; 32]).unwrap(), previous_header);
+        assert_eq!(
+            vec![filter_chain
+                .get_filter(&second_hash)
+                .unwrap()
+                .get_filter_hash()
+                .unwrap()],
+            filter_hashes
+        );
+
+        assert_eq!(Some(Vec::new()), filter_chain.get_checkpoints(&second_hash));
+        assert_eq!(2, filter_chain.get_filters(0, &second_hash).unwrap().len());
+        assert!(filter_chain.get_filters(2, &second_hash).is_none());
+    }
+
+    #[test]
+    fn test_03_chain_after_genesis_only_serves_the_filters_with_all_the_scripts_spent() {
+        let first_block = create_block(
+            [0; 32],
+            create_transaction(Outpoint::new([1; 32], 0), vec![0x51; 25]),
+        );
+        let first_hash = first_block.header.get_hash256d().unwrap();
+
+        let mut second_block = create_block(
+            first_hash,
+            create_transaction(Outpoint::new([2; 32], 0), vec![0x52; 25]),
+        );
+        second_block
+            .append_transaction(create_transaction(
+                Outpoint::new([3; 32], 0),
+                vec![0x53; 25],
+            ))
+            .unwrap();
+        let second_hash = second_block.header.get_hash256d().unwrap();
+
+        let mut filter_chain = FilterChain::default();
+        filter_chain.add_block(&first_block, 1000).unwrap();
+        filter_chain.add_block(&second_block, 1001).unwrap();
+
+        assert!(!filter_chain.is_complete());
+        assert_eq!(
+            1,
+            filter_chain.get_filters(1000, &first_hash).unwrap().len()
+        );
+        assert!(filter_chain.get_filters(1000, &second_hash).is_none());
+        assert_eq!(
+            None,
+            filter_chain.get_filter_hashes(1000, &first_hash).unwrap()
+        );
+        assert_eq!(None, filter_chain.get_checkpoints(&first_hash));
+    }
+
+    #[test]
+    fn test_04_blocks_may_match_only_if_their_filters_have_the_scripts() {
+        let first_block = create_block(
+            [0; 32],
+            create_transaction(Outpoint::new([1; 32], 0), vec![0x51; 25]),
+        );
+        let first_hash = first_block.header.get_hash256d().unwrap();
+        let mut second_block = create_block(
+            first_hash,
+            create_transaction(Outpoint::new([2; 32], 0), vec![0x52; 25]),
+        );
+        second_block
+            .append_transaction(create_transaction(
+                Outpoint::new([3; 32], 0),
+                vec![0x53; 25],
+            ))
+            .unwrap();
+        let second_hash = second_block.header.get_hash256d().unwrap();
+
+        let mut filter_chain = FilterChain::default();
+        filter_chain.add_block(&first_block, 0).unwrap();
+        filter_chain.add_block(&second_block, 1).unwrap();
+
+        assert!(filter_chain.may_match(&first_hash, &[vec![0x51; 25]]));
+        assert!(!filter_chain.may_match(&first_hash, &[vec![0x54; 25]]));
+        assert!(filter_chain.may_match(&second_hash, &[vec![0x54; 25]]));
+        assert!(filter_chain.may_match(&[9; 32], &[vec![0x54; 25]]));
+    }
+
+    #[test]
+    fn test_05_correct_filter_chain_serialization() {
+        let first_block = create_block(
+            [0; 32],
+            create_transaction(Outpoint::new([1; 32], 0), vec![0x51; 25]),
+        );
+        let first_hash = first_block.header.get_hash256d().unwrap();
+        let mut second_block = create_block(
+            first_hash,
+            create_transaction(Outpoint::new([2; 32], 0), vec![0x52; 25]),
+        );
+        second_block
+            .append_transaction(create_transaction(
+                Outpoint::new([3; 32], 0),
+                vec![0x53; 25],
+            ))
+            .unwrap();
+        let second_hash = second_block.header.get_hash256d().unwrap();
+
+        let mut filter_chain = FilterChain::default();
+        filter_chain.add_block(&first_block, 0).unwrap();
+        filter_chain.add_block(&second_block, 1).unwrap();
+
+        let mut stream: Vec<u8> = Vec::new();
+        filter_chain.io_serialize(&mut stream).unwrap();
+        let deserialized_filter_chain =
+            FilterChain::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert_eq!(Some(1), deserialized_filter_chain.get_tip_height());
+        assert!(!deserialized_filter_chain.is_complete());
+        assert_eq!(
+            filter_chain.get_filter(&second_hash),
+            deserialized_filter_chain.get_filter(&second_hash)
+        );
+        assert_eq!(filter_chain.headers, deserialized_filter_chain.headers);
+        assert_eq!(
+            filter_chain.spendable_scripts,
+            deserialized_filter_chain.spendable_scripts
+        );
+    }
+
+    #[test]
+    fn test_06_chain_follows_the_block_chain_with_its_last_block() {
+        let first_block = create_block(
+            [0; 32],
+            create_transaction(Outpoint::new([1; 32], 0), vec![0x51; 25]),
+        );
+        let second_block = create_block(
+            first_block.header.get_hash256d().unwrap(),
+            create_transaction(Outpoint::new([2; 32], 0), vec![0x52; 25]),
+        );
+        let other_block = create_block(
+            [7; 32],
+            create_transaction(Outpoint::new([3; 32], 0), vec![0x53; 25]),
+        );
+
+        let mut block_chain = BlockChain::new(first_block).unwrap();
+        block_chain.append_block(second_block).unwrap();
+        let other_block_chain = BlockChain::new(other_block).unwrap();
+
+        let filter_chain = FilterChain::from_block_chain(&block_chain).unwrap();
+        assert!(filter_chain.follows(&block_chain));
+        assert!(!filter_chain.follows(&other_block_chain));
+        assert!(FilterChain::default().follows(&other_block_chain));
+    }
+}
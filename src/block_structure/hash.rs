@@ -1,6 +1,6 @@
 use crate::serialization::error_serialization::ErrorSerialization;
 
-use bitcoin_hashes::{hash160, sha256, sha256d, Hash};
+use bitcoin_hashes::{hash160, sha256, sha256d, siphash24, Hash};
 
 pub const HASH_TYPE_SIZE: usize = 32;
 pub const HASH_TYPE_REDUCE_SIZE: usize = 4;
@@ -84,6 +84,48 @@ pub fn hash256d_reduce(bytes: &[u8]) -> Result<HashTypeReduced, ErrorSerializati
     Ok(hash_byte_4)
 }
 
+/// It hashes a byte array using SipHash-2-4 with the two given keys
+pub fn sip_hash24(keys: (u64, u64), bytes: &[u8]) -> u64 {
+    siphash24::Hash::hash_to_u64_with_keys(keys.0, keys.1, bytes)
+}
+
+/// It hashes a byte array using the 32 bits version of murmur3 with the given seed, as used by the bloom filters (BIP37)
+pub fn murmur3(seed: u32, bytes: &[u8]) -> u32 {
+    const C1: u32 = 0xcc9e2d51;
+    const C2: u32 = 0x1b873593;
+
+    let mut hash = seed;
+    let mut chunks = bytes.chunks_exact(4);
+    for chunk in chunks.by_ref() {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+
+        hash ^= k;
+        hash = hash
+            .rotate_left(13)
+            .wrapping_mul(5)
+            .wrapping_add(0xe6546b64);
+    }
+
+    let remainder = chunks.remainder();
+    if !remainder.is_empty() {
+        let mut k: u32 = 0;
+        for (index, byte) in remainder.iter().enumerate() {
+            k ^= (*byte as u32) << (8 * index);
+        }
+        hash ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    hash ^= bytes.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85ebca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2ae35);
+    hash ^= hash >> 16;
+
+    hash
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,4 +187,21 @@ mod tests {
         let hash_expected = [0xC7, 0xF1, 0x8F, 0xE8];
         assert_eq!(hash, hash_expected);
     }
+
+    #[test]
+    fn test_05_correct_sip_hash24() {
+        let keys = (0x0706050403020100, 0x0f0e0d0c0b0a0908);
+        let hash = sip_hash24(keys, &[]);
+        assert_eq!(hash, 0x726fdb47dd0e0e31);
+    }
+
+    #[test]
+    fn test_06_correct_murmur3() {
+        assert_eq!(0x00000000, murmur3(0x00000000, &[]));
+        assert_eq!(0x6a396f08, murmur3(0xFBA4C795, &[]));
+        assert_eq!(0x514E28B7, murmur3(0x00000000, &[0x00]));
+        assert_eq!(0xF55B516B, murmur3(0x00000000, &[0x21, 0x43, 0x65, 0x87]));
+        assert_eq!(0x2362F9DE, murmur3(0x5082EDEE, &[0x21, 0x43, 0x65, 0x87]));
+        assert_eq!(0x7E4A8634, murmur3(0x00000000, &[0x21, 0x43, 0x65]));
+    }
 }
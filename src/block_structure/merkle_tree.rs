@@ -99,6 +99,69 @@ impl MerkleTree {
         }
     }
 
+    /// Returns the hashes and flags of the partial merkle tree (BIP37) that proves the inclusion of the
+    /// matched transactions. There must be a match for each transaction of the block
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::NoHashFound`: It will appear when the matches don't correspond with the transactions of the tree
+    pub fn get_partial_path(
+        &self,
+        matches: &[bool],
+    ) -> Result<(Vec<HashType>, Vec<bool>), ErrorBlock> {
+        let mut hashes: Vec<HashType> = Vec::new();
+        let mut flags: Vec<bool> = Vec::new();
+
+        let first_level_size = self.levels[FIRST_LEVEL_INDEX].len();
+        if matches.is_empty()
+            || matches.len() > first_level_size
+            || matches.len() + 1 < first_level_size
+        {
+            return Err(ErrorBlock::NoHashFound);
+        }
+
+        self.traverse_and_build(self.levels.len() - 1, 0, matches, (&mut hashes, &mut flags))?;
+
+        Ok((hashes, flags))
+    }
+
+    /// Walks the tree depth first, adding a flag for each node visited and the hash of the nodes
+    /// that are not a parent of a matched transaction
+    fn traverse_and_build(
+        &self,
+        height: usize,
+        position: usize,
+        matches: &[bool],
+        partial_path: (&mut Vec<HashType>, &mut Vec<bool>),
+    ) -> Result<(), ErrorBlock> {
+        let (hashes, flags) = partial_path;
+
+        let first = position << height;
+        let last = ((position + 1) << height).min(matches.len());
+        let is_parent_of_match = matches[first..last].iter().any(|matched| *matched);
+        flags.push(is_parent_of_match);
+
+        if height == 0 || !is_parent_of_match {
+            match self
+                .levels
+                .get(height)
+                .and_then(|level| level.get(position))
+            {
+                Some(hash) => hashes.push(*hash),
+                None => return Err(ErrorBlock::NoHashFound),
+            }
+            return Ok(());
+        }
+
+        self.traverse_and_build(height - 1, position * 2, matches, (hashes, flags))?;
+
+        let width_below = (matches.len() + (1 << (height - 1)) - 1) >> (height - 1);
+        if position * 2 + 1 < width_below {
+            self.traverse_and_build(height - 1, position * 2 + 1, matches, (hashes, flags))?;
+        }
+
+        Ok(())
+    }
+
     /// Returns the merkle path and some information of the given transaction
     fn get_merkle_path_for_verification(
         &self,
@@ -469,4 +532,40 @@ mod tests {
         let transaction_2_hash = transaction_2.get_tx_id().unwrap();
         assert!(MerkleTree::merkle_proof_of_inclusion(&block, &transaction_2_hash).unwrap());
     }
+
+    #[test]
+    fn test_03_correct_partial_path_of_matched_transactions() {
+        let transactions: Vec<Transaction> = (0..5)
+            .map(|index| Transaction {
+                version: 1,
+                tx_in: vec![TransactionInput::new(
+                    Outpoint::new([index; 32], 0),
+                    vec![1, 2, 3],
+                    24,
+                )],
+                tx_out: vec![TransactionOutput {
+                    value: 10,
+                    pk_script: vec![4, 5, 6],
+                }],
+                time: 0,
+            })
+            .collect();
+
+        let merkle_tree = MerkleTree::new(&transactions).unwrap();
+        let (hashes, flags) = merkle_tree
+            .get_partial_path(&[false, true, false, false, false])
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                merkle_tree.levels[0][0],
+                merkle_tree.levels[0][1],
+                merkle_tree.levels[1][1],
+                merkle_tree.levels[2][1],
+            ],
+            hashes
+        );
+        assert_eq!(vec![true, true, true, false, true, false, false], flags);
+        assert!(merkle_tree.get_partial_path(&[true; 7]).is_err());
+    }
 }
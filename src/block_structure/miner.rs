@@ -0,0 +1,292 @@
+use super::{
+    block::Block, block_chain::BlockChain, block_header::BlockHeader, block_version::BlockVersion,
+    compact256::Compact256, error_block::ErrorBlock, merkle_tree::MerkleTree, outpoint::Outpoint,
+    transaction::Transaction, transaction_input::TransactionInput,
+    transaction_output::TransactionOutput, utxo_set::UTXOSet,
+};
+
+use crate::{connections::network::Network, messages::compact_size::CompactSize};
+
+use std::{cmp, collections::HashSet};
+
+/// The amount of blocks of the regtest network between each halving of the subsidy
+pub const REGTEST_HALVING_INTERVAL: u64 = 150;
+
+const INITIAL_SUBSIDY: i64 = 50 * 100_000_000;
+const MAX_HALVINGS: u64 = 64;
+
+const MINED_BLOCK_VERSION: i32 = 0x20000000;
+const COINBASE_SEQUENCE: u32 = 0xFFFFFFFF;
+
+const OP_0: u8 = 0x00;
+const OP_1: u8 = 0x51;
+
+/// It's the miner of the regtest network. It assembles a coinbase paying to its script and the pending
+/// transactions into the next block of the chain, and grinds the nonce until the block passes the proof of work
+#[derive(Debug, Clone)]
+pub struct Miner {
+    pk_script: Vec<u8>,
+    n_bits: Compact256,
+}
+
+impl Miner {
+    /// Creates a miner that pays the subsidy and the fees to the given script
+    pub fn new(pk_script: Vec<u8>) -> Self {
+        Miner {
+            pk_script,
+            n_bits: Compact256::from(Network::Regtest.get_pow_limit()),
+        }
+    }
+
+    /// Creates the block after the tip of the block chain, with the pending transactions of the mempool whose
+    /// outputs spent are unspent. The time of the block is after the one of the tip
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::NodeChainReferenceNotFound`: It will appear when the block chain doesn't have blocks
+    ///  * `ErrorBlock::CouldNotGetTxId`: It will appear when the id of a transaction could not be created
+    ///  * `ErrorBlock::CouldNotMine`: It will appear when no nonce gives a header hash under the target
+    pub fn mine_block(
+        &self,
+        block_chain: &BlockChain,
+        mempool: &UTXOSet,
+        time: u32,
+    ) -> Result<Block, ErrorBlock> {
+        let previous_block = match block_chain.get_tip_hash() {
+            Some(tip_hash) => block_chain.get_block_with_hash(&tip_hash),
+            None => None,
+        };
+        let previous_block = match previous_block {
+            Some(previous_block) => previous_block,
+            None => return Err(ErrorBlock::NodeChainReferenceNotFound),
+        };
+        let previous_block_header_hash = match previous_block.header.get_hash256d() {
+            Ok(previous_block_header_hash) => previous_block_header_hash,
+            Err(_) => return Err(ErrorBlock::CouldNotHash),
+        };
+
+        let mut fees: i64 = 0;
+        let mut spent_outputs: HashSet<Outpoint> = HashSet::new();
+        let mut transactions: Vec<Transaction> = Vec::new();
+        for transaction in mempool.pending_transactions() {
+            let fee = match mempool.get_fee(transaction) {
+                Some(fee) if fee >= 0 => fee,
+                _ => continue,
+            };
+
+            if transaction
+                .tx_in
+                .iter()
+                .any(|input| spent_outputs.contains(&input.previous_output))
+            {
+                continue;
+            }
+
+            spent_outputs.extend(
+                transaction
+                    .tx_in
+                    .iter()
+                    .map(|input| input.previous_output.clone()),
+            );
+            fees += fee;
+            transactions.push(transaction.clone());
+        }
+
+        let height = block_chain.get_height() + 1;
+        transactions.insert(0, self.create_coinbase(height, fees));
+
+        let mut header = BlockHeader::new(
+            BlockVersion::version(MINED_BLOCK_VERSION),
+            previous_block_header_hash,
+            MerkleTree::new(&transactions)?.get_root(),
+            cmp::max(time, previous_block.header.time + 1),
+            self.n_bits,
+            0,
+            CompactSize::new(transactions.len() as u64),
+        );
+        grind(&mut header)?;
+
+        Ok(Block {
+            header,
+            transactions,
+        })
+    }
+
+    /// Creates the coinbase of the block at the given height, paying the subsidy and the fees to the script of the miner
+    pub fn create_coinbase(&self, height: u64, fees: i64) -> Transaction {
+        Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([0; 32], u32::MAX),
+                get_height_script(height),
+                COINBASE_SEQUENCE,
+            )],
+            tx_out: vec![TransactionOutput {
+                value: get_subsidy(height) + fees,
+                pk_script: self.pk_script.clone(),
+            }],
+            time: 0,
+        }
+    }
+}
+
+/// Changes the nonce of the header until its hash is under the target
+///
+/// ### Error
+///  * `ErrorBlock::CouldNotMine`: It will appear when no nonce gives a header hash under the target
+pub fn grind(header: &mut BlockHeader) -> Result<(), ErrorBlock> {
+    for nonce in 0..=u32::MAX {
+        header.nonce = nonce;
+        if header.proof_of_work() {
+            return Ok(());
+        }
+    }
+
+    Err(ErrorBlock::CouldNotMine)
+}
+
+/// Returns the new coins created by the block at the given height, halved every interval
+pub fn get_subsidy(height: u64) -> i64 {
+    let halvings = height / REGTEST_HALVING_INTERVAL;
+    match halvings >= MAX_HALVINGS {
+        true => 0,
+        false => INITIAL_SUBSIDY >> halvings,
+    }
+}
+
+/// Returns the signature script of the coinbase, starting with the height of the block (BIP34) so every coinbase
+/// has a different id
+fn get_height_script(height: u64) -> Vec<u8> {
+    let mut script = match height {
+        0 => vec![OP_0],
+        1..=16 => vec![OP_1 + (height - 1) as u8],
+        _ => {
+            let mut number: Vec<u8> = height
+                .to_le_bytes()
+                .into_iter()
+                .rev()
+                .skip_while(|byte| *byte == 0)
+                .collect();
+            number.reverse();
+
+            if number.last().is_some_and(|byte| byte & 0x80 != 0) {
+                number.push(0x00);
+            }
+
+            let mut script = vec![number.len() as u8];
+            script.extend(number);
+            script
+        }
+    };
+
+    script.push(OP_0);
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINER_SCRIPT: [u8; 3] = [0x51, 0x52, 0x53];
+
+    fn create_regtest_block_chain() -> BlockChain {
+        BlockChain::new(Block::new(BlockHeader::generate_genesis_block_header(
+            Network::Regtest,
+        )))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_01_mined_block_follows_the_tip_and_passes_the_proof_of_work() {
+        let mut block_chain = create_regtest_block_chain();
+        let miner = Miner::new(MINER_SCRIPT.to_vec());
+
+        let block = miner
+            .mine_block(&block_chain, &UTXOSet::new(Vec::new()), 0)
+            .unwrap();
+
+        assert!(block.header.proof_of_work());
+        assert!(block.proof_of_inclusion());
+        assert_eq!(
+            BlockHeader::generate_genesis_block_header(Network::Regtest)
+                .get_hash256d()
+                .unwrap(),
+            block.header.previous_block_header_hash
+        );
+        assert!(
+            block.header.time > BlockHeader::generate_genesis_block_header(Network::Regtest).time
+        );
+
+        assert_eq!(1, block.transactions.len());
+        assert_eq!(INITIAL_SUBSIDY, block.transactions[0].tx_out[0].value);
+
+        block_chain.append_block(block).unwrap();
+        assert_eq!(1, block_chain.get_height());
+    }
+
+    #[test]
+    fn test_02_mined_block_has_the_pending_transactions_and_their_fees() {
+        let mut block_chain = create_regtest_block_chain();
+        let miner = Miner::new(MINER_SCRIPT.to_vec());
+
+        let first_block = miner
+            .mine_block(&block_chain, &UTXOSet::new(Vec::new()), 0)
+            .unwrap();
+        let coinbase_id = first_block.transactions[0].get_tx_id().unwrap();
+        block_chain.append_block(first_block).unwrap();
+
+        let spending_transaction = Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new(coinbase_id, 0),
+                vec![1, 2, 3],
+                COINBASE_SEQUENCE,
+            )],
+            tx_out: vec![TransactionOutput {
+                value: INITIAL_SUBSIDY - 1000,
+                pk_script: vec![4, 5, 6],
+            }],
+            time: 0,
+        };
+        let mut double_spend = spending_transaction.clone();
+        double_spend.tx_out[0].value = INITIAL_SUBSIDY - 2000;
+
+        let mut mempool = UTXOSet::from_blockchain(&block_chain);
+        mempool.append_pending_transaction(spending_transaction.clone());
+        mempool.append_pending_transaction(double_spend);
+
+        let block = miner.mine_block(&block_chain, &mempool, 0).unwrap();
+
+        assert_eq!(2, block.transactions.len());
+        assert_eq!(spending_transaction, block.transactions[1]);
+        assert_eq!(
+            INITIAL_SUBSIDY + 1000,
+            block.transactions[0].tx_out[0].value
+        );
+        assert!(block.proof_of_inclusion());
+
+        block_chain.append_block(block).unwrap();
+        assert_eq!(2, block_chain.get_height());
+    }
+
+    #[test]
+    fn test_03_coinbase_has_the_height_and_the_halved_subsidy() {
+        let miner = Miner::new(MINER_SCRIPT.to_vec());
+
+        assert_eq!(
+            vec![0x51, 0x00],
+            miner.create_coinbase(1, 0).tx_in[0].signature_script
+        );
+        assert_eq!(
+            vec![0x02, 0x80, 0x00, 0x00],
+            miner.create_coinbase(128, 0).tx_in[0].signature_script
+        );
+        assert_eq!(
+            vec![0x02, 0xe8, 0x03, 0x00],
+            miner.create_coinbase(1000, 0).tx_in[0].signature_script
+        );
+
+        assert_eq!(INITIAL_SUBSIDY, get_subsidy(149));
+        assert_eq!(INITIAL_SUBSIDY / 2, get_subsidy(150));
+        assert_eq!(0, get_subsidy(REGTEST_HALVING_INTERVAL * MAX_HALVINGS));
+    }
+}
@@ -1,14 +1,20 @@
 pub mod block;
 pub mod block_chain;
+pub mod block_filter;
 pub mod block_header;
 pub mod block_version;
+pub mod checkpoint;
 pub mod node_chain;
 
 pub mod compact256;
+pub mod filter_chain;
 pub mod hash;
 
 pub mod merkle_tree;
+pub mod miner;
 pub mod outpoint;
+pub mod partial_block;
+pub mod partial_merkle_tree;
 pub mod transaction;
 pub mod transaction_input;
 pub mod transaction_output;
@@ -153,6 +153,7 @@ mod tests {
         transaction::Transaction, transaction_input::TransactionInput,
         transaction_output::TransactionOutput,
     };
+    use crate::connections::network::Network;
     use crate::messages::compact_size::CompactSize;
 
     #[test]
@@ -187,7 +188,9 @@ mod tests {
 
     #[test]
     pub fn test_03_correct_is_previous_of() {
-        let block = Block::new(BlockHeader::generate_genesis_block_header());
+        let block = Block::new(BlockHeader::generate_genesis_block_header(
+            Network::Testnet3,
+        ));
 
         let node_chain = NodeChain::new(block, 23, 0).unwrap();
         let block = Block::new(BlockHeader::new(
@@ -208,14 +211,18 @@ mod tests {
 
     #[test]
     pub fn test_04_correct_is_equal() {
-        let block = Block::new(BlockHeader::generate_genesis_block_header());
+        let block = Block::new(BlockHeader::generate_genesis_block_header(
+            Network::Testnet3,
+        ));
         let node_chain = NodeChain::new(block.clone(), 23, 0).unwrap();
         assert!(node_chain.is_equal(&block));
     }
 
     #[test]
     pub fn test_05_correct_node_chain_update() {
-        let block_1 = Block::new(BlockHeader::generate_genesis_block_header());
+        let block_1 = Block::new(BlockHeader::generate_genesis_block_header(
+            Network::Testnet3,
+        ));
         let mut node_chain = NodeChain::new(block_1, 23, 0).unwrap();
         let block_2 = Block::new(BlockHeader::new(
             BlockVersion::version(1),
@@ -9,6 +9,7 @@ use crate::serialization::{
 };
 use std::{
     cmp::PartialEq,
+    fmt::Display,
     hash::{Hash, Hasher},
     io::{Read, Write},
 };
@@ -24,6 +25,11 @@ impl Outpoint {
     pub fn new(hash: HashType, index: u32) -> Self {
         Outpoint { hash, index }
     }
+
+    /// Returns the id of the transaction that created the output
+    pub fn get_transaction_id(&self) -> &HashType {
+        &self.hash
+    }
 }
 
 impl SerializableInternalOrder for Outpoint {
@@ -44,6 +50,15 @@ impl DeserializableInternalOrder for Outpoint {
     }
 }
 
+impl Display for Outpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in self.hash.iter().rev() {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, ":{}", self.index)
+    }
+}
+
 impl Hash for Outpoint {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.hash.hash(state);
@@ -96,4 +111,17 @@ mod tests {
         let outpoint_deserialized = Outpoint::io_deserialize(&mut stream).unwrap();
         assert_eq!(outpoint, outpoint_deserialized);
     }
+
+    #[test]
+    fn test_03_outpoint_is_shown_with_the_transaction_id_reversed() {
+        let mut hash = [0; 32];
+        hash[0] = 0xab;
+        hash[31] = 0x01;
+        let outpoint = Outpoint::new(hash, 2);
+
+        assert_eq!(
+            "01000000000000000000000000000000000000000000000000000000000000ab:2",
+            outpoint.to_string()
+        );
+    }
 }
@@ -0,0 +1,214 @@
+use super::{
+    block::Block, block_header::BlockHeader, error_block::ErrorBlock, hash::HashType,
+    transaction::Transaction,
+};
+
+use crate::messages::cmpct_block_message::{CmpctBlockMessage, ShortTransactionId};
+
+use std::collections::HashMap;
+
+/// It's a block being rebuilt from a compact block, where some of the transactions may still be missing
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialBlock {
+    header: BlockHeader,
+    transactions: Vec<Option<Transaction>>,
+}
+
+impl PartialBlock {
+    /// Rebuilds the block of the compact block with the prefilled transactions and the available transactions
+    /// that match a short id. A short id matching more than one transaction is left missing
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotGetTxId`: It will appear when the short id of a transaction could not be calculated
+    pub fn new(
+        compact_block: CmpctBlockMessage,
+        available_transactions: &[Transaction],
+    ) -> Result<Self, ErrorBlock> {
+        let keys = match compact_block.get_short_id_keys() {
+            Ok(keys) => keys,
+            Err(_) => return Err(ErrorBlock::CouldNotGetTxId),
+        };
+
+        let mut candidates: HashMap<ShortTransactionId, Option<&Transaction>> = HashMap::new();
+        for transaction in available_transactions {
+            let short_id = match CmpctBlockMessage::calculate_short_id(keys, transaction) {
+                Ok(short_id) => short_id,
+                Err(_) => return Err(ErrorBlock::CouldNotGetTxId),
+            };
+
+            candidates
+                .entry(short_id)
+                .and_modify(|candidate| *candidate = None)
+                .or_insert(Some(transaction));
+        }
+
+        let mut transactions: Vec<Option<Transaction>> =
+            vec![None; compact_block.transaction_count()];
+        for prefilled_transaction in compact_block.prefilled_transactions {
+            if let Some(position) = transactions.get_mut(prefilled_transaction.index as usize) {
+                *position = Some(prefilled_transaction.transaction);
+            }
+        }
+
+        let mut short_ids = compact_block.short_ids.iter();
+        for position in transactions
+            .iter_mut()
+            .filter(|position| position.is_none())
+        {
+            let short_id = match short_ids.next() {
+                Some(short_id) => short_id,
+                None => break,
+            };
+
+            if let Some(Some(transaction)) = candidates.get(short_id) {
+                *position = Some((*transaction).clone());
+            }
+        }
+
+        Ok(PartialBlock {
+            header: compact_block.header,
+            transactions,
+        })
+    }
+
+    /// Returns the hash of the header of the block
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotHash`: It will appear when the header could not be hashed
+    pub fn get_hash(&self) -> Result<HashType, ErrorBlock> {
+        match self.header.get_hash256d() {
+            Ok(hash) => Ok(hash),
+            Err(_) => Err(ErrorBlock::CouldNotHash),
+        }
+    }
+
+    /// Returns the indexes of the transactions still missing, in increasing order
+    pub fn missing_indexes(&self) -> Vec<u64> {
+        self.transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, transaction)| transaction.is_none())
+            .map(|(index, _)| index as u64)
+            .collect()
+    }
+
+    /// Fills the missing transactions with the given ones, in the order of the missing indexes
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::TransactionNotFound`: It will appear when the amount of transactions is not the amount missing
+    pub fn fill_missing(&mut self, transactions: Vec<Transaction>) -> Result<(), ErrorBlock> {
+        if transactions.len() != self.missing_indexes().len() {
+            return Err(ErrorBlock::TransactionNotFound);
+        }
+
+        let mut transactions = transactions.into_iter();
+        for position in self
+            .transactions
+            .iter_mut()
+            .filter(|position| position.is_none())
+        {
+            *position = transactions.next();
+        }
+
+        Ok(())
+    }
+
+    /// Returns the complete block, or `None` if there are still missing transactions
+    pub fn into_block(self) -> Option<Block> {
+        let mut transactions = Vec::new();
+        for transaction in self.transactions {
+            transactions.push(transaction?);
+        }
+
+        Some(Block {
+            header: self.header,
+            transactions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        block_structure::{
+            block_version::BlockVersion, compact256::Compact256, outpoint::Outpoint,
+            transaction_input::TransactionInput, transaction_output::TransactionOutput,
+        },
+        messages::compact_size::CompactSize,
+    };
+
+    fn create_transaction(time: u32) -> Transaction {
+        Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([1; 32], 23),
+                vec![1, 2, 3],
+                24,
+            )],
+            tx_out: vec![TransactionOutput {
+                value: 10,
+                pk_script: vec![4, 5, 6],
+            }],
+            time,
+        }
+    }
+
+    fn create_block() -> Block {
+        let mut block = Block::new(BlockHeader::new(
+            BlockVersion::version(1),
+            [3; 32],
+            [4; 32],
+            5,
+            Compact256::from(u32::MAX),
+            6,
+            CompactSize::new(4),
+        ));
+        for time in 0..4 {
+            block.append_transaction(create_transaction(time)).unwrap();
+        }
+        block
+    }
+
+    #[test]
+    fn test_01_block_is_rebuilt_with_available_transactions() {
+        let block = create_block();
+        let compact_block = CmpctBlockMessage::from_block(&block, 7).unwrap();
+
+        let available_transactions = vec![
+            create_transaction(10),
+            block.transactions[3].clone(),
+            block.transactions[1].clone(),
+            block.transactions[2].clone(),
+        ];
+        let partial_block = PartialBlock::new(compact_block, &available_transactions).unwrap();
+
+        assert!(partial_block.missing_indexes().is_empty());
+        assert_eq!(Some(block), partial_block.into_block());
+    }
+
+    #[test]
+    fn test_02_missing_transactions_are_filled() {
+        let block = create_block();
+        let compact_block = CmpctBlockMessage::from_block(&block, 7).unwrap();
+
+        let available_transactions = vec![block.transactions[2].clone()];
+        let mut partial_block = PartialBlock::new(compact_block, &available_transactions).unwrap();
+
+        assert_eq!(vec![1, 3], partial_block.missing_indexes());
+        assert_eq!(None, partial_block.clone().into_block());
+
+        assert!(partial_block
+            .fill_missing(vec![block.transactions[1].clone()])
+            .is_err());
+        partial_block
+            .fill_missing(vec![
+                block.transactions[1].clone(),
+                block.transactions[3].clone(),
+            ])
+            .unwrap();
+
+        assert_eq!(Some(block), partial_block.into_block());
+    }
+}
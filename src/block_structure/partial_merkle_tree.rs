@@ -0,0 +1,325 @@
+use super::{
+    block::Block,
+    block_header::BlockHeader,
+    error_block::ErrorBlock,
+    hash::{hash256d, HashType},
+    merkle_tree::MerkleTree,
+    transaction::Transaction,
+};
+
+use crate::{
+    messages::compact_size::CompactSize,
+    serialization::{
+        deserializable_big_endian::DeserializableBigEndian,
+        deserializable_internal_order::DeserializableInternalOrder,
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization, serializable_big_endian::SerializableBigEndian,
+        serializable_internal_order::SerializableInternalOrder,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::io::{Read, Write};
+
+/// It's the partial merkle tree of a block (BIP37). It has the hashes and flags needed to rebuild the
+/// merkle root from some of the transactions of the block, proving they are included in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialMerkleTree {
+    pub total_transactions: u32,
+    pub hashes: Vec<HashType>,
+    pub flags: Vec<bool>,
+}
+
+impl PartialMerkleTree {
+    /// Creates the partial merkle tree of the block that proves the inclusion of the given transactions
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotGetTxId`: It will appear when the id of a transaction could not be created
+    ///  * `ErrorBlock::NoHashFound`: It will appear when the block doesn't have transactions
+    pub fn from_block(block: &Block, matched_ids: &[HashType]) -> Result<Self, ErrorBlock> {
+        let transaction_ids = Transaction::get_vec_txids(&block.transactions)?;
+        let matches: Vec<bool> = transaction_ids
+            .iter()
+            .map(|transaction_id| matched_ids.contains(transaction_id))
+            .collect();
+
+        Self::from_matches(&block.transactions, &matches)
+    }
+
+    /// Creates the partial merkle tree of the transactions, with a match for each one of them
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::CouldNotGetTxId`: It will appear when the id of a transaction could not be created
+    ///  * `ErrorBlock::NoHashFound`: It will appear when the matches don't correspond with the transactions
+    pub fn from_matches(
+        transactions: &[Transaction],
+        matches: &[bool],
+    ) -> Result<Self, ErrorBlock> {
+        let (hashes, flags) = MerkleTree::new(transactions)?.get_partial_path(matches)?;
+
+        Ok(PartialMerkleTree {
+            total_transactions: transactions.len() as u32,
+            hashes,
+            flags,
+        })
+    }
+
+    /// Verifies the tree against the merkle root of the header, returning the ids of the matched transactions
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::InvalidPartialMerkleTree`: It will appear when the hashes and flags don't form a valid tree
+    ///  * `ErrorBlock::MerkleRootMismatch`: It will appear when the root of the tree is not the one of the header
+    pub fn verify(&self, header: &BlockHeader) -> Result<Vec<HashType>, ErrorBlock> {
+        let (root, matched_ids) = self.extract_matches()?;
+
+        if root != header.merkle_root_hash {
+            return Err(ErrorBlock::MerkleRootMismatch);
+        }
+
+        Ok(matched_ids)
+    }
+
+    /// Rebuilds the merkle root from the hashes and flags, returning it with the ids of the matched transactions
+    ///
+    /// ### Error
+    ///  * `ErrorBlock::InvalidPartialMerkleTree`: It will appear when the hashes and flags don't form a valid tree
+    pub fn extract_matches(&self) -> Result<(HashType, Vec<HashType>), ErrorBlock> {
+        if self.total_transactions == 0
+            || self.hashes.len() > self.total_transactions as usize
+            || self.flags.len() < self.hashes.len()
+        {
+            return Err(ErrorBlock::InvalidPartialMerkleTree);
+        }
+
+        let mut height = 0;
+        while self.width(height) > 1 {
+            height += 1;
+        }
+
+        let mut matched_ids: Vec<HashType> = Vec::new();
+        let mut used = (0, 0);
+        let root = self.traverse_and_extract(height, 0, &mut used, &mut matched_ids)?;
+
+        let (flags_used, hashes_used) = used;
+        if hashes_used != self.hashes.len()
+            || flags_used.div_ceil(8) != self.flags.len().div_ceil(8)
+        {
+            return Err(ErrorBlock::InvalidPartialMerkleTree);
+        }
+
+        Ok((root, matched_ids))
+    }
+
+    /// Returns the amount of nodes in the given height of the tree
+    fn width(&self, height: usize) -> usize {
+        (self.total_transactions as usize + (1 << height) - 1) >> height
+    }
+
+    /// Walks the tree depth first consuming the flags and hashes, the same way they were built, and
+    /// returns the hash of the node
+    fn traverse_and_extract(
+        &self,
+        height: usize,
+        position: usize,
+        used: &mut (usize, usize),
+        matched_ids: &mut Vec<HashType>,
+    ) -> Result<HashType, ErrorBlock> {
+        let is_parent_of_match = match self.flags.get(used.0) {
+            Some(flag) => *flag,
+            None => return Err(ErrorBlock::InvalidPartialMerkleTree),
+        };
+        used.0 += 1;
+
+        if height == 0 || !is_parent_of_match {
+            let hash = match self.hashes.get(used.1) {
+                Some(hash) => *hash,
+                None => return Err(ErrorBlock::InvalidPartialMerkleTree),
+            };
+            used.1 += 1;
+
+            if height == 0 && is_parent_of_match {
+                matched_ids.push(hash);
+            }
+            return Ok(hash);
+        }
+
+        let left = self.traverse_and_extract(height - 1, position * 2, used, matched_ids)?;
+        let right = match position * 2 + 1 < self.width(height - 1) {
+            true => {
+                let right =
+                    self.traverse_and_extract(height - 1, position * 2 + 1, used, matched_ids)?;
+                if right == left {
+                    return Err(ErrorBlock::InvalidPartialMerkleTree);
+                }
+                right
+            }
+            false => left,
+        };
+
+        let mut combined = left.to_vec();
+        combined.extend_from_slice(&right);
+
+        match hash256d(&combined) {
+            Ok(hash) => Ok(hash),
+            Err(_) => Err(ErrorBlock::CouldNotHash),
+        }
+    }
+}
+
+impl SerializableInternalOrder for PartialMerkleTree {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.total_transactions.le_serialize(stream)?;
+
+        CompactSize::new(self.hashes.len() as u64).le_serialize(stream)?;
+        for hash in self.hashes.iter() {
+            hash.be_serialize(stream)?;
+        }
+
+        let mut flags: Vec<u8> = vec![0; self.flags.len().div_ceil(8)];
+        for (index, flag) in self.flags.iter().enumerate() {
+            if *flag {
+                flags[index / 8] |= 1 << (index % 8);
+            }
+        }
+
+        CompactSize::new(flags.len() as u64).le_serialize(stream)?;
+        flags.io_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for PartialMerkleTree {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let total_transactions = u32::le_deserialize(stream)?;
+
+        let hash_count = CompactSize::le_deserialize(stream)?.value;
+        if hash_count > total_transactions as u64 {
+            return Err(ErrorSerialization::ErrorInDeserialization(format!(
+                "Partial merkle tree with {hash_count} hashes for {total_transactions} transactions"
+            )));
+        }
+
+        let mut hashes: Vec<HashType> = Vec::new();
+        for _ in 0..hash_count {
+            hashes.push(HashType::be_deserialize(stream)?);
+        }
+
+        let flag_bytes = CompactSize::le_deserialize(stream)?.value;
+        let mut flags: Vec<bool> = Vec::new();
+        for _ in 0..flag_bytes {
+            let byte = u8::le_deserialize(stream)?;
+            flags.extend((0..8).map(|bit| byte & (1 << bit) != 0));
+        }
+
+        Ok(PartialMerkleTree {
+            total_transactions,
+            hashes,
+            flags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::block_structure::{
+        block_version::BlockVersion, compact256::Compact256, outpoint::Outpoint,
+        transaction_input::TransactionInput, transaction_output::TransactionOutput,
+    };
+
+    fn create_block(transaction_count: u8) -> Block {
+        let transactions: Vec<Transaction> = (0..transaction_count)
+            .map(|index| Transaction {
+                version: 1,
+                tx_in: vec![TransactionInput::new(
+                    Outpoint::new([index; 32], 0),
+                    vec![1, 2, 3],
+                    24,
+                )],
+                tx_out: vec![TransactionOutput {
+                    value: 10,
+                    pk_script: vec![4, 5, 6],
+                }],
+                time: 0,
+            })
+            .collect();
+
+        let merkle_root = MerkleTree::new(&transactions).unwrap().get_root();
+
+        Block {
+            header: BlockHeader::new(
+                BlockVersion::version(1),
+                [0; 32],
+                merkle_root,
+                0,
+                Compact256::from(10),
+                0,
+                CompactSize::new(transaction_count as u64),
+            ),
+            transactions,
+        }
+    }
+
+    #[test]
+    fn test_01_partial_merkle_tree_proves_the_matched_transactions() {
+        for transaction_count in 1..12 {
+            let block = create_block(transaction_count);
+            let transaction_ids = Transaction::get_vec_txids(&block.transactions).unwrap();
+            let matched_ids: Vec<HashType> = transaction_ids.iter().step_by(3).cloned().collect();
+
+            let partial_merkle_tree = PartialMerkleTree::from_block(&block, &matched_ids).unwrap();
+
+            assert_eq!(
+                matched_ids,
+                partial_merkle_tree.verify(&block.header).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_02_serialized_partial_merkle_tree_is_still_valid() {
+        let block = create_block(7);
+        let transaction_id = block.transactions[5].get_tx_id().unwrap();
+        let partial_merkle_tree = PartialMerkleTree::from_block(&block, &[transaction_id]).unwrap();
+
+        let mut stream: Vec<u8> = Vec::new();
+        partial_merkle_tree.io_serialize(&mut stream).unwrap();
+        let deserialized_tree = PartialMerkleTree::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert_eq!(partial_merkle_tree.hashes, deserialized_tree.hashes);
+        assert_eq!(
+            vec![transaction_id],
+            deserialized_tree.verify(&block.header).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_03_tampered_partial_merkle_tree_is_rejected() {
+        let block = create_block(4);
+        let transaction_id = block.transactions[1].get_tx_id().unwrap();
+        let partial_merkle_tree = PartialMerkleTree::from_block(&block, &[transaction_id]).unwrap();
+
+        let mut wrong_hash = partial_merkle_tree.clone();
+        wrong_hash.hashes[0] = [9; 32];
+        assert!(matches!(
+            wrong_hash.verify(&block.header),
+            Err(ErrorBlock::MerkleRootMismatch)
+        ));
+
+        let mut extra_hash = partial_merkle_tree.clone();
+        extra_hash.hashes.push([9; 32]);
+        assert!(matches!(
+            extra_hash.verify(&block.header),
+            Err(ErrorBlock::InvalidPartialMerkleTree)
+        ));
+
+        let mut missing_flags = partial_merkle_tree;
+        missing_flags.flags.truncate(2);
+        assert!(matches!(
+            missing_flags.verify(&block.header),
+            Err(ErrorBlock::InvalidPartialMerkleTree)
+        ));
+    }
+}
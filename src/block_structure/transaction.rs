@@ -205,6 +205,8 @@ impl DeserializableInternalOrder for Transaction {
 mod tests {
     use super::*;
 
+    use crate::connections::network::Network;
+
     #[test]
     fn test_01_correct_transaction_serialization() {
         let transaction_input =
@@ -299,8 +301,10 @@ mod tests {
 
     #[test]
     fn test_04_correct_verification_of_transaction_ownership() {
-        let account_old = Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw").unwrap();
-        let account_new = Address::new("mrhW6tcF2LDetj3kJvaDTvatrVxNK64NXk").unwrap();
+        let account_old =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
+        let account_new =
+            Address::new("mrhW6tcF2LDetj3kJvaDTvatrVxNK64NXk", Network::Testnet3).unwrap();
         let transaction_bytes: Vec<u8> = vec![
             0x01, 0x00, 0x00, 0x00, 0x01, 0x20, 0x25, 0xEF, 0x69, 0x2C, 0xA9, 0x87, 0xB3, 0x9A,
             0x81, 0x33, 0x6E, 0xFB, 0x59, 0xB0, 0x56, 0xFB, 0x90, 0xC0, 0x3A, 0x5E, 0xA4, 0xC4,
@@ -332,6 +336,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -134,6 +134,8 @@ impl DeserializableInternalOrder for TransactionInput {
 mod tests {
     use super::*;
 
+    use crate::connections::network::Network;
+
     #[test]
     fn test_01_correct_transaction_input_from_outpoint() {
         let previous_output = Outpoint::new(
@@ -169,6 +171,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
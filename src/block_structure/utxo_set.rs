@@ -33,9 +33,19 @@ impl UTXOSet {
         utxo_set
     }
 
-    /// Creates a new UTXOSet from a blockchain
+    /// Creates a new UTXOSet from a blockchain, starting from the outputs left unspent by its pruned blocks
     pub fn from_blockchain(blockchain: &BlockChain) -> UTXOSet {
-        Self::new(blockchain.get_all_blocks())
+        let mut utxo_set = UTXOSet {
+            utxo: blockchain.get_pruned_outputs().clone(),
+            pending: Vec::new(),
+        };
+
+        blockchain
+            .get_all_blocks()
+            .iter()
+            .for_each(|block| utxo_set.update_utxo_with_block(block));
+
+        utxo_set
     }
 
     /// Returns a list of the utxo that have not been spent yet
@@ -127,6 +137,23 @@ impl UTXOSet {
         &self.pending
     }
 
+    /// Returns the fee of the transaction, the value of the outputs it spends minus the value of its outputs.
+    /// Returns None if it spends an output that is not in the set
+    pub fn get_fee(&self, transaction: &Transaction) -> Option<i64> {
+        let mut fee: i64 = 0;
+        for input in transaction.tx_in.iter() {
+            fee += self.utxo.get(&input.previous_output)?.value;
+        }
+
+        Some(
+            fee - transaction
+                .tx_out
+                .iter()
+                .map(|output| output.value)
+                .sum::<i64>(),
+        )
+    }
+
     /// Returns the balance of the UTXOSet in Satoshis.
     pub fn get_balance_in_satoshis(&self, address: &Address) -> i64 {
         let mut balance: i64 = 0;
@@ -169,6 +196,7 @@ mod tests {
         transaction_output::TransactionOutput,
     };
 
+    use crate::connections::network::Network;
     use crate::messages::compact_size::CompactSize;
 
     fn create_transaction(time: u32) -> Transaction {
@@ -214,7 +242,8 @@ mod tests {
         let blockchain = BlockChain::new(block).unwrap();
 
         let utxo_set_blockchain = UTXOSet::from_blockchain(&blockchain);
-        let address = Address::new(&"mrhW6tcF2LDetj3kJvaDTvatrVxNK64NXk".to_string()).unwrap();
+        let address =
+            Address::new("mrhW6tcF2LDetj3kJvaDTvatrVxNK64NXk", Network::Testnet3).unwrap();
         assert_eq!(utxo_set_blockchain.utxo.len(), 1);
         assert_eq!(utxo_set_blockchain.get_balance_in_satoshis(&address), 10);
     }
@@ -227,7 +256,8 @@ mod tests {
         let blockchain = BlockChain::new(block).unwrap();
 
         let utxo_set_blockchain = UTXOSet::from_blockchain(&blockchain);
-        let address = Address::new(&"mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw".to_string()).unwrap();
+        let address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
         assert_eq!(utxo_set_blockchain.utxo.len(), 1);
         assert!(utxo_set_blockchain.get_balance_in_satoshis(&address) == 0);
     }
@@ -274,7 +304,8 @@ mod tests {
         let blockchain = BlockChain::new(block_1).unwrap();
 
         let mut utxo_set = UTXOSet::from_blockchain(&blockchain);
-        let address = Address::new(&"mrhW6tcF2LDetj3kJvaDTvatrVxNK64NXk".to_string()).unwrap();
+        let address =
+            Address::new("mrhW6tcF2LDetj3kJvaDTvatrVxNK64NXk", Network::Testnet3).unwrap();
 
         assert_eq!(utxo_set.utxo.len(), 2);
         assert_eq!(utxo_set.get_balance_in_satoshis(&address), 30);
@@ -326,7 +357,8 @@ mod tests {
         let blockchain = BlockChain::new(block).unwrap();
 
         let utxo_set_blockchain = UTXOSet::from_blockchain(&blockchain);
-        let address = Address::new(&"mrhW6tcF2LDetj3kJvaDTvatrVxNK64NXk".to_string()).unwrap();
+        let address =
+            Address::new("mrhW6tcF2LDetj3kJvaDTvatrVxNK64NXk", Network::Testnet3).unwrap();
         assert_eq!(
             utxo_set_blockchain.get_balance_in_tbtc(&address),
             (10.0 / FROM_SATOSHIS_TO_TBTC)
@@ -363,7 +395,8 @@ mod tests {
         let blockchain = BlockChain::new(block).unwrap();
 
         let mut utxo_set_blockchain = UTXOSet::from_blockchain(&blockchain);
-        let address = Address::new(&"mrhW6tcF2LDetj3kJvaDTvatrVxNK64NXk".to_string()).unwrap();
+        let address =
+            Address::new("mrhW6tcF2LDetj3kJvaDTvatrVxNK64NXk", Network::Testnet3).unwrap();
 
         let mut transaction_id = vec![];
         transaction.io_serialize(&mut transaction_id).unwrap();
@@ -6,6 +6,7 @@ use std::{
     convert::Into,
     io::{Read, Write},
     sync::mpsc::{Receiver, TryRecvError},
+    time::Instant,
 };
 
 #[derive(Debug)]
@@ -20,18 +21,45 @@ impl<I> Work<I> {
         stream: &mut RW,
         receiver: &Receiver<M>,
     ) -> Self {
+        match Self::wait(stream, receiver, None) {
+            Some(work) => work,
+            None => Work::Stop,
+        }
+    }
+
+    /// Same as `listen`, but returns `None` if nothing arrives before the deadline
+    pub fn listen_until<RW: Read + Write, M: Into<Work<I>>>(
+        stream: &mut RW,
+        receiver: &Receiver<M>,
+        deadline: Instant,
+    ) -> Option<Self> {
+        Self::wait(stream, receiver, Some(deadline))
+    }
+
+    /// Waits for a message from the stream or the receiver, returning `None` if the deadline passes first
+    fn wait<RW: Read + Write, M: Into<Work<I>>>(
+        stream: &mut RW,
+        receiver: &Receiver<M>,
+        deadline: Option<Instant>,
+    ) -> Option<Self> {
         loop {
             match MessageHeader::deserialize_header(stream) {
-                Ok(header) => return Work::Message(header),
+                Ok(header) => return Some(Work::Message(header)),
                 Err(ErrorSerialization::InformationNotReady) => {}
-                _ => return Work::Stop,
+                _ => return Some(Work::Stop),
             }
 
             match receiver.try_recv() {
-                Ok(message) => return message.into(),
-                Err(TryRecvError::Disconnected) => return Work::Stop,
+                Ok(message) => return Some(message.into()),
+                Err(TryRecvError::Disconnected) => return Some(Work::Stop),
                 Err(_) => {}
             }
+
+            if let Some(deadline) = deadline {
+                if Instant::now() >= deadline {
+                    return None;
+                }
+            }
         }
     }
 }
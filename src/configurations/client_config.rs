@@ -3,6 +3,8 @@ use super::{
     parsable::{parse_structure, value_from_map, KeyValueMap, Parsable},
 };
 
+use crate::connections::network::Network;
+
 use std::{cmp::PartialEq, net::Ipv4Addr};
 
 const PORT: &str = "port";
@@ -11,8 +13,8 @@ const ADDRESS: &str = "address";
 /// Configuration for the client process
 #[derive(Debug, PartialEq, Clone)]
 pub struct ClientConfig {
-    /// It's the port number where the client will be connected to
-    pub port: u16,
+    /// It's the port number where the client will be connected to, by default the one of the network
+    pub port: Option<u16>,
 
     /// It's the address where the client will be connected to
     pub address: Ipv4Addr,
@@ -24,12 +26,22 @@ impl Parsable for ClientConfig {
         let map = parse_structure(structure)?;
 
         Ok(ClientConfig {
-            port: u16::parse(PORT, &map)?,
+            port: Option::<u16>::parse(PORT, &map)?,
             address: Ipv4Addr::parse(ADDRESS, &map)?,
         })
     }
 }
 
+impl ClientConfig {
+    /// Returns the port number where the client will be connected to in the given network
+    pub fn get_port(&self, network: Network) -> u16 {
+        match self.port {
+            Some(port) => port,
+            None => network.get_default_port(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -47,7 +59,7 @@ mod tests {
         let client_result = ClientConfig::parse(name, &map);
 
         let config_client = ClientConfig {
-            port: 18333,
+            port: Some(18333),
             address: Ipv4Addr::new(127, 0, 0, 1),
         };
 
@@ -67,7 +79,7 @@ mod tests {
         let client_result = ClientConfig::parse(name, &map);
 
         let client_config = ClientConfig {
-            port: 18333,
+            port: Some(18333),
             address: Ipv4Addr::new(127, 0, 0, 1),
         };
 
@@ -77,7 +89,7 @@ mod tests {
     #[test]
     fn test03_does_not_accept_input_with_missing_values() {
         let client = "client {
-            address = 127.0.0.1
+            port = 18333
         }";
 
         let name = "client";
@@ -102,7 +114,7 @@ mod tests {
         let client_result = ClientConfig::parse(name, &map);
 
         let client_config = ClientConfig {
-            port: 18333,
+            port: Some(18333),
             address: Ipv4Addr::new(127, 0, 0, 1),
         };
 
@@ -120,4 +132,19 @@ mod tests {
 
         assert_eq!(Err(ErrorConfiguration::ValueNotFound), client_result);
     }
+
+    #[test]
+    fn test06_uses_the_port_of_the_network_when_not_given() {
+        let client = "client {
+            address = 127.0.0.1
+        }";
+
+        let name = "client";
+        let map = parse_structure(client.to_string()).unwrap();
+
+        let client_config = ClientConfig::parse(name, &map).unwrap();
+
+        assert_eq!(None, client_config.port);
+        assert_eq!(18444, client_config.get_port(Network::Regtest));
+    }
 }
@@ -3,7 +3,9 @@ use super::{
     parsable::{parse_structure, value_from_map, KeyValueMap, Parsable},
 };
 
-use crate::connections::{ibd_methods::IBDMethod, p2p_protocol::ProtocolVersionP2P};
+use crate::connections::{
+    ibd_methods::IBDMethod, network::Network, p2p_protocol::ProtocolVersionP2P,
+};
 
 use crate::messages::{bitfield_services::BitfieldServices, message_header::MagicType};
 
@@ -17,6 +19,11 @@ const MAGIC_NUMBERS: &str = "magic_numbers";
 const NONCE: &str = "nonce";
 const USER_AGENT: &str = "user_agent";
 const RELAY: &str = "relay";
+const PING_TIMEOUT: &str = "ping_timeout";
+const NETWORK: &str = "network";
+
+/// The time, in seconds, a peer has to answer a ping when it's not given in the configuration
+pub const DEFAULT_PING_TIMEOUT: u64 = 20 * 60;
 
 /// It represents all the data needed to establish a connection
 #[derive(Debug, PartialEq, Clone)]
@@ -33,7 +40,7 @@ pub struct ConnectionConfig {
     /// It's the services that this node will offer
     pub services: BitfieldServices,
 
-    /// It's the magic numbers that will be used to identify the network
+    /// It's the magic numbers that will be used to identify the network. If they are given, they must be the ones of the network
     pub magic_numbers: MagicType,
 
     /// It's used to detect connections to self
@@ -44,6 +51,12 @@ pub struct ConnectionConfig {
 
     /// It's the flag that indicates if the node will relay transactions
     pub relay: bool,
+
+    /// It's the time, in seconds, a peer has to answer a ping before being disconnected
+    pub ping_timeout: u64,
+
+    /// It's the network the node is part of, which gives the genesis block, the checkpoints and the addresses prefixes
+    pub network: Network,
 }
 
 impl Parsable for ConnectionConfig {
@@ -51,15 +64,28 @@ impl Parsable for ConnectionConfig {
         let structure = value_from_map(name.to_string(), map)?;
         let map = parse_structure(structure)?;
 
+        let network = Option::<Network>::parse(NETWORK, &map)?.unwrap_or_default();
+        let magic_numbers = match Option::<MagicType>::parse(MAGIC_NUMBERS, &map)? {
+            Some(magic_numbers) if magic_numbers != network.get_magic_numbers() => {
+                return Err(ErrorConfiguration::ErrorCantParseValue(format!(
+                    "magic numbers of {:?} that are not the ones of the network {network}",
+                    magic_numbers
+                )))
+            }
+            _ => network.get_magic_numbers(),
+        };
+
         Ok(ConnectionConfig {
             p2p_protocol_version: ProtocolVersionP2P::parse(P2P_PROTOCOL_VERSION, &map)?,
             ibd_method: IBDMethod::parse(IBD_METHOD, &map)?,
             block_height: i32::parse(BLOCK_HEIGHT, &map)?,
             services: BitfieldServices::parse(SERVICES, &map)?,
-            magic_numbers: MagicType::parse(MAGIC_NUMBERS, &map)?,
+            magic_numbers,
             nonce: u64::parse(NONCE, &map)?,
             user_agent: Option::<String>::parse(USER_AGENT, &map)?.unwrap_or_default(),
             relay: bool::parse(RELAY, &map)?,
+            ping_timeout: Option::<u64>::parse(PING_TIMEOUT, &map)?.unwrap_or(DEFAULT_PING_TIMEOUT),
+            network,
         })
     }
 }
@@ -77,7 +103,7 @@ mod tests {
             ibd_method = HeaderFirst
             block_height = 0
             services = [Unname]
-            magic_numbers = [1, 2, 3, 4]
+            magic_numbers = [11, 17, 9, 7]
             nonce = 0
             user_agent = Tanto tiempo
             relay = true
@@ -95,10 +121,12 @@ mod tests {
             services: BitfieldServices {
                 elements: vec![SupportedServices::Unname],
             },
-            magic_numbers: [1, 2, 3, 4],
+            magic_numbers: [11, 17, 9, 7],
             nonce: 0,
             user_agent: "Tanto tiempo".to_string(),
             relay: true,
+            ping_timeout: DEFAULT_PING_TIMEOUT,
+            network: Network::Testnet3,
         };
 
         assert_eq!(Ok(config_connection), connection_result);
@@ -111,7 +139,7 @@ mod tests {
             ibd_method=                                 HeaderFirst
             block_height = 0
             services = [Unname]
-            magic_numbers = [1, 2, 3, 4]
+            magic_numbers = [11, 17, 9, 7]
             nonce = 0
             user_agent = Tanto tiempo
             relay = true
@@ -129,10 +157,12 @@ mod tests {
             services: BitfieldServices {
                 elements: vec![SupportedServices::Unname],
             },
-            magic_numbers: [1, 2, 3, 4],
+            magic_numbers: [11, 17, 9, 7],
             nonce: 0,
             user_agent: "Tanto tiempo".to_string(),
             relay: true,
+            ping_timeout: DEFAULT_PING_TIMEOUT,
+            network: Network::Testnet3,
         };
 
         assert_eq!(Ok(config_connection), connection_result);
@@ -144,7 +174,7 @@ mod tests {
             p2p_protocol_version = V70015
             block_height = 0
             services = [Unname]
-            magic_numbers = [1, 2, 3, 4]
+            magic_numbers = [11, 17, 9, 7]
             nonce = 0
             user_agent = Tanto tiempo
             relay = true
@@ -166,7 +196,7 @@ mod tests {
             ibd_method = HeaderFirst
             block_height = 0
             services = [Unname]
-            magic_numbers = [1, 2, 3, 4]
+            magic_numbers = [11, 17, 9, 7]
             nonce = 0
             user_agent = Tanto tiempo
             relay = true
@@ -184,10 +214,12 @@ mod tests {
             services: BitfieldServices {
                 elements: vec![SupportedServices::Unname],
             },
-            magic_numbers: [1, 2, 3, 4],
+            magic_numbers: [11, 17, 9, 7],
             nonce: 0,
             user_agent: "Tanto tiempo".to_string(),
             relay: true,
+            ping_timeout: DEFAULT_PING_TIMEOUT,
+            network: Network::Testnet3,
         };
 
         assert_eq!(Ok(config_connection), connection_result);
@@ -204,4 +236,100 @@ mod tests {
 
         assert_eq!(Err(ErrorConfiguration::ValueNotFound), connection_result);
     }
+
+    #[test]
+    fn test06_accept_input_with_ping_timeout() {
+        let configuration = "connection {
+            p2p_protocol_version = V70015
+            ibd_method = HeaderFirst
+            block_height = 0
+            services = [Unname]
+            magic_numbers = [11, 17, 9, 7]
+            nonce = 0
+            relay = true
+            ping_timeout = 60
+        }";
+
+        let name = "connection";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let connection_result = ConnectionConfig::parse(name, &map);
+
+        let config_connection = ConnectionConfig {
+            p2p_protocol_version: ProtocolVersionP2P::V70015,
+            ibd_method: IBDMethod::HeaderFirst,
+            block_height: 0,
+            services: BitfieldServices {
+                elements: vec![SupportedServices::Unname],
+            },
+            magic_numbers: [11, 17, 9, 7],
+            nonce: 0,
+            user_agent: "".to_string(),
+            relay: true,
+            ping_timeout: 60,
+            network: Network::Testnet3,
+        };
+
+        assert_eq!(Ok(config_connection), connection_result);
+    }
+
+    #[test]
+    fn test07_network_gives_the_magic_numbers() {
+        let configuration = "connection {
+            p2p_protocol_version = V70015
+            ibd_method = HeaderFirst
+            block_height = 0
+            services = [Unname]
+            nonce = 0
+            relay = true
+            network = Regtest
+        }";
+
+        let name = "connection";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let connection_config = ConnectionConfig::parse(name, &map).unwrap();
+
+        assert_eq!(Network::Regtest, connection_config.network);
+        assert_eq!([0xfa, 0xbf, 0xb5, 0xda], connection_config.magic_numbers);
+    }
+
+    #[test]
+    fn test08_testnet3_is_the_default_network() {
+        let configuration = "connection {
+            p2p_protocol_version = V70015
+            ibd_method = HeaderFirst
+            block_height = 0
+            services = [Unname]
+            nonce = 0
+            relay = true
+        }";
+
+        let name = "connection";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let connection_config = ConnectionConfig::parse(name, &map).unwrap();
+
+        assert_eq!(Network::Testnet3, connection_config.network);
+        assert_eq!([0x0b, 0x11, 0x09, 0x07], connection_config.magic_numbers);
+    }
+
+    #[test]
+    fn test09_does_not_accept_magic_numbers_of_another_network() {
+        let configuration = "connection {
+            p2p_protocol_version = V70015
+            ibd_method = HeaderFirst
+            block_height = 0
+            services = [Unname]
+            magic_numbers = [11, 17, 9, 7]
+            nonce = 0
+            relay = true
+            network = Regtest
+        }";
+
+        let name = "connection";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        assert!(ConnectionConfig::parse(name, &map).is_err());
+    }
 }
@@ -4,12 +4,20 @@ use super::{
 };
 
 const TIMESTAMP: &str = "timestamp";
+const PRUNE_DEPTH: &str = "prune_depth";
+
+/// It's the minimum amount of blocks with transactions a pruned node keeps, the ones it
+/// can still serve to its peers as a limited node
+pub const MIN_PRUNE_DEPTH: u64 = 288;
 
 /// It represents all the data needed in the download process
 #[derive(Debug, PartialEq, Clone)]
 pub struct DownloadConfig {
     /// It's the time from where the block download will start
     pub timestamp: u32,
+
+    /// It's the amount of the latest blocks that keep their transactions. Without it the node is not pruned
+    pub prune_depth: Option<u64>,
 }
 
 impl Parsable for DownloadConfig {
@@ -17,8 +25,18 @@ impl Parsable for DownloadConfig {
         let structure = value_from_map(name.to_string(), map)?;
         let map = parse_structure(structure)?;
 
+        let prune_depth = Option::<u64>::parse(PRUNE_DEPTH, &map)?;
+        if let Some(prune_depth) = prune_depth {
+            if prune_depth < MIN_PRUNE_DEPTH {
+                return Err(ErrorConfiguration::ErrorCantParseValue(format!(
+                    "prune depth of {prune_depth}, it must keep at least {MIN_PRUNE_DEPTH} blocks"
+                )));
+            }
+        }
+
         Ok(DownloadConfig {
             timestamp: u32::parse(TIMESTAMP, &map)?,
+            prune_depth,
         })
     }
 }
@@ -27,7 +45,10 @@ impl Parsable for DownloadConfig {
 mod tests {
     use super::*;
 
-    const CONFIG_CONNECTION: DownloadConfig = DownloadConfig { timestamp: 0 };
+    const CONFIG_CONNECTION: DownloadConfig = DownloadConfig {
+        timestamp: 0,
+        prune_depth: None,
+    };
 
     #[test]
     fn test01_accept_valid_input() {
@@ -96,4 +117,43 @@ mod tests {
 
         assert_eq!(Err(ErrorConfiguration::ValueNotFound), connection_result);
     }
+
+    #[test]
+    fn test06_accept_input_with_prune_depth() {
+        let configuration = "download {
+            timestamp = 0
+            prune_depth = 550
+        }";
+
+        let name = "download";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let connection_result = DownloadConfig::parse(name, &map);
+
+        assert_eq!(
+            Ok(DownloadConfig {
+                timestamp: 0,
+                prune_depth: Some(550),
+            }),
+            connection_result
+        );
+    }
+
+    #[test]
+    fn test07_does_not_accept_prune_depth_below_the_minimum() {
+        let configuration = "download {
+            timestamp = 0
+            prune_depth = 100
+        }";
+
+        let name = "download";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let connection_result = DownloadConfig::parse(name, &map);
+
+        assert!(matches!(
+            connection_result,
+            Err(ErrorConfiguration::ErrorCantParseValue(_))
+        ));
+    }
 }
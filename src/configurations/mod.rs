@@ -5,6 +5,7 @@ pub mod log_config;
 pub mod mode_config;
 pub mod save_config;
 pub mod server_config;
+pub mod spv_config;
 pub mod ui_config;
 
 pub mod interface;
@@ -1,4 +1,4 @@
-use super::{client_config::ClientConfig, server_config::ServerConfig};
+use super::{client_config::ClientConfig, server_config::ServerConfig, spv_config::SpvConfig};
 
 use std::cmp::PartialEq;
 
@@ -10,4 +10,7 @@ pub enum ModeConfig {
 
     /// Client if mode config contains client information
     Client(ClientConfig),
+
+    /// Spv if mode config contains spv information
+    Spv(SpvConfig),
 }
@@ -11,6 +11,21 @@ const WRITE_BLOCK_CHAIN: &str = "write_block_chain";
 const READ_WALLET: &str = "read_wallet";
 const WRITE_WALLET: &str = "write_wallet";
 
+const READ_ADDRESS_BOOK: &str = "read_address_book";
+const WRITE_ADDRESS_BOOK: &str = "write_address_book";
+
+const READ_PEER_ADDRESSES: &str = "read_peer_addresses";
+const WRITE_PEER_ADDRESSES: &str = "write_peer_addresses";
+
+const READ_BAN_LIST: &str = "read_ban_list";
+const WRITE_BAN_LIST: &str = "write_ban_list";
+
+const READ_TRANSACTION_HISTORY: &str = "read_transaction_history";
+const WRITE_TRANSACTION_HISTORY: &str = "write_transaction_history";
+
+const READ_FILTER_CHAIN: &str = "read_filter_chain";
+const WRITE_FILTER_CHAIN: &str = "write_filter_chain";
+
 /// It represents all the data needed to load and save the data of the program
 #[derive(Debug, PartialEq, Clone)]
 pub struct SaveConfig {
@@ -25,6 +40,36 @@ pub struct SaveConfig {
 
     /// It's the file name where the wallet will be saved
     pub write_wallet: Option<String>,
+
+    /// It's the file name where the address book will be loaded
+    pub read_address_book: Option<String>,
+
+    /// It's the file name where the address book will be saved
+    pub write_address_book: Option<String>,
+
+    /// It's the file name where the addresses of potential peers will be loaded
+    pub read_peer_addresses: Option<String>,
+
+    /// It's the file name where the addresses of potential peers will be saved
+    pub write_peer_addresses: Option<String>,
+
+    /// It's the file name where the banned addresses will be loaded
+    pub read_ban_list: Option<String>,
+
+    /// It's the file name where the banned addresses will be saved
+    pub write_ban_list: Option<String>,
+
+    /// It's the file name where the transaction history of the wallet will be loaded
+    pub read_transaction_history: Option<String>,
+
+    /// It's the file name where the transaction history of the wallet will be saved
+    pub write_transaction_history: Option<String>,
+
+    /// It's the file name where the compact filters of the blocks will be loaded
+    pub read_filter_chain: Option<String>,
+
+    /// It's the file name where the compact filters of the blocks will be saved
+    pub write_filter_chain: Option<String>,
 }
 
 impl Parsable for SaveConfig {
@@ -37,6 +82,16 @@ impl Parsable for SaveConfig {
             write_block_chain: Option::<String>::parse(WRITE_BLOCK_CHAIN, &map)?,
             read_wallet: Option::<String>::parse(READ_WALLET, &map)?,
             write_wallet: Option::<String>::parse(WRITE_WALLET, &map)?,
+            read_address_book: Option::<String>::parse(READ_ADDRESS_BOOK, &map)?,
+            write_address_book: Option::<String>::parse(WRITE_ADDRESS_BOOK, &map)?,
+            read_peer_addresses: Option::<String>::parse(READ_PEER_ADDRESSES, &map)?,
+            write_peer_addresses: Option::<String>::parse(WRITE_PEER_ADDRESSES, &map)?,
+            read_ban_list: Option::<String>::parse(READ_BAN_LIST, &map)?,
+            write_ban_list: Option::<String>::parse(WRITE_BAN_LIST, &map)?,
+            read_transaction_history: Option::<String>::parse(READ_TRANSACTION_HISTORY, &map)?,
+            write_transaction_history: Option::<String>::parse(WRITE_TRANSACTION_HISTORY, &map)?,
+            read_filter_chain: Option::<String>::parse(READ_FILTER_CHAIN, &map)?,
+            write_filter_chain: Option::<String>::parse(WRITE_FILTER_CHAIN, &map)?,
         })
     }
 }
@@ -63,6 +118,16 @@ mod tests {
             write_block_chain: Some("save_test2.txt".to_string()),
             read_wallet: Some("save_w_test.txt".to_string()),
             write_wallet: Some("save_w_test2.txt".to_string()),
+            read_address_book: None,
+            write_address_book: None,
+            read_peer_addresses: None,
+            write_peer_addresses: None,
+            read_ban_list: None,
+            write_ban_list: None,
+            read_transaction_history: None,
+            write_transaction_history: None,
+            read_filter_chain: None,
+            write_filter_chain: None,
         };
 
         assert_eq!(Ok(config_save), log_result);
@@ -86,6 +151,16 @@ mod tests {
             write_block_chain: Some("save_test2.txt".to_string()),
             read_wallet: Some("save_w_test.txt".to_string()),
             write_wallet: Some("save_w_test2.txt".to_string()),
+            read_address_book: None,
+            write_address_book: None,
+            read_peer_addresses: None,
+            write_peer_addresses: None,
+            read_ban_list: None,
+            write_ban_list: None,
+            read_transaction_history: None,
+            write_transaction_history: None,
+            read_filter_chain: None,
+            write_filter_chain: None,
         };
 
         assert_eq!(Ok(config_save), log_result);
@@ -108,6 +183,16 @@ mod tests {
             write_block_chain: None,
             read_wallet: Some("save_w_test.txt".to_string()),
             write_wallet: Some("save_w_test2.txt".to_string()),
+            read_address_book: None,
+            write_address_book: None,
+            read_peer_addresses: None,
+            write_peer_addresses: None,
+            read_ban_list: None,
+            write_ban_list: None,
+            read_transaction_history: None,
+            write_transaction_history: None,
+            read_filter_chain: None,
+            write_filter_chain: None,
         };
 
         assert_eq!(Ok(config_missing), log_result);
@@ -132,6 +217,16 @@ mod tests {
             write_block_chain: Some("save_test2.txt".to_string()),
             read_wallet: Some("save_w_test.txt".to_string()),
             write_wallet: Some("save_w_test2.txt".to_string()),
+            read_address_book: None,
+            write_address_book: None,
+            read_peer_addresses: None,
+            write_peer_addresses: None,
+            read_ban_list: None,
+            write_ban_list: None,
+            read_transaction_history: None,
+            write_transaction_history: None,
+            read_filter_chain: None,
+            write_filter_chain: None,
         };
 
         assert_eq!(Ok(config_save), log_result);
@@ -147,4 +242,161 @@ mod tests {
 
         assert_eq!(Err(ErrorConfiguration::ValueNotFound), log_result);
     }
+
+    #[test]
+    fn test06_accept_input_with_address_book() {
+        let configuration = "save {
+            read_wallet = save_w_test.txt
+            write_wallet = save_w_test2.txt
+            read_address_book = save_a_test.txt
+            write_address_book = save_a_test2.txt
+        }";
+        let name = "save";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let log_result = SaveConfig::parse(name, &map);
+
+        let config_save = SaveConfig {
+            read_block_chain: None,
+            write_block_chain: None,
+            read_wallet: Some("save_w_test.txt".to_string()),
+            write_wallet: Some("save_w_test2.txt".to_string()),
+            read_address_book: Some("save_a_test.txt".to_string()),
+            write_address_book: Some("save_a_test2.txt".to_string()),
+            read_peer_addresses: None,
+            write_peer_addresses: None,
+            read_ban_list: None,
+            write_ban_list: None,
+            read_transaction_history: None,
+            write_transaction_history: None,
+            read_filter_chain: None,
+            write_filter_chain: None,
+        };
+
+        assert_eq!(Ok(config_save), log_result);
+    }
+
+    #[test]
+    fn test07_accept_input_with_peer_addresses() {
+        let configuration = "save {
+            read_peer_addresses = save_p_test.txt
+            write_peer_addresses = save_p_test2.txt
+        }";
+        let name = "save";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let log_result = SaveConfig::parse(name, &map);
+
+        let config_save = SaveConfig {
+            read_block_chain: None,
+            write_block_chain: None,
+            read_wallet: None,
+            write_wallet: None,
+            read_address_book: None,
+            write_address_book: None,
+            read_peer_addresses: Some("save_p_test.txt".to_string()),
+            write_peer_addresses: Some("save_p_test2.txt".to_string()),
+            read_ban_list: None,
+            write_ban_list: None,
+            read_transaction_history: None,
+            write_transaction_history: None,
+            read_filter_chain: None,
+            write_filter_chain: None,
+        };
+
+        assert_eq!(Ok(config_save), log_result);
+    }
+
+    #[test]
+    fn test08_accept_input_with_ban_list() {
+        let configuration = "save {
+            read_ban_list = save_b_test.txt
+            write_ban_list = save_b_test2.txt
+        }";
+        let name = "save";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let log_result = SaveConfig::parse(name, &map);
+
+        let config_save = SaveConfig {
+            read_block_chain: None,
+            write_block_chain: None,
+            read_wallet: None,
+            write_wallet: None,
+            read_address_book: None,
+            write_address_book: None,
+            read_peer_addresses: None,
+            write_peer_addresses: None,
+            read_ban_list: Some("save_b_test.txt".to_string()),
+            write_ban_list: Some("save_b_test2.txt".to_string()),
+            read_transaction_history: None,
+            write_transaction_history: None,
+            read_filter_chain: None,
+            write_filter_chain: None,
+        };
+
+        assert_eq!(Ok(config_save), log_result);
+    }
+
+    #[test]
+    fn test09_accept_input_with_transaction_history() {
+        let configuration = "save {
+            read_transaction_history = save_h_test.txt
+            write_transaction_history = save_h_test2.txt
+        }";
+        let name = "save";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let log_result = SaveConfig::parse(name, &map);
+
+        let config_save = SaveConfig {
+            read_block_chain: None,
+            write_block_chain: None,
+            read_wallet: None,
+            write_wallet: None,
+            read_address_book: None,
+            write_address_book: None,
+            read_peer_addresses: None,
+            write_peer_addresses: None,
+            read_ban_list: None,
+            write_ban_list: None,
+            read_transaction_history: Some("save_h_test.txt".to_string()),
+            write_transaction_history: Some("save_h_test2.txt".to_string()),
+            read_filter_chain: None,
+            write_filter_chain: None,
+        };
+
+        assert_eq!(Ok(config_save), log_result);
+    }
+
+    #[test]
+    fn test10_accept_input_with_filter_chain() {
+        let configuration = "save {
+            read_filter_chain = save_f_test.txt
+            write_filter_chain = save_f_test2.txt
+        }";
+        let name = "save";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let log_result = SaveConfig::parse(name, &map);
+
+        let config_save = SaveConfig {
+            read_block_chain: None,
+            write_block_chain: None,
+            read_wallet: None,
+            write_wallet: None,
+            read_address_book: None,
+            write_address_book: None,
+            read_peer_addresses: None,
+            write_peer_addresses: None,
+            read_ban_list: None,
+            write_ban_list: None,
+            read_transaction_history: None,
+            write_transaction_history: None,
+            read_filter_chain: Some("save_f_test.txt".to_string()),
+            write_filter_chain: Some("save_f_test2.txt".to_string()),
+        };
+
+        assert_eq!(Ok(config_save), log_result);
+    }
 }
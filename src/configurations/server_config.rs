@@ -3,7 +3,7 @@ use super::{
     parsable::{parse_structure, value_from_map, KeyValueMap, Parsable},
 };
 
-use crate::connections::dns_seeder::DNSSeeder;
+use crate::connections::{dns_seeder::DNSSeeder, network::Network};
 
 use std::{cmp::PartialEq, net::Ipv4Addr};
 
@@ -15,14 +15,14 @@ const ADDRESS: &str = "address";
 /// Configuration for the server process
 #[derive(Debug, PartialEq, Clone)]
 pub struct ServerConfig {
-    /// It's the DNS from where the potential peers will be obtaineds
-    pub dns_seeder: DNSSeeder,
+    /// It's the DNS from where the potential peers will be obtaineds, by default the ones of the network
+    pub dns_seeder: Option<DNSSeeder>,
 
     /// It's the maximum number of peers that will be connected
     pub peer_count_max: usize,
 
-    /// It's the port number where the server will be listening
-    pub own_port: u16,
+    /// It's the port number where the server will be listening, by default the one of the network
+    pub own_port: Option<u16>,
 
     /// The address' where the server will be listening
     pub address: Ipv4Addr,
@@ -34,14 +34,32 @@ impl Parsable for ServerConfig {
         let map = parse_structure(structure)?;
 
         Ok(ServerConfig {
-            dns_seeder: DNSSeeder::parse(DNS_SEEDER, &map)?,
+            dns_seeder: Option::<DNSSeeder>::parse(DNS_SEEDER, &map)?,
             peer_count_max: usize::parse(PEER_COUNT_MAX, &map)?,
-            own_port: u16::parse(PORT, &map)?,
+            own_port: Option::<u16>::parse(PORT, &map)?,
             address: Ipv4Addr::parse(ADDRESS, &map)?,
         })
     }
 }
 
+impl ServerConfig {
+    /// Returns the DNS seeders from where the potential peers of the given network will be obtained
+    pub fn get_dns_seeders(&self, network: Network) -> Vec<DNSSeeder> {
+        match &self.dns_seeder {
+            Some(dns_seeder) => vec![dns_seeder.clone()],
+            None => network.get_dns_seeders(),
+        }
+    }
+
+    /// Returns the port number where the server will be listening in the given network
+    pub fn get_own_port(&self, network: Network) -> u16 {
+        match self.own_port {
+            Some(own_port) => own_port,
+            None => network.get_default_port(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::net::Ipv4Addr;
@@ -66,9 +84,9 @@ mod tests {
         let server_result = ServerConfig::parse(name, &map);
 
         let config_server = ServerConfig {
-            dns_seeder: DNSSeeder::new("seed.testnet.bitcoin.sprovoost.nl", 18333),
+            dns_seeder: Some(DNSSeeder::new("seed.testnet.bitcoin.sprovoost.nl", 18333)),
             peer_count_max: 8,
-            own_port: 18333,
+            own_port: Some(18333),
             address: Ipv4Addr::new(127, 0, 0, 1),
         };
 
@@ -93,9 +111,9 @@ mod tests {
         let server_result = ServerConfig::parse(name, &map);
 
         let server_config = ServerConfig {
-            dns_seeder: DNSSeeder::new("seed.testnet.bitcoin.sprovoost.nl", 18333),
+            dns_seeder: Some(DNSSeeder::new("seed.testnet.bitcoin.sprovoost.nl", 18333)),
             peer_count_max: 8,
-            own_port: 18333,
+            own_port: Some(18333),
             address: Ipv4Addr::new(127, 0, 0, 1),
         };
 
@@ -140,9 +158,9 @@ mod tests {
         let server_result = ServerConfig::parse(name, &map);
 
         let server_config = ServerConfig {
-            dns_seeder: DNSSeeder::new("seed.testnet.bitcoin.sprovoost.nl", 18333),
+            dns_seeder: Some(DNSSeeder::new("seed.testnet.bitcoin.sprovoost.nl", 18333)),
             peer_count_max: 8,
-            own_port: 18333,
+            own_port: Some(18333),
             address: Ipv4Addr::new(127, 0, 0, 1),
         };
 
@@ -160,4 +178,23 @@ mod tests {
 
         assert_eq!(Err(ErrorConfiguration::ValueNotFound), server_result);
     }
+
+    #[test]
+    fn test06_uses_the_seeders_and_port_of_the_network_when_not_given() {
+        let server = "server {
+            peer_count_max = 8
+            address = 127.0.0.1
+        }";
+
+        let name = "server";
+        let map = parse_structure(server.to_string()).unwrap();
+
+        let server_config = ServerConfig::parse(name, &map).unwrap();
+
+        assert_eq!(
+            Network::Mainnet.get_dns_seeders(),
+            server_config.get_dns_seeders(Network::Mainnet)
+        );
+        assert_eq!(8333, server_config.get_own_port(Network::Mainnet));
+    }
 }
@@ -0,0 +1,110 @@
+use super::{
+    error_configuration::ErrorConfiguration,
+    parsable::{parse_structure, value_from_map, KeyValueMap, Parsable},
+};
+
+use crate::connections::{dns_seeder::DNSSeeder, network::Network};
+
+use std::cmp::PartialEq;
+
+const DNS_SEEDER: &str = "dns_seeder";
+const PEER_COUNT_MAX: &str = "peer_count_max";
+
+/// It's the rate of false positives of the bloom filter loaded in the peers, so they can't tell
+/// exactly which transactions are from the wallet
+pub const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.0001;
+
+/// Configuration for the SPV process, that only downloads the headers and the transactions of the wallet
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpvConfig {
+    /// It's the DNS from where the potential peers will be obtaineds, by default the ones of the network
+    pub dns_seeder: Option<DNSSeeder>,
+
+    /// It's the maximum number of peers that will be connected
+    pub peer_count_max: usize,
+}
+
+impl Parsable for SpvConfig {
+    fn parse(name: &str, map: &KeyValueMap) -> Result<Self, ErrorConfiguration> {
+        let structure = value_from_map(name.to_string(), map)?;
+        let map = parse_structure(structure)?;
+
+        Ok(SpvConfig {
+            dns_seeder: Option::<DNSSeeder>::parse(DNS_SEEDER, &map)?,
+            peer_count_max: usize::parse(PEER_COUNT_MAX, &map)?,
+        })
+    }
+}
+
+impl SpvConfig {
+    /// Returns the DNS seeders from where the potential peers of the given network will be obtained
+    pub fn get_dns_seeders(&self, network: Network) -> Vec<DNSSeeder> {
+        match &self.dns_seeder {
+            Some(dns_seeder) => vec![dns_seeder.clone()],
+            None => network.get_dns_seeders(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test01_accept_valid_input() {
+        let spv = "spv {
+            dns_seeder {
+                seed = seed.testnet.bitcoin.sprovoost.nl
+                port = 18333
+            }
+            peer_count_max = 4
+        }";
+
+        let name = "spv";
+        let map = parse_structure(spv.to_string()).unwrap();
+
+        let spv_result = SpvConfig::parse(name, &map);
+
+        let spv_config = SpvConfig {
+            dns_seeder: Some(DNSSeeder::new("seed.testnet.bitcoin.sprovoost.nl", 18333)),
+            peer_count_max: 4,
+        };
+
+        assert_eq!(Ok(spv_config), spv_result);
+    }
+
+    #[test]
+    fn test02_does_not_accept_input_with_missing_values() {
+        let spv = "spv {
+            dns_seeder {
+                seed = seed.testnet.bitcoin.sprovoost.nl
+                port = 18333
+            }
+        }";
+
+        let name = "spv";
+        let map = parse_structure(spv.to_string()).unwrap();
+
+        let spv_result = SpvConfig::parse(name, &map);
+
+        assert_eq!(Err(ErrorConfiguration::ValueNotFound), spv_result);
+    }
+
+    #[test]
+    fn test03_uses_the_seeders_of_the_network_when_not_given() {
+        let spv = "spv {
+            peer_count_max = 4
+        }";
+
+        let name = "spv";
+        let map = parse_structure(spv.to_string()).unwrap();
+
+        let spv_config = SpvConfig::parse(name, &map).unwrap();
+
+        assert_eq!(None, spv_config.dns_seeder);
+        assert_eq!(
+            Network::Signet.get_dns_seeders(),
+            spv_config.get_dns_seeders(Network::Signet)
+        );
+    }
+}
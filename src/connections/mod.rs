@@ -1,5 +1,7 @@
 pub mod ibd_methods;
 
+pub mod network;
+
 pub mod p2p_protocol;
 
 pub mod dns_seeder;
@@ -13,3 +15,6 @@ pub mod socket_conversion;
 pub mod type_identifier;
 
 pub mod network_ip_address;
+pub mod network_ip_address_v2;
+
+pub mod network_address;
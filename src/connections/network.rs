@@ -0,0 +1,392 @@
+use crate::configurations::{
+    error_configuration::ErrorConfiguration,
+    parsable::{value_from_map, KeyValueMap, Parsable},
+};
+
+use crate::block_structure::{
+    checkpoint::{
+        Checkpoint, MAINNET_CHECKPOINTS, SIGNET_CHECKPOINTS, TESTNET4_CHECKPOINTS,
+        TESTNET_CHECKPOINTS,
+    },
+    hash::HashType,
+};
+
+use crate::messages::message_header::MagicType;
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+};
+
+use super::dns_seeder::DNSSeeder;
+
+use std::{
+    cmp::PartialEq,
+    fmt::Display,
+    io::{Read, Write},
+    str::FromStr,
+};
+
+const MAINNET: &str = "Mainnet";
+const TESTNET3: &str = "Testnet3";
+const TESTNET4: &str = "Testnet4";
+const SIGNET: &str = "Signet";
+const REGTEST: &str = "Regtest";
+
+/// The amount of blocks between each change of the target
+pub const DIFFICULTY_ADJUSTMENT_INTERVAL: u64 = 2016;
+
+/// The time, in seconds, the blocks of a difficulty adjustment interval are expected to take
+pub const TARGET_TIMESPAN: u32 = 14 * 24 * 60 * 60;
+
+/// The time, in seconds, each block is expected to take
+pub const TARGET_SPACING: u32 = 10 * 60;
+
+/// The merkle root of the coinbase of the genesis block shared by the mainnet, testnet3, signet and regtest
+const GENESIS_MERKLE_ROOT_HASH: HashType = [
+    0x3b, 0xa3, 0xed, 0xfd, 0x7a, 0x7b, 0x12, 0xb2, 0x7a, 0xc7, 0x2c, 0x3e, 0x67, 0x76, 0x8f, 0x61,
+    0x7f, 0xc8, 0x1b, 0xc3, 0x88, 0x8a, 0x51, 0x32, 0x3a, 0x9f, 0xb8, 0xaa, 0x4b, 0x1e, 0x5e, 0x4a,
+];
+
+/// The merkle root of the coinbase of the testnet4 genesis block, which has its own message
+const TESTNET4_GENESIS_MERKLE_ROOT_HASH: HashType = [
+    0x4e, 0x7b, 0x2b, 0x91, 0x28, 0xfe, 0x02, 0x91, 0xdb, 0x06, 0x93, 0xaf, 0x2a, 0xe4, 0x18, 0xb7,
+    0x67, 0xe6, 0x57, 0xcd, 0x40, 0x7e, 0x80, 0xcb, 0x14, 0x34, 0x22, 0x1e, 0xae, 0xa7, 0xa0, 0x7a,
+];
+
+const MAINNET_POW_LIMIT: u32 = 0x1d00ffff;
+const SIGNET_POW_LIMIT: u32 = 0x1e0377ae;
+const REGTEST_POW_LIMIT: u32 = 0x207fffff;
+
+const MAINNET_ADDRESS_VERSION_BYTE: u8 = 0x00;
+const TESTNET_ADDRESS_VERSION_BYTE: u8 = 0x6f;
+const MAINNET_SCRIPT_VERSION_BYTE: u8 = 0x05;
+const TESTNET_SCRIPT_VERSION_BYTE: u8 = 0xc4;
+const MAINNET_WIF_VERSION_BYTE: u8 = 0x80;
+const TESTNET_WIF_VERSION_BYTE: u8 = 0xef;
+
+const MAINNET_DNS_SEEDS: [&str; 7] = [
+    "seed.bitcoin.sipa.be",
+    "dnsseed.bluematt.me",
+    "seed.bitcoin.jonasschnelli.ch",
+    "seed.btc.petertodd.net",
+    "seed.bitcoin.sprovoost.nl",
+    "dnsseed.emzy.de",
+    "seed.bitcoin.wiz.biz",
+];
+const TESTNET3_DNS_SEEDS: [&str; 4] = [
+    "testnet-seed.bitcoin.jonasschnelli.ch",
+    "seed.tbtc.petertodd.net",
+    "seed.testnet.bitcoin.sprovoost.nl",
+    "testnet-seed.bluematt.me",
+];
+const TESTNET4_DNS_SEEDS: [&str; 2] = [
+    "seed.testnet4.bitcoin.sprovoost.nl",
+    "seed.testnet4.wiz.biz",
+];
+const SIGNET_DNS_SEEDS: [&str; 1] = ["seed.signet.bitcoin.sprovoost.nl"];
+
+/// It's the representation of the bitcoin network the node is part of, with the parameters of its chain
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Network {
+    Mainnet,
+    #[default]
+    Testnet3,
+    Testnet4,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    /// Returns the magic numbers that start every message of the network
+    pub fn get_magic_numbers(&self) -> MagicType {
+        match self {
+            Network::Mainnet => [0xf9, 0xbe, 0xb4, 0xd9],
+            Network::Testnet3 => [0x0b, 0x11, 0x09, 0x07],
+            Network::Testnet4 => [0x1c, 0x16, 0x3f, 0x28],
+            Network::Signet => [0x0a, 0x03, 0xcf, 0x40],
+            Network::Regtest => [0xfa, 0xbf, 0xb5, 0xda],
+        }
+    }
+
+    /// Returns the port the nodes of the network listen to by default
+    pub fn get_default_port(&self) -> u16 {
+        match self {
+            Network::Mainnet => 8333,
+            Network::Testnet3 => 18333,
+            Network::Testnet4 => 48333,
+            Network::Signet => 38333,
+            Network::Regtest => 18444,
+        }
+    }
+
+    /// Returns the DNS seeders that give the potential peers of the network, at its default port
+    pub fn get_dns_seeders(&self) -> Vec<DNSSeeder> {
+        let seeds: &[&str] = match self {
+            Network::Mainnet => &MAINNET_DNS_SEEDS,
+            Network::Testnet3 => &TESTNET3_DNS_SEEDS,
+            Network::Testnet4 => &TESTNET4_DNS_SEEDS,
+            Network::Signet => &SIGNET_DNS_SEEDS,
+            Network::Regtest => &[],
+        };
+
+        seeds
+            .iter()
+            .map(|seed| DNSSeeder::new(seed, self.get_default_port()))
+            .collect()
+    }
+
+    /// Returns the merkle root of the genesis block, in the order it's serialized in the header
+    pub fn get_genesis_merkle_root_hash(&self) -> HashType {
+        match self {
+            Network::Testnet4 => TESTNET4_GENESIS_MERKLE_ROOT_HASH,
+            _ => GENESIS_MERKLE_ROOT_HASH,
+        }
+    }
+
+    /// Returns the time of the genesis block
+    pub fn get_genesis_time(&self) -> u32 {
+        match self {
+            Network::Mainnet => 0x495fab29,
+            Network::Testnet3 | Network::Regtest => 0x4d49e5da,
+            Network::Testnet4 => 0x66356f04,
+            Network::Signet => 0x5f4d8f00,
+        }
+    }
+
+    /// Returns the nonce that gives the genesis block its proof of work
+    pub fn get_genesis_nonce(&self) -> u32 {
+        match self {
+            Network::Mainnet => 0x7c2bac1d,
+            Network::Testnet3 => 0x18aea41a,
+            Network::Testnet4 => 0x17780cbb,
+            Network::Signet => 0x0322d28a,
+            Network::Regtest => 0x00000002,
+        }
+    }
+
+    /// Returns the easiest target allowed in the network, in its compact form. It's the target of the genesis block
+    pub fn get_pow_limit(&self) -> u32 {
+        match self {
+            Network::Mainnet | Network::Testnet3 | Network::Testnet4 => MAINNET_POW_LIMIT,
+            Network::Signet => SIGNET_POW_LIMIT,
+            Network::Regtest => REGTEST_POW_LIMIT,
+        }
+    }
+
+    /// Returns true if a block can use the easiest target when it comes twice the expected time after the previous one
+    pub fn allows_min_difficulty_blocks(&self) -> bool {
+        matches!(
+            self,
+            Network::Testnet3 | Network::Testnet4 | Network::Regtest
+        )
+    }
+
+    /// Returns true if the target changes every difficulty adjustment interval
+    pub fn retargets(&self) -> bool {
+        !matches!(self, Network::Regtest)
+    }
+
+    /// Returns the checkpoints the block chain of the network must follow
+    pub fn get_checkpoints(&self) -> Vec<Checkpoint> {
+        match self {
+            Network::Mainnet => MAINNET_CHECKPOINTS.to_vec(),
+            Network::Testnet3 => TESTNET_CHECKPOINTS.to_vec(),
+            Network::Testnet4 => TESTNET4_CHECKPOINTS.to_vec(),
+            Network::Signet => SIGNET_CHECKPOINTS.to_vec(),
+            Network::Regtest => Vec::new(),
+        }
+    }
+
+    /// Returns the first byte of the P2PKH addresses
+    pub fn get_address_version_byte(&self) -> u8 {
+        match self {
+            Network::Mainnet => MAINNET_ADDRESS_VERSION_BYTE,
+            _ => TESTNET_ADDRESS_VERSION_BYTE,
+        }
+    }
+
+    /// Returns the first byte of the P2SH addresses
+    pub fn get_script_version_byte(&self) -> u8 {
+        match self {
+            Network::Mainnet => MAINNET_SCRIPT_VERSION_BYTE,
+            _ => TESTNET_SCRIPT_VERSION_BYTE,
+        }
+    }
+
+    /// Returns the first byte of the private keys in the wallet import format
+    pub fn get_wif_version_byte(&self) -> u8 {
+        match self {
+            Network::Mainnet => MAINNET_WIF_VERSION_BYTE,
+            _ => TESTNET_WIF_VERSION_BYTE,
+        }
+    }
+
+    /// Returns the human readable part of the bech32 addresses
+    pub fn get_bech32_hrp(&self) -> &'static str {
+        match self {
+            Network::Mainnet => "bc",
+            Network::Testnet3 | Network::Testnet4 | Network::Signet => "tb",
+            Network::Regtest => "bcrt",
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = ErrorConfiguration;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            MAINNET => Ok(Network::Mainnet),
+            TESTNET3 => Ok(Network::Testnet3),
+            TESTNET4 => Ok(Network::Testnet4),
+            SIGNET => Ok(Network::Signet),
+            REGTEST => Ok(Network::Regtest),
+            _ => Err(ErrorConfiguration::ErrorCantParseValue(format!(
+                "network of {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+impl Parsable for Network {
+    fn parse(name: &str, map: &KeyValueMap) -> Result<Self, ErrorConfiguration> {
+        let value = value_from_map(name.to_string(), map)?;
+        value.parse::<Network>()
+    }
+}
+
+impl SerializableInternalOrder for Network {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.get_magic_numbers().io_serialize(stream)
+    }
+}
+
+impl DeserializableInternalOrder for Network {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let magic_numbers = MagicType::io_deserialize(stream)?;
+
+        match [
+            Network::Mainnet,
+            Network::Testnet3,
+            Network::Testnet4,
+            Network::Signet,
+            Network::Regtest,
+        ]
+        .into_iter()
+        .find(|network| network.get_magic_numbers() == magic_numbers)
+        {
+            Some(network) => Ok(network),
+            None => Err(ErrorSerialization::ErrorInDeserialization(format!(
+                "Unknown network with magic numbers {:?}",
+                magic_numbers
+            ))),
+        }
+    }
+}
+
+impl Display for Network {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "{MAINNET}"),
+            Network::Testnet3 => write!(f, "{TESTNET3}"),
+            Network::Testnet4 => write!(f, "{TESTNET4}"),
+            Network::Signet => write!(f, "{SIGNET}"),
+            Network::Regtest => write!(f, "{REGTEST}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::{
+        block_structure::block_header::BlockHeader, configurations::parsable::parse_structure,
+    };
+
+    #[test]
+    fn test01_accept_valid_input() {
+        let configuration = "network = Signet";
+
+        let name = "network";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        let network_result = Network::parse(name, &map);
+
+        assert_eq!(Ok(Network::Signet), network_result);
+    }
+
+    #[test]
+    fn test02_reject_unknown_network() {
+        let configuration = "network = Testnet5";
+
+        let name = "network";
+        let map = parse_structure(configuration.to_string()).unwrap();
+
+        assert!(Network::parse(name, &map).is_err());
+    }
+
+    #[test]
+    fn test03_genesis_block_follows_the_checkpoints_of_the_network() {
+        for network in [
+            Network::Mainnet,
+            Network::Testnet3,
+            Network::Testnet4,
+            Network::Signet,
+        ] {
+            let genesis_header = BlockHeader::generate_genesis_block_header(network);
+
+            assert!(genesis_header.proof_of_work());
+            assert_eq!(
+                network.get_checkpoints()[0].header_hash,
+                genesis_header.get_hash256d().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test04_test_networks_share_the_address_prefixes() {
+        assert_eq!(0x00, Network::Mainnet.get_address_version_byte());
+        assert_eq!(0x80, Network::Mainnet.get_wif_version_byte());
+
+        for network in [
+            Network::Testnet3,
+            Network::Testnet4,
+            Network::Signet,
+            Network::Regtest,
+        ] {
+            assert_eq!(0x6f, network.get_address_version_byte());
+            assert_eq!(0xc4, network.get_script_version_byte());
+            assert_eq!(0xef, network.get_wif_version_byte());
+        }
+
+        assert_eq!("bcrt", Network::Regtest.get_bech32_hrp());
+    }
+
+    #[test]
+    fn test05_dns_seeders_use_the_default_port_of_the_network() {
+        assert_eq!(
+            DNSSeeder::new("seed.testnet4.bitcoin.sprovoost.nl", 48333),
+            Network::Testnet4.get_dns_seeders()[0]
+        );
+        assert_eq!(7, Network::Mainnet.get_dns_seeders().len());
+        assert!(Network::Regtest.get_dns_seeders().is_empty());
+    }
+
+    #[test]
+    fn test06_network_is_serialized_with_its_magic_numbers() {
+        let mut stream: Vec<u8> = Vec::new();
+        Network::Signet.io_serialize(&mut stream).unwrap();
+
+        assert_eq!(Network::Signet.get_magic_numbers().to_vec(), stream);
+        assert_eq!(
+            Network::Signet,
+            Network::io_deserialize(&mut stream.as_slice()).unwrap()
+        );
+        assert!(Network::io_deserialize(&mut [0u8; 4].as_slice()).is_err());
+    }
+}
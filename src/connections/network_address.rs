@@ -0,0 +1,242 @@
+use crate::{
+    messages::compact_size::CompactSize,
+    serialization::{
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization,
+        serializable_internal_order::SerializableInternalOrder,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::{
+    fmt::{Display, Formatter},
+    io::{Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+const IPV4_NETWORK_ID: u8 = 0x01;
+const IPV6_NETWORK_ID: u8 = 0x02;
+const TORV3_NETWORK_ID: u8 = 0x04;
+const I2P_NETWORK_ID: u8 = 0x05;
+const CJDNS_NETWORK_ID: u8 = 0x06;
+
+const IPV4_SIZE: usize = 4;
+const IPV6_SIZE: usize = 16;
+const TORV3_SIZE: usize = 32;
+const I2P_SIZE: usize = 32;
+
+const CJDNS_PREFIX: u8 = 0xfc;
+const MAX_ADDRESS_SIZE: usize = 512;
+
+/// It's the address of a node in one of the networks of the addresses version 2 (BIP155).
+/// The addresses of networks we don't know are kept as they are, so they can be ignored
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NetworkAddress {
+    IPv4(Ipv4Addr),
+    IPv6(Ipv6Addr),
+    TorV3([u8; TORV3_SIZE]),
+    I2P([u8; I2P_SIZE]),
+    Cjdns(Ipv6Addr),
+    Unknown(u8, Vec<u8>),
+}
+
+impl NetworkAddress {
+    /// Creates the address from the network id and its bytes
+    ///
+    /// ### Error
+    ///  * `ErrorSerialization::ErrorInDeserialization`: It will appear when the bytes are not valid for the network
+    pub fn from_network_id(network_id: u8, bytes: Vec<u8>) -> Result<Self, ErrorSerialization> {
+        let expected_size = match network_id {
+            IPV4_NETWORK_ID => IPV4_SIZE,
+            IPV6_NETWORK_ID | CJDNS_NETWORK_ID => IPV6_SIZE,
+            TORV3_NETWORK_ID => TORV3_SIZE,
+            I2P_NETWORK_ID => I2P_SIZE,
+            _ => return Ok(NetworkAddress::Unknown(network_id, bytes)),
+        };
+
+        if bytes.len() != expected_size {
+            return Err(ErrorSerialization::ErrorInDeserialization(format!(
+                "Address of network {network_id} with {} bytes instead of {expected_size}",
+                bytes.len()
+            )));
+        }
+
+        match network_id {
+            IPV4_NETWORK_ID => Ok(NetworkAddress::IPv4(Ipv4Addr::new(
+                bytes[0], bytes[1], bytes[2], bytes[3],
+            ))),
+            IPV6_NETWORK_ID => Ok(NetworkAddress::IPv6(Ipv6Addr::from(to_array(&bytes)))),
+            TORV3_NETWORK_ID => Ok(NetworkAddress::TorV3(to_array(&bytes))),
+            I2P_NETWORK_ID => Ok(NetworkAddress::I2P(to_array(&bytes))),
+            _ => {
+                if bytes[0] != CJDNS_PREFIX {
+                    return Err(ErrorSerialization::ErrorInDeserialization(
+                        "CJDNS address outside of fc00::/8".to_string(),
+                    ));
+                }
+                Ok(NetworkAddress::Cjdns(Ipv6Addr::from(to_array(&bytes))))
+            }
+        }
+    }
+
+    /// Returns the network id of the address
+    pub fn get_network_id(&self) -> u8 {
+        match self {
+            NetworkAddress::IPv4(_) => IPV4_NETWORK_ID,
+            NetworkAddress::IPv6(_) => IPV6_NETWORK_ID,
+            NetworkAddress::TorV3(_) => TORV3_NETWORK_ID,
+            NetworkAddress::I2P(_) => I2P_NETWORK_ID,
+            NetworkAddress::Cjdns(_) => CJDNS_NETWORK_ID,
+            NetworkAddress::Unknown(network_id, _) => *network_id,
+        }
+    }
+
+    /// Returns the bytes of the address, as they are sent in the messages
+    pub fn get_bytes(&self) -> Vec<u8> {
+        match self {
+            NetworkAddress::IPv4(ip_address) => ip_address.octets().to_vec(),
+            NetworkAddress::IPv6(ip_address) | NetworkAddress::Cjdns(ip_address) => {
+                ip_address.octets().to_vec()
+            }
+            NetworkAddress::TorV3(public_key) => public_key.to_vec(),
+            NetworkAddress::I2P(hash) => hash.to_vec(),
+            NetworkAddress::Unknown(_, bytes) => bytes.clone(),
+        }
+    }
+
+    /// Returns the IP address if it's one we can connect to, and None if it's from other network
+    pub fn get_ip_address(&self) -> Option<IpAddr> {
+        match self {
+            NetworkAddress::IPv4(ip_address) => Some(IpAddr::V4(*ip_address)),
+            NetworkAddress::IPv6(ip_address) => match ip_address.to_ipv4_mapped() {
+                Some(ip_address) => Some(IpAddr::V4(ip_address)),
+                None => Some(IpAddr::V6(*ip_address)),
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns true if the address is from a network we know
+    pub fn is_known(&self) -> bool {
+        !matches!(self, NetworkAddress::Unknown(..))
+    }
+}
+
+/// Converts the bytes to an array, the size must be already checked
+fn to_array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut array = [0; N];
+    array.copy_from_slice(bytes);
+    array
+}
+
+impl From<IpAddr> for NetworkAddress {
+    fn from(ip_address: IpAddr) -> Self {
+        match ip_address {
+            IpAddr::V4(ip_address) => NetworkAddress::IPv4(ip_address),
+            IpAddr::V6(ip_address) => match ip_address.to_ipv4_mapped() {
+                Some(ip_address) => NetworkAddress::IPv4(ip_address),
+                None => NetworkAddress::IPv6(ip_address),
+            },
+        }
+    }
+}
+
+impl Display for NetworkAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (network, bytes) = match self {
+            NetworkAddress::IPv4(ip_address) => return write!(f, "{ip_address}"),
+            NetworkAddress::IPv6(ip_address) => return write!(f, "{ip_address}"),
+            NetworkAddress::Cjdns(ip_address) => return write!(f, "cjdns:{ip_address}"),
+            NetworkAddress::TorV3(public_key) => ("torv3".to_string(), public_key.to_vec()),
+            NetworkAddress::I2P(hash) => ("i2p".to_string(), hash.to_vec()),
+            NetworkAddress::Unknown(network_id, bytes) => {
+                (format!("network {network_id}"), bytes.clone())
+            }
+        };
+
+        let bytes: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+        write!(f, "{network}:{bytes}")
+    }
+}
+
+impl SerializableLittleEndian for NetworkAddress {
+    fn le_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        let bytes = self.get_bytes();
+        self.get_network_id().le_serialize(stream)?;
+        CompactSize::new(bytes.len() as u64).le_serialize(stream)?;
+        bytes.io_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableLittleEndian for NetworkAddress {
+    fn le_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let network_id = u8::le_deserialize(stream)?;
+        let size = CompactSize::le_deserialize(stream)?.value as usize;
+        if size > MAX_ADDRESS_SIZE {
+            return Err(ErrorSerialization::ErrorInDeserialization(format!(
+                "Address of {size} bytes is bigger than the maximum allowed"
+            )));
+        }
+
+        let mut bytes: Vec<u8> = vec![0; size];
+        if stream.read_exact(&mut bytes).is_err() {
+            return Err(ErrorSerialization::ErrorWhileReading);
+        }
+
+        NetworkAddress::from_network_id(network_id, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test01_network_addresses_are_serialized_with_their_network_id() {
+        let addresses = vec![
+            NetworkAddress::IPv4(Ipv4Addr::new(127, 0, 0, 1)),
+            NetworkAddress::IPv6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+            NetworkAddress::TorV3([7; 32]),
+            NetworkAddress::I2P([9; 32]),
+            NetworkAddress::Cjdns(Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 1)),
+            NetworkAddress::Unknown(0x42, vec![1, 2, 3]),
+        ];
+
+        for address in addresses {
+            let mut stream: Vec<u8> = Vec::new();
+            address.le_serialize(&mut stream).unwrap();
+
+            assert_eq!(address.get_network_id(), stream[0]);
+            assert_eq!(
+                address,
+                NetworkAddress::le_deserialize(&mut stream.as_slice()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test02_only_ip_addresses_can_be_connected_to() {
+        let mapped_address =
+            NetworkAddress::from(IpAddr::V6(Ipv4Addr::new(10, 0, 0, 1).to_ipv6_mapped()));
+
+        assert_eq!(
+            NetworkAddress::IPv4(Ipv4Addr::new(10, 0, 0, 1)),
+            mapped_address
+        );
+        assert_eq!(
+            Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+            mapped_address.get_ip_address()
+        );
+        assert_eq!(None, NetworkAddress::TorV3([7; 32]).get_ip_address());
+        assert!(!NetworkAddress::Unknown(0x42, vec![1]).is_known());
+    }
+
+    #[test]
+    fn test03_addresses_with_wrong_size_are_rejected() {
+        assert!(NetworkAddress::from_network_id(IPV4_NETWORK_ID, vec![1, 2, 3]).is_err());
+        assert!(NetworkAddress::from_network_id(TORV3_NETWORK_ID, vec![0; 16]).is_err());
+        assert!(NetworkAddress::from_network_id(CJDNS_NETWORK_ID, vec![0; 16]).is_err());
+    }
+}
@@ -5,15 +5,17 @@ use crate::serialization::{
     serializable_little_endian::SerializableLittleEndian,
 };
 
+use super::socket_conversion::{ipv6_port_to_socket, socket_to_ipv6_port};
+
 use crate::messages::bitfield_services::BitfieldServices;
 
 use std::{
     io::{Read, Write},
-    net::Ipv6Addr,
+    net::{Ipv6Addr, SocketAddr},
 };
 
 /// It's the representation of a new potential peer to connect to
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct NetworkIpAddress {
     time: u32,
     services: BitfieldServices,
@@ -21,6 +23,28 @@ pub struct NetworkIpAddress {
     port: u16,
 }
 
+impl NetworkIpAddress {
+    pub fn new(time: u32, services: BitfieldServices, socket_address: &SocketAddr) -> Self {
+        let (ip_address, port) = socket_to_ipv6_port(socket_address);
+        NetworkIpAddress {
+            time,
+            services,
+            ip_address,
+            port,
+        }
+    }
+
+    /// Returns the address of the potential peer
+    pub fn get_socket_address(&self) -> SocketAddr {
+        ipv6_port_to_socket(&self.ip_address, self.port)
+    }
+
+    /// Returns the last time the potential peer was seen
+    pub fn get_time(&self) -> u32 {
+        self.time
+    }
+}
+
 impl SerializableLittleEndian for NetworkIpAddress {
     fn le_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
         self.time.le_serialize(stream)?;
@@ -0,0 +1,146 @@
+use super::network_address::NetworkAddress;
+
+use crate::{
+    messages::{bitfield_services::BitfieldServices, compact_size::CompactSize},
+    serialization::{
+        deserializable_big_endian::DeserializableBigEndian,
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization, serializable_big_endian::SerializableBigEndian,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::{
+    io::{Read, Write},
+    net::SocketAddr,
+};
+
+/// It's the representation of a new potential peer in the addresses version 2 (BIP155),
+/// which can be from networks other than IPv4 and IPv6
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkIpAddressV2 {
+    time: u32,
+    services: BitfieldServices,
+    address: NetworkAddress,
+    port: u16,
+}
+
+impl NetworkIpAddressV2 {
+    pub fn new(time: u32, services: BitfieldServices, address: NetworkAddress, port: u16) -> Self {
+        NetworkIpAddressV2 {
+            time,
+            services,
+            address,
+            port,
+        }
+    }
+
+    /// Returns the address of the potential peer, in the network it belongs
+    pub fn get_address(&self) -> &NetworkAddress {
+        &self.address
+    }
+
+    /// Returns the port of the potential peer
+    pub fn get_port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the socket address of the potential peer if we can connect to it
+    pub fn get_socket_address(&self) -> Option<SocketAddr> {
+        self.address
+            .get_ip_address()
+            .map(|ip_address| SocketAddr::new(ip_address, self.port))
+    }
+
+    /// Returns the last time the potential peer was seen
+    pub fn get_time(&self) -> u32 {
+        self.time
+    }
+}
+
+impl SerializableLittleEndian for NetworkIpAddressV2 {
+    fn le_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        let mut services: Vec<u8> = Vec::new();
+        self.services.le_serialize(&mut services)?;
+        let services = u64::le_deserialize(&mut services.as_slice())?;
+
+        self.time.le_serialize(stream)?;
+        CompactSize::new(services).le_serialize(stream)?;
+        self.address.le_serialize(stream)?;
+        self.port.be_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableLittleEndian for NetworkIpAddressV2 {
+    fn le_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let time = u32::le_deserialize(stream)?;
+
+        let mut services: Vec<u8> = Vec::new();
+        CompactSize::le_deserialize(stream)?
+            .value
+            .le_serialize(&mut services)?;
+
+        Ok(NetworkIpAddressV2 {
+            time,
+            services: BitfieldServices::le_deserialize(&mut services.as_slice())?,
+            address: NetworkAddress::le_deserialize(stream)?,
+            port: u16::be_deserialize(stream)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::connections::supported_services::SupportedServices;
+
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[test]
+    fn test01_serialize_correctly_network_ip_address_v2() -> Result<(), ErrorSerialization> {
+        let network_ip_address = NetworkIpAddressV2::new(
+            1234,
+            BitfieldServices::new(vec![
+                SupportedServices::NodeNetwork,
+                SupportedServices::NodeNetworkLimited,
+            ]),
+            NetworkAddress::TorV3([5; 32]),
+            9050,
+        );
+
+        let mut stream: Vec<u8> = Vec::new();
+        network_ip_address.le_serialize(&mut stream)?;
+
+        assert_eq!(vec![0xfd, 0x01, 0x04], stream[4..7].to_vec());
+        assert_eq!(
+            network_ip_address,
+            NetworkIpAddressV2::le_deserialize(&mut stream.as_slice())?
+        );
+        assert_eq!(None, network_ip_address.get_socket_address());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test02_ip_address_has_socket_address() {
+        let network_ip_address = NetworkIpAddressV2::new(
+            1234,
+            BitfieldServices::new(vec![SupportedServices::NodeNetwork]),
+            NetworkAddress::IPv4(Ipv4Addr::new(10, 0, 0, 1)),
+            18333,
+        );
+
+        assert_eq!(
+            Some(SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+                18333
+            )),
+            network_ip_address.get_socket_address()
+        );
+        assert_eq!(18333, network_ip_address.get_port());
+        assert_eq!(1234, network_ip_address.get_time());
+    }
+}
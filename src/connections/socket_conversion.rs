@@ -8,3 +8,11 @@ pub fn socket_to_ipv6_port(socket_addr: &SocketAddr) -> (Ipv6Addr, u16) {
     };
     (ip, socket_addr.port())
 }
+
+/// Converts an IPv6 address and a port to a socket address, using IPv4 when the address is an IPv4-mapped one
+pub fn ipv6_port_to_socket(ip_address: &Ipv6Addr, port: u16) -> SocketAddr {
+    match ip_address.to_ipv4_mapped() {
+        Some(v4) => SocketAddr::new(IpAddr::V4(v4), port),
+        None => SocketAddr::new(IpAddr::V6(*ip_address), port),
+    }
+}
@@ -0,0 +1,49 @@
+use super::{command_name::CommandName, compact_size::CompactSize, message::Message};
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use crate::connections::network_ip_address_v2::NetworkIpAddressV2;
+
+use std::io::{Read, Write};
+
+/// It's the address message version 2 (BIP155), sent instead of the address message to the peers that asked for it with the send addresses v2 message
+#[derive(Debug)]
+pub struct AddrV2Message {
+    pub ip_addresses: Vec<NetworkIpAddressV2>,
+}
+
+impl Message for AddrV2Message {
+    fn get_command_name() -> CommandName {
+        CommandName::AddrV2
+    }
+}
+
+impl SerializableInternalOrder for AddrV2Message {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        CompactSize::new(self.ip_addresses.len() as u64).le_serialize(stream)?;
+        for ip_address in self.ip_addresses.iter() {
+            ip_address.le_serialize(stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for AddrV2Message {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let ip_address_count = CompactSize::le_deserialize(stream)?.value;
+        let mut ip_addresses: Vec<NetworkIpAddressV2> = Vec::new();
+
+        for _ in 0..ip_address_count {
+            ip_addresses.push(NetworkIpAddressV2::le_deserialize(stream)?);
+        }
+
+        Ok(AddrV2Message { ip_addresses })
+    }
+}
@@ -27,6 +27,26 @@ impl BitfieldServices {
             false => BitfieldServices { elements },
         }
     }
+
+    /// Changes the full network service for the limited one, used by the pruned nodes that only
+    /// serve the latest blocks
+    pub fn with_network_limited(mut self) -> Self {
+        if let Some(position) = self
+            .elements
+            .iter()
+            .position(|element| *element == SupportedServices::NodeNetwork)
+        {
+            self.elements.remove(position);
+            if !self
+                .elements
+                .contains(&SupportedServices::NodeNetworkLimited)
+            {
+                self.elements.push(SupportedServices::NodeNetworkLimited);
+            }
+        }
+
+        self
+    }
 }
 
 impl PartialEq for BitfieldServices {
@@ -106,6 +126,7 @@ impl DeserializableLittleEndian for BitfieldServices {
             SupportedServices::NodeBloom,
             SupportedServices::NodeWitness,
             SupportedServices::NodeXThin,
+            SupportedServices::NodeCompactFilters,
             SupportedServices::NodeNetworkLimited,
         ];
 
@@ -191,4 +212,20 @@ mod tests {
 
         assert_eq!(Ok(expected_bitfield), bitfield_result);
     }
+
+    #[test]
+    fn test04_pruned_node_advertises_limited_network() {
+        let services = BitfieldServices::new(vec![
+            SupportedServices::NodeNetwork,
+            SupportedServices::NodeBloom,
+        ])
+        .with_network_limited();
+
+        let expected_services = BitfieldServices::new(vec![
+            SupportedServices::NodeBloom,
+            SupportedServices::NodeNetworkLimited,
+        ]);
+
+        assert_eq!(expected_services, services);
+    }
 }
@@ -0,0 +1,56 @@
+use super::{command_name::CommandName, compact_size::CompactSize, message::Message};
+
+use crate::block_structure::{hash::HashType, transaction::Transaction};
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::io::{Read, Write};
+
+/// It's the block transactions message, the answer to a get block transactions message
+#[derive(Debug, PartialEq)]
+pub struct BlockTxnMessage {
+    pub block_hash: HashType,
+    pub transactions: Vec<Transaction>,
+}
+
+impl Message for BlockTxnMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::BlockTxn
+    }
+}
+
+impl SerializableInternalOrder for BlockTxnMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.block_hash.le_serialize(stream)?;
+
+        CompactSize::new(self.transactions.len() as u64).le_serialize(stream)?;
+        for transaction in self.transactions.iter() {
+            transaction.io_serialize(stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for BlockTxnMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let block_hash = HashType::le_deserialize(stream)?;
+
+        let count = CompactSize::le_deserialize(stream)?.value;
+        let mut transactions = Vec::new();
+        for _ in 0..count {
+            transactions.push(Transaction::io_deserialize(stream)?);
+        }
+
+        Ok(BlockTxnMessage {
+            block_hash,
+            transactions,
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use super::{command_name::CommandName, compact_size::CompactSize, message::Message};
+
+use crate::block_structure::hash::HashType;
+
+use crate::serialization::{
+    deserializable_big_endian::DeserializableBigEndian,
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization, serializable_big_endian::SerializableBigEndian,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::io::{Read, Write};
+
+/// It's the compact filter checkpoint message (BIP157), with the filter headers at every thousand blocks
+#[derive(Debug, PartialEq)]
+pub struct CFCheckptMessage {
+    pub filter_type: u8,
+    pub stop_hash: HashType,
+    pub filter_headers: Vec<HashType>,
+}
+
+impl Message for CFCheckptMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::CFCheckpt
+    }
+}
+
+impl SerializableInternalOrder for CFCheckptMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.filter_type.le_serialize(stream)?;
+        self.stop_hash.le_serialize(stream)?;
+
+        CompactSize::new(self.filter_headers.len() as u64).le_serialize(stream)?;
+        for filter_header in self.filter_headers.iter() {
+            filter_header.be_serialize(stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for CFCheckptMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let filter_type = u8::le_deserialize(stream)?;
+        let stop_hash = HashType::le_deserialize(stream)?;
+
+        let count = CompactSize::le_deserialize(stream)?.value;
+        let mut filter_headers: Vec<HashType> = Vec::new();
+        for _ in 0..count {
+            filter_headers.push(HashType::be_deserialize(stream)?);
+        }
+
+        Ok(CFCheckptMessage {
+            filter_type,
+            stop_hash,
+            filter_headers,
+        })
+    }
+}
@@ -0,0 +1,72 @@
+use super::{command_name::CommandName, compact_size::CompactSize, message::Message};
+
+use crate::block_structure::{filter_chain::MAX_GET_CFHEADERS_SIZE, hash::HashType};
+
+use crate::serialization::{
+    deserializable_big_endian::DeserializableBigEndian,
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization, serializable_big_endian::SerializableBigEndian,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::io::{Read, Write};
+
+/// It's the compact filter headers message (BIP157), with the header of the filter before the range
+/// and the hashes of the filters in it, so the headers of the range can be calculated
+#[derive(Debug, PartialEq)]
+pub struct CFHeadersMessage {
+    pub filter_type: u8,
+    pub stop_hash: HashType,
+    pub previous_filter_header: HashType,
+    pub filter_hashes: Vec<HashType>,
+}
+
+impl Message for CFHeadersMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::CFHeaders
+    }
+}
+
+impl SerializableInternalOrder for CFHeadersMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.filter_type.le_serialize(stream)?;
+        self.stop_hash.le_serialize(stream)?;
+        self.previous_filter_header.be_serialize(stream)?;
+
+        CompactSize::new(self.filter_hashes.len() as u64).le_serialize(stream)?;
+        for filter_hash in self.filter_hashes.iter() {
+            filter_hash.be_serialize(stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for CFHeadersMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let filter_type = u8::le_deserialize(stream)?;
+        let stop_hash = HashType::le_deserialize(stream)?;
+        let previous_filter_header = HashType::be_deserialize(stream)?;
+
+        let count = CompactSize::le_deserialize(stream)?.value;
+        if count > MAX_GET_CFHEADERS_SIZE {
+            return Err(ErrorSerialization::ErrorInDeserialization(format!(
+                "Compact filter headers message with {count} hashes is bigger than the maximum allowed"
+            )));
+        }
+
+        let mut filter_hashes: Vec<HashType> = Vec::new();
+        for _ in 0..count {
+            filter_hashes.push(HashType::be_deserialize(stream)?);
+        }
+
+        Ok(CFHeadersMessage {
+            filter_type,
+            stop_hash,
+            previous_filter_header,
+            filter_hashes,
+        })
+    }
+}
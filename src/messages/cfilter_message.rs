@@ -0,0 +1,58 @@
+use super::{command_name::CommandName, compact_size::CompactSize, message::Message};
+
+use crate::block_structure::hash::HashType;
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::io::{Read, Write};
+
+/// It's the compact filter message (BIP157), with the filter of a block as it's encoded
+#[derive(Debug, PartialEq)]
+pub struct CFilterMessage {
+    pub filter_type: u8,
+    pub block_hash: HashType,
+    pub filter: Vec<u8>,
+}
+
+impl Message for CFilterMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::CFilter
+    }
+}
+
+impl SerializableInternalOrder for CFilterMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.filter_type.le_serialize(stream)?;
+        self.block_hash.le_serialize(stream)?;
+
+        CompactSize::new(self.filter.len() as u64).le_serialize(stream)?;
+        self.filter.io_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for CFilterMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let filter_type = u8::le_deserialize(stream)?;
+        let block_hash = HashType::le_deserialize(stream)?;
+
+        let size = CompactSize::le_deserialize(stream)?.value as usize;
+        let mut filter: Vec<u8> = vec![0; size];
+        if stream.read_exact(&mut filter).is_err() {
+            return Err(ErrorSerialization::ErrorWhileReading);
+        }
+
+        Ok(CFilterMessage {
+            filter_type,
+            block_hash,
+            filter,
+        })
+    }
+}
@@ -0,0 +1,293 @@
+use super::{
+    command_name::CommandName, compact_size::CompactSize, message::Message,
+    prefilled_transaction::PrefilledTransaction,
+};
+
+use crate::block_structure::{
+    block::Block,
+    block_header::BlockHeader,
+    hash::{hash256, sip_hash24, HashType},
+    transaction::Transaction,
+};
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::io::{Read, Write};
+
+pub const SHORT_ID_SIZE: usize = 6;
+
+pub type ShortTransactionId = [u8; SHORT_ID_SIZE];
+
+/// It's the compact block message (BIP152). It has the header of the block, the coinbase and
+/// a short id for each other transaction, so the receiver can rebuild the block with the transactions it already has
+#[derive(Debug, Clone, PartialEq)]
+pub struct CmpctBlockMessage {
+    pub header: BlockHeader,
+    pub nonce: u64,
+    pub short_ids: Vec<ShortTransactionId>,
+    pub prefilled_transactions: Vec<PrefilledTransaction>,
+}
+
+impl CmpctBlockMessage {
+    /// Creates the compact block of the given block, sending the coinbase completely
+    ///
+    /// ### Error
+    ///  * `ErrorSerialization::ErrorInSerialization`: It will appear when a transaction or the header could not be hashed
+    pub fn from_block(block: &Block, nonce: u64) -> Result<Self, ErrorSerialization> {
+        let mut compact_block = CmpctBlockMessage {
+            header: block.header,
+            nonce,
+            short_ids: Vec::new(),
+            prefilled_transactions: Vec::new(),
+        };
+
+        let keys = compact_block.get_short_id_keys()?;
+
+        for (index, transaction) in block.transactions.iter().enumerate() {
+            if index == 0 {
+                compact_block
+                    .prefilled_transactions
+                    .push(PrefilledTransaction::new(0, transaction.clone()));
+                continue;
+            }
+
+            compact_block
+                .short_ids
+                .push(Self::calculate_short_id(keys, transaction)?);
+        }
+
+        Ok(compact_block)
+    }
+
+    /// Returns the amount of transactions in the block
+    pub fn transaction_count(&self) -> usize {
+        self.short_ids.len() + self.prefilled_transactions.len()
+    }
+
+    /// Returns the keys used to calculate the short ids of this block. They are the first
+    /// 16 bytes of the sha256 of the header and the nonce
+    ///
+    /// ### Error
+    ///  * `ErrorSerialization::ErrorInSerialization`: It will appear when the header could not be hashed
+    pub fn get_short_id_keys(&self) -> Result<(u64, u64), ErrorSerialization> {
+        let mut buffer: Vec<u8> = Vec::new();
+        self.header.io_serialize_without_count(&mut buffer)?;
+        self.nonce.le_serialize(&mut buffer)?;
+
+        let hash = hash256(&buffer)?;
+
+        let mut first_key = [0; 8];
+        let mut second_key = [0; 8];
+        first_key.copy_from_slice(&hash[0..8]);
+        second_key.copy_from_slice(&hash[8..16]);
+
+        Ok((
+            u64::from_le_bytes(first_key),
+            u64::from_le_bytes(second_key),
+        ))
+    }
+
+    /// Calculates the short id of a transaction with the keys of a block
+    ///
+    /// ### Error
+    ///  * `ErrorSerialization::ErrorInSerialization`: It will appear when the transaction could not be hashed
+    pub fn calculate_short_id(
+        keys: (u64, u64),
+        transaction: &Transaction,
+    ) -> Result<ShortTransactionId, ErrorSerialization> {
+        let transaction_id: HashType = match transaction.get_tx_id() {
+            Ok(transaction_id) => transaction_id,
+            Err(_) => {
+                return Err(ErrorSerialization::ErrorInSerialization(
+                    "While getting the id of a transaction for its short id".to_string(),
+                ))
+            }
+        };
+
+        let hash = sip_hash24(keys, &transaction_id).to_le_bytes();
+
+        let mut short_id: ShortTransactionId = [0; SHORT_ID_SIZE];
+        short_id.copy_from_slice(&hash[..SHORT_ID_SIZE]);
+
+        Ok(short_id)
+    }
+}
+
+impl Message for CmpctBlockMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::CmpctBlock
+    }
+}
+
+impl SerializableInternalOrder for CmpctBlockMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.header.io_serialize_without_count(stream)?;
+        self.nonce.le_serialize(stream)?;
+
+        CompactSize::new(self.short_ids.len() as u64).le_serialize(stream)?;
+        for short_id in self.short_ids.iter() {
+            short_id.io_serialize(stream)?;
+        }
+
+        CompactSize::new(self.prefilled_transactions.len() as u64).le_serialize(stream)?;
+        let mut next_index: u64 = 0;
+        for prefilled_transaction in self.prefilled_transactions.iter() {
+            let differential_index = match prefilled_transaction.index.checked_sub(next_index) {
+                Some(differential_index) => differential_index,
+                None => {
+                    return Err(ErrorSerialization::ErrorInSerialization(
+                        "The prefilled transactions are not in order".to_string(),
+                    ))
+                }
+            };
+
+            CompactSize::new(differential_index).le_serialize(stream)?;
+            prefilled_transaction.transaction.io_serialize(stream)?;
+            next_index = prefilled_transaction.index + 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for CmpctBlockMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let mut header = BlockHeader::io_deserialize_without_count(stream)?;
+        let nonce = u64::le_deserialize(stream)?;
+
+        let short_ids_count = CompactSize::le_deserialize(stream)?.value;
+        let mut short_ids = Vec::new();
+        for _ in 0..short_ids_count {
+            short_ids.push(ShortTransactionId::io_deserialize(stream)?);
+        }
+
+        let prefilled_count = CompactSize::le_deserialize(stream)?.value;
+        let mut prefilled_transactions = Vec::new();
+        let mut next_index: u64 = 0;
+        for _ in 0..prefilled_count {
+            let differential_index = CompactSize::le_deserialize(stream)?.value;
+            let index = match next_index.checked_add(differential_index) {
+                Some(index) if index < short_ids_count + prefilled_count => index,
+                _ => {
+                    return Err(ErrorSerialization::ErrorInDeserialization(
+                        "The index of a prefilled transaction is out of the block".to_string(),
+                    ))
+                }
+            };
+
+            prefilled_transactions.push(PrefilledTransaction::new(
+                index,
+                Transaction::io_deserialize(stream)?,
+            ));
+            next_index = index + 1;
+        }
+
+        header.transaction_count = CompactSize::new(short_ids_count + prefilled_count);
+
+        Ok(CmpctBlockMessage {
+            header,
+            nonce,
+            short_ids,
+            prefilled_transactions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::block_structure::{
+        block_version::BlockVersion, compact256::Compact256, outpoint::Outpoint,
+        transaction_input::TransactionInput, transaction_output::TransactionOutput,
+    };
+
+    fn create_transaction(time: u32) -> Transaction {
+        Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([1; 32], 23),
+                vec![1, 2, 3],
+                24,
+            )],
+            tx_out: vec![TransactionOutput {
+                value: 10,
+                pk_script: vec![4, 5, 6],
+            }],
+            time,
+        }
+    }
+
+    fn create_block() -> Block {
+        let mut block = Block::new(BlockHeader::new(
+            BlockVersion::version(1),
+            [3; 32],
+            [4; 32],
+            5,
+            Compact256::from(u32::MAX),
+            6,
+            CompactSize::new(3),
+        ));
+        for time in 0..3 {
+            block.append_transaction(create_transaction(time)).unwrap();
+        }
+        block
+    }
+
+    #[test]
+    fn test_01_compact_block_has_coinbase_and_short_ids() {
+        let block = create_block();
+        let compact_block = CmpctBlockMessage::from_block(&block, 42).unwrap();
+
+        assert_eq!(3, compact_block.transaction_count());
+        assert_eq!(
+            vec![PrefilledTransaction::new(0, block.transactions[0].clone())],
+            compact_block.prefilled_transactions
+        );
+
+        let keys = compact_block.get_short_id_keys().unwrap();
+        assert_eq!(
+            vec![
+                CmpctBlockMessage::calculate_short_id(keys, &block.transactions[1]).unwrap(),
+                CmpctBlockMessage::calculate_short_id(keys, &block.transactions[2]).unwrap(),
+            ],
+            compact_block.short_ids
+        );
+    }
+
+    #[test]
+    fn test_02_short_ids_depend_on_the_nonce() {
+        let block = create_block();
+        let first_compact_block = CmpctBlockMessage::from_block(&block, 1).unwrap();
+        let second_compact_block = CmpctBlockMessage::from_block(&block, 2).unwrap();
+
+        assert_ne!(
+            first_compact_block.short_ids,
+            second_compact_block.short_ids
+        );
+    }
+
+    #[test]
+    fn test_03_correct_compact_block_serialization() {
+        let block = create_block();
+        let mut compact_block = CmpctBlockMessage::from_block(&block, 42).unwrap();
+        compact_block
+            .prefilled_transactions
+            .push(PrefilledTransaction::new(2, block.transactions[2].clone()));
+        compact_block.short_ids.pop();
+
+        let mut stream: Vec<u8> = Vec::new();
+        compact_block.io_serialize(&mut stream).unwrap();
+
+        let deserialized_compact_block =
+            CmpctBlockMessage::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert_eq!(compact_block, deserialized_compact_block);
+    }
+}
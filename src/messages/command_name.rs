@@ -6,7 +6,10 @@ use crate::serialization::{
 
 use std::io::{Read, Write};
 
-use std::convert::{TryFrom, TryInto};
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt::{Display, Formatter},
+};
 
 type CommandNameType = [u8; 12];
 
@@ -19,12 +22,18 @@ const VERACK_NAME: CommandNameType = [
 const GET_HEADERS_NAME: CommandNameType = [
     b'g', b'e', b't', b'h', b'e', b'a', b'd', b'e', b'r', b's', b'\0', b'\0',
 ];
+const GET_BLOCKS_NAME: CommandNameType = [
+    b'g', b'e', b't', b'b', b'l', b'o', b'c', b'k', b's', b'\0', b'\0', b'\0',
+];
 const HEADERS_NAME: CommandNameType = [
     b'h', b'e', b'a', b'd', b'e', b'r', b's', b'\0', b'\0', b'\0', b'\0', b'\0',
 ];
 const INVENTORY_NAME: CommandNameType = [
     b'i', b'n', b'v', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0',
 ];
+const NOT_FOUND_NAME: CommandNameType = [
+    b'n', b'o', b't', b'f', b'o', b'u', b'n', b'd', b'\0', b'\0', b'\0', b'\0',
+];
 const BLOCK_NAME: CommandNameType = [
     b'b', b'l', b'o', b'c', b'k', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0',
 ];
@@ -40,6 +49,9 @@ const SEND_HEADERS_NAME: CommandNameType = [
 const SEND_CMPCT_NAME: CommandNameType = [
     b's', b'e', b'n', b'd', b'c', b'm', b'p', b'c', b't', b'\0', b'\0', b'\0',
 ];
+const GET_ADDR_NAME: CommandNameType = [
+    b'g', b'e', b't', b'a', b'd', b'd', b'r', b'\0', b'\0', b'\0', b'\0', b'\0',
+];
 const ADDR_NAME: CommandNameType = [
     b'a', b'd', b'd', b'r', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0',
 ];
@@ -55,6 +67,57 @@ const ALERT_NAME: CommandNameType = [
 const TX_NAME: CommandNameType = [
     b't', b'x', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0',
 ];
+const CMPCT_BLOCK_NAME: CommandNameType = [
+    b'c', b'm', b'p', b'c', b't', b'b', b'l', b'o', b'c', b'k', b'\0', b'\0',
+];
+const GET_BLOCK_TXN_NAME: CommandNameType = [
+    b'g', b'e', b't', b'b', b'l', b'o', b'c', b'k', b't', b'x', b'n', b'\0',
+];
+const BLOCK_TXN_NAME: CommandNameType = [
+    b'b', b'l', b'o', b'c', b'k', b't', b'x', b'n', b'\0', b'\0', b'\0', b'\0',
+];
+const REJECT_NAME: CommandNameType = [
+    b'r', b'e', b'j', b'e', b'c', b't', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0',
+];
+const ADDR_V2_NAME: CommandNameType = [
+    b'a', b'd', b'd', b'r', b'v', b'2', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0',
+];
+const WTXID_RELAY_NAME: CommandNameType = [
+    b'w', b't', b'x', b'i', b'd', b'r', b'e', b'l', b'a', b'y', b'\0', b'\0',
+];
+const SEND_ADDR_V2_NAME: CommandNameType = [
+    b's', b'e', b'n', b'd', b'a', b'd', b'd', b'r', b'v', b'2', b'\0', b'\0',
+];
+const FILTER_LOAD_NAME: CommandNameType = [
+    b'f', b'i', b'l', b't', b'e', b'r', b'l', b'o', b'a', b'd', b'\0', b'\0',
+];
+const FILTER_ADD_NAME: CommandNameType = [
+    b'f', b'i', b'l', b't', b'e', b'r', b'a', b'd', b'd', b'\0', b'\0', b'\0',
+];
+const FILTER_CLEAR_NAME: CommandNameType = [
+    b'f', b'i', b'l', b't', b'e', b'r', b'c', b'l', b'e', b'a', b'r', b'\0',
+];
+const MERKLE_BLOCK_NAME: CommandNameType = [
+    b'm', b'e', b'r', b'k', b'l', b'e', b'b', b'l', b'o', b'c', b'k', b'\0',
+];
+const GET_CFILTERS_NAME: CommandNameType = [
+    b'g', b'e', b't', b'c', b'f', b'i', b'l', b't', b'e', b'r', b's', b'\0',
+];
+const CFILTER_NAME: CommandNameType = [
+    b'c', b'f', b'i', b'l', b't', b'e', b'r', b'\0', b'\0', b'\0', b'\0', b'\0',
+];
+const GET_CFHEADERS_NAME: CommandNameType = [
+    b'g', b'e', b't', b'c', b'f', b'h', b'e', b'a', b'd', b'e', b'r', b's',
+];
+const CFHEADERS_NAME: CommandNameType = [
+    b'c', b'f', b'h', b'e', b'a', b'd', b'e', b'r', b's', b'\0', b'\0', b'\0',
+];
+const GET_CFCHECKPT_NAME: CommandNameType = [
+    b'g', b'e', b't', b'c', b'f', b'c', b'h', b'e', b'c', b'k', b'p', b't',
+];
+const CFCHECKPT_NAME: CommandNameType = [
+    b'c', b'f', b'c', b'h', b'e', b'c', b'k', b'p', b't', b'\0', b'\0', b'\0',
+];
 
 /// It's the representation of the type of message received or sent
 #[derive(Debug, Copy, Clone, std::cmp::PartialEq)]
@@ -62,18 +125,39 @@ pub enum CommandName {
     Version,
     Verack,
     GetHeaders,
+    GetBlocks,
     Headers,
     Inventory,
+    NotFound,
     Block,
     Ping,
     Pong,
     SendHeaders,
     SendCmpct,
+    GetAddr,
     Addr,
     FeeFilter,
     GetData,
     Alert,
     Tx,
+    CmpctBlock,
+    GetBlockTxn,
+    BlockTxn,
+    Reject,
+    WtxidRelay,
+    SendAddrV2,
+    AddrV2,
+    FilterLoad,
+    FilterAdd,
+    FilterClear,
+    MerkleBlock,
+    GetCFilters,
+    CFilter,
+    GetCFHeaders,
+    CFHeaders,
+    GetCFCheckpt,
+    CFCheckpt,
+    Unknown(CommandNameType),
 }
 
 impl From<CommandName> for CommandNameType {
@@ -82,18 +166,39 @@ impl From<CommandName> for CommandNameType {
             CommandName::Version => VERSION_NAME,
             CommandName::Verack => VERACK_NAME,
             CommandName::GetHeaders => GET_HEADERS_NAME,
+            CommandName::GetBlocks => GET_BLOCKS_NAME,
             CommandName::Headers => HEADERS_NAME,
             CommandName::Inventory => INVENTORY_NAME,
+            CommandName::NotFound => NOT_FOUND_NAME,
             CommandName::Block => BLOCK_NAME,
             CommandName::Ping => PING_NAME,
             CommandName::Pong => PONG_NAME,
             CommandName::SendHeaders => SEND_HEADERS_NAME,
             CommandName::SendCmpct => SEND_CMPCT_NAME,
+            CommandName::GetAddr => GET_ADDR_NAME,
             CommandName::Addr => ADDR_NAME,
             CommandName::FeeFilter => FEE_FILTER_NAME,
             CommandName::GetData => GET_DATA_NAME,
             CommandName::Alert => ALERT_NAME,
             CommandName::Tx => TX_NAME,
+            CommandName::CmpctBlock => CMPCT_BLOCK_NAME,
+            CommandName::GetBlockTxn => GET_BLOCK_TXN_NAME,
+            CommandName::BlockTxn => BLOCK_TXN_NAME,
+            CommandName::Reject => REJECT_NAME,
+            CommandName::WtxidRelay => WTXID_RELAY_NAME,
+            CommandName::SendAddrV2 => SEND_ADDR_V2_NAME,
+            CommandName::AddrV2 => ADDR_V2_NAME,
+            CommandName::FilterLoad => FILTER_LOAD_NAME,
+            CommandName::FilterAdd => FILTER_ADD_NAME,
+            CommandName::FilterClear => FILTER_CLEAR_NAME,
+            CommandName::MerkleBlock => MERKLE_BLOCK_NAME,
+            CommandName::GetCFilters => GET_CFILTERS_NAME,
+            CommandName::CFilter => CFILTER_NAME,
+            CommandName::GetCFHeaders => GET_CFHEADERS_NAME,
+            CommandName::CFHeaders => CFHEADERS_NAME,
+            CommandName::GetCFCheckpt => GET_CFCHECKPT_NAME,
+            CommandName::CFCheckpt => CFCHECKPT_NAME,
+            CommandName::Unknown(command_name) => command_name,
         }
     }
 }
@@ -106,18 +211,39 @@ impl TryFrom<CommandNameType> for CommandName {
             VERSION_NAME => Ok(CommandName::Version),
             VERACK_NAME => Ok(CommandName::Verack),
             GET_HEADERS_NAME => Ok(CommandName::GetHeaders),
+            GET_BLOCKS_NAME => Ok(CommandName::GetBlocks),
             HEADERS_NAME => Ok(CommandName::Headers),
             INVENTORY_NAME => Ok(CommandName::Inventory),
+            NOT_FOUND_NAME => Ok(CommandName::NotFound),
             BLOCK_NAME => Ok(CommandName::Block),
             PING_NAME => Ok(CommandName::Ping),
             PONG_NAME => Ok(CommandName::Pong),
             SEND_HEADERS_NAME => Ok(CommandName::SendHeaders),
             SEND_CMPCT_NAME => Ok(CommandName::SendCmpct),
+            GET_ADDR_NAME => Ok(CommandName::GetAddr),
             ADDR_NAME => Ok(CommandName::Addr),
             FEE_FILTER_NAME => Ok(CommandName::FeeFilter),
             GET_DATA_NAME => Ok(CommandName::GetData),
             ALERT_NAME => Ok(CommandName::Alert),
             TX_NAME => Ok(CommandName::Tx),
+            CMPCT_BLOCK_NAME => Ok(CommandName::CmpctBlock),
+            GET_BLOCK_TXN_NAME => Ok(CommandName::GetBlockTxn),
+            BLOCK_TXN_NAME => Ok(CommandName::BlockTxn),
+            REJECT_NAME => Ok(CommandName::Reject),
+            WTXID_RELAY_NAME => Ok(CommandName::WtxidRelay),
+            SEND_ADDR_V2_NAME => Ok(CommandName::SendAddrV2),
+            ADDR_V2_NAME => Ok(CommandName::AddrV2),
+            FILTER_LOAD_NAME => Ok(CommandName::FilterLoad),
+            FILTER_ADD_NAME => Ok(CommandName::FilterAdd),
+            FILTER_CLEAR_NAME => Ok(CommandName::FilterClear),
+            MERKLE_BLOCK_NAME => Ok(CommandName::MerkleBlock),
+            GET_CFILTERS_NAME => Ok(CommandName::GetCFilters),
+            CFILTER_NAME => Ok(CommandName::CFilter),
+            GET_CFHEADERS_NAME => Ok(CommandName::GetCFHeaders),
+            CFHEADERS_NAME => Ok(CommandName::CFHeaders),
+            GET_CFCHECKPT_NAME => Ok(CommandName::GetCFCheckpt),
+            CFCHECKPT_NAME => Ok(CommandName::CFCheckpt),
+            _ if is_well_formed(&value) => Ok(CommandName::Unknown(value)),
             _ => Err(ErrorSerialization::ErrorInDeserialization(format!(
                 "Invalid command name, we get: {:?}",
                 value
@@ -126,6 +252,35 @@ impl TryFrom<CommandNameType> for CommandName {
     }
 }
 
+/// Returns true if the command name is made of printable ASCII characters padded with null bytes,
+/// so a command that we don't know can be told apart from a corrupted header
+fn is_well_formed(command_name: &CommandNameType) -> bool {
+    let length = command_name
+        .iter()
+        .position(|byte| *byte == b'\0')
+        .unwrap_or(command_name.len());
+
+    length > 0
+        && command_name[..length]
+            .iter()
+            .all(|byte| byte.is_ascii_graphic())
+        && command_name[length..].iter().all(|byte| *byte == b'\0')
+}
+
+impl Display for CommandName {
+    /// Shows the name of the command as it's sent in the messages
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let command_name: CommandNameType = (*self).into();
+        let command_name: String = command_name
+            .iter()
+            .take_while(|byte| **byte != b'\0')
+            .map(|byte| *byte as char)
+            .collect();
+
+        write!(f, "{command_name}")
+    }
+}
+
 impl SerializableInternalOrder for CommandName {
     fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
         let command_name: CommandNameType = (*self).into();
@@ -174,4 +329,31 @@ mod tests {
 
         assert_eq!(deserialized_command, command_name_alert);
     }
+
+    #[test]
+    fn test_04_unknown_command_name_is_kept() {
+        let command_name_type: CommandNameType = [
+            b'm', b'e', b'm', b'p', b'o', b'o', b'l', b'\0', b'\0', b'\0', b'\0', b'\0',
+        ];
+        let command_name: CommandName = command_name_type.try_into().unwrap();
+
+        assert_eq!(CommandName::Unknown(command_name_type), command_name);
+        assert_eq!("mempool", command_name.to_string());
+
+        let mut vec = Vec::new();
+        command_name.io_serialize(&mut vec).unwrap();
+
+        assert_eq!(command_name_type.to_vec(), vec);
+    }
+
+    #[test]
+    fn test_05_malformed_command_name_is_rejected() {
+        let empty_command_name: CommandNameType = [0; 12];
+        let not_padded_command_name: CommandNameType = [
+            b'p', b'i', b'n', b'g', b'\0', b'x', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0',
+        ];
+
+        assert!(CommandName::try_from(empty_command_name).is_err());
+        assert!(CommandName::try_from(not_padded_command_name).is_err());
+    }
 }
@@ -0,0 +1,52 @@
+use super::{command_name::CommandName, compact_size::CompactSize, message::Message};
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use crate::node_structure::bloom_filter::MAX_ELEMENT_SIZE;
+
+use std::io::{Read, Write};
+
+/// It's the filter add message (BIP37), sent by a lightweight client to add an element to the filter it already loaded
+#[derive(Debug)]
+pub struct FilterAddMessage {
+    pub data: Vec<u8>,
+}
+
+impl Message for FilterAddMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::FilterAdd
+    }
+}
+
+impl SerializableInternalOrder for FilterAddMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        CompactSize::new(self.data.len() as u64).le_serialize(stream)?;
+        self.data.io_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for FilterAddMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let size = CompactSize::le_deserialize(stream)?.value as usize;
+        if size > MAX_ELEMENT_SIZE {
+            return Err(ErrorSerialization::ErrorInDeserialization(format!(
+                "Filter element of {size} bytes is bigger than the maximum allowed"
+            )));
+        }
+
+        let mut data: Vec<u8> = vec![0; size];
+        if stream.read_exact(&mut data).is_err() {
+            return Err(ErrorSerialization::ErrorWhileReading);
+        }
+
+        Ok(FilterAddMessage { data })
+    }
+}
@@ -0,0 +1,38 @@
+use super::{
+    command_name::CommandName,
+    message::{Message, CHECKSUM_EMPTY_PAYLOAD},
+};
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+};
+
+use std::io::{Read, Write};
+
+/// It's the filter clear message (BIP37), sent by a lightweight client to remove its filter
+#[derive(Debug, std::cmp::PartialEq)]
+pub struct FilterClearMessage;
+
+impl Message for FilterClearMessage {
+    fn calculate_checksum(_: &[u8]) -> Result<[u8; 4], ErrorSerialization> {
+        Ok(CHECKSUM_EMPTY_PAYLOAD)
+    }
+
+    fn get_command_name() -> CommandName {
+        CommandName::FilterClear
+    }
+}
+
+impl SerializableInternalOrder for FilterClearMessage {
+    fn io_serialize(&self, _: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for FilterClearMessage {
+    fn io_deserialize(_: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        Ok(FilterClearMessage)
+    }
+}
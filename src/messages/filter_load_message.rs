@@ -0,0 +1,37 @@
+use super::{command_name::CommandName, message::Message};
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+};
+
+use crate::node_structure::bloom_filter::BloomFilter;
+
+use std::io::{Read, Write};
+
+/// It's the filter load message (BIP37), sent by a lightweight client so we only relay it the transactions matching the filter
+#[derive(Debug)]
+pub struct FilterLoadMessage {
+    pub bloom_filter: BloomFilter,
+}
+
+impl Message for FilterLoadMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::FilterLoad
+    }
+}
+
+impl SerializableInternalOrder for FilterLoadMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.bloom_filter.io_serialize(stream)
+    }
+}
+
+impl DeserializableInternalOrder for FilterLoadMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        Ok(FilterLoadMessage {
+            bloom_filter: BloomFilter::io_deserialize(stream)?,
+        })
+    }
+}
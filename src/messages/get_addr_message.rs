@@ -0,0 +1,99 @@
+use super::{
+    command_name::CommandName,
+    message::{Message, CHECKSUM_EMPTY_PAYLOAD},
+};
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+};
+
+use std::io::{Read, Write};
+
+/// It's the get addresses message
+#[derive(Debug, std::cmp::PartialEq)]
+pub struct GetAddrMessage;
+
+impl Message for GetAddrMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::GetAddr
+    }
+
+    fn calculate_checksum(_: &[u8]) -> Result<[u8; 4], ErrorSerialization> {
+        Ok(CHECKSUM_EMPTY_PAYLOAD)
+    }
+}
+
+impl SerializableInternalOrder for GetAddrMessage {
+    fn io_serialize(&self, _: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for GetAddrMessage {
+    fn io_deserialize(_: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        Ok(GetAddrMessage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DeserializableInternalOrder, ErrorSerialization, GetAddrMessage, SerializableInternalOrder,
+        CHECKSUM_EMPTY_PAYLOAD,
+    };
+
+    use crate::messages::{
+        command_name::CommandName, message::Message, message_header::MessageHeader,
+    };
+
+    use crate::serialization::serializable_little_endian::SerializableLittleEndian;
+
+    #[test]
+    fn test01_serialize() -> Result<(), ErrorSerialization> {
+        let magic_bytes: [u8; 4] = [0x55, 0x66, 0xee, 0xee];
+
+        let get_addr_message = GetAddrMessage;
+        let mut stream: Vec<u8> = Vec::new();
+
+        GetAddrMessage::serialize_message(&mut stream, magic_bytes, &get_addr_message)?;
+
+        let mut expected_stream: Vec<u8> = Vec::new();
+        magic_bytes.io_serialize(&mut expected_stream)?;
+        CommandName::GetAddr.io_serialize(&mut expected_stream)?;
+        vec![0, 0, 0, 0].le_serialize(&mut expected_stream)?;
+        CHECKSUM_EMPTY_PAYLOAD.io_serialize(&mut expected_stream)?;
+
+        assert_eq!(expected_stream, stream);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test02_deserialize() -> Result<(), ErrorSerialization> {
+        let magic_bytes: [u8; 4] = [0x55, 0x66, 0xee, 0xee];
+
+        let header = MessageHeader {
+            magic_numbers: magic_bytes,
+            command_name: CommandName::GetAddr,
+            payload_size: 0,
+            checksum: CHECKSUM_EMPTY_PAYLOAD,
+        };
+
+        let mut stream: Vec<u8> = Vec::new();
+        magic_bytes.io_serialize(&mut stream)?;
+        CommandName::GetAddr.io_serialize(&mut stream)?;
+        vec![0, 0, 0, 0].le_serialize(&mut stream)?;
+        CHECKSUM_EMPTY_PAYLOAD.io_serialize(&mut stream)?;
+        let mut stream: &[u8] = &stream;
+
+        let expected_get_addr = GetAddrMessage::deserialize_message(&mut stream, header)?;
+
+        let get_addr = GetAddrMessage::io_deserialize(&mut stream)?;
+
+        assert_eq!(expected_get_addr, get_addr);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use super::{command_name::CommandName, compact_size::CompactSize, message::Message};
+
+use crate::block_structure::hash::HashType;
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::io::{Read, Write};
+
+/// It's the get block transactions message, asking for the transactions of a compact block that could not be rebuilt.
+/// The indexes are absolute and in increasing order
+#[derive(Debug, PartialEq)]
+pub struct GetBlockTxnMessage {
+    pub block_hash: HashType,
+    pub indexes: Vec<u64>,
+}
+
+impl Message for GetBlockTxnMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::GetBlockTxn
+    }
+}
+
+impl SerializableInternalOrder for GetBlockTxnMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.block_hash.le_serialize(stream)?;
+
+        CompactSize::new(self.indexes.len() as u64).le_serialize(stream)?;
+        let mut next_index: u64 = 0;
+        for index in self.indexes.iter() {
+            let differential_index = match index.checked_sub(next_index) {
+                Some(differential_index) => differential_index,
+                None => {
+                    return Err(ErrorSerialization::ErrorInSerialization(
+                        "The indexes of the transactions are not in order".to_string(),
+                    ))
+                }
+            };
+
+            CompactSize::new(differential_index).le_serialize(stream)?;
+            next_index = index + 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for GetBlockTxnMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let block_hash = HashType::le_deserialize(stream)?;
+
+        let count = CompactSize::le_deserialize(stream)?.value;
+        let mut indexes = Vec::new();
+        let mut next_index: u64 = 0;
+        for _ in 0..count {
+            let differential_index = CompactSize::le_deserialize(stream)?.value;
+            let index = match next_index.checked_add(differential_index) {
+                Some(index) => index,
+                None => {
+                    return Err(ErrorSerialization::ErrorInDeserialization(
+                        "The index of a transaction overflows".to_string(),
+                    ))
+                }
+            };
+
+            indexes.push(index);
+            next_index = index + 1;
+        }
+
+        Ok(GetBlockTxnMessage {
+            block_hash,
+            indexes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_01_indexes_are_differentially_encoded() {
+        let get_block_txn = GetBlockTxnMessage {
+            block_hash: [1; 32],
+            indexes: vec![1, 2, 5],
+        };
+
+        let mut stream: Vec<u8> = Vec::new();
+        get_block_txn.io_serialize(&mut stream).unwrap();
+
+        assert_eq!(vec![3, 1, 0, 2], stream[32..].to_vec());
+
+        let deserialized_get_block_txn =
+            GetBlockTxnMessage::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert_eq!(get_block_txn, deserialized_get_block_txn);
+    }
+}
@@ -0,0 +1,131 @@
+use super::{command_name::CommandName, compact_size::CompactSize, message::Message};
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use crate::block_structure::hash::HashType;
+
+use crate::connections::p2p_protocol::ProtocolVersionP2P;
+
+use std::io::{Read, Write};
+
+/// It's the get blocks message
+#[derive(Debug, std::cmp::PartialEq)]
+pub struct GetBlocksMessage {
+    pub version: ProtocolVersionP2P,
+    pub block_locator_hashes: Vec<HashType>,
+    pub stop_hash: HashType,
+}
+
+impl GetBlocksMessage {
+    pub fn new(
+        version: ProtocolVersionP2P,
+        block_locator_hashes: Vec<HashType>,
+        stop_hash: HashType,
+    ) -> Self {
+        GetBlocksMessage {
+            version,
+            block_locator_hashes,
+            stop_hash,
+        }
+    }
+}
+
+impl Message for GetBlocksMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::GetBlocks
+    }
+}
+
+impl SerializableInternalOrder for GetBlocksMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.version.le_serialize(stream)?;
+        CompactSize::new(self.block_locator_hashes.len() as u64).le_serialize(stream)?;
+
+        for hash in self.block_locator_hashes.iter() {
+            hash.le_serialize(stream)?;
+        }
+
+        self.stop_hash.le_serialize(stream)?;
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for GetBlocksMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let version = ProtocolVersionP2P::le_deserialize(stream)?;
+        let size = CompactSize::le_deserialize(stream)?;
+
+        let mut block_locator_hashes: Vec<HashType> = Vec::new();
+        for _ in 0..size.value {
+            let block_locator_hash = HashType::le_deserialize(stream)?;
+            block_locator_hashes.push(block_locator_hash);
+        }
+
+        let stop_hash = HashType::le_deserialize(stream)?;
+
+        Ok(GetBlocksMessage {
+            version,
+            block_locator_hashes,
+            stop_hash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test01_serialize() -> Result<(), ErrorSerialization> {
+        let version = ProtocolVersionP2P::V70015;
+        let block_locator_hash: Vec<HashType> = vec![[1; 32], [2; 32], [0; 32]];
+        let length = CompactSize::new(block_locator_hash.len() as u64);
+        let stop_hash: HashType = [1; 32];
+
+        let mut expected_stream: Vec<u8> = Vec::new();
+
+        version.le_serialize(&mut expected_stream)?;
+        length.le_serialize(&mut expected_stream)?;
+        for block_hash in block_locator_hash.iter() {
+            block_hash.le_serialize(&mut expected_stream)?;
+        }
+        stop_hash.le_serialize(&mut expected_stream)?;
+
+        let get_blocks_message = GetBlocksMessage::new(version, block_locator_hash, stop_hash);
+
+        let mut stream: Vec<u8> = Vec::new();
+        get_blocks_message.io_serialize(&mut stream)?;
+
+        assert_eq!(expected_stream, stream);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test02_deserialize() {
+        let version = ProtocolVersionP2P::V70015;
+        let stop_hash: HashType = [1; 32];
+
+        let mut serialized_stream: Vec<u8> = Vec::new();
+        let get_blocks_message = GetBlocksMessage {
+            version,
+            block_locator_hashes: vec![[1; 32], [2; 32], [0; 32]],
+            stop_hash,
+        };
+
+        get_blocks_message
+            .io_serialize(&mut serialized_stream)
+            .unwrap();
+        let deserialized_message =
+            GetBlocksMessage::io_deserialize(&mut serialized_stream.as_slice()).unwrap();
+
+        assert_eq!(deserialized_message, get_blocks_message);
+    }
+}
@@ -0,0 +1,45 @@
+use super::{command_name::CommandName, message::Message};
+
+use crate::block_structure::hash::HashType;
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::io::{Read, Write};
+
+/// It's the get compact filter checkpoints message (BIP157), asking for the filter headers at every
+/// thousand blocks up to the stop hash
+#[derive(Debug, PartialEq)]
+pub struct GetCFCheckptMessage {
+    pub filter_type: u8,
+    pub stop_hash: HashType,
+}
+
+impl Message for GetCFCheckptMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::GetCFCheckpt
+    }
+}
+
+impl SerializableInternalOrder for GetCFCheckptMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.filter_type.le_serialize(stream)?;
+        self.stop_hash.le_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for GetCFCheckptMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        Ok(GetCFCheckptMessage {
+            filter_type: u8::le_deserialize(stream)?,
+            stop_hash: HashType::le_deserialize(stream)?,
+        })
+    }
+}
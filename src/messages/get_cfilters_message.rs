@@ -0,0 +1,47 @@
+use super::{command_name::CommandName, message::Message};
+
+use crate::block_structure::hash::HashType;
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::io::{Read, Write};
+
+/// It's the get compact filters message (BIP157), asking for the filters of the blocks from the start height to the stop hash
+#[derive(Debug, PartialEq)]
+pub struct GetCFiltersMessage {
+    pub filter_type: u8,
+    pub start_height: u32,
+    pub stop_hash: HashType,
+}
+
+impl Message for GetCFiltersMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::GetCFilters
+    }
+}
+
+impl SerializableInternalOrder for GetCFiltersMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.filter_type.le_serialize(stream)?;
+        self.start_height.le_serialize(stream)?;
+        self.stop_hash.le_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for GetCFiltersMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        Ok(GetCFiltersMessage {
+            filter_type: u8::le_deserialize(stream)?,
+            start_height: u32::le_deserialize(stream)?,
+            stop_hash: HashType::le_deserialize(stream)?,
+        })
+    }
+}
@@ -35,6 +35,15 @@ impl GetDataMessage {
 
         GetDataMessage::new(inventory_vectors)
     }
+
+    pub fn get_filtered_blocks(hash_vector: Vec<HashType>) -> GetDataMessage {
+        let mut inventory_vectors = Vec::new();
+        for hash in hash_vector {
+            inventory_vectors.push(InventoryVector::new(TypeIdentifier::FilteredBlock, hash));
+        }
+
+        GetDataMessage::new(inventory_vectors)
+    }
 }
 
 impl Message for GetDataMessage {
@@ -49,9 +49,11 @@ impl DeserializableInternalOrder for HeadersMessage {
 mod tests {
     use super::*;
 
+    use crate::connections::network::Network;
+
     #[test]
     fn test_01_correct_headers_message_serialization() {
-        let genesis_block_header = BlockHeader::generate_genesis_block_header();
+        let genesis_block_header = BlockHeader::generate_genesis_block_header(Network::Testnet3);
         let field = vec![genesis_block_header.clone()];
         let header_message = HeadersMessage {
             headers: vec![genesis_block_header.clone()],
@@ -0,0 +1,96 @@
+use super::{command_name::CommandName, compact_size::CompactSize, message::Message};
+
+use crate::block_structure::{
+    block::Block, block_header::BlockHeader, partial_merkle_tree::PartialMerkleTree,
+    transaction::Transaction,
+};
+
+use crate::node_structure::bloom_filter::BloomFilter;
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+};
+
+use std::io::{Read, Write};
+
+/// It's the merkle block message (BIP37). It has the header of a block and the partial merkle tree
+/// that proves which of its transactions matched the filter of a lightweight client
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleBlockMessage {
+    pub header: BlockHeader,
+    pub partial_merkle_tree: PartialMerkleTree,
+}
+
+impl MerkleBlockMessage {
+    /// Creates the merkle block of the given block with the transactions that match the filter, updating it.
+    /// Returns the message with the matched transactions, which are sent after it
+    ///
+    /// ### Error
+    ///  * `ErrorSerialization::ErrorInSerialization`: It will appear when the partial merkle tree of the block could not be built
+    pub fn from_block(
+        block: &Block,
+        bloom_filter: &mut BloomFilter,
+    ) -> Result<(Self, Vec<Transaction>), ErrorSerialization> {
+        let matches: Vec<bool> = block
+            .transactions
+            .iter()
+            .map(|transaction| bloom_filter.is_relevant_and_update(transaction))
+            .collect();
+
+        let partial_merkle_tree =
+            match PartialMerkleTree::from_matches(&block.transactions, &matches) {
+                Ok(partial_merkle_tree) => partial_merkle_tree,
+                Err(_) => {
+                    return Err(ErrorSerialization::ErrorInSerialization(
+                        "While building the partial merkle tree of the block".to_string(),
+                    ))
+                }
+            };
+
+        let matched_transactions = block
+            .transactions
+            .iter()
+            .zip(matches)
+            .filter(|(_, matched)| *matched)
+            .map(|(transaction, _)| transaction.clone())
+            .collect();
+
+        Ok((
+            MerkleBlockMessage {
+                header: block.header,
+                partial_merkle_tree,
+            },
+            matched_transactions,
+        ))
+    }
+}
+
+impl Message for MerkleBlockMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::MerkleBlock
+    }
+}
+
+impl SerializableInternalOrder for MerkleBlockMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.header.io_serialize_without_count(stream)?;
+        self.partial_merkle_tree.io_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for MerkleBlockMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let mut header = BlockHeader::io_deserialize_without_count(stream)?;
+        let partial_merkle_tree = PartialMerkleTree::io_deserialize(stream)?;
+        header.transaction_count = CompactSize::new(partial_merkle_tree.total_transactions as u64);
+
+        Ok(MerkleBlockMessage {
+            header,
+            partial_merkle_tree,
+        })
+    }
+}
@@ -8,28 +8,50 @@ use crate::block_structure::hash::hash256d_reduce;
 
 use super::{
     addr_message::AddrMessage,
+    addr_v2_message::AddrV2Message,
     alert_message::AlertMessage,
     block_message::BlockMessage,
+    block_txn_message::BlockTxnMessage,
+    cfcheckpt_message::CFCheckptMessage,
+    cfheaders_message::CFHeadersMessage,
+    cfilter_message::CFilterMessage,
+    cmpct_block_message::CmpctBlockMessage,
     command_name::CommandName,
     fee_filter_message::FeeFilterMessage,
+    filter_add_message::FilterAddMessage,
+    filter_clear_message::FilterClearMessage,
+    filter_load_message::FilterLoadMessage,
+    get_addr_message::GetAddrMessage,
+    get_block_txn_message::GetBlockTxnMessage,
+    get_blocks_message::GetBlocksMessage,
+    get_cfcheckpt_message::GetCFCheckptMessage,
+    get_cfheaders_message::GetCFHeadersMessage,
+    get_cfilters_message::GetCFiltersMessage,
     get_data_message::GetDataMessage,
     get_headers_message::GetHeadersMessage,
     headers_message::HeadersMessage,
     inventory_message::InventoryMessage,
+    merkle_block_message::MerkleBlockMessage,
     message_header::{MagicType, MessageHeader},
+    not_found_message::NotFoundMessage,
     ping_message::PingMessage,
     pong_message::PongMessage,
+    reject_message::RejectMessage,
+    send_addr_v2_message::SendAddrV2Message,
     send_cmpct_message::SendCmpctMessage,
     send_headers_message::SendHeadersMessage,
     tx_message::TxMessage,
     verack_message::VerackMessage,
     version_message::VersionMessage,
+    wtxid_relay_message::WtxidRelayMessage,
 };
 
 use std::io::{ErrorKind, Read, Write};
 
 pub const CHECKSUM_EMPTY_PAYLOAD: MagicType = [0x5d, 0xf6, 0xe0, 0xe2];
 
+pub const MAX_PAYLOAD_SIZE: u32 = 4_000_000;
+
 pub trait Message: SerializableInternalOrder + DeserializableInternalOrder {
     /// Serialize a message with a payload that is serializable
     ///
@@ -144,8 +166,10 @@ pub fn deserialize_until_found<RW: Read + Write>(
             CommandName::Version => ignore_message::<RW, VersionMessage>(stream, header)?,
             CommandName::Verack => ignore_message::<RW, VerackMessage>(stream, header)?,
             CommandName::GetHeaders => ignore_message::<RW, GetHeadersMessage>(stream, header)?,
+            CommandName::GetBlocks => ignore_message::<RW, GetBlocksMessage>(stream, header)?,
             CommandName::Headers => ignore_message::<RW, HeadersMessage>(stream, header)?,
             CommandName::Inventory => ignore_message::<RW, InventoryMessage>(stream, header)?,
+            CommandName::NotFound => ignore_message::<RW, NotFoundMessage>(stream, header)?,
             CommandName::Block => ignore_message::<RW, BlockMessage>(stream, header)?,
             CommandName::Ping => {
                 let ping = PingMessage::deserialize_message(stream, header)?;
@@ -157,11 +181,30 @@ pub fn deserialize_until_found<RW: Read + Write>(
             CommandName::Pong => ignore_message::<RW, PongMessage>(stream, header)?,
             CommandName::SendHeaders => ignore_message::<RW, SendHeadersMessage>(stream, header)?,
             CommandName::SendCmpct => ignore_message::<RW, SendCmpctMessage>(stream, header)?,
+            CommandName::GetAddr => ignore_message::<RW, GetAddrMessage>(stream, header)?,
             CommandName::Addr => ignore_message::<RW, AddrMessage>(stream, header)?,
             CommandName::FeeFilter => ignore_message::<RW, FeeFilterMessage>(stream, header)?,
             CommandName::GetData => ignore_message::<RW, GetDataMessage>(stream, header)?,
             CommandName::Alert => ignore_message::<RW, AlertMessage>(stream, header)?,
             CommandName::Tx => ignore_message::<RW, TxMessage>(stream, header)?,
+            CommandName::CmpctBlock => ignore_message::<RW, CmpctBlockMessage>(stream, header)?,
+            CommandName::GetBlockTxn => ignore_message::<RW, GetBlockTxnMessage>(stream, header)?,
+            CommandName::BlockTxn => ignore_message::<RW, BlockTxnMessage>(stream, header)?,
+            CommandName::Reject => ignore_message::<RW, RejectMessage>(stream, header)?,
+            CommandName::WtxidRelay => ignore_message::<RW, WtxidRelayMessage>(stream, header)?,
+            CommandName::SendAddrV2 => ignore_message::<RW, SendAddrV2Message>(stream, header)?,
+            CommandName::AddrV2 => ignore_message::<RW, AddrV2Message>(stream, header)?,
+            CommandName::FilterLoad => ignore_message::<RW, FilterLoadMessage>(stream, header)?,
+            CommandName::FilterAdd => ignore_message::<RW, FilterAddMessage>(stream, header)?,
+            CommandName::FilterClear => ignore_message::<RW, FilterClearMessage>(stream, header)?,
+            CommandName::MerkleBlock => ignore_message::<RW, MerkleBlockMessage>(stream, header)?,
+            CommandName::GetCFilters => ignore_message::<RW, GetCFiltersMessage>(stream, header)?,
+            CommandName::CFilter => ignore_message::<RW, CFilterMessage>(stream, header)?,
+            CommandName::GetCFHeaders => ignore_message::<RW, GetCFHeadersMessage>(stream, header)?,
+            CommandName::CFHeaders => ignore_message::<RW, CFHeadersMessage>(stream, header)?,
+            CommandName::GetCFCheckpt => ignore_message::<RW, GetCFCheckptMessage>(stream, header)?,
+            CommandName::CFCheckpt => ignore_message::<RW, CFCheckptMessage>(stream, header)?,
+            CommandName::Unknown(_) => skip_payload(stream, header)?,
         }
     }
 }
@@ -174,3 +217,23 @@ pub fn ignore_message<R: Read, M: Message>(
     let _ = M::deserialize_message(stream, header)?;
     Ok(())
 }
+
+/// Skips the payload of a message without reading its content, used for the messages we don't know how to deserialize
+///
+/// ### Error
+///  * `ErrorSerialization::ErrorInDeserialization`: It will appear when the payload is bigger than the maximum allowed
+///  * `ErrorSerialization::ErrorWhileReading`: It will appear when there is an error in the reading from a stream
+pub fn skip_payload<R: Read>(
+    stream: &mut R,
+    header: MessageHeader,
+) -> Result<(), ErrorSerialization> {
+    if header.payload_size > MAX_PAYLOAD_SIZE {
+        return Err(ErrorSerialization::ErrorInDeserialization(format!(
+            "Payload size {} of {} is bigger than the maximum allowed",
+            header.payload_size, header.command_name
+        )));
+    }
+
+    let mut buffer: Vec<u8> = vec![0; header.payload_size as usize];
+    read_exact(stream, &mut buffer)
+}
@@ -5,20 +5,44 @@ pub mod message_header;
 pub mod verack_message;
 pub mod version_message;
 
+pub mod get_blocks_message;
 pub mod get_data_message;
 pub mod get_headers_message;
 
 pub mod block_message;
 pub mod headers_message;
+pub mod merkle_block_message;
 pub mod tx_message;
 
+pub mod block_txn_message;
+pub mod cmpct_block_message;
+pub mod get_block_txn_message;
+pub mod prefilled_transaction;
+
+pub mod cfcheckpt_message;
+pub mod cfheaders_message;
+pub mod cfilter_message;
+pub mod get_cfcheckpt_message;
+pub mod get_cfheaders_message;
+pub mod get_cfilters_message;
+
 pub mod addr_message;
+pub mod addr_v2_message;
 pub mod fee_filter_message;
+pub mod filter_add_message;
+pub mod filter_clear_message;
+pub mod filter_load_message;
+pub mod get_addr_message;
 pub mod inventory_message;
+pub mod not_found_message;
 pub mod ping_message;
 pub mod pong_message;
+pub mod reject_code;
+pub mod reject_message;
+pub mod send_addr_v2_message;
 pub mod send_cmpct_message;
 pub mod send_headers_message;
+pub mod wtxid_relay_message;
 
 pub mod bitfield_services;
 pub mod compact_size;
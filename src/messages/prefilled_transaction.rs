@@ -0,0 +1,14 @@
+use crate::block_structure::transaction::Transaction;
+
+/// It's a transaction sent completely inside a compact block, with its absolute index in the block
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefilledTransaction {
+    pub index: u64,
+    pub transaction: Transaction,
+}
+
+impl PrefilledTransaction {
+    pub fn new(index: u64, transaction: Transaction) -> Self {
+        PrefilledTransaction { index, transaction }
+    }
+}
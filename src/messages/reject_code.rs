@@ -0,0 +1,99 @@
+use crate::serialization::{
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization, serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::{
+    fmt::{Display, Formatter},
+    io::{Read, Write},
+};
+
+const MALFORMED_VALUE: u8 = 0x01;
+const INVALID_VALUE: u8 = 0x10;
+const OBSOLETE_VALUE: u8 = 0x11;
+const DUPLICATE_VALUE: u8 = 0x12;
+const NON_STANDARD_VALUE: u8 = 0x40;
+const DUST_VALUE: u8 = 0x41;
+const INSUFFICIENT_FEE_VALUE: u8 = 0x42;
+const CHECKPOINT_VALUE: u8 = 0x43;
+
+/// It's the reason why a message was rejected (BIP61)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RejectCode {
+    /// The message could not be deserialized
+    Malformed,
+
+    /// The block or transaction is not valid
+    Invalid,
+
+    /// The version of the message is no longer supported
+    Obsolete,
+
+    /// The block or transaction was already received
+    Duplicate,
+
+    /// The transaction is valid but it's not relayed
+    NonStandard,
+
+    /// The transaction has outputs too small to be spent
+    Dust,
+
+    /// The transaction doesn't pay enough fee
+    InsufficientFee,
+
+    /// The block doesn't follow the checkpoints
+    Checkpoint,
+}
+
+impl SerializableLittleEndian for RejectCode {
+    fn le_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        let value: u8 = match self {
+            RejectCode::Malformed => MALFORMED_VALUE,
+            RejectCode::Invalid => INVALID_VALUE,
+            RejectCode::Obsolete => OBSOLETE_VALUE,
+            RejectCode::Duplicate => DUPLICATE_VALUE,
+            RejectCode::NonStandard => NON_STANDARD_VALUE,
+            RejectCode::Dust => DUST_VALUE,
+            RejectCode::InsufficientFee => INSUFFICIENT_FEE_VALUE,
+            RejectCode::Checkpoint => CHECKPOINT_VALUE,
+        };
+
+        value.le_serialize(stream)
+    }
+}
+
+impl DeserializableLittleEndian for RejectCode {
+    fn le_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let value = u8::le_deserialize(stream)?;
+
+        match value {
+            MALFORMED_VALUE => Ok(RejectCode::Malformed),
+            INVALID_VALUE => Ok(RejectCode::Invalid),
+            OBSOLETE_VALUE => Ok(RejectCode::Obsolete),
+            DUPLICATE_VALUE => Ok(RejectCode::Duplicate),
+            NON_STANDARD_VALUE => Ok(RejectCode::NonStandard),
+            DUST_VALUE => Ok(RejectCode::Dust),
+            INSUFFICIENT_FEE_VALUE => Ok(RejectCode::InsufficientFee),
+            CHECKPOINT_VALUE => Ok(RejectCode::Checkpoint),
+            _ => Err(ErrorSerialization::ErrorInDeserialization(format!(
+                "While deserializing the reject code, we get: {}",
+                value
+            ))),
+        }
+    }
+}
+
+impl Display for RejectCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RejectCode::Malformed => write!(f, "Malformed"),
+            RejectCode::Invalid => write!(f, "Invalid"),
+            RejectCode::Obsolete => write!(f, "Obsolete"),
+            RejectCode::Duplicate => write!(f, "Duplicate"),
+            RejectCode::NonStandard => write!(f, "Non standard"),
+            RejectCode::Dust => write!(f, "Dust"),
+            RejectCode::InsufficientFee => write!(f, "Insufficient fee"),
+            RejectCode::Checkpoint => write!(f, "Checkpoint"),
+        }
+    }
+}
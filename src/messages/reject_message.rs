@@ -0,0 +1,148 @@
+use super::{
+    command_name::CommandName, compact_size::CompactSize, message::Message, reject_code::RejectCode,
+};
+
+use crate::block_structure::hash::HashType;
+
+use crate::serialization::{
+    deserializable_fix_size::DeserializableFixSize,
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::io::{Read, Write};
+
+const HASH_SIZE: usize = 32;
+
+/// It's the reject message (BIP61). It tells the peer that one of its messages was refused, with the
+/// reason why. When a block or a transaction is rejected its hash is sent as extra data
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectMessage {
+    pub message: String,
+    pub code: RejectCode,
+    pub reason: String,
+    pub data: Option<HashType>,
+}
+
+impl RejectMessage {
+    pub fn new(
+        command_name: CommandName,
+        code: RejectCode,
+        reason: &str,
+        data: Option<HashType>,
+    ) -> Self {
+        RejectMessage {
+            message: command_name.to_string(),
+            code,
+            reason: reason.to_string(),
+            data,
+        }
+    }
+
+    /// Returns true if the rejected message is of the given type
+    pub fn is_rejecting(&self, command_name: CommandName) -> bool {
+        self.message == command_name.to_string()
+    }
+}
+
+impl Message for RejectMessage {
+    fn get_command_name() -> CommandName {
+        CommandName::Reject
+    }
+}
+
+impl SerializableInternalOrder for RejectMessage {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        CompactSize::new(self.message.len() as u64).le_serialize(stream)?;
+        self.message.le_serialize(stream)?;
+        self.code.le_serialize(stream)?;
+        CompactSize::new(self.reason.len() as u64).le_serialize(stream)?;
+        self.reason.le_serialize(stream)?;
+
+        if let Some(data) = self.data {
+            data.le_serialize(stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for RejectMessage {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let message_length = CompactSize::le_deserialize(stream)?.value as usize;
+        let message = String::deserialize_fix_size(stream, message_length)?;
+        let code = RejectCode::le_deserialize(stream)?;
+        let reason_length = CompactSize::le_deserialize(stream)?.value as usize;
+        let reason = String::deserialize_fix_size(stream, reason_length)?;
+
+        let mut extra_data: Vec<u8> = Vec::new();
+        if stream.read_to_end(&mut extra_data).is_err() {
+            return Err(ErrorSerialization::ErrorWhileReading);
+        }
+
+        let data = match extra_data.len() {
+            0 => None,
+            HASH_SIZE => Some(HashType::le_deserialize(&mut extra_data.as_slice())?),
+            length => {
+                return Err(ErrorSerialization::ErrorInDeserialization(format!(
+                    "The extra data of a reject message has {length} bytes"
+                )))
+            }
+        };
+
+        Ok(RejectMessage {
+            message,
+            code,
+            reason,
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_01_correct_reject_message_serialization() {
+        let reject_message = RejectMessage::new(
+            CommandName::Tx,
+            RejectCode::InsufficientFee,
+            "min relay fee not met",
+            Some([3; 32]),
+        );
+
+        let mut stream: Vec<u8> = Vec::new();
+        reject_message.io_serialize(&mut stream).unwrap();
+
+        assert_eq!(2, stream[0]);
+        assert_eq!(b"tx", &stream[1..3]);
+        assert_eq!(0x42, stream[3]);
+
+        let deserialized_message = RejectMessage::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert_eq!(reject_message, deserialized_message);
+        assert!(deserialized_message.is_rejecting(CommandName::Tx));
+    }
+
+    #[test]
+    fn test_02_reject_message_without_data() {
+        let reject_message = RejectMessage::new(
+            CommandName::Version,
+            RejectCode::Obsolete,
+            "version too old",
+            None,
+        );
+
+        let mut stream: Vec<u8> = Vec::new();
+        reject_message.io_serialize(&mut stream).unwrap();
+
+        let deserialized_message = RejectMessage::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert_eq!(reject_message, deserialized_message);
+        assert!(!deserialized_message.is_rejecting(CommandName::Tx));
+    }
+}
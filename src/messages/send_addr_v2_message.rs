@@ -0,0 +1,38 @@
+use super::{
+    command_name::CommandName,
+    message::{Message, CHECKSUM_EMPTY_PAYLOAD},
+};
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+};
+
+use std::io::{Read, Write};
+
+/// It's the send addresses v2 message (BIP155). The peer announces it before the verack when it can receive the addresses in the version 2 format
+#[derive(Debug, std::cmp::PartialEq)]
+pub struct SendAddrV2Message;
+
+impl Message for SendAddrV2Message {
+    fn calculate_checksum(_: &[u8]) -> Result<[u8; 4], ErrorSerialization> {
+        Ok(CHECKSUM_EMPTY_PAYLOAD)
+    }
+
+    fn get_command_name() -> CommandName {
+        CommandName::SendAddrV2
+    }
+}
+
+impl SerializableInternalOrder for SendAddrV2Message {
+    fn io_serialize(&self, _: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for SendAddrV2Message {
+    fn io_deserialize(_: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        Ok(SendAddrV2Message)
+    }
+}
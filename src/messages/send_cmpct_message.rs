@@ -13,6 +13,8 @@ use std::{
     io::{Read, Write},
 };
 
+pub const COMPACT_BLOCKS_VERSION: u64 = 1;
+
 /// It's the sendcmpct message
 #[derive(Debug, PartialEq)]
 pub struct SendCmpctMessage {
@@ -0,0 +1,38 @@
+use super::{
+    command_name::CommandName,
+    message::{Message, CHECKSUM_EMPTY_PAYLOAD},
+};
+
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+};
+
+use std::io::{Read, Write};
+
+/// It's the wtxid relay message (BIP339). The peer announces it before the verack when it wants the transactions to be announced by their witness id
+#[derive(Debug, std::cmp::PartialEq)]
+pub struct WtxidRelayMessage;
+
+impl Message for WtxidRelayMessage {
+    fn calculate_checksum(_: &[u8]) -> Result<[u8; 4], ErrorSerialization> {
+        Ok(CHECKSUM_EMPTY_PAYLOAD)
+    }
+
+    fn get_command_name() -> CommandName {
+        CommandName::WtxidRelay
+    }
+}
+
+impl SerializableInternalOrder for WtxidRelayMessage {
+    fn io_serialize(&self, _: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for WtxidRelayMessage {
+    fn io_deserialize(_: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        Ok(WtxidRelayMessage)
+    }
+}
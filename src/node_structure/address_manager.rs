@@ -0,0 +1,486 @@
+use super::{error_node::ErrorNode, peer_address::PeerAddress};
+
+use crate::{
+    configurations::try_default::TryDefault,
+    connections::{
+        network_address::NetworkAddress, network_ip_address::NetworkIpAddress,
+        network_ip_address_v2::NetworkIpAddressV2,
+    },
+    serialization::{
+        deserializable_big_endian::DeserializableBigEndian,
+        deserializable_internal_order::DeserializableInternalOrder,
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization, serializable_big_endian::SerializableBigEndian,
+        serializable_internal_order::SerializableInternalOrder,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    net::SocketAddr,
+};
+
+const MAX_NEW_ADDRESSES: usize = 1024;
+const MAX_TRIED_ADDRESSES: usize = 256;
+const MAX_UNREACHABLE_ADDRESSES: usize = 256;
+const MAX_FAILURES: u32 = 8;
+
+/// It keeps the addresses of the potential peers, separating the ones we never connected to (new)
+/// from the ones we connected successfully at least once (tried). The addresses of networks we
+/// can't connect to (like Tor or I2P) are kept apart with the last time they were seen
+#[derive(Debug, PartialEq)]
+pub struct AddressManager {
+    new_addresses: HashMap<SocketAddr, PeerAddress>,
+    tried_addresses: HashMap<SocketAddr, PeerAddress>,
+    unreachable_addresses: HashMap<(NetworkAddress, u16), u32>,
+}
+
+impl AddressManager {
+    pub fn new() -> Self {
+        AddressManager {
+            new_addresses: HashMap::new(),
+            tried_addresses: HashMap::new(),
+            unreachable_addresses: HashMap::new(),
+        }
+    }
+
+    /// Adds the address of a potential peer seen at the given time, or updates the last time it was seen
+    pub fn add_address(&mut self, address: SocketAddr, last_seen: u32) {
+        if let Some(peer_address) = self.tried_addresses.get_mut(&address) {
+            peer_address.last_seen = peer_address.last_seen.max(last_seen);
+            return;
+        }
+
+        match self.new_addresses.get_mut(&address) {
+            Some(peer_address) => peer_address.last_seen = peer_address.last_seen.max(last_seen),
+            None => {
+                if self.new_addresses.len() >= MAX_NEW_ADDRESSES {
+                    Self::remove_oldest(&mut self.new_addresses);
+                }
+                self.new_addresses
+                    .insert(address, PeerAddress::new(address, last_seen));
+            }
+        }
+    }
+
+    /// Adds the addresses announced by a peer in an `addr` message
+    pub fn add_network_addresses(&mut self, network_addresses: &[NetworkIpAddress]) {
+        for network_address in network_addresses {
+            self.add_address(
+                network_address.get_socket_address(),
+                network_address.get_time(),
+            );
+        }
+    }
+
+    /// Adds the addresses announced by a peer in an `addrv2` message. The addresses of networks we can't
+    /// connect to are kept apart, and the ones of networks we don't know are ignored
+    pub fn add_network_addresses_v2(&mut self, network_addresses: &[NetworkIpAddressV2]) {
+        for network_address in network_addresses {
+            match network_address.get_socket_address() {
+                Some(address) => self.add_address(address, network_address.get_time()),
+                None => self.add_unreachable_address(
+                    network_address.get_address().clone(),
+                    network_address.get_port(),
+                    network_address.get_time(),
+                ),
+            }
+        }
+    }
+
+    /// Adds the address of a potential peer of a network we can't connect to, or updates the last time it was seen
+    fn add_unreachable_address(&mut self, address: NetworkAddress, port: u16, last_seen: u32) {
+        if !address.is_known() {
+            return;
+        }
+
+        match self.unreachable_addresses.get_mut(&(address.clone(), port)) {
+            Some(seen) => *seen = (*seen).max(last_seen),
+            None => {
+                if self.unreachable_addresses.len() >= MAX_UNREACHABLE_ADDRESSES {
+                    let oldest = self
+                        .unreachable_addresses
+                        .iter()
+                        .min_by_key(|(_, seen)| **seen)
+                        .map(|(key, _)| key.clone());
+                    if let Some(oldest) = oldest {
+                        self.unreachable_addresses.remove(&oldest);
+                    }
+                }
+                self.unreachable_addresses
+                    .insert((address, port), last_seen);
+            }
+        }
+    }
+
+    /// Registers an attempt to connect to the address at the given time. The address is forgotten after too many attempts without success
+    pub fn mark_attempt(&mut self, address: &SocketAddr, timestamp: u32) {
+        for addresses in [&mut self.new_addresses, &mut self.tried_addresses] {
+            if let Some(peer_address) = addresses.get_mut(address) {
+                peer_address.failures += 1;
+                peer_address.last_attempt = timestamp;
+                if peer_address.failures >= MAX_FAILURES {
+                    addresses.remove(address);
+                }
+            }
+        }
+    }
+
+    /// Registers a successful connection to the address, moving it to the tried addresses
+    pub fn mark_good(&mut self, address: SocketAddr, timestamp: u32) {
+        self.new_addresses.remove(&address);
+
+        if !self.tried_addresses.contains_key(&address)
+            && self.tried_addresses.len() >= MAX_TRIED_ADDRESSES
+        {
+            if let Some(mut evicted) = Self::remove_oldest(&mut self.tried_addresses) {
+                evicted.failures = 0;
+                self.new_addresses.insert(evicted.address, evicted);
+            }
+        }
+
+        let mut peer_address = PeerAddress::new(address, timestamp);
+        peer_address.last_attempt = timestamp;
+        self.tried_addresses.insert(address, peer_address);
+    }
+
+    /// Returns up to `amount` addresses to connect to at the given time, without the excluded ones nor the ones still waiting their backoff.
+    /// The tried addresses come first, and in each group the ones with less failures and seen most recently
+    pub fn select_addresses(
+        &self,
+        amount: usize,
+        excluded: &[SocketAddr],
+        now: u32,
+    ) -> Vec<SocketAddr> {
+        let mut selected = Vec::new();
+
+        for addresses in [&self.tried_addresses, &self.new_addresses] {
+            let mut candidates: Vec<&PeerAddress> = addresses
+                .values()
+                .filter(|peer_address| {
+                    !excluded.contains(&peer_address.address) && peer_address.can_attempt(now)
+                })
+                .collect();
+            candidates.sort_by(|a, b| {
+                a.failures
+                    .cmp(&b.failures)
+                    .then(b.last_seen.cmp(&a.last_seen))
+            });
+
+            for candidate in candidates {
+                if selected.len() >= amount {
+                    return selected;
+                }
+                selected.push(candidate.address);
+            }
+        }
+
+        selected
+    }
+
+    /// Returns true if an attempt to connect to the address started less than `window` seconds before the given
+    /// time and did not succeed yet, so the connection may still be in its handshake
+    pub fn is_attempt_pending(&self, address: &SocketAddr, now: u32, window: u32) -> bool {
+        match self.get_address(address) {
+            Some(peer_address) => {
+                peer_address.failures > 0 && now < peer_address.last_attempt.saturating_add(window)
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the amount of addresses, without the excluded ones, with an attempt to connect that may still be in its handshake
+    pub fn count_pending_attempts(&self, excluded: &[SocketAddr], now: u32, window: u32) -> usize {
+        self.new_addresses
+            .keys()
+            .chain(self.tried_addresses.keys())
+            .filter(|address| {
+                !excluded.contains(address) && self.is_attempt_pending(address, now, window)
+            })
+            .count()
+    }
+
+    /// Returns the information known of an address
+    pub fn get_address(&self, address: &SocketAddr) -> Option<&PeerAddress> {
+        match self.tried_addresses.get(address) {
+            Some(peer_address) => Some(peer_address),
+            None => self.new_addresses.get(address),
+        }
+    }
+
+    /// Returns true if the address was connected successfully at least once and false otherwise
+    pub fn is_tried(&self, address: &SocketAddr) -> bool {
+        self.tried_addresses.contains_key(address)
+    }
+
+    /// Returns the addresses and ports of the potential peers of networks we can't connect to
+    pub fn get_unreachable_addresses(&self) -> Vec<(NetworkAddress, u16)> {
+        self.unreachable_addresses.keys().cloned().collect()
+    }
+
+    /// Returns the amount of addresses known that we can connect to
+    pub fn len(&self) -> usize {
+        self.new_addresses.len() + self.tried_addresses.len()
+    }
+
+    /// Returns true if there are no addresses known and false otherwise
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes and returns the address that was seen the longest time ago
+    fn remove_oldest(addresses: &mut HashMap<SocketAddr, PeerAddress>) -> Option<PeerAddress> {
+        let oldest = addresses
+            .values()
+            .min_by_key(|peer_address| peer_address.last_seen)
+            .map(|peer_address| peer_address.address)?;
+
+        addresses.remove(&oldest)
+    }
+}
+
+impl Default for AddressManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryDefault for AddressManager {
+    type Error = ErrorNode;
+
+    fn try_default() -> Result<Self, Self::Error> {
+        Ok(AddressManager::new())
+    }
+}
+
+impl SerializableInternalOrder for AddressManager {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        for addresses in [&self.new_addresses, &self.tried_addresses] {
+            (addresses.len() as u64).le_serialize(stream)?;
+            for peer_address in addresses.values() {
+                peer_address.io_serialize(stream)?;
+            }
+        }
+
+        (self.unreachable_addresses.len() as u64).le_serialize(stream)?;
+        for ((address, port), last_seen) in self.unreachable_addresses.iter() {
+            address.le_serialize(stream)?;
+            port.be_serialize(stream)?;
+            last_seen.le_serialize(stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for AddressManager {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let mut address_manager = AddressManager::new();
+
+        for addresses in [
+            &mut address_manager.new_addresses,
+            &mut address_manager.tried_addresses,
+        ] {
+            let addresses_len = u64::le_deserialize(stream)?;
+            for _ in 0..addresses_len {
+                let peer_address = PeerAddress::io_deserialize(stream)?;
+                addresses.insert(peer_address.address, peer_address);
+            }
+        }
+
+        let unreachable_len = u64::le_deserialize(stream)?;
+        for _ in 0..unreachable_len {
+            let address = NetworkAddress::le_deserialize(stream)?;
+            let port = u16::be_deserialize(stream)?;
+            let last_seen = u32::le_deserialize(stream)?;
+            address_manager
+                .unreachable_addresses
+                .insert((address, port), last_seen);
+        }
+
+        Ok(address_manager)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        connections::supported_services::SupportedServices,
+        messages::bitfield_services::BitfieldServices,
+    };
+
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    fn socket(last_byte: u8) -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last_byte)), 18333)
+    }
+
+    #[test]
+    fn test_01_addresses_from_addr_message_are_added_as_new() {
+        let mut address_manager = AddressManager::new();
+
+        address_manager.add_network_addresses(&[
+            NetworkIpAddress::new(
+                100,
+                BitfieldServices::new(vec![SupportedServices::NodeNetwork]),
+                &socket(1),
+            ),
+            NetworkIpAddress::new(
+                200,
+                BitfieldServices::new(vec![SupportedServices::NodeNetwork]),
+                &socket(2),
+            ),
+        ]);
+
+        assert_eq!(2, address_manager.len());
+        assert!(!address_manager.is_tried(&socket(1)));
+        assert_eq!(
+            200,
+            address_manager.get_address(&socket(2)).unwrap().last_seen
+        );
+    }
+
+    #[test]
+    fn test_02_tried_addresses_are_selected_first() {
+        let mut address_manager = AddressManager::new();
+        address_manager.add_address(socket(1), 300);
+        address_manager.add_address(socket(2), 100);
+        address_manager.add_address(socket(3), 200);
+        address_manager.mark_good(socket(2), 150);
+
+        assert_eq!(
+            vec![socket(2), socket(1), socket(3)],
+            address_manager.select_addresses(5, &[], 1000)
+        );
+        assert_eq!(
+            vec![socket(2)],
+            address_manager.select_addresses(1, &[socket(1)], 1000)
+        );
+        assert_eq!(
+            vec![socket(3)],
+            address_manager.select_addresses(1, &[socket(1), socket(2)], 1000)
+        );
+    }
+
+    #[test]
+    fn test_03_addresses_are_forgotten_after_too_many_failures() {
+        let mut address_manager = AddressManager::new();
+        address_manager.add_address(socket(1), 100);
+
+        address_manager.mark_attempt(&socket(1), 150);
+        assert_eq!(1, address_manager.get_address(&socket(1)).unwrap().failures);
+
+        address_manager.mark_good(socket(1), 200);
+        assert_eq!(0, address_manager.get_address(&socket(1)).unwrap().failures);
+
+        for attempt in 0..MAX_FAILURES {
+            address_manager.mark_attempt(&socket(1), 300 + attempt);
+        }
+        assert!(address_manager.is_empty());
+    }
+
+    #[test]
+    fn test_04_correct_address_manager_serialization() {
+        let mut address_manager = AddressManager::new();
+        address_manager.add_address(socket(1), 100);
+        address_manager.add_address(socket(2), 200);
+        address_manager.mark_good(socket(2), 300);
+        address_manager.mark_attempt(&socket(1), 400);
+
+        let mut stream: Vec<u8> = Vec::new();
+        address_manager.io_serialize(&mut stream).unwrap();
+
+        let deserialized_address_manager =
+            AddressManager::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert_eq!(address_manager, deserialized_address_manager);
+    }
+
+    #[test]
+    fn test_05_addresses_are_not_selected_during_their_backoff() {
+        let mut address_manager = AddressManager::new();
+        address_manager.add_address(socket(1), 100);
+        address_manager.add_address(socket(2), 200);
+
+        address_manager.mark_attempt(&socket(2), 1000);
+
+        assert_eq!(
+            vec![socket(1)],
+            address_manager.select_addresses(2, &[], 1001)
+        );
+        assert_eq!(
+            vec![socket(1), socket(2)],
+            address_manager.select_addresses(2, &[], 2000)
+        );
+    }
+
+    #[test]
+    fn test_06_addresses_v2_of_other_networks_are_kept_apart() {
+        let mut address_manager = AddressManager::new();
+        let services = BitfieldServices::new(vec![SupportedServices::NodeNetwork]);
+
+        address_manager.add_network_addresses_v2(&[
+            NetworkIpAddressV2::new(
+                100,
+                services.clone(),
+                NetworkAddress::IPv4(Ipv4Addr::new(10, 0, 0, 1)),
+                18333,
+            ),
+            NetworkIpAddressV2::new(200, services.clone(), NetworkAddress::TorV3([7; 32]), 18333),
+            NetworkIpAddressV2::new(
+                300,
+                services.clone(),
+                NetworkAddress::Cjdns(Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 1)),
+                18333,
+            ),
+            NetworkIpAddressV2::new(400, services, NetworkAddress::Unknown(0x42, vec![1]), 18333),
+        ]);
+
+        assert_eq!(1, address_manager.len());
+        assert_eq!(
+            vec![socket(1)],
+            address_manager.select_addresses(5, &[], 1000)
+        );
+        assert_eq!(2, address_manager.get_unreachable_addresses().len());
+        assert!(address_manager
+            .get_unreachable_addresses()
+            .contains(&(NetworkAddress::TorV3([7; 32]), 18333)));
+
+        let mut stream: Vec<u8> = Vec::new();
+        address_manager.io_serialize(&mut stream).unwrap();
+
+        assert_eq!(
+            address_manager,
+            AddressManager::io_deserialize(&mut stream.as_slice()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_07_attempts_are_pending_during_the_handshake_window() {
+        let mut address_manager = AddressManager::new();
+        address_manager.add_address(socket(1), 100);
+        address_manager.add_address(socket(2), 100);
+        address_manager.add_address(socket(3), 100);
+        address_manager.add_address(socket(4), 100);
+
+        address_manager.mark_attempt(&socket(1), 1000);
+        address_manager.mark_attempt(&socket(2), 1000);
+        address_manager.mark_attempt(&socket(3), 1000);
+        address_manager.mark_good(socket(3), 1010);
+
+        assert!(address_manager.is_attempt_pending(&socket(1), 1030, 60));
+        assert!(!address_manager.is_attempt_pending(&socket(3), 1030, 60));
+        assert!(!address_manager.is_attempt_pending(&socket(4), 1030, 60));
+
+        assert_eq!(2, address_manager.count_pending_attempts(&[], 1030, 60));
+        assert_eq!(
+            1,
+            address_manager.count_pending_attempts(&[socket(2)], 1030, 60)
+        );
+        assert_eq!(0, address_manager.count_pending_attempts(&[], 1060, 60));
+    }
+}
@@ -0,0 +1,206 @@
+use super::{connection_id::ConnectionId, error_node::ErrorNode, misbehavior::Misbehavior};
+
+use crate::{
+    configurations::try_default::TryDefault,
+    serialization::{
+        deserializable_big_endian::DeserializableBigEndian,
+        deserializable_internal_order::DeserializableInternalOrder,
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization, serializable_big_endian::SerializableBigEndian,
+        serializable_internal_order::SerializableInternalOrder,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    net::{IpAddr, Ipv6Addr},
+};
+
+const BAN_SCORE: u32 = 100;
+const BAN_DURATION_SECONDS: u32 = 24 * 60 * 60;
+
+/// It keeps the misbehavior score of each connection and the addresses banned until a given time.
+/// Only the banned addresses are saved, the scores are forgotten when the program ends
+#[derive(Debug, PartialEq)]
+pub struct BanList {
+    misbehavior_scores: HashMap<ConnectionId, u32>,
+    banned_addresses: HashMap<IpAddr, u32>,
+}
+
+impl BanList {
+    pub fn new() -> Self {
+        BanList {
+            misbehavior_scores: HashMap::new(),
+            banned_addresses: HashMap::new(),
+        }
+    }
+
+    /// Adds the score of the misbehavior to the connection. When the score reaches the limit the address
+    /// of the connection is banned from the given time and the score is reset.
+    /// Returns true if the address was banned and false otherwise
+    pub fn add_misbehavior(
+        &mut self,
+        connection: ConnectionId,
+        misbehavior: Misbehavior,
+        now: u32,
+    ) -> bool {
+        let score = self.misbehavior_scores.entry(connection).or_insert(0);
+        *score += misbehavior.score();
+
+        if *score < BAN_SCORE {
+            return false;
+        }
+
+        self.misbehavior_scores.remove(&connection);
+        self.ban(
+            connection.address.ip(),
+            now.saturating_add(BAN_DURATION_SECONDS),
+        );
+        true
+    }
+
+    /// Bans the address until the given time
+    pub fn ban(&mut self, address: IpAddr, until: u32) {
+        let banned_until = self.banned_addresses.entry(address).or_insert(until);
+        *banned_until = (*banned_until).max(until);
+    }
+
+    /// Returns true if the address is banned at the given time and false otherwise
+    pub fn is_banned(&self, address: &IpAddr, now: u32) -> bool {
+        match self.banned_addresses.get(address) {
+            Some(banned_until) => now < *banned_until,
+            None => false,
+        }
+    }
+
+    /// Removes the bans that already expired at the given time
+    pub fn remove_expired(&mut self, now: u32) {
+        self.banned_addresses
+            .retain(|_, banned_until| now < *banned_until);
+    }
+
+    /// Returns the misbehavior score of the connection
+    pub fn get_score(&self, connection: &ConnectionId) -> u32 {
+        match self.misbehavior_scores.get(connection) {
+            Some(score) => *score,
+            None => 0,
+        }
+    }
+
+    /// Returns the banned addresses with the time until they are banned
+    pub fn get_banned_addresses(&self) -> &HashMap<IpAddr, u32> {
+        &self.banned_addresses
+    }
+}
+
+impl Default for BanList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryDefault for BanList {
+    type Error = ErrorNode;
+
+    fn try_default() -> Result<Self, Self::Error> {
+        Ok(BanList::new())
+    }
+}
+
+impl SerializableInternalOrder for BanList {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        (self.banned_addresses.len() as u64).le_serialize(stream)?;
+        for (address, banned_until) in self.banned_addresses.iter() {
+            let address = match address {
+                IpAddr::V4(address) => address.to_ipv6_mapped(),
+                IpAddr::V6(address) => *address,
+            };
+            address.be_serialize(stream)?;
+            banned_until.le_serialize(stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for BanList {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let mut ban_list = BanList::new();
+
+        let banned_addresses_len = u64::le_deserialize(stream)?;
+        for _ in 0..banned_addresses_len {
+            let address = Ipv6Addr::be_deserialize(stream)?;
+            let address = match address.to_ipv4_mapped() {
+                Some(address) => IpAddr::V4(address),
+                None => IpAddr::V6(address),
+            };
+            ban_list.ban(address, u32::le_deserialize(stream)?);
+        }
+
+        Ok(ban_list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::node_structure::connection_type::ConnectionType;
+
+    use std::net::{Ipv4Addr, SocketAddr};
+
+    fn connection(last_byte: u8) -> ConnectionId {
+        ConnectionId::new(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last_byte)), 18333),
+            ConnectionType::Peer,
+        )
+    }
+
+    #[test]
+    fn test_01_misbehavior_accumulates_until_ban() {
+        let mut ban_list = BanList::new();
+
+        for _ in 0..4 {
+            assert!(!ban_list.add_misbehavior(connection(1), Misbehavior::MalformedMessage, 1000));
+        }
+        assert_eq!(80, ban_list.get_score(&connection(1)));
+        assert!(!ban_list.is_banned(&connection(1).address.ip(), 1000));
+
+        assert!(ban_list.add_misbehavior(connection(1), Misbehavior::MalformedMessage, 1000));
+        assert_eq!(0, ban_list.get_score(&connection(1)));
+        assert!(ban_list.is_banned(&connection(1).address.ip(), 1000));
+        assert!(!ban_list.is_banned(&connection(2).address.ip(), 1000));
+    }
+
+    #[test]
+    fn test_02_ban_expires() {
+        let mut ban_list = BanList::new();
+        assert!(ban_list.add_misbehavior(connection(1), Misbehavior::InvalidBlock, 1000));
+
+        let address = connection(1).address.ip();
+        assert!(ban_list.is_banned(&address, 1000 + BAN_DURATION_SECONDS - 1));
+        assert!(!ban_list.is_banned(&address, 1000 + BAN_DURATION_SECONDS));
+
+        ban_list.remove_expired(1000 + BAN_DURATION_SECONDS);
+        assert!(ban_list.get_banned_addresses().is_empty());
+    }
+
+    #[test]
+    fn test_03_correct_ban_list_serialization() {
+        let mut ban_list = BanList::new();
+        ban_list.ban(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 5000);
+        ban_list.ban(
+            IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)),
+            6000,
+        );
+
+        let mut stream: Vec<u8> = Vec::new();
+        ban_list.io_serialize(&mut stream).unwrap();
+
+        let deserialized_ban_list = BanList::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert_eq!(ban_list, deserialized_ban_list);
+    }
+}
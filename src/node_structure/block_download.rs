@@ -1,4 +1,6 @@
-use super::error_node::ErrorNode;
+use super::{
+    connection_id::ConnectionId, download_scheduler::DownloadScheduler, error_node::ErrorNode,
+};
 
 use crate::messages::{
     block_message::BlockMessage,
@@ -8,15 +10,23 @@ use crate::messages::{
     message_header::MagicType,
 };
 
+use crate::serialization::error_serialization::ErrorSerialization;
+
 use crate::notifications::{notification::Notification, notifier::Notifier};
 
 use crate::logs::logger_sender::LoggerSender;
 
 use crate::block_structure::{block::Block, hash::HashType};
 
-use std::io::{Read, Write};
+use std::{
+    io::{Read, Write},
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
 
 const MAX_HEADERS_COUNT: usize = 50_000;
+const NOT_READY_BACKOFF: Duration = Duration::from_millis(100);
 
 /// It represents the download of blocks given the headers to the block to download
 #[derive(Debug, Clone)]
@@ -122,6 +132,115 @@ impl BlockDownload {
 
         self.receive_blocks(peer_stream, headers_count, notifier)
     }
+
+    /// Downloads from the peer the blocks the scheduler gives it, keeping its window of requests full,
+    /// until all the blocks are received or the peer is removed from the scheduler.
+    /// If the download fails the peer is removed, so its requests are given to the other peers
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileValidating`: It will appear when a block does not pass the proof of inclusion
+    ///  * `ErrorNode::NodeNotResponding`: It will appear when the peer was removed for stalling the download
+    ///  * `ErrorNode::FailThread`: It will appear when the scheduler could not be locked
+    pub fn get_data_from_scheduler<RW: Read + Write>(
+        &self,
+        peer_stream: &mut RW,
+        peer: ConnectionId,
+        scheduler: &Mutex<DownloadScheduler>,
+    ) -> Result<(), ErrorNode> {
+        let result = self.download_scheduled_blocks(peer_stream, peer, scheduler);
+
+        if result.is_err() {
+            let _ = self
+                .sender_log
+                .log_connection(format!("Stop downloading blocks from peer {peer}"));
+            if let Ok(mut scheduler) = scheduler.lock() {
+                scheduler.remove_peer(&peer);
+            }
+        }
+
+        result
+    }
+
+    /// Requests and receives the blocks of the scheduler from the peer
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileValidating`: It will appear when a block does not pass the proof of inclusion
+    ///  * `ErrorNode::NodeNotResponding`: It will appear when the peer was removed for stalling the download
+    ///  * `ErrorNode::FailThread`: It will appear when the scheduler could not be locked
+    fn download_scheduled_blocks<RW: Read + Write>(
+        &self,
+        peer_stream: &mut RW,
+        peer: ConnectionId,
+        scheduler: &Mutex<DownloadScheduler>,
+    ) -> Result<(), ErrorNode> {
+        let mut requests = match scheduler.lock() {
+            Ok(mut scheduler) => next_requests(&mut scheduler, peer)?,
+            Err(_) => return Err(ErrorNode::FailThread),
+        };
+
+        loop {
+            match requests {
+                Some(requests) if !requests.is_empty() => {
+                    self.send_get_data_message(peer_stream, requests)?
+                }
+                Some(_) => {}
+                None => return Ok(()),
+            }
+
+            let header = match message::deserialize_until_found(peer_stream, CommandName::Block) {
+                Ok(header) => header,
+                Err(ErrorSerialization::InformationNotReady) => {
+                    thread::sleep(NOT_READY_BACKOFF);
+                    requests = match scheduler.lock() {
+                        Ok(mut scheduler) => next_requests(&mut scheduler, peer)?,
+                        Err(_) => return Err(ErrorNode::FailThread),
+                    };
+                    continue;
+                }
+                Err(error) => return Err(error.into()),
+            };
+            let block_message = BlockMessage::deserialize_message(peer_stream, header)?;
+
+            if !block_message.block.proof_of_inclusion() {
+                return Err(ErrorNode::WhileValidating(
+                    "Failed proof of inclusion".to_string(),
+                ));
+            }
+
+            requests = match scheduler.lock() {
+                Ok(mut scheduler) => {
+                    scheduler.block_received(peer, block_message.block);
+                    next_requests(&mut scheduler, peer)?
+                }
+                Err(_) => return Err(ErrorNode::FailThread),
+            };
+        }
+    }
+}
+
+/// Returns the next blocks to request to the peer, filling the room left in its window,
+/// or None if all the blocks were downloaded
+///
+/// ### Error
+///  * `ErrorNode::NodeNotResponding`: It will appear when the peer was removed for stalling the download
+fn next_requests(
+    scheduler: &mut DownloadScheduler,
+    peer: ConnectionId,
+) -> Result<Option<Vec<HashType>>, ErrorNode> {
+    if scheduler.is_complete() {
+        return Ok(None);
+    }
+    if !scheduler.has_peer(&peer) {
+        return Err(ErrorNode::NodeNotResponding(
+            "The peer stalled the download of blocks".to_string(),
+        ));
+    }
+
+    Ok(Some(scheduler.next_requests(peer, Instant::now())))
 }
 
 #[cfg(test)]
@@ -311,4 +430,65 @@ mod tests {
             expected_inventory_vectors
         );
     }
+
+    #[test]
+    fn test02_block_download_from_scheduler_successfully() {
+        let mut stream = Stream::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let mut first_block = create_empty_block(1);
+        first_block
+            .append_transaction(create_transaction(0))
+            .unwrap();
+        update_merkle_root_hash(&mut first_block);
+        let first_block_header_hash = first_block.header.get_hash256d().unwrap();
+
+        let mut second_block = create_block(first_block_header_hash, 1);
+        second_block
+            .append_transaction(create_transaction(1))
+            .unwrap();
+        update_merkle_root_hash(&mut second_block);
+        let second_block_header_hash = second_block.header.get_hash256d().unwrap();
+
+        serialize_block_message(&mut stream, magic_numbers, second_block.clone()).unwrap();
+        serialize_block_message(&mut stream, magic_numbers, first_block.clone()).unwrap();
+
+        let peer = ConnectionId::new(
+            "127.0.0.1:18333".parse().unwrap(),
+            crate::node_structure::connection_type::ConnectionType::Peer,
+        );
+        let mut scheduler = DownloadScheduler::new(
+            vec![first_block_header_hash, second_block_header_hash],
+            16,
+            std::time::Duration::from_secs(10),
+        );
+        scheduler.add_peer(peer);
+        let scheduler = Mutex::new(scheduler);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let block_download = BlockDownload::new(magic_numbers, sender);
+
+        block_download
+            .get_data_from_scheduler(&mut stream, peer, &scheduler)
+            .unwrap();
+
+        let mut scheduler = scheduler.into_inner().unwrap();
+        assert!(scheduler.is_complete());
+        assert_eq!(
+            vec![first_block, second_block],
+            scheduler.take_ready_blocks()
+        );
+
+        let header = message::deserialize_until_found(&mut stream, CommandName::GetData).unwrap();
+        let get_data_message = GetDataMessage::deserialize_message(&mut stream, header).unwrap();
+
+        assert_eq!(
+            get_data_message.inventory_vectors,
+            vec![
+                InventoryVector::new(TypeIdentifier::Block, first_block_header_hash),
+                InventoryVector::new(TypeIdentifier::Block, second_block_header_hash),
+            ]
+        );
+    }
 }
@@ -0,0 +1,408 @@
+use super::bloom_update::BloomUpdate;
+
+use crate::{
+    block_structure::{hash::murmur3, outpoint::Outpoint, transaction::Transaction},
+    messages::compact_size::CompactSize,
+    serialization::{
+        deserializable_internal_order::DeserializableInternalOrder,
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization,
+        serializable_internal_order::SerializableInternalOrder,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::{
+    f64::consts::LN_2,
+    io::{Read, Write},
+};
+
+pub const MAX_FILTER_SIZE: usize = 36_000;
+pub const MAX_HASH_FUNCTIONS: u32 = 50;
+pub const MAX_ELEMENT_SIZE: usize = 520;
+
+const HASH_SEED_MULTIPLIER: u32 = 0xfba4c795;
+
+const OP_PUSHDATA1: u8 = 0x4c;
+const OP_PUSHDATA2: u8 = 0x4d;
+const OP_PUSHDATA4: u8 = 0x4e;
+const OP_CHECKSIG: u8 = 0xac;
+const OP_CHECKMULTISIG: u8 = 0xae;
+
+/// It's the bloom filter a lightweight client loads in a peer (BIP37), so the peer only sends it
+/// the transactions that may be relevant to it without knowing exactly which ones they are
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloomFilter {
+    data: Vec<u8>,
+    hash_functions: u32,
+    tweak: u32,
+    flags: BloomUpdate,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter for the amount of elements expected, with the given rate of false positives
+    pub fn new(elements: u32, false_positive_rate: f64, tweak: u32, flags: BloomUpdate) -> Self {
+        let size = -1.0 / (LN_2 * LN_2) * elements as f64 * false_positive_rate.ln() / 8.0;
+        let size = (size as usize).clamp(1, MAX_FILTER_SIZE);
+
+        let hash_functions = (size as f64 * 8.0 / elements.max(1) as f64 * LN_2) as u32;
+        let hash_functions = hash_functions.clamp(1, MAX_HASH_FUNCTIONS);
+
+        BloomFilter {
+            data: vec![0; size],
+            hash_functions,
+            tweak,
+            flags,
+        }
+    }
+
+    /// Creates a filter matching the transactions that push any of the given data in their scripts or spend
+    /// any of the given outpoints. The outputs matched are added to the filter, so the transactions spending them also match
+    pub fn from_elements(
+        script_data: &[Vec<u8>],
+        outpoints: &[Outpoint],
+        false_positive_rate: f64,
+        tweak: u32,
+    ) -> Self {
+        let mut bloom_filter = BloomFilter::new(
+            (script_data.len() + outpoints.len()) as u32,
+            false_positive_rate,
+            tweak,
+            BloomUpdate::All,
+        );
+
+        for data in script_data {
+            bloom_filter.insert(data);
+        }
+
+        for outpoint in outpoints {
+            let mut serialized_outpoint: Vec<u8> = Vec::new();
+            if outpoint.io_serialize(&mut serialized_outpoint).is_ok() {
+                bloom_filter.insert(&serialized_outpoint);
+            }
+        }
+
+        bloom_filter
+    }
+
+    /// Returns true if the filter doesn't exceed the limits of the protocol
+    pub fn is_within_size_constraints(&self) -> bool {
+        self.data.len() <= MAX_FILTER_SIZE && self.hash_functions <= MAX_HASH_FUNCTIONS
+    }
+
+    /// Adds an element to the filter
+    pub fn insert(&mut self, element: &[u8]) {
+        if self.data.is_empty() {
+            return;
+        }
+
+        for hash_number in 0..self.hash_functions {
+            let index = self.get_bit_index(hash_number, element);
+            self.data[index >> 3] |= 1 << (7 & index);
+        }
+    }
+
+    /// Returns true if the element may be in the filter and false if it's surely not
+    pub fn contains(&self, element: &[u8]) -> bool {
+        if self.data.is_empty() {
+            return false;
+        }
+
+        (0..self.hash_functions).all(|hash_number| {
+            let index = self.get_bit_index(hash_number, element);
+            self.data[index >> 3] & (1 << (7 & index)) != 0
+        })
+    }
+
+    /// Returns true if the transaction matches the filter, by its id, the data of its scripts or the outpoints
+    /// it spends. The outpoints of the matching outputs are added to the filter as the flags say, so the
+    /// transactions spending them also match
+    pub fn is_relevant_and_update(&mut self, transaction: &Transaction) -> bool {
+        let transaction_id = match transaction.get_tx_id() {
+            Ok(transaction_id) => transaction_id,
+            Err(_) => return false,
+        };
+
+        let mut is_relevant = self.contains(&transaction_id);
+
+        for (index, output) in transaction.tx_out.iter().enumerate() {
+            if !get_pushed_data(&output.pk_script)
+                .iter()
+                .any(|data| self.contains(data))
+            {
+                continue;
+            }
+
+            is_relevant = true;
+            let should_update = match self.flags {
+                BloomUpdate::None => false,
+                BloomUpdate::All => true,
+                BloomUpdate::P2PubKeyOnly => pays_to_public_keys(&output.pk_script),
+            };
+
+            if should_update {
+                let mut outpoint: Vec<u8> = Vec::new();
+                if Outpoint::new(transaction_id, index as u32)
+                    .io_serialize(&mut outpoint)
+                    .is_ok()
+                {
+                    self.insert(&outpoint);
+                }
+            }
+        }
+
+        if is_relevant {
+            return true;
+        }
+
+        transaction.tx_in.iter().any(|input| {
+            let mut outpoint: Vec<u8> = Vec::new();
+            if input.previous_output.io_serialize(&mut outpoint).is_ok() && self.contains(&outpoint)
+            {
+                return true;
+            }
+
+            get_pushed_data(&input.signature_script)
+                .iter()
+                .any(|data| self.contains(data))
+        })
+    }
+
+    /// Returns the index of the bit of the element for the given hash function
+    fn get_bit_index(&self, hash_number: u32, element: &[u8]) -> usize {
+        let seed = hash_number
+            .wrapping_mul(HASH_SEED_MULTIPLIER)
+            .wrapping_add(self.tweak);
+
+        murmur3(seed, element) as usize % (self.data.len() * 8)
+    }
+}
+
+/// Returns the data pushed by the script, stopping at the first push that goes beyond the script
+fn get_pushed_data(script: &[u8]) -> Vec<&[u8]> {
+    let mut pushed_data = Vec::new();
+    let mut position = 0;
+
+    while position < script.len() {
+        let opcode = script[position];
+        position += 1;
+
+        let (size, size_length) = match opcode {
+            0x01..=0x4b => (opcode as usize, 0),
+            OP_PUSHDATA1 => (read_size(script, position, 1), 1),
+            OP_PUSHDATA2 => (read_size(script, position, 2), 2),
+            OP_PUSHDATA4 => (read_size(script, position, 4), 4),
+            _ => continue,
+        };
+
+        let start = position + size_length;
+        let end = start.saturating_add(size);
+        if end > script.len() {
+            break;
+        }
+
+        pushed_data.push(&script[start..end]);
+        position = end;
+    }
+
+    pushed_data
+}
+
+/// Reads the little endian size of a push data, returning a size out of the script if it's incomplete
+fn read_size(script: &[u8], position: usize, size_length: usize) -> usize {
+    match script.get(position..position + size_length) {
+        Some(bytes) => bytes
+            .iter()
+            .rev()
+            .fold(0, |size, byte| (size << 8) | *byte as usize),
+        None => usize::MAX,
+    }
+}
+
+/// Returns true if the script pays to a public key or is a multisig
+fn pays_to_public_keys(script: &[u8]) -> bool {
+    match script.last() {
+        Some(&OP_CHECKMULTISIG) => true,
+        Some(&OP_CHECKSIG) => {
+            let pushed_data = get_pushed_data(script);
+            pushed_data.len() == 1
+                && matches!(pushed_data[0].len(), 33 | 65)
+                && pushed_data[0].len() + 2 == script.len()
+        }
+        _ => false,
+    }
+}
+
+impl SerializableInternalOrder for BloomFilter {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        CompactSize::new(self.data.len() as u64).le_serialize(stream)?;
+        self.data.io_serialize(stream)?;
+        self.hash_functions.le_serialize(stream)?;
+        self.tweak.le_serialize(stream)?;
+        self.flags.le_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for BloomFilter {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let size = CompactSize::le_deserialize(stream)?.value as usize;
+        if size > MAX_FILTER_SIZE {
+            return Err(ErrorSerialization::ErrorInDeserialization(format!(
+                "Bloom filter of {size} bytes is bigger than the maximum allowed"
+            )));
+        }
+
+        let mut data: Vec<u8> = vec![0; size];
+        if stream.read_exact(&mut data).is_err() {
+            return Err(ErrorSerialization::ErrorWhileReading);
+        }
+
+        Ok(BloomFilter {
+            data,
+            hash_functions: u32::le_deserialize(stream)?,
+            tweak: u32::le_deserialize(stream)?,
+            flags: BloomUpdate::le_deserialize(stream)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::block_structure::{
+        transaction_input::TransactionInput, transaction_output::TransactionOutput,
+    };
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_01_elements_inserted_are_contained() {
+        let mut bloom_filter = BloomFilter::new(3, 0.01, 0, BloomUpdate::All);
+
+        bloom_filter.insert(&from_hex("99108ad8ed9bb6274d3980bab5a85c048f0950c8"));
+        assert!(bloom_filter.contains(&from_hex("99108ad8ed9bb6274d3980bab5a85c048f0950c8")));
+        assert!(!bloom_filter.contains(&from_hex("19108ad8ed9bb6274d3980bab5a85c048f0950c8")));
+
+        bloom_filter.insert(&from_hex("b5a2c786d9ef4658287ced5914b37a1b4aa32eee"));
+        bloom_filter.insert(&from_hex("b9300670b4c5366e95b2699e8b18bc75e5f729c5"));
+
+        let mut stream: Vec<u8> = Vec::new();
+        bloom_filter.io_serialize(&mut stream).unwrap();
+
+        assert_eq!(from_hex("03614e9b050000000000000001"), stream);
+        assert_eq!(
+            bloom_filter,
+            BloomFilter::io_deserialize(&mut stream.as_slice()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_02_tweak_changes_the_filter() {
+        let mut bloom_filter = BloomFilter::new(3, 0.01, 2147483649, BloomUpdate::All);
+
+        bloom_filter.insert(&from_hex("99108ad8ed9bb6274d3980bab5a85c048f0950c8"));
+        bloom_filter.insert(&from_hex("b5a2c786d9ef4658287ced5914b37a1b4aa32eee"));
+        bloom_filter.insert(&from_hex("b9300670b4c5366e95b2699e8b18bc75e5f729c5"));
+
+        let mut stream: Vec<u8> = Vec::new();
+        bloom_filter.io_serialize(&mut stream).unwrap();
+
+        assert_eq!(from_hex("03ce4299050000000100008001"), stream);
+    }
+
+    #[test]
+    fn test_03_spending_transaction_matches_after_update() {
+        let public_key_hash = [7; 20];
+        let mut pk_script = vec![0x76, 0xa9, 0x14];
+        pk_script.extend_from_slice(&public_key_hash);
+        pk_script.extend_from_slice(&[0x88, 0xac]);
+
+        let funding_transaction = Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([1; 32], 0),
+                vec![0x01, 0x02],
+                0xffffffff,
+            )],
+            tx_out: vec![TransactionOutput::new(10, pk_script)],
+            time: 0,
+        };
+        let spending_transaction = Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new(funding_transaction.get_tx_id().unwrap(), 0),
+                vec![0x01, 0x03],
+                0xffffffff,
+            )],
+            tx_out: vec![TransactionOutput::new(5, vec![0x51])],
+            time: 0,
+        };
+
+        let mut bloom_filter = BloomFilter::new(10, 0.000001, 0, BloomUpdate::All);
+        assert!(!bloom_filter.is_relevant_and_update(&funding_transaction));
+
+        bloom_filter.insert(&public_key_hash);
+        assert!(bloom_filter.is_relevant_and_update(&funding_transaction));
+        assert!(bloom_filter.is_relevant_and_update(&spending_transaction));
+    }
+
+    #[test]
+    fn test_04_pushed_data_is_read_from_the_script() {
+        let script = [0x02, 0xaa, 0xbb, 0x76, OP_PUSHDATA1, 0x01, 0xcc, 0x4b];
+
+        assert_eq!(
+            vec![&[0xaa, 0xbb][..], &[0xcc][..]],
+            get_pushed_data(&script)
+        );
+    }
+
+    #[test]
+    fn test_05_filter_from_elements_matches_their_transactions() {
+        let public_key_hash = [7; 20];
+        let mut pk_script = vec![0x76, 0xa9, 0x14];
+        pk_script.extend_from_slice(&public_key_hash);
+        pk_script.extend_from_slice(&[0x88, 0xac]);
+
+        let paying_transaction = Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([1; 32], 0),
+                vec![0x01, 0x02],
+                0xffffffff,
+            )],
+            tx_out: vec![TransactionOutput::new(10, pk_script)],
+            time: 0,
+        };
+        let spending_transaction = Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([2; 32], 1),
+                vec![0x01, 0x03],
+                0xffffffff,
+            )],
+            tx_out: vec![TransactionOutput::new(5, vec![0x51])],
+            time: 0,
+        };
+
+        let mut bloom_filter = BloomFilter::from_elements(
+            &[public_key_hash.to_vec()],
+            &[Outpoint::new([2; 32], 1)],
+            0.000001,
+            0,
+        );
+        assert!(bloom_filter.is_relevant_and_update(&paying_transaction));
+        assert!(bloom_filter.is_relevant_and_update(&spending_transaction));
+
+        let mut empty_filter = BloomFilter::from_elements(&[], &[], 0.000001, 0);
+        assert!(!empty_filter.is_relevant_and_update(&paying_transaction));
+        assert!(!empty_filter.is_relevant_and_update(&spending_transaction));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::serialization::{
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization, serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::io::{Read, Write};
+
+const BLOOM_UPDATE_NONE: u8 = 0;
+const BLOOM_UPDATE_ALL: u8 = 1;
+const BLOOM_UPDATE_P2PUBKEY_ONLY: u8 = 2;
+
+/// It's how a bloom filter is updated when one of the outputs of a transaction matches it (BIP37)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BloomUpdate {
+    /// The filter is never updated
+    None,
+
+    /// The outpoint of every output that matches is added to the filter
+    All,
+
+    /// The outpoint is only added when the output pays to a public key or is a multisig
+    P2PubKeyOnly,
+}
+
+impl SerializableLittleEndian for BloomUpdate {
+    fn le_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        let value: u8 = match self {
+            BloomUpdate::None => BLOOM_UPDATE_NONE,
+            BloomUpdate::All => BLOOM_UPDATE_ALL,
+            BloomUpdate::P2PubKeyOnly => BLOOM_UPDATE_P2PUBKEY_ONLY,
+        };
+
+        value.le_serialize(stream)
+    }
+}
+
+impl DeserializableLittleEndian for BloomUpdate {
+    fn le_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        match u8::le_deserialize(stream)? {
+            BLOOM_UPDATE_NONE => Ok(BloomUpdate::None),
+            BLOOM_UPDATE_ALL => Ok(BloomUpdate::All),
+            BLOOM_UPDATE_P2PUBKEY_ONLY => Ok(BloomUpdate::P2PubKeyOnly),
+            value => Err(ErrorSerialization::ErrorInDeserialization(format!(
+                "Invalid bloom filter flags, we get: {value}"
+            ))),
+        }
+    }
+}
@@ -6,6 +6,7 @@ use super::{
 use crate::{
     block_structure::{block::Block, transaction::Transaction},
     logs::logger_sender::LoggerSender,
+    messages::reject_message::RejectMessage,
     notifications::{notification::Notification, notifier::Notifier},
 };
 
@@ -24,6 +25,7 @@ where
     RW: Read + Write + Send + 'static,
 {
     peers: Vec<HandleSender<(RW, ConnectionId)>>,
+    connections: Vec<ConnectionId>,
     logger: LoggerSender,
 }
 
@@ -34,6 +36,7 @@ where
     pub fn new(logger: LoggerSender) -> Self {
         Broadcasting {
             peers: Vec::new(),
+            connections: Vec::new(),
             logger,
         }
     }
@@ -44,11 +47,73 @@ where
         peer_manager: PeerManager<RW, N>,
         sender_receiver: SenderReceiver<MessageToPeer>,
     ) {
+        self.connections.push(peer_manager.get_id());
         let handle = thread::spawn(move || peer_manager.connecting_to_peer(sender_receiver.1));
         self.peers.push((handle, sender_receiver.0));
     }
 
-    /// It sends a transaction to all the peers
+    /// Returns the connections that are still running
+    pub fn get_connections(&self) -> Vec<ConnectionId> {
+        self.peers
+            .iter()
+            .zip(self.connections.iter())
+            .filter(|((handle, _), _)| !handle.is_finished())
+            .map(|(_, connection)| *connection)
+            .collect()
+    }
+
+    /// It removes the connections whose thread already finished, because of an error or
+    /// because the peer closed the connection, and returns them
+    pub fn remove_finished_connections(&mut self) -> Vec<ConnectionId> {
+        let mut finished_connections = Vec::new();
+
+        let mut index = 0;
+        while index < self.peers.len() {
+            if !self.peers[index].0.is_finished() {
+                index += 1;
+                continue;
+            }
+
+            let (handle, _) = self.peers.remove(index);
+            let connection = self.connections.remove(index);
+
+            match handle.join() {
+                Ok(Ok(_)) => {
+                    let _ = self
+                        .logger
+                        .log_broadcasting(format!("Connection with {connection} finished"));
+                }
+                Ok(Err(error)) => {
+                    let _ = self.logger.log_broadcasting(format!(
+                        "Connection with {connection} finished with error: {:?}",
+                        error
+                    ));
+                }
+                Err(_) => {
+                    let _ = self
+                        .logger
+                        .log_error("Thread could not finish correctly".to_string());
+                }
+            }
+
+            finished_connections.push(connection);
+        }
+
+        finished_connections
+    }
+
+    /// It stops the peer of the given connection, which will be removed once its thread finishes
+    pub fn close_connection(&mut self, connection: &ConnectionId) {
+        for ((_, sender), peer_connection) in self.peers.iter().zip(self.connections.iter()) {
+            if peer_connection == connection && sender.send(MessageToPeer::Stop).is_err() {
+                let _ = self
+                    .logger
+                    .log_error("Sending closing message to peer".to_string());
+            }
+        }
+    }
+
+    /// It sends a transaction to all the peers, each peer manager announces it with an inventory message after a random delay
     ///
     /// ### Error
     ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to a peer
@@ -70,7 +135,8 @@ where
         Ok(())
     }
 
-    /// It broadcast a transaction to all the peers except the peer that sent the transaction
+    /// It broadcast a transaction to all the peers except the peer that sent the transaction.
+    /// Each peer manager announces it with an inventory message, unless the peer already knows it
     ///
     /// ### Error
     ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to a peer
@@ -99,7 +165,30 @@ where
         Ok(())
     }
 
-    /// It broadcast a block to all the peers except the peer that sent the block
+    /// It sends a block mined by us to all the peers, each peer manager announces it with a headers or inventory message
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to a peer
+    pub fn send_block(&mut self, block: Block) -> Result<(), ErrorNode> {
+        let _ = self
+            .logger
+            .log_broadcasting("Broadcasting own block".to_string());
+        for (_, sender) in self.peers.iter() {
+            if sender
+                .send(MessageToPeer::SendBlock(block.clone(), None))
+                .is_err()
+            {
+                let _ = self
+                    .logger
+                    .log_error("Sending block message to peer".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// It announces a block to all the peers except the peer that sent the block.
+    /// Each peer manager sends a headers or inventory message, and the full block only when it's requested
     ///
     /// ### Error
     ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to a peer
@@ -109,7 +198,7 @@ where
             .log_broadcasting("Broadcasting a block".to_string());
         for (_, sender) in self.peers.iter() {
             if sender
-                .send(MessageToPeer::SendBlock(block.clone(), from))
+                .send(MessageToPeer::SendBlock(block.clone(), Some(from)))
                 .is_err()
             {
                 let _ = self
@@ -121,6 +210,25 @@ where
         Ok(())
     }
 
+    /// It sends a reject message to the peer of the given connection, telling it that one of its messages was refused
+    pub fn send_reject(&mut self, reject: RejectMessage, to: &ConnectionId) {
+        let _ = self.logger.log_broadcasting(format!(
+            "Rejecting {} of peer {to}: {}",
+            reject.message, reject.reason
+        ));
+        for ((_, sender), peer_connection) in self.peers.iter().zip(self.connections.iter()) {
+            if peer_connection == to
+                && sender
+                    .send(MessageToPeer::SendReject(reject.clone()))
+                    .is_err()
+            {
+                let _ = self
+                    .logger
+                    .log_error("Sending reject message to peer".to_string());
+            }
+        }
+    }
+
     /// It stops all the peers and returns their streams
     ///
     /// ### Error
@@ -141,6 +249,7 @@ where
         for peer in self.peers.drain(..) {
             peers.push(peer);
         }
+        self.connections.clear();
 
         let mut peers_streams = Vec::new();
         for (handle, _) in peers {
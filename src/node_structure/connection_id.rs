@@ -1,6 +1,11 @@
 use super::connection_type::ConnectionType;
 
-use std::{cmp::PartialEq, fmt::Display, net::SocketAddr};
+use std::{
+    cmp::PartialEq,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    net::SocketAddr,
+};
 
 /// The identifier of a connection given by the address and the type of connection
 #[derive(Debug, Clone, Copy)]
@@ -24,6 +29,14 @@ impl PartialEq for ConnectionId {
     }
 }
 
+impl Eq for ConnectionId {}
+
+impl Hash for ConnectionId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+    }
+}
+
 impl Display for ConnectionId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?} - {:?}", self.connection_type, self.address)
@@ -0,0 +1,380 @@
+use super::connection_id::ConnectionId;
+
+use crate::block_structure::{block::Block, hash::HashType};
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+const MAX_STALLS: u32 = 3;
+
+/// It schedules the download of a range of blocks between many peers. Each peer has a window of
+/// blocks in flight, the requests that take too long are given to other peers, and the blocks
+/// are returned in the order of the range no matter the order they arrive
+#[derive(Debug)]
+pub struct DownloadScheduler {
+    hashes: Vec<HashType>,
+    positions: HashMap<HashType, usize>,
+    pending: VecDeque<usize>,
+    stalled_by: HashMap<usize, ConnectionId>,
+    in_flight: HashMap<usize, (ConnectionId, Instant)>,
+    blocks: Vec<Option<Block>>,
+    next_to_deliver: usize,
+    received_count: usize,
+    peers: HashMap<ConnectionId, u32>,
+    window: usize,
+    stall_timeout: Duration,
+}
+
+impl DownloadScheduler {
+    /// Creates the scheduler for the blocks with the given hashes, in the order they must be delivered
+    pub fn new(hashes: Vec<HashType>, window: usize, stall_timeout: Duration) -> Self {
+        let positions = hashes
+            .iter()
+            .enumerate()
+            .map(|(position, hash)| (*hash, position))
+            .collect();
+
+        DownloadScheduler {
+            pending: (0..hashes.len()).collect(),
+            blocks: vec![None; hashes.len()],
+            hashes,
+            positions,
+            stalled_by: HashMap::new(),
+            in_flight: HashMap::new(),
+            next_to_deliver: 0,
+            received_count: 0,
+            peers: HashMap::new(),
+            window,
+            stall_timeout,
+        }
+    }
+
+    /// Adds a peer to download blocks from
+    pub fn add_peer(&mut self, peer: ConnectionId) {
+        self.peers.entry(peer).or_insert(0);
+    }
+
+    /// Removes the peer, giving its requests in flight back to the other peers
+    pub fn remove_peer(&mut self, peer: &ConnectionId) {
+        if self.peers.remove(peer).is_none() {
+            return;
+        }
+
+        let mut requests: Vec<usize> = self
+            .in_flight
+            .iter()
+            .filter(|(_, (requested_peer, _))| requested_peer == peer)
+            .map(|(position, _)| *position)
+            .collect();
+        requests.sort_unstable();
+
+        for position in requests.into_iter().rev() {
+            self.in_flight.remove(&position);
+            self.pending.push_front(position);
+        }
+    }
+
+    /// Returns true if the peer is still downloading blocks
+    pub fn has_peer(&self, peer: &ConnectionId) -> bool {
+        self.peers.contains_key(peer)
+    }
+
+    /// Returns the amount of peers downloading blocks
+    pub fn peer_count(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// Returns the hashes of the next blocks to request to the peer, filling its window of blocks in flight.
+    /// A block that stalled with the peer is left for the others, unless the peer is the only one left
+    pub fn next_requests(&mut self, peer: ConnectionId, now: Instant) -> Vec<HashType> {
+        if !self.has_peer(&peer) {
+            return Vec::new();
+        }
+
+        let in_flight_count = self
+            .in_flight
+            .values()
+            .filter(|(requested_peer, _)| *requested_peer == peer)
+            .count();
+        let mut available = self.window.saturating_sub(in_flight_count);
+
+        let only_peer = self.peers.len() == 1;
+        let mut requests = Vec::new();
+        let mut skipped = VecDeque::new();
+
+        while available > 0 {
+            let position = match self.pending.pop_front() {
+                Some(position) => position,
+                None => break,
+            };
+
+            if !only_peer && self.stalled_by.get(&position) == Some(&peer) {
+                skipped.push_back(position);
+                continue;
+            }
+
+            self.in_flight.insert(position, (peer, now));
+            requests.push(self.hashes[position]);
+            available -= 1;
+        }
+
+        while let Some(position) = skipped.pop_back() {
+            self.pending.push_front(position);
+        }
+
+        requests
+    }
+
+    /// Stores a block received from the peer. Returns false if the block was not requested or was already received
+    pub fn block_received(&mut self, peer: ConnectionId, block: Block) -> bool {
+        let hash = match block.header.get_hash256d() {
+            Ok(hash) => hash,
+            Err(_) => return false,
+        };
+
+        let position = match self.positions.get(&hash) {
+            Some(position) => *position,
+            None => return false,
+        };
+
+        self.in_flight.remove(&position);
+        if self.blocks[position].is_some() || position < self.next_to_deliver {
+            return false;
+        }
+
+        self.pending.retain(|pending| *pending != position);
+        self.stalled_by.remove(&position);
+        if let Some(stalls) = self.peers.get_mut(&peer) {
+            *stalls = 0;
+        }
+
+        self.blocks[position] = Some(block);
+        self.received_count += 1;
+
+        true
+    }
+
+    /// Gives the requests in flight for longer than the stall timeout to the other peers.
+    /// Returns the peers removed for stalling too many times in a row
+    pub fn reassign_stalled(&mut self, now: Instant) -> Vec<ConnectionId> {
+        let mut stalled: Vec<(usize, ConnectionId)> = self
+            .in_flight
+            .iter()
+            .filter(|(_, (_, requested))| now.duration_since(*requested) >= self.stall_timeout)
+            .map(|(position, (peer, _))| (*position, *peer))
+            .collect();
+        stalled.sort_unstable_by_key(|(position, _)| *position);
+
+        let mut removed_peers = Vec::new();
+        for (position, peer) in stalled.into_iter().rev() {
+            self.in_flight.remove(&position);
+            self.pending.push_front(position);
+            self.stalled_by.insert(position, peer);
+
+            if let Some(stalls) = self.peers.get_mut(&peer) {
+                *stalls += 1;
+                if *stalls >= MAX_STALLS && !removed_peers.contains(&peer) {
+                    removed_peers.push(peer);
+                }
+            }
+        }
+
+        for peer in removed_peers.iter() {
+            self.remove_peer(peer);
+        }
+
+        removed_peers
+    }
+
+    /// Returns the received blocks that follow the last delivered one, in order
+    pub fn take_ready_blocks(&mut self) -> Vec<Block> {
+        let mut ready_blocks = Vec::new();
+        while let Some(block) = self
+            .blocks
+            .get_mut(self.next_to_deliver)
+            .and_then(|block| block.take())
+        {
+            ready_blocks.push(block);
+            self.next_to_deliver += 1;
+        }
+
+        ready_blocks
+    }
+
+    /// Returns the amount of blocks received and the total amount of blocks to download
+    pub fn get_progress(&self) -> (usize, usize) {
+        (self.received_count, self.hashes.len())
+    }
+
+    /// Returns true if all the blocks were received
+    pub fn is_complete(&self) -> bool {
+        self.received_count == self.hashes.len()
+    }
+
+    /// Returns true if all the blocks were delivered
+    pub fn is_finished(&self) -> bool {
+        self.next_to_deliver == self.hashes.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        block_structure::{
+            block_header::BlockHeader, block_version::BlockVersion, compact256::Compact256,
+        },
+        messages::compact_size::CompactSize,
+        node_structure::connection_type::ConnectionType,
+    };
+
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+    fn create_peer(port: u16) -> ConnectionId {
+        ConnectionId::new(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port),
+            ConnectionType::Peer,
+        )
+    }
+
+    fn create_blocks(amount: u32) -> Vec<Block> {
+        (0..amount)
+            .map(|time| {
+                Block::new(BlockHeader::new(
+                    BlockVersion::version(1),
+                    [0; 32],
+                    [0; 32],
+                    time,
+                    Compact256::from(u32::MAX),
+                    0,
+                    CompactSize::new(0),
+                ))
+            })
+            .collect()
+    }
+
+    fn create_scheduler(blocks: &[Block], window: usize) -> DownloadScheduler {
+        let hashes = blocks
+            .iter()
+            .map(|block| block.header.get_hash256d().unwrap())
+            .collect();
+        DownloadScheduler::new(hashes, window, Duration::from_secs(10))
+    }
+
+    #[test]
+    fn test_01_blocks_are_split_between_peers() {
+        let blocks = create_blocks(5);
+        let mut scheduler = create_scheduler(&blocks, 2);
+        let now = Instant::now();
+
+        scheduler.add_peer(create_peer(1));
+        scheduler.add_peer(create_peer(2));
+
+        let first_requests = scheduler.next_requests(create_peer(1), now);
+        let second_requests = scheduler.next_requests(create_peer(2), now);
+
+        assert_eq!(
+            vec![
+                blocks[0].header.get_hash256d().unwrap(),
+                blocks[1].header.get_hash256d().unwrap()
+            ],
+            first_requests
+        );
+        assert_eq!(
+            vec![
+                blocks[2].header.get_hash256d().unwrap(),
+                blocks[3].header.get_hash256d().unwrap()
+            ],
+            second_requests
+        );
+        assert!(scheduler.next_requests(create_peer(1), now).is_empty());
+    }
+
+    #[test]
+    fn test_02_blocks_are_delivered_in_order() {
+        let blocks = create_blocks(3);
+        let mut scheduler = create_scheduler(&blocks, 3);
+
+        scheduler.add_peer(create_peer(1));
+        scheduler.next_requests(create_peer(1), Instant::now());
+
+        assert!(scheduler.block_received(create_peer(1), blocks[1].clone()));
+        assert!(scheduler.take_ready_blocks().is_empty());
+
+        assert!(scheduler.block_received(create_peer(1), blocks[0].clone()));
+        assert!(!scheduler.block_received(create_peer(1), blocks[0].clone()));
+        assert_eq!(
+            vec![blocks[0].clone(), blocks[1].clone()],
+            scheduler.take_ready_blocks()
+        );
+
+        assert!(scheduler.block_received(create_peer(1), blocks[2].clone()));
+        assert!(scheduler.is_complete());
+        assert_eq!(vec![blocks[2].clone()], scheduler.take_ready_blocks());
+        assert!(scheduler.is_finished());
+    }
+
+    #[test]
+    fn test_03_stalled_requests_are_given_to_other_peers() {
+        let blocks = create_blocks(2);
+        let mut scheduler = create_scheduler(&blocks, 2);
+        let now = Instant::now();
+
+        scheduler.add_peer(create_peer(1));
+        scheduler.add_peer(create_peer(2));
+        scheduler.next_requests(create_peer(1), now);
+
+        assert!(scheduler
+            .reassign_stalled(now + Duration::from_secs(1))
+            .is_empty());
+        assert!(scheduler.next_requests(create_peer(2), now).is_empty());
+
+        scheduler.reassign_stalled(now + Duration::from_secs(10));
+
+        assert!(scheduler.next_requests(create_peer(1), now).is_empty());
+        assert_eq!(
+            vec![
+                blocks[0].header.get_hash256d().unwrap(),
+                blocks[1].header.get_hash256d().unwrap()
+            ],
+            scheduler.next_requests(create_peer(2), now)
+        );
+    }
+
+    #[test]
+    fn test_04_removed_peer_gives_back_its_requests() {
+        let blocks = create_blocks(2);
+        let mut scheduler = create_scheduler(&blocks, 2);
+        let now = Instant::now();
+
+        scheduler.add_peer(create_peer(1));
+        scheduler.add_peer(create_peer(2));
+        scheduler.next_requests(create_peer(1), now);
+        scheduler.remove_peer(&create_peer(1));
+
+        assert!(!scheduler.has_peer(&create_peer(1)));
+        assert_eq!(1, scheduler.peer_count());
+        assert_eq!(2, scheduler.next_requests(create_peer(2), now).len());
+    }
+
+    #[test]
+    fn test_05_peer_stalling_too_many_times_is_removed() {
+        let blocks = create_blocks(1);
+        let mut scheduler = create_scheduler(&blocks, 1);
+        let mut now = Instant::now();
+
+        scheduler.add_peer(create_peer(1));
+        for _ in 0..MAX_STALLS {
+            assert!(scheduler.has_peer(&create_peer(1)));
+            scheduler.next_requests(create_peer(1), now);
+            now += Duration::from_secs(10);
+            scheduler.reassign_stalled(now);
+        }
+
+        assert!(!scheduler.has_peer(&create_peer(1)));
+        assert_eq!(0, scheduler.peer_count());
+    }
+}
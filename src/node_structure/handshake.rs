@@ -2,9 +2,17 @@ use super::handshake_data::HandshakeData;
 
 use crate::{
     messages::{
-        bitfield_services::BitfieldServices, message::Message, message_header::MessageHeader,
-        send_headers_message::SendHeadersMessage, verack_message::VerackMessage,
+        bitfield_services::BitfieldServices,
+        command_name::CommandName,
+        get_addr_message::GetAddrMessage,
+        message::{self, Message},
+        message_header::MessageHeader,
+        send_addr_v2_message::SendAddrV2Message,
+        send_cmpct_message::{SendCmpctMessage, COMPACT_BLOCKS_VERSION},
+        send_headers_message::SendHeadersMessage,
+        verack_message::VerackMessage,
         version_message::VersionMessage,
+        wtxid_relay_message::WtxidRelayMessage,
     },
     serialization::error_serialization::ErrorSerialization,
 };
@@ -97,26 +105,63 @@ impl Handshake {
         }
     }
 
-    /// Function that receives a version message from the given potential peer.
+    /// Function that receives a version message from the given potential peer, returning the protocol version of the peer.
+    /// The peer must use the magic numbers of our network
     ///
     /// ### Error
     ///  * `ErrorSerialization::ErrorSerialization`: It will appear when there is an error in the serialization
-    ///  * `ErrorSerialization::ErrorInDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorSerialization::ErrorInDeserialization`: It will appear when there is an error in the deserialization or the peer is from another network
     ///  * `ErrorSerialization::ErrorWhileReading`: It will appear when there is an error in the reading from a stream
     pub fn receive_version_message<RW: Read + Write>(
         &self,
         peer_stream: &mut RW,
         header: MessageHeader,
         potential_peer: &SocketAddr,
-    ) -> Result<(), ErrorSerialization> {
-        if let Err(error) = VersionMessage::deserialize_message(peer_stream, header) {
+    ) -> Result<ProtocolVersionP2P, ErrorSerialization> {
+        if header.magic_numbers != self.data.magic_number {
             let _ = self.sender_log.log_connection(format!(
-                "Error while receiving version message from peer {}: {:?}",
-                potential_peer, error
+                "Peer {} is from another network, its magic numbers are {:?}",
+                potential_peer, header.magic_numbers
             ));
-            return Err(error);
+            return Err(ErrorSerialization::ErrorInDeserialization(
+                "The peer is from another network".to_string(),
+            ));
+        }
+
+        match VersionMessage::deserialize_message(peer_stream, header) {
+            Ok(version_message) => Ok(version_message.version),
+            Err(error) => {
+                let _ = self.sender_log.log_connection(format!(
+                    "Error while receiving version message from peer {}: {:?}",
+                    potential_peer, error
+                ));
+                Err(error)
+            }
+        }
+    }
+
+    /// Sends the send addresses v2 message to the peer, asking for the addresses in the version 2 format.
+    /// It's only sent when both we and the peer use a protocol version that supports it, and must be sent before the verack
+    ///
+    /// ### Error
+    ///  * `ErrorSerialization::ErrorSerialization`: It will appear when there is an error in the serialization
+    pub fn send_sendaddrv2_message<RW: Read + Write>(
+        &self,
+        peer_stream: &mut RW,
+        peer_version: ProtocolVersionP2P,
+    ) -> Result<(), ErrorSerialization> {
+        let minimum_version = i32::from(ProtocolVersionP2P::V70016);
+        if i32::from(self.protocol_version) < minimum_version
+            || i32::from(peer_version) < minimum_version
+        {
+            return Ok(());
         }
-        Ok(())
+
+        SendAddrV2Message::serialize_message(
+            peer_stream,
+            self.data.magic_number,
+            &SendAddrV2Message,
+        )
     }
 
     /// Function that sends a verack message to the given potential peer.
@@ -144,7 +189,9 @@ impl Handshake {
         }
     }
 
-    /// Function that receives a verack message from the given potential peer.
+    /// Function that receives a verack message from the given potential peer. The negotiation messages
+    /// (wtxidrelay and sendaddrv2) sent before the verack are accepted, and any other message is skipped.
+    /// Returns true when the verack was received
     ///
     /// ### Error
     ///  * `ErrorSerialization::ErrorSerialization`: It will appear when there is an error in the serialization
@@ -155,16 +202,46 @@ impl Handshake {
         peer_stream: &mut RW,
         header: MessageHeader,
         potential_peer: &SocketAddr,
-    ) -> Result<(), ErrorSerialization> {
-        if let Err(error) = VerackMessage::deserialize_message(peer_stream, header) {
+    ) -> Result<bool, ErrorSerialization> {
+        let command_name = header.command_name;
+        let result = match command_name {
+            CommandName::Verack => {
+                VerackMessage::deserialize_message(peer_stream, header).map(|_| ())
+            }
+            CommandName::WtxidRelay => {
+                WtxidRelayMessage::deserialize_message(peer_stream, header).map(|_| ())
+            }
+            CommandName::SendAddrV2 => {
+                SendAddrV2Message::deserialize_message(peer_stream, header).map(|_| ())
+            }
+            _ => message::skip_payload(peer_stream, header),
+        };
+
+        if let Err(error) = result {
             let _ = self.sender_log.log_connection(format!(
-                "Error while receiving verack message from peer {}: {:?}",
+                "Error while receiving {command_name} message from peer {}: {:?}",
                 potential_peer, error
             ));
             return Err(error);
         }
 
-        Ok(())
+        match command_name {
+            CommandName::Verack => Ok(true),
+            CommandName::WtxidRelay | CommandName::SendAddrV2 => {
+                let _ = self.sender_log.log_connection(format!(
+                    "Peer {} announced {command_name} during the handshake",
+                    potential_peer
+                ));
+                Ok(false)
+            }
+            _ => {
+                let _ = self.sender_log.log_connection(format!(
+                    "Skipping {command_name} message from peer {} before the verack",
+                    potential_peer
+                ));
+                Ok(false)
+            }
+        }
     }
 
     /// Sends a send header message to the peer.
@@ -181,4 +258,31 @@ impl Handshake {
             &SendHeadersMessage,
         )
     }
+
+    /// Sends the send compact message to the peer, asking it to announce the new blocks with compact blocks
+    ///
+    /// ### Error
+    ///  * `ErrorSerialization::ErrorSerialization`: It will appear when there is an error in the serialization
+    pub fn send_sendcmpct_message<RW: Read + Write>(
+        &self,
+        peer_stream: &mut RW,
+    ) -> Result<(), ErrorSerialization> {
+        let send_cmpct = SendCmpctMessage {
+            announce: true,
+            version: COMPACT_BLOCKS_VERSION,
+        };
+
+        SendCmpctMessage::serialize_message(peer_stream, self.data.magic_number, &send_cmpct)
+    }
+
+    /// Sends the get addresses message to the peer, asking for the addresses of other potential peers
+    ///
+    /// ### Error
+    ///  * `ErrorSerialization::ErrorSerialization`: It will appear when there is an error in the serialization
+    pub fn send_getaddr_message<RW: Read + Write>(
+        &self,
+        peer_stream: &mut RW,
+    ) -> Result<(), ErrorSerialization> {
+        GetAddrMessage::serialize_message(peer_stream, self.data.magic_number, &GetAddrMessage)
+    }
 }
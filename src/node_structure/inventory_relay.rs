@@ -0,0 +1,215 @@
+use crate::block_structure::{hash::HashType, transaction::Transaction};
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+const MAX_KNOWN_INVENTORY: usize = 50_000;
+const MAX_ANNOUNCED_TRANSACTIONS: usize = 5_000;
+const MAX_DELAY_FACTOR: f64 = 8.0;
+
+/// It keeps the inventory a peer already knows and the transactions waiting to be announced to it.
+/// The announcements are trickled: they are sent together after a random delay, so the peer can't
+/// tell which transactions were created by this node
+#[derive(Debug)]
+pub struct InventoryRelay {
+    average_delay: Duration,
+    next_trickle: Instant,
+    random_state: u64,
+    known_inventory: HashSet<HashType>,
+    known_order: VecDeque<HashType>,
+    pending_announcements: Vec<HashType>,
+    announced_transactions: HashMap<HashType, Transaction>,
+    announced_order: VecDeque<HashType>,
+}
+
+impl InventoryRelay {
+    /// Creates the relay from the given time, waiting on average the given delay between announcements
+    pub fn new(average_delay: Duration, now: Instant) -> Self {
+        let random_state = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_nanos() as u64,
+            Err(_) => 0,
+        };
+
+        InventoryRelay {
+            average_delay,
+            next_trickle: now,
+            random_state: random_state | 1,
+            known_inventory: HashSet::new(),
+            known_order: VecDeque::new(),
+            pending_announcements: Vec::new(),
+            announced_transactions: HashMap::new(),
+            announced_order: VecDeque::new(),
+        }
+    }
+
+    /// Registers that the peer knows the inventory with the given hash, forgetting the oldest one if there are too many
+    pub fn add_known(&mut self, hash: HashType) {
+        if !self.known_inventory.insert(hash) {
+            return;
+        }
+
+        self.known_order.push_back(hash);
+        if self.known_order.len() > MAX_KNOWN_INVENTORY {
+            if let Some(oldest) = self.known_order.pop_front() {
+                self.known_inventory.remove(&oldest);
+            }
+        }
+    }
+
+    /// Returns true if the peer already knows the inventory with the given hash
+    pub fn is_known(&self, hash: &HashType) -> bool {
+        self.known_inventory.contains(hash)
+    }
+
+    /// Queues the transaction to be announced in the next trickle. Returns false if the peer already knows it
+    pub fn queue_transaction(
+        &mut self,
+        transaction_id: HashType,
+        transaction: Transaction,
+        now: Instant,
+    ) -> bool {
+        if self.is_known(&transaction_id) || self.pending_announcements.contains(&transaction_id) {
+            return false;
+        }
+
+        if self.pending_announcements.is_empty() && self.next_trickle <= now {
+            self.next_trickle = now + self.random_delay();
+        }
+
+        self.pending_announcements.push(transaction_id);
+        if self
+            .announced_transactions
+            .insert(transaction_id, transaction)
+            .is_none()
+        {
+            self.announced_order.push_back(transaction_id);
+        }
+
+        if self.announced_order.len() > MAX_ANNOUNCED_TRANSACTIONS {
+            if let Some(oldest) = self.announced_order.pop_front() {
+                self.announced_transactions.remove(&oldest);
+            }
+        }
+
+        true
+    }
+
+    /// Returns the time of the next trickle, if there are transactions waiting to be announced
+    pub fn next_deadline(&self) -> Option<Instant> {
+        match self.pending_announcements.is_empty() {
+            true => None,
+            false => Some(self.next_trickle),
+        }
+    }
+
+    /// Returns the transactions to announce if it's time for the trickle, marking them as known by the peer
+    pub fn take_announcements(&mut self, now: Instant) -> Vec<HashType> {
+        if self.pending_announcements.is_empty() || now < self.next_trickle {
+            return Vec::new();
+        }
+
+        self.next_trickle = now + self.random_delay();
+
+        let announcements: Vec<HashType> = self.pending_announcements.drain(..).collect();
+        for transaction_id in announcements.iter() {
+            self.add_known(*transaction_id);
+        }
+
+        announcements
+    }
+
+    /// Returns the transaction announced to the peer with the given id
+    pub fn get_announced_transaction(&self, transaction_id: &HashType) -> Option<&Transaction> {
+        self.announced_transactions.get(transaction_id)
+    }
+
+    /// Returns a random delay following an exponential distribution with the average delay as mean
+    fn random_delay(&mut self) -> Duration {
+        self.random_state ^= self.random_state << 13;
+        self.random_state ^= self.random_state >> 7;
+        self.random_state ^= self.random_state << 17;
+
+        let uniform = ((self.random_state >> 11) as f64 + 1.0) / ((1u64 << 53) as f64 + 1.0);
+        let factor = (-uniform.ln()).min(MAX_DELAY_FACTOR);
+
+        self.average_delay.mul_f64(factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::block_structure::{
+        outpoint::Outpoint, transaction_input::TransactionInput,
+        transaction_output::TransactionOutput,
+    };
+
+    fn create_transaction(time: u32) -> Transaction {
+        Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([1; 32], 23),
+                vec![1, 2, 3],
+                24,
+            )],
+            tx_out: vec![TransactionOutput {
+                value: 10,
+                pk_script: vec![4, 5, 6],
+            }],
+            time,
+        }
+    }
+
+    #[test]
+    fn test_01_known_transactions_are_not_announced() {
+        let now = Instant::now();
+        let mut relay = InventoryRelay::new(Duration::from_secs(5), now);
+
+        relay.add_known([1; 32]);
+
+        assert!(!relay.queue_transaction([1; 32], create_transaction(1), now));
+        assert!(relay.queue_transaction([2; 32], create_transaction(2), now));
+        assert!(!relay.queue_transaction([2; 32], create_transaction(2), now));
+        assert!(relay.get_announced_transaction(&[2; 32]).is_some());
+    }
+
+    #[test]
+    fn test_02_announcements_wait_for_the_trickle() {
+        let now = Instant::now();
+        let mut relay = InventoryRelay::new(Duration::from_secs(5), now);
+
+        assert_eq!(None, relay.next_deadline());
+
+        relay.queue_transaction([1; 32], create_transaction(1), now);
+        relay.queue_transaction([2; 32], create_transaction(2), now);
+
+        let deadline = relay.next_deadline().unwrap();
+        assert!(deadline <= now + Duration::from_secs(5).mul_f64(MAX_DELAY_FACTOR));
+
+        if deadline > now {
+            assert!(relay.take_announcements(now).is_empty());
+        }
+
+        assert_eq!(vec![[1; 32], [2; 32]], relay.take_announcements(deadline));
+        assert!(relay.is_known(&[1; 32]));
+        assert!(relay.is_known(&[2; 32]));
+        assert_eq!(None, relay.next_deadline());
+    }
+
+    #[test]
+    fn test_03_known_inventory_is_bounded() {
+        let mut relay = InventoryRelay::new(Duration::from_secs(5), Instant::now());
+
+        for index in 0..=MAX_KNOWN_INVENTORY {
+            let mut hash = [0; 32];
+            hash[..8].copy_from_slice(&(index as u64).to_le_bytes());
+            relay.add_known(hash);
+        }
+
+        assert!(!relay.is_known(&[0; 32]));
+        assert_eq!(MAX_KNOWN_INVENTORY, relay.known_inventory.len());
+    }
+}
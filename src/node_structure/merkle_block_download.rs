@@ -0,0 +1,289 @@
+use super::{bloom_filter::BloomFilter, error_node::ErrorNode};
+
+use crate::messages::{
+    command_name::CommandName,
+    filter_load_message::FilterLoadMessage,
+    get_data_message::GetDataMessage,
+    merkle_block_message::MerkleBlockMessage,
+    message::{self, Message},
+    message_header::MagicType,
+    tx_message::TxMessage,
+};
+
+use crate::logs::logger_sender::LoggerSender;
+
+use crate::block_structure::{block::Block, hash::HashType};
+
+use std::io::{Read, Write};
+
+const MAX_HEADERS_COUNT: usize = 50_000;
+
+/// It represents the download of the transactions of a wallet, with the merkle blocks (BIP37) of the blocks
+/// to download. The blocks obtained only have the transactions matching the filter loaded in the peer
+#[derive(Debug, Clone)]
+pub struct MerkleBlockDownload {
+    magic_numbers: MagicType,
+    sender_log: LoggerSender,
+}
+
+impl MerkleBlockDownload {
+    pub fn new(magic_numbers: MagicType, sender_log: LoggerSender) -> Self {
+        MerkleBlockDownload {
+            magic_numbers,
+            sender_log,
+        }
+    }
+
+    /// It loads the bloom filter in the peer, so it only sends the transactions matching it
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    pub fn load_filter<RW: Read + Write>(
+        &self,
+        peer_stream: &mut RW,
+        bloom_filter: BloomFilter,
+    ) -> Result<(), ErrorNode> {
+        let _ = self
+            .sender_log
+            .log_connection("Loading the bloom filter in the peer".to_string());
+
+        let filter_load = FilterLoadMessage { bloom_filter };
+        FilterLoadMessage::serialize_message(peer_stream, self.magic_numbers, &filter_load)?;
+
+        Ok(())
+    }
+
+    /// Get the filtered blocks from the peer given the hashed headers. Each block has the header and the transactions
+    /// matching the filter, after verifying they are included in the block with the merkle proof
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileValidating`: It will appear when a merkle block or its transactions don't match what was requested
+    ///  * `ErrorNode::RequestedDataTooBig`: It will appear when the headers count is bigger than the maximum headers count of 50_000
+    pub fn get_filtered_blocks<RW: Read + Write>(
+        &self,
+        peer_stream: &mut RW,
+        hashed_headers: Vec<HashType>,
+    ) -> Result<Vec<Block>, ErrorNode> {
+        let headers_count = hashed_headers.len();
+
+        if headers_count >= MAX_HEADERS_COUNT {
+            let _ = self
+                .sender_log
+                .log_connection("More headers than possible".to_string());
+            return Err(ErrorNode::RequestedDataTooBig);
+        }
+
+        let get_data_message = GetDataMessage::get_filtered_blocks(hashed_headers.clone());
+        GetDataMessage::serialize_message(peer_stream, self.magic_numbers, &get_data_message)?;
+
+        let _ = self
+            .sender_log
+            .log_connection(format!("Downloading {headers_count} merkle blocks"));
+
+        let mut blocks: Vec<Block> = Vec::new();
+        for _ in 0..headers_count {
+            let block = self.receive_filtered_block(peer_stream, &hashed_headers)?;
+            blocks.push(block);
+        }
+
+        Ok(blocks)
+    }
+
+    /// It receives a merkle block and the transactions matched in it
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileValidating`: It will appear when the merkle block or its transactions don't match what was requested
+    fn receive_filtered_block<RW: Read + Write>(
+        &self,
+        peer_stream: &mut RW,
+        hashed_headers: &[HashType],
+    ) -> Result<Block, ErrorNode> {
+        let header = message::deserialize_until_found(peer_stream, CommandName::MerkleBlock)?;
+        let merkle_block = MerkleBlockMessage::deserialize_message(peer_stream, header)?;
+
+        match merkle_block.header.get_hash256d() {
+            Ok(hash) if hashed_headers.contains(&hash) => {}
+            _ => {
+                return Err(ErrorNode::WhileValidating(
+                    "Received a merkle block that was not requested".to_string(),
+                ))
+            }
+        }
+
+        let matched_ids = match merkle_block
+            .partial_merkle_tree
+            .verify(&merkle_block.header)
+        {
+            Ok(matched_ids) => matched_ids,
+            Err(_) => {
+                return Err(ErrorNode::WhileValidating(
+                    "Failed merkle proof of the merkle block".to_string(),
+                ))
+            }
+        };
+
+        let mut block = Block::new(merkle_block.header);
+        for _ in 0..matched_ids.len() {
+            let header = message::deserialize_until_found(peer_stream, CommandName::Tx)?;
+            let transaction = TxMessage::deserialize_message(peer_stream, header)?.transaction;
+
+            match transaction.get_tx_id() {
+                Ok(transaction_id) if matched_ids.contains(&transaction_id) => {}
+                _ => {
+                    return Err(ErrorNode::WhileValidating(
+                        "Received a transaction that is not in the merkle block".to_string(),
+                    ))
+                }
+            }
+
+            if block.append_transaction(transaction).is_err() {
+                return Err(ErrorNode::WhileValidating(
+                    "Received a transaction of the merkle block twice".to_string(),
+                ));
+            }
+        }
+
+        Ok(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::{
+        block_structure::{
+            block_header::BlockHeader, block_version::BlockVersion, compact256::Compact256,
+            merkle_tree::MerkleTree, outpoint::Outpoint, transaction::Transaction,
+            transaction_input::TransactionInput, transaction_output::TransactionOutput,
+        },
+        logs::logger,
+        messages::compact_size::CompactSize,
+        node_structure::bloom_update::BloomUpdate,
+    };
+
+    struct Stream {
+        stream: Vec<u8>,
+        pointer: usize,
+    }
+
+    impl Stream {
+        pub fn new() -> Stream {
+            Stream {
+                stream: Vec::new(),
+                pointer: 0,
+            }
+        }
+    }
+
+    impl Read for Stream {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let mut i = 0;
+            while i < buf.len() && self.pointer < self.stream.len() {
+                buf[i] = self.stream[self.pointer];
+                self.pointer += 1;
+                i += 1;
+            }
+            Ok(i)
+        }
+    }
+
+    impl Write for Stream {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.stream.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn create_transaction(index: u8) -> Transaction {
+        Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([index; 32], 0),
+                vec![1, 2, 3],
+                24,
+            )],
+            tx_out: vec![TransactionOutput {
+                value: 10,
+                pk_script: vec![0x51, index],
+            }],
+            time: 0,
+        }
+    }
+
+    fn create_block(transactions: Vec<Transaction>) -> Block {
+        let merkle_root = MerkleTree::new(&transactions).unwrap().get_root();
+
+        Block {
+            header: BlockHeader::new(
+                BlockVersion::version(1),
+                [0; 32],
+                merkle_root,
+                0,
+                Compact256::from(u32::MAX),
+                0,
+                CompactSize::new(transactions.len() as u64),
+            ),
+            transactions,
+        }
+    }
+
+    fn serialize_merkle_block(stream: &mut Stream, block: &Block, matched: &Transaction) {
+        let mut bloom_filter = BloomFilter::new(1, 0.0001, 0, BloomUpdate::None);
+        bloom_filter.insert(&matched.get_tx_id().unwrap());
+
+        let (merkle_block, transactions) =
+            MerkleBlockMessage::from_block(block, &mut bloom_filter).unwrap();
+        MerkleBlockMessage::serialize_message(stream, [11, 17, 9, 7], &merkle_block).unwrap();
+        for transaction in transactions {
+            TxMessage::serialize_message(stream, [11, 17, 9, 7], &TxMessage { transaction })
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test01_filtered_blocks_have_only_the_matched_transactions() {
+        let (sender, _) = logger::initialize_logger(Vec::new(), false);
+        let merkle_block_download = MerkleBlockDownload::new([11, 17, 9, 7], sender);
+
+        let transactions: Vec<Transaction> = (0..5).map(create_transaction).collect();
+        let block = create_block(transactions.clone());
+        let block_hash = block.header.get_hash256d().unwrap();
+
+        let mut stream = Stream::new();
+        serialize_merkle_block(&mut stream, &block, &transactions[3]);
+
+        let blocks = merkle_block_download
+            .get_filtered_blocks(&mut stream, vec![block_hash])
+            .unwrap();
+
+        assert_eq!(1, blocks.len());
+        assert_eq!(block.header, blocks[0].header);
+        assert_eq!(vec![transactions[3].clone()], blocks[0].transactions);
+    }
+
+    #[test]
+    fn test02_merkle_block_not_requested_is_rejected() {
+        let (sender, _) = logger::initialize_logger(Vec::new(), false);
+        let merkle_block_download = MerkleBlockDownload::new([11, 17, 9, 7], sender);
+
+        let transactions: Vec<Transaction> = (0..3).map(create_transaction).collect();
+        let block = create_block(transactions.clone());
+
+        let mut stream = Stream::new();
+        serialize_merkle_block(&mut stream, &block, &transactions[0]);
+
+        assert!(matches!(
+            merkle_block_download.get_filtered_blocks(&mut stream, vec![[1; 32]]),
+            Err(ErrorNode::WhileValidating(_))
+        ));
+    }
+}
@@ -1,6 +1,9 @@
 use super::connection_id::ConnectionId;
 
-use crate::block_structure::{block::Block, transaction::Transaction};
+use crate::{
+    block_structure::{block::Block, transaction::Transaction},
+    messages::reject_message::RejectMessage,
+};
 
 use std::cmp::PartialEq;
 
@@ -8,5 +11,6 @@ use std::cmp::PartialEq;
 #[derive(Debug, Clone, PartialEq)]
 pub enum MessageBroadcast {
     Transaction(Transaction, Option<ConnectionId>),
-    Block(Block, ConnectionId),
+    Block(Block, Option<ConnectionId>),
+    Reject(RejectMessage),
 }
@@ -1,6 +1,11 @@
-use super::connection_id::ConnectionId;
+use super::{connection_id::ConnectionId, misbehavior::Misbehavior};
 
-use crate::block_structure::{block::Block, transaction::Transaction};
+use crate::{
+    block_structure::{block::Block, transaction::Transaction},
+    connections::{
+        network_ip_address::NetworkIpAddress, network_ip_address_v2::NetworkIpAddressV2,
+    },
+};
 
 use std::cmp::PartialEq;
 
@@ -8,5 +13,9 @@ use std::cmp::PartialEq;
 #[derive(Debug, Clone, PartialEq)]
 pub enum MessageResponse {
     Block(Block, ConnectionId),
+    FilteredBlock(Block, ConnectionId),
     Transaction(Transaction, ConnectionId),
+    Addresses(Vec<NetworkIpAddress>, ConnectionId),
+    AddressesV2(Vec<NetworkIpAddressV2>, ConnectionId),
+    Misbehavior(Misbehavior, ConnectionId),
 }
@@ -3,6 +3,7 @@ use super::{connection_id::ConnectionId, message_broadcast::MessageBroadcast};
 use crate::{
     block_structure::{block::Block, transaction::Transaction},
     concurrency::work::Work,
+    messages::reject_message::RejectMessage,
 };
 
 use std::convert::From;
@@ -11,7 +12,8 @@ use std::convert::From;
 #[derive(Debug)]
 pub enum MessageToPeer {
     SendTransaction(Transaction, Option<ConnectionId>),
-    SendBlock(Block, ConnectionId),
+    SendBlock(Block, Option<ConnectionId>),
+    SendReject(RejectMessage),
     Stop,
 }
 
@@ -24,6 +26,9 @@ impl From<MessageToPeer> for Work<MessageBroadcast> {
             MessageToPeer::SendBlock(block, id) => {
                 Work::Information(MessageBroadcast::Block(block, id))
             }
+            MessageToPeer::SendReject(reject) => {
+                Work::Information(MessageBroadcast::Reject(reject))
+            }
             MessageToPeer::Stop => Work::Stop,
         }
     }
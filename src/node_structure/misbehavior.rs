@@ -0,0 +1,45 @@
+use std::fmt::{Display, Formatter, Result};
+
+const INVALID_HEADERS_SCORE: u32 = 100;
+const INVALID_BLOCK_SCORE: u32 = 100;
+const MALFORMED_MESSAGE_SCORE: u32 = 20;
+const INVALID_FILTER_SCORE: u32 = 100;
+
+/// Represents the ways a peer can misbehave, each one with its own score
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Misbehavior {
+    /// The peer sent headers that do not pass the proof of work
+    InvalidHeaders,
+
+    /// The peer sent a block that does not pass the proof of inclusion
+    InvalidBlock,
+
+    /// The peer sent a message that could not be deserialized
+    MalformedMessage,
+
+    /// The peer sent a bloom filter that is too big or that we don't serve
+    InvalidFilter,
+}
+
+impl Misbehavior {
+    /// Returns the score added to the peer for this misbehavior
+    pub fn score(&self) -> u32 {
+        match self {
+            Misbehavior::InvalidHeaders => INVALID_HEADERS_SCORE,
+            Misbehavior::InvalidBlock => INVALID_BLOCK_SCORE,
+            Misbehavior::MalformedMessage => MALFORMED_MESSAGE_SCORE,
+            Misbehavior::InvalidFilter => INVALID_FILTER_SCORE,
+        }
+    }
+}
+
+impl Display for Misbehavior {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Misbehavior::InvalidHeaders => write!(f, "Invalid headers"),
+            Misbehavior::InvalidBlock => write!(f, "Invalid block"),
+            Misbehavior::MalformedMessage => write!(f, "Malformed message"),
+            Misbehavior::InvalidFilter => write!(f, "Invalid filter"),
+        }
+    }
+}
@@ -1,17 +1,30 @@
 pub mod block_download;
+pub mod download_scheduler;
 pub mod handshake;
 pub mod handshake_data;
 pub mod initial_headers_download;
+pub mod merkle_block_download;
 pub mod process_connection;
 
 pub mod broadcasting;
+pub mod inventory_relay;
 pub mod message_broadcast;
 pub mod message_response;
 pub mod message_to_peer;
 pub mod peer_manager;
+pub mod ping_tracker;
 
 pub mod connection_event;
 pub mod connection_id;
 pub mod connection_type;
 
+pub mod address_manager;
+pub mod peer_address;
+
+pub mod bloom_filter;
+pub mod bloom_update;
+
+pub mod ban_list;
+pub mod misbehavior;
+
 pub mod error_node;
@@ -0,0 +1,135 @@
+use crate::{
+    connections::socket_conversion::{ipv6_port_to_socket, socket_to_ipv6_port},
+    serialization::{
+        deserializable_big_endian::DeserializableBigEndian,
+        deserializable_internal_order::DeserializableInternalOrder,
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization, serializable_big_endian::SerializableBigEndian,
+        serializable_internal_order::SerializableInternalOrder,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::{
+    cmp::PartialEq,
+    io::{Read, Write},
+    net::{Ipv6Addr, SocketAddr},
+};
+
+const BASE_BACKOFF_SECONDS: u32 = 30;
+const MAX_BACKOFF_SECONDS: u32 = 60 * 60;
+
+/// It's the information known about the address of a potential peer
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerAddress {
+    pub address: SocketAddr,
+
+    /// The last time, in seconds since the epoch, the peer was seen by us or announced by others
+    pub last_seen: u32,
+
+    /// The amount of attempts to connect to the peer since the last successful connection
+    pub failures: u32,
+
+    /// The last time, in seconds since the epoch, we tried to connect to the peer
+    pub last_attempt: u32,
+}
+
+impl PeerAddress {
+    pub fn new(address: SocketAddr, last_seen: u32) -> Self {
+        PeerAddress {
+            address,
+            last_seen,
+            failures: 0,
+            last_attempt: 0,
+        }
+    }
+
+    /// Returns true if enough time has passed since the last attempt to try to connect again.
+    /// The time to wait doubles with each failure, up to a maximum
+    pub fn can_attempt(&self, now: u32) -> bool {
+        if self.failures == 0 {
+            return true;
+        }
+
+        let backoff = BASE_BACKOFF_SECONDS
+            .checked_shl(self.failures - 1)
+            .unwrap_or(MAX_BACKOFF_SECONDS)
+            .min(MAX_BACKOFF_SECONDS);
+
+        now >= self.last_attempt.saturating_add(backoff)
+    }
+}
+
+impl SerializableInternalOrder for PeerAddress {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        let (ip_address, port) = socket_to_ipv6_port(&self.address);
+        ip_address.be_serialize(stream)?;
+        port.be_serialize(stream)?;
+        self.last_seen.le_serialize(stream)?;
+        self.failures.le_serialize(stream)?;
+        self.last_attempt.le_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for PeerAddress {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let ip_address = Ipv6Addr::be_deserialize(stream)?;
+        let port = u16::be_deserialize(stream)?;
+
+        Ok(PeerAddress {
+            address: ipv6_port_to_socket(&ip_address, port),
+            last_seen: u32::le_deserialize(stream)?,
+            failures: u32::le_deserialize(stream)?,
+            last_attempt: u32::le_deserialize(stream)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::{IpAddr, Ipv4Addr};
+
+    #[test]
+    fn test_01_correct_peer_address_serialization() {
+        let mut peer_address = PeerAddress::new(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 18333),
+            1234,
+        );
+        peer_address.failures = 2;
+        peer_address.last_attempt = 1300;
+
+        let mut stream: Vec<u8> = Vec::new();
+        peer_address.io_serialize(&mut stream).unwrap();
+
+        let deserialized_peer_address =
+            PeerAddress::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert_eq!(peer_address, deserialized_peer_address);
+    }
+
+    #[test]
+    fn test_02_backoff_doubles_with_each_failure() {
+        let mut peer_address = PeerAddress::new(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 18333),
+            1234,
+        );
+        assert!(peer_address.can_attempt(0));
+
+        peer_address.last_attempt = 1000;
+        peer_address.failures = 1;
+        assert!(!peer_address.can_attempt(1000 + BASE_BACKOFF_SECONDS - 1));
+        assert!(peer_address.can_attempt(1000 + BASE_BACKOFF_SECONDS));
+
+        peer_address.failures = 3;
+        assert!(!peer_address.can_attempt(1000 + BASE_BACKOFF_SECONDS * 4 - 1));
+        assert!(peer_address.can_attempt(1000 + BASE_BACKOFF_SECONDS * 4));
+
+        peer_address.failures = 40;
+        assert!(!peer_address.can_attempt(1000 + MAX_BACKOFF_SECONDS - 1));
+        assert!(peer_address.can_attempt(1000 + MAX_BACKOFF_SECONDS));
+    }
+}
@@ -1,47 +1,84 @@
 use super::{
-    connection_id::ConnectionId, error_node::ErrorNode, message_broadcast::MessageBroadcast,
-    message_response::MessageResponse, message_to_peer::MessageToPeer,
+    bloom_filter::BloomFilter, connection_id::ConnectionId, connection_type::ConnectionType,
+    error_node::ErrorNode, inventory_relay::InventoryRelay, message_broadcast::MessageBroadcast,
+    message_response::MessageResponse, message_to_peer::MessageToPeer, misbehavior::Misbehavior,
+    ping_tracker::PingTracker,
 };
 
 use crate::{
     block_structure::{
-        block::Block, block_chain::BlockChain, hash::HashType, transaction::Transaction,
+        block::Block,
+        block_chain::{BlockChain, MAX_BLOCKS_SIZE},
+        block_filter::BASIC_FILTER_TYPE,
+        filter_chain::FilterChain,
+        hash::HashType,
+        partial_block::PartialBlock,
+        transaction::Transaction,
+        utxo_set::UTXOSet,
     },
     concurrency::work::Work,
+    configurations::connection_config::DEFAULT_PING_TIMEOUT,
     connections::type_identifier::TypeIdentifier,
     logs::logger_sender::LoggerSender,
     messages::{
         addr_message::AddrMessage,
+        addr_v2_message::AddrV2Message,
         alert_message::AlertMessage,
         block_message::BlockMessage,
+        block_txn_message::BlockTxnMessage,
+        cfcheckpt_message::CFCheckptMessage,
+        cfheaders_message::CFHeadersMessage,
+        cfilter_message::CFilterMessage,
+        cmpct_block_message::CmpctBlockMessage,
         command_name::CommandName,
         fee_filter_message::FeeFilterMessage,
+        filter_add_message::FilterAddMessage,
+        filter_clear_message::FilterClearMessage,
+        filter_load_message::FilterLoadMessage,
+        get_addr_message::GetAddrMessage,
+        get_block_txn_message::GetBlockTxnMessage,
+        get_blocks_message::GetBlocksMessage,
+        get_cfcheckpt_message::GetCFCheckptMessage,
+        get_cfheaders_message::GetCFHeadersMessage,
+        get_cfilters_message::GetCFiltersMessage,
         get_data_message::GetDataMessage,
         get_headers_message::GetHeadersMessage,
         headers_message::HeadersMessage,
         inventory_message::InventoryMessage,
         inventory_vector::InventoryVector,
-        message::{ignore_message, Message},
+        merkle_block_message::MerkleBlockMessage,
+        message::{ignore_message, skip_payload, Message},
         message_header::MessageHeader,
+        not_found_message::NotFoundMessage,
         ping_message::PingMessage,
         pong_message::PongMessage,
-        send_cmpct_message::SendCmpctMessage,
+        reject_code::RejectCode,
+        reject_message::RejectMessage,
+        send_addr_v2_message::SendAddrV2Message,
+        send_cmpct_message::{SendCmpctMessage, COMPACT_BLOCKS_VERSION},
         send_headers_message::SendHeadersMessage,
         tx_message::TxMessage,
         verack_message::VerackMessage,
         version_message::VersionMessage,
+        wtxid_relay_message::WtxidRelayMessage,
     },
     notifications::{notification::Notification, notifier::Notifier},
 };
 
 use std::{
+    collections::{HashMap, VecDeque},
     io::{Read, Write},
     sync::{
         mpsc::{Receiver, Sender},
-        Arc, Mutex,
+        Arc, Mutex, MutexGuard,
     },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+const OUTBOUND_TRICKLE_DELAY: Duration = Duration::from_secs(2);
+const MAX_PARTIAL_BLOCKS: usize = 3;
+const INBOUND_TRICKLE_DELAY: Duration = Duration::from_secs(5);
+
 /// It represents how to manage the the peer, listening to the there messages and sending them transactions
 pub struct PeerManager<RW, N>
 where
@@ -53,6 +90,19 @@ where
     sender: Sender<MessageResponse>,
     blockchain: Arc<Mutex<BlockChain>>,
     magic_numbers: [u8; 4],
+    ping_tracker: PingTracker,
+    prefers_headers: bool,
+    prefers_compact_blocks: bool,
+    mempool: Option<Arc<Mutex<UTXOSet>>>,
+    partial_blocks: VecDeque<(HashType, PartialBlock)>,
+    inventory_relay: InventoryRelay,
+    own_transactions: HashMap<HashType, Transaction>,
+    bloom_filters_enabled: bool,
+    bloom_filter: Option<BloomFilter>,
+    filter_chain: Option<Arc<Mutex<FilterChain>>>,
+    filtered_blocks: bool,
+    pending_filtered_block: Option<(Block, Vec<HashType>)>,
+    continue_hash: Option<HashType>,
     notifier: N,
     logger: LoggerSender,
 }
@@ -71,43 +121,147 @@ where
         notifier: N,
         logger: LoggerSender,
     ) -> Self {
+        let trickle_delay = match id.connection_type {
+            ConnectionType::Peer => OUTBOUND_TRICKLE_DELAY,
+            ConnectionType::Client => INBOUND_TRICKLE_DELAY,
+        };
+
         PeerManager {
             id,
             peer,
             sender,
             blockchain,
             magic_numbers,
+            ping_tracker: PingTracker::new(
+                Duration::from_secs(DEFAULT_PING_TIMEOUT),
+                Instant::now(),
+            ),
+            prefers_headers: false,
+            prefers_compact_blocks: false,
+            mempool: None,
+            partial_blocks: VecDeque::new(),
+            inventory_relay: InventoryRelay::new(trickle_delay, Instant::now()),
+            own_transactions: HashMap::new(),
+            bloom_filters_enabled: false,
+            bloom_filter: None,
+            filter_chain: None,
+            filtered_blocks: false,
+            pending_filtered_block: None,
+            continue_hash: None,
             notifier,
             logger,
         }
     }
 
+    /// Changes the time, in seconds, the peer has to answer a ping before being disconnected
+    pub fn with_ping_timeout(mut self, ping_timeout: u64) -> Self {
+        self.ping_tracker = PingTracker::new(Duration::from_secs(ping_timeout), Instant::now());
+        self
+    }
+
+    /// Changes the average delay between the announcements of transactions to the peer
+    pub fn with_trickle_delay(mut self, trickle_delay: Duration) -> Self {
+        self.inventory_relay = InventoryRelay::new(trickle_delay, Instant::now());
+        self
+    }
+
+    /// Gives the pending transactions used to rebuild the compact blocks received from the peer,
+    /// to avoid requesting the transactions already seen and to answer the requests of transactions
+    pub fn with_mempool(mut self, mempool: Arc<Mutex<UTXOSet>>) -> Self {
+        self.mempool = Some(mempool);
+        self
+    }
+
+    /// Changes if the peer can load a bloom filter (BIP37), so only the transactions and blocks
+    /// matching it are relayed. A peer loading a filter when it's not enabled is punished
+    pub fn with_bloom_filters(mut self, bloom_filters_enabled: bool) -> Self {
+        self.bloom_filters_enabled = bloom_filters_enabled;
+        self
+    }
+
+    /// Gives the compact block filters (BIP157) used to answer the requests of filters, filter headers
+    /// and checkpoints of the peer. Without them those requests are ignored
+    pub fn with_filter_chain(mut self, filter_chain: Arc<Mutex<FilterChain>>) -> Self {
+        self.filter_chain = Some(filter_chain);
+        self
+    }
+
+    /// Changes if the blocks are requested as merkle blocks (BIP37), so the peer only sends the transactions
+    /// matching the bloom filter loaded in it. The blocks are sent back with only those transactions
+    pub fn with_filtered_blocks(mut self, filtered_blocks: bool) -> Self {
+        self.filtered_blocks = filtered_blocks;
+        self
+    }
+
+    /// Returns the identification of the connection with the peer
+    pub fn get_id(&self) -> ConnectionId {
+        self.id
+    }
+
     /// Listens and send messages to the peer
     ///
     /// ### Error
     ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
     ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
-    ///  * `ErrorNode::NodeNotResponding`: It will appear when the node is not responding to the messages
+    ///  * `ErrorNode::NodeNotResponding`: It will appear when the node is not responding to the messages or pings
     pub fn connecting_to_peer(
         mut self,
         receiver: Receiver<MessageToPeer>,
     ) -> Result<(RW, ConnectionId), ErrorNode> {
         loop {
-            match Work::listen(&mut self.peer, &receiver) {
-                Work::Message(header) => self.manage_message(header)?,
+            self.announce_transactions()?;
+            self.check_liveness()?;
+
+            let deadline = match self.inventory_relay.next_deadline() {
+                Some(trickle) => trickle.min(self.ping_tracker.next_deadline()),
+                None => self.ping_tracker.next_deadline(),
+            };
+
+            let work = match Work::listen_until(&mut self.peer, &receiver, deadline) {
+                Some(work) => work,
+                None => continue,
+            };
+
+            match work {
+                Work::Message(header) => {
+                    let command_name = header.command_name;
+                    match self.manage_message(header) {
+                        Ok(()) => {}
+                        Err(ErrorNode::WhileDeserializing(error)) => {
+                            self.send_reject(RejectMessage::new(
+                                command_name,
+                                RejectCode::Malformed,
+                                "error parsing message",
+                                None,
+                            ));
+                            let _ = self.sender.send(MessageResponse::Misbehavior(
+                                Misbehavior::MalformedMessage,
+                                self.id,
+                            ));
+                            return Err(ErrorNode::WhileDeserializing(error));
+                        }
+                        Err(error) => return Err(error),
+                    }
+                }
                 Work::Information(MessageBroadcast::Transaction(transaction, None)) => {
-                    self.send_transaction(transaction)?
+                    if let Ok(transaction_id) = transaction.get_tx_id() {
+                        self.own_transactions
+                            .insert(transaction_id, transaction.clone());
+                    }
+                    self.queue_transaction(transaction)?
                 }
                 Work::Information(MessageBroadcast::Transaction(transaction, Some(from))) => {
                     if from != self.id {
-                        self.send_transaction(transaction)?
+                        self.queue_transaction(transaction)?
                     }
                 }
                 Work::Information(MessageBroadcast::Block(block, from)) => {
-                    if from != self.id {
-                        self.send_block(block)?
+                    self.forget_confirmed_transactions(&block);
+                    if from != Some(self.id) {
+                        self.announce_block(block)?
                     }
                 }
+                Work::Information(MessageBroadcast::Reject(reject)) => self.send_reject(reject),
                 Work::Stop => {
                     let _ = self
                         .logger
@@ -147,24 +301,122 @@ where
 
                 PongMessage::serialize_message(&mut self.peer, magic_numbers, &pong)?;
             }
-            CommandName::Pong => ignore_message::<RW, PongMessage>(&mut self.peer, header)?,
+            CommandName::Pong => self.receive_pong(header)?,
             CommandName::GetHeaders => self.replay_to_get_headers_message(header)?,
+            CommandName::GetBlocks => self.reply_to_get_blocks_message(header)?,
             CommandName::Headers => self.receive_headers(header)?,
             CommandName::GetData => self.reply_to_get_data_message(header)?,
             CommandName::Block => self.receive_blocks(header)?,
             CommandName::Inventory => self.receive_inventory_message(header)?,
-            CommandName::SendHeaders => {
-                ignore_message::<RW, SendHeadersMessage>(&mut self.peer, header)?
-            }
-            CommandName::SendCmpct => {
-                ignore_message::<RW, SendCmpctMessage>(&mut self.peer, header)?
-            }
-            CommandName::Addr => ignore_message::<RW, AddrMessage>(&mut self.peer, header)?,
+            CommandName::NotFound => self.receive_not_found(header)?,
+            CommandName::SendHeaders => self.receive_send_headers(header)?,
+            CommandName::SendCmpct => self.receive_send_cmpct(header)?,
+            CommandName::GetAddr => ignore_message::<RW, GetAddrMessage>(&mut self.peer, header)?,
+            CommandName::Addr => self.receive_addresses(header)?,
             CommandName::FeeFilter => {
                 ignore_message::<RW, FeeFilterMessage>(&mut self.peer, header)?
             }
             CommandName::Alert => ignore_message::<RW, AlertMessage>(&mut self.peer, header)?,
             CommandName::Tx => self.receive_transaction(header)?,
+            CommandName::CmpctBlock => self.receive_compact_block(header)?,
+            CommandName::GetBlockTxn => self.reply_to_get_block_txn_message(header)?,
+            CommandName::BlockTxn => self.receive_block_transactions(header)?,
+            CommandName::Reject => self.receive_reject(header)?,
+            CommandName::WtxidRelay => {
+                self.log_late_negotiation(CommandName::WtxidRelay);
+                ignore_message::<RW, WtxidRelayMessage>(&mut self.peer, header)?
+            }
+            CommandName::SendAddrV2 => {
+                self.log_late_negotiation(CommandName::SendAddrV2);
+                ignore_message::<RW, SendAddrV2Message>(&mut self.peer, header)?
+            }
+            CommandName::AddrV2 => self.receive_addresses_v2(header)?,
+            CommandName::FilterLoad => self.receive_filter_load(header)?,
+            CommandName::FilterAdd => self.receive_filter_add(header)?,
+            CommandName::FilterClear => self.receive_filter_clear(header)?,
+            CommandName::MerkleBlock if self.filtered_blocks => {
+                self.receive_merkle_block(header)?
+            }
+            CommandName::MerkleBlock => {
+                ignore_message::<RW, MerkleBlockMessage>(&mut self.peer, header)?
+            }
+            CommandName::GetCFilters => self.reply_to_get_cfilters_message(header)?,
+            CommandName::CFilter => ignore_message::<RW, CFilterMessage>(&mut self.peer, header)?,
+            CommandName::GetCFHeaders => self.reply_to_get_cfheaders_message(header)?,
+            CommandName::CFHeaders => {
+                ignore_message::<RW, CFHeadersMessage>(&mut self.peer, header)?
+            }
+            CommandName::GetCFCheckpt => self.reply_to_get_cfcheckpt_message(header)?,
+            CommandName::CFCheckpt => {
+                ignore_message::<RW, CFCheckptMessage>(&mut self.peer, header)?
+            }
+            CommandName::Unknown(_) => {
+                let _ = self.logger.log_connection(format!(
+                    "Skipping unknown message {} from peer {}",
+                    header.command_name, self.id
+                ));
+                skip_payload(&mut self.peer, header)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Logs a negotiation message that the peer should have sent before the verack, which is ignored
+    fn log_late_negotiation(&self, command_name: CommandName) {
+        let _ = self.logger.log_connection(format!(
+            "Peer {} sent {command_name} after the handshake, it will be ignored",
+            self.id
+        ));
+    }
+
+    /// Sends a ping to the peer when it's time, and checks that the last one was answered in time
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::NodeNotResponding`: It will appear when the peer does not answer the ping within the timeout
+    fn check_liveness(&mut self) -> Result<(), ErrorNode> {
+        let now = Instant::now();
+
+        if self.ping_tracker.timed_out(now) {
+            let _ = self
+                .logger
+                .log_connection(format!("Peer {} did not answer the ping", self.id));
+            return Err(ErrorNode::NodeNotResponding(
+                "The peer did not answer the ping".to_string(),
+            ));
+        }
+
+        if self.ping_tracker.should_send_ping(now) {
+            let ping = PingMessage {
+                nonce: self.ping_tracker.ping_sent(now),
+            };
+
+            let _ = self
+                .logger
+                .log_connection(format!("Sending ping to peer {}", self.id));
+
+            PingMessage::serialize_message(&mut self.peer, self.magic_numbers, &ping)?;
+        }
+
+        Ok(())
+    }
+
+    /// Receives the answer of a ping, and notifies the latency of the peer if it's the answer of the last ping sent
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    fn receive_pong(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let pong = PongMessage::deserialize_message(&mut self.peer, header)?;
+
+        if let Some(latency) = self.ping_tracker.pong_received(pong.nonce, Instant::now()) {
+            let _ = self.logger.log_connection(format!(
+                "Latency of peer {}: {} ms",
+                self.id,
+                latency.as_millis()
+            ));
+            self.notifier
+                .notify(Notification::UpdatedPeerLatency(self.id, latency));
         }
 
         Ok(())
@@ -189,7 +441,10 @@ where
             })
             .collect();
 
-        let get_data_message = GetDataMessage::get_blocks(headers);
+        let get_data_message = match self.filtered_blocks {
+            true => GetDataMessage::get_filtered_blocks(headers),
+            false => GetDataMessage::get_blocks(headers),
+        };
 
         let _ = self
             .logger
@@ -232,6 +487,66 @@ where
         Ok(())
     }
 
+    /// Receives the addresses of potential peers, and send them to others threads via the sender
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to others threads
+    fn receive_addresses(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let addr_message = AddrMessage::deserialize_message(&mut self.peer, header)?;
+
+        let _ = self.logger.log_connection(format!(
+            "Receiving {} addresses of potential peers",
+            addr_message.ip_addresses.len()
+        ));
+
+        if self
+            .sender
+            .send(MessageResponse::Addresses(
+                addr_message.ip_addresses,
+                self.id,
+            ))
+            .is_err()
+        {
+            return Err(ErrorNode::WhileSendingMessage(
+                "Sending addresses back".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Receives the addresses version 2 of potential peers, which can be from networks other than IPv4 and IPv6,
+    /// and send them to others threads via the sender
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to others threads
+    fn receive_addresses_v2(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let addr_message = AddrV2Message::deserialize_message(&mut self.peer, header)?;
+
+        let _ = self.logger.log_connection(format!(
+            "Receiving {} addresses version 2 of potential peers",
+            addr_message.ip_addresses.len()
+        ));
+
+        if self
+            .sender
+            .send(MessageResponse::AddressesV2(
+                addr_message.ip_addresses,
+                self.id,
+            ))
+            .is_err()
+        {
+            return Err(ErrorNode::WhileSendingMessage(
+                "Sending addresses back".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Receives the message of a new transaction, and send it to others threads via the sender
     ///
     /// ### Error
@@ -246,6 +561,18 @@ where
             tx_message.transaction
         ));
 
+        if let Ok(transaction_id) = tx_message.transaction.get_tx_id() {
+            self.inventory_relay.add_known(transaction_id);
+
+            if let Some((block, missing_ids)) = self.pending_filtered_block.as_mut() {
+                if let Some(position) = missing_ids.iter().position(|id| *id == transaction_id) {
+                    missing_ids.remove(position);
+                    let _ = block.append_transaction(tx_message.transaction);
+                    return self.send_pending_filtered_block(false);
+                }
+            }
+        }
+
         if self
             .sender
             .send(MessageResponse::Transaction(
@@ -262,6 +589,127 @@ where
         Ok(())
     }
 
+    /// Receives a merkle block, verifying its partial merkle tree. The block is sent to others threads
+    /// via the sender after receiving all the transactions matched in it
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileValidating`: It will appear when the partial merkle tree doesn't match the header
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to others threads
+    fn receive_merkle_block(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let merkle_block = MerkleBlockMessage::deserialize_message(&mut self.peer, header)?;
+        self.send_pending_filtered_block(true)?;
+
+        let matched_ids = match merkle_block
+            .partial_merkle_tree
+            .verify(&merkle_block.header)
+        {
+            Ok(matched_ids) => matched_ids,
+            Err(_) => {
+                return Err(ErrorNode::WhileValidating(format!(
+                    "Failed merkle proof of the merkle block from peer {}",
+                    self.id
+                )))
+            }
+        };
+
+        let _ = self.logger.log_connection(format!(
+            "Receiving a merkle block with {} matched transactions",
+            matched_ids.len()
+        ));
+
+        self.pending_filtered_block = Some((Block::new(merkle_block.header), matched_ids));
+        self.send_pending_filtered_block(false)
+    }
+
+    /// Sends the filtered block being received to others threads via the sender, if all its transactions
+    /// were received or if it's forced because the peer didn't send them
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to others threads
+    fn send_pending_filtered_block(&mut self, force: bool) -> Result<(), ErrorNode> {
+        let (block, missing_ids) = match self.pending_filtered_block.take() {
+            Some(pending_filtered_block) => pending_filtered_block,
+            None => return Ok(()),
+        };
+
+        if !missing_ids.is_empty() && !force {
+            self.pending_filtered_block = Some((block, missing_ids));
+            return Ok(());
+        }
+
+        if self
+            .sender
+            .send(MessageResponse::FilteredBlock(block, self.id))
+            .is_err()
+        {
+            return Err(ErrorNode::WhileSendingMessage(
+                "Sending filtered block back".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Receives the items the peer didn't have from the ones we asked for, they are only logged
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    fn receive_not_found(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let not_found = NotFoundMessage::deserialize_message(&mut self.peer, header)?;
+
+        let _ = self.logger.log_connection(format!(
+            "Peer {} doesn't have {} of the items we asked for",
+            self.id,
+            not_found.inventory_vectors.len()
+        ));
+
+        Ok(())
+    }
+
+    /// Receives the reject of one of our messages. If it's a transaction created by us, it's notified
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    fn receive_reject(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let reject = RejectMessage::deserialize_message(&mut self.peer, header)?;
+
+        let _ = self.logger.log_connection(format!(
+            "Peer {} rejected our {} message with code {}: {}",
+            self.id, reject.message, reject.code, reject.reason
+        ));
+
+        if !reject.is_rejecting(CommandName::Tx) {
+            return Ok(());
+        }
+
+        let transaction = match reject
+            .data
+            .and_then(|transaction_id| self.own_transactions.get(&transaction_id))
+        {
+            Some(transaction) => transaction.clone(),
+            None => return Ok(()),
+        };
+
+        self.notifier.notify(Notification::TransactionRejected(
+            transaction,
+            reject.code,
+            reject.reason,
+        ));
+
+        Ok(())
+    }
+
+    /// Sends the reject message to the peer. It's not an error if it can't be sent, because the peer
+    /// may not be listening anymore
+    fn send_reject(&mut self, reject: RejectMessage) {
+        if RejectMessage::serialize_message(&mut self.peer, self.magic_numbers, &reject).is_err() {
+            let _ = self
+                .logger
+                .log_connection(format!("Could not send reject message to peer {}", self.id));
+        }
+    }
+
     /// Receives the inventory message for requesting to know about a new transaction
     ///
     /// ### Error
@@ -277,7 +725,25 @@ where
         let mut inventory_vectors: Vec<InventoryVector> = Vec::new();
         for inventory_vector in inventory_message.inventory_vectors {
             match inventory_vector.type_identifier.clone() {
-                TypeIdentifier::TransactionId | TypeIdentifier::Block => {
+                TypeIdentifier::TransactionId => {
+                    let transaction_id = inventory_vector.hash_value;
+                    if self.inventory_relay.is_known(&transaction_id)
+                        || self.get_pending_transaction(&transaction_id)?.is_some()
+                    {
+                        self.inventory_relay.add_known(transaction_id);
+                        continue;
+                    }
+
+                    self.inventory_relay.add_known(transaction_id);
+                    inventory_vectors.push(inventory_vector);
+                }
+                TypeIdentifier::Block if self.filtered_blocks => {
+                    inventory_vectors.push(InventoryVector::new(
+                        TypeIdentifier::FilteredBlock,
+                        inventory_vector.hash_value,
+                    ));
+                }
+                TypeIdentifier::Block => {
                     inventory_vectors.push(inventory_vector);
                 }
                 _ => {}
@@ -355,282 +821,1789 @@ where
         })
     }
 
-    /// Creates a response to a get data message
-    fn reply_to_get_data_message(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+    /// Replies to a get blocks message with an inventory of the blocks after the most recent hash of the locator.
+    /// When the limit of blocks is reached, the last one is remembered to tell the peer to continue asking
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileCreatingMessage`: It will appear when there is an error while creating the message
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    fn reply_to_get_blocks_message(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
         let magic_numbers = header.magic_numbers;
-        let get_data_message = GetDataMessage::deserialize_message(&mut self.peer, header)?;
-
-        for inventory_vector in get_data_message.inventory_vectors.iter() {
-            if let TypeIdentifier::Block = inventory_vector.type_identifier {
-                let blockchain = match self.blockchain.lock() {
-                    Ok(blockchain) => blockchain,
+        let get_blocks = GetBlocksMessage::deserialize_message(&mut self.peer, header)?;
+
+        let block_hashes = {
+            let mut blockchain = match self.blockchain.lock() {
+                Ok(blockchain) => blockchain,
+                Err(_) => {
+                    return Err(ErrorNode::WhileCreatingMessage(
+                        "While locking the blockchain to create the inventory message".to_string(),
+                    ))
+                }
+            };
+            let most_recent_hash =
+                match blockchain.get_most_recent_hash(get_blocks.block_locator_hashes) {
+                    Ok(most_recent_hash) => most_recent_hash,
                     Err(_) => {
                         return Err(ErrorNode::WhileCreatingMessage(
-                            "While locking the blockchain to create the get data message"
+                            "While getting the most recent hash to create the inventory message"
                                 .to_string(),
                         ))
                     }
                 };
-                if let Some(block) = blockchain.get_block_with_hash(&inventory_vector.hash_value) {
-                    BlockMessage::serialize_message(&mut self.peer, magic_numbers, &block)?;
+            match blockchain
+                .get_block_hashes_from_header_hash(&most_recent_hash, &get_blocks.stop_hash)
+            {
+                Ok(block_hashes) => block_hashes,
+                Err(_) => {
+                    return Err(ErrorNode::WhileCreatingMessage(
+                        "While getting the blocks to create the inventory message".to_string(),
+                    ))
                 }
             }
+        };
+
+        if block_hashes.is_empty() {
+            return Ok(());
+        }
+
+        if block_hashes.len() >= MAX_BLOCKS_SIZE {
+            self.continue_hash = block_hashes.last().copied();
         }
+
+        let inventory_message = InventoryMessage::new(
+            block_hashes
+                .into_iter()
+                .map(|hash| InventoryVector::new(TypeIdentifier::Block, hash))
+                .collect(),
+        );
+        InventoryMessage::serialize_message(&mut self.peer, magic_numbers, &inventory_message)?;
         Ok(())
     }
 
-    /// Sends a transaction to the peer
+    /// Sends an inventory with the tip of the block chain after the peer asked for the last block of a full
+    /// get blocks reply, so it continues asking for the blocks after it
     ///
     /// ### Error
+    ///  * `ErrorNode::WhileCreatingMessage`: It will appear when the blockchain could not be locked
     ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
-    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
-    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to others threads
-    fn send_transaction(&mut self, transaction: Transaction) -> Result<(), ErrorNode> {
-        let tx_message = TxMessage { transaction };
+    fn send_continue_inventory(&mut self, magic_numbers: [u8; 4]) -> Result<(), ErrorNode> {
+        let tip_hash = match self.blockchain.lock() {
+            Ok(blockchain) => blockchain.get_tip_hash(),
+            Err(_) => {
+                return Err(ErrorNode::WhileCreatingMessage(
+                    "While locking the blockchain to get its tip".to_string(),
+                ))
+            }
+        };
 
-        if TxMessage::serialize_message(&mut self.peer, self.magic_numbers, &tx_message).is_err() {
-            return Err(ErrorNode::WhileSendingMessage(
-                "Sending transaction to peers".to_string(),
-            ));
+        self.continue_hash = None;
+        if let Some(tip_hash) = tip_hash {
+            let inventory_message =
+                InventoryMessage::new(vec![InventoryVector::new(TypeIdentifier::Block, tip_hash)]);
+            InventoryMessage::serialize_message(&mut self.peer, magic_numbers, &inventory_message)?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates a response to a get data message. The items we don't have are sent back in a not found message
+    fn reply_to_get_data_message(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let magic_numbers = header.magic_numbers;
+        let get_data_message = GetDataMessage::deserialize_message(&mut self.peer, header)?;
+        let mut not_found: Vec<InventoryVector> = Vec::new();
+
+        for inventory_vector in get_data_message.inventory_vectors.iter() {
+            if let TypeIdentifier::TransactionId = inventory_vector.type_identifier {
+                match self.get_transaction(&inventory_vector.hash_value)? {
+                    Some(transaction) => {
+                        let tx_message = TxMessage { transaction };
+                        TxMessage::serialize_message(&mut self.peer, magic_numbers, &tx_message)?;
+                    }
+                    None => not_found.push(inventory_vector.clone()),
+                }
+                continue;
+            }
+
+            let block = match inventory_vector.type_identifier {
+                TypeIdentifier::Block
+                | TypeIdentifier::CompactBlock
+                | TypeIdentifier::FilteredBlock => {
+                    match self.get_block(&inventory_vector.hash_value)? {
+                        Some(block) if !block.transactions.is_empty() => block,
+                        _ => {
+                            not_found.push(inventory_vector.clone());
+                            continue;
+                        }
+                    }
+                }
+                _ => {
+                    not_found.push(inventory_vector.clone());
+                    continue;
+                }
+            };
+
+            if let TypeIdentifier::FilteredBlock = inventory_vector.type_identifier {
+                if !self.send_merkle_block(&block, magic_numbers)? {
+                    not_found.push(inventory_vector.clone());
+                    continue;
+                }
+            } else if let TypeIdentifier::CompactBlock = inventory_vector.type_identifier {
+                let compact_block = CmpctBlockMessage::from_block(&block, generate_nonce())?;
+                CmpctBlockMessage::serialize_message(
+                    &mut self.peer,
+                    magic_numbers,
+                    &compact_block,
+                )?;
+            } else {
+                BlockMessage::serialize_message(&mut self.peer, magic_numbers, &block)?;
+            }
+
+            if self.continue_hash == Some(inventory_vector.hash_value) {
+                self.send_continue_inventory(magic_numbers)?;
+            }
         }
 
+        if !not_found.is_empty() {
+            let not_found_message = NotFoundMessage::new(not_found);
+            NotFoundMessage::serialize_message(&mut self.peer, magic_numbers, &not_found_message)?;
+        }
         Ok(())
     }
 
-    /// Sends a block to the peer
+    /// Sends the merkle block of the block with the transactions that match the filter of the peer, followed by
+    /// those transactions. Returns false, without sending anything, if the peer didn't load a filter
     ///
     /// ### Error
     ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    fn send_merkle_block(
+        &mut self,
+        block: &Block,
+        magic_numbers: [u8; 4],
+    ) -> Result<bool, ErrorNode> {
+        let bloom_filter = match &mut self.bloom_filter {
+            Some(bloom_filter) => bloom_filter,
+            None => return Ok(false),
+        };
+
+        let (merkle_block, transactions) = MerkleBlockMessage::from_block(block, bloom_filter)?;
+        MerkleBlockMessage::serialize_message(&mut self.peer, magic_numbers, &merkle_block)?;
+
+        for transaction in transactions {
+            let tx_message = TxMessage { transaction };
+            TxMessage::serialize_message(&mut self.peer, magic_numbers, &tx_message)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Receives the bloom filter of the peer, replacing the one it had. The peer is punished if the filter
+    /// is too big or if we don't serve bloom filters
+    ///
+    /// ### Error
     ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
-    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to others threads
-    fn send_block(&mut self, block: Block) -> Result<(), ErrorNode> {
-        let block_message = BlockMessage { block };
+    fn receive_filter_load(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let filter_load = FilterLoadMessage::deserialize_message(&mut self.peer, header)?;
 
-        if BlockMessage::serialize_message(&mut self.peer, self.magic_numbers, &block_message)
-            .is_err()
-        {
-            return Err(ErrorNode::WhileSendingMessage(
-                "Sending transaction to peers".to_string(),
-            ));
+        if !self.bloom_filters_enabled || !filter_load.bloom_filter.is_within_size_constraints() {
+            self.report_invalid_filter("an invalid filter load");
+            return Ok(());
         }
 
+        let _ = self
+            .logger
+            .log_connection(format!("Peer {} loaded a bloom filter", self.id));
+        self.bloom_filter = Some(filter_load.bloom_filter);
+
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
+    /// Receives an element to add to the bloom filter of the peer. The peer is punished if it didn't load a filter
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    fn receive_filter_add(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let filter_add = FilterAddMessage::deserialize_message(&mut self.peer, header)?;
 
-    use super::*;
+        match (&mut self.bloom_filter, self.bloom_filters_enabled) {
+            (Some(bloom_filter), true) => bloom_filter.insert(&filter_add.data),
+            _ => self.report_invalid_filter("a filter add without a filter"),
+        }
 
-    use crate::{
-        block_structure::{
-            block::Block, block_header::BlockHeader, block_version::BlockVersion,
-            compact256::Compact256, outpoint::Outpoint, transaction::Transaction,
-            transaction_input::TransactionInput, transaction_output::TransactionOutput,
-        },
-        connections::type_identifier::TypeIdentifier,
-        logs::logger,
-        messages::{compact_size::CompactSize, inventory_vector::InventoryVector, message},
-        node_structure::connection_type::ConnectionType,
-        notifications::{notification::Notification, notifier::Notifier},
-        serialization::error_serialization::ErrorSerialization,
-    };
+        Ok(())
+    }
 
-    use std::{
-        net::{IpAddr, Ipv4Addr, SocketAddr},
-        sync::mpsc::channel,
-    };
+    /// Receives the removal of the bloom filter of the peer, so every transaction is relayed again
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    fn receive_filter_clear(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        FilterClearMessage::deserialize_message(&mut self.peer, header)?;
 
-    #[derive(Clone)]
-    struct NotificationMock {}
+        if !self.bloom_filters_enabled {
+            self.report_invalid_filter("a filter clear");
+            return Ok(());
+        }
 
-    impl Notifier for NotificationMock {
-        fn notify(&self, _notification: Notification) {}
+        let _ = self
+            .logger
+            .log_connection(format!("Peer {} cleared its bloom filter", self.id));
+        self.bloom_filter = None;
+
+        Ok(())
     }
 
-    struct Stream {
-        write_stream: Vec<u8>,
-        read_stream: Vec<u8>,
-        pointer: usize,
+    /// Reports to the other threads that the peer sent a filter message it shouldn't
+    fn report_invalid_filter(&self, description: &str) {
+        let _ = self
+            .logger
+            .log_connection(format!("Peer {} sent {description}", self.id));
+        let _ = self.sender.send(MessageResponse::Misbehavior(
+            Misbehavior::InvalidFilter,
+            self.id,
+        ));
     }
 
-    impl Stream {
-        pub fn new(read_stream: Vec<u8>) -> Stream {
-            Stream {
-                read_stream,
-                write_stream: Vec::new(),
-                pointer: 0,
-            }
+    /// Replies to the request of compact filters with a filter message for each block in the range
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileCreatingMessage`: It will appear when the filters could not be updated
+    fn reply_to_get_cfilters_message(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let magic_numbers = header.magic_numbers;
+        let get_cfilters = GetCFiltersMessage::deserialize_message(&mut self.peer, header)?;
+
+        if get_cfilters.filter_type != BASIC_FILTER_TYPE {
+            self.log_unserved_filter_request(CommandName::GetCFilters);
+            return Ok(());
         }
 
-        pub fn get_write_stream(&self) -> Stream {
-            Stream {
-                read_stream: self.write_stream.clone(),
-                write_stream: Vec::new(),
-                pointer: 0,
+        let filters = match self.lock_filter_chain()? {
+            Some(filter_chain) => {
+                filter_chain.get_filters(get_cfilters.start_height as u64, &get_cfilters.stop_hash)
             }
+            None => None,
+        };
+
+        let filters = match filters {
+            Some(filters) => filters,
+            None => {
+                self.log_unserved_filter_request(CommandName::GetCFilters);
+                return Ok(());
+            }
+        };
+
+        for filter in filters {
+            let cfilter = CFilterMessage {
+                filter_type: BASIC_FILTER_TYPE,
+                block_hash: filter.get_block_hash(),
+                filter: filter.get_bytes().to_vec(),
+            };
+            CFilterMessage::serialize_message(&mut self.peer, magic_numbers, &cfilter)?;
         }
+
+        Ok(())
     }
 
-    impl Read for Stream {
-        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-            let mut i = 0;
-            while i < buf.len() && self.pointer < self.read_stream.len() {
-                buf[i] = self.read_stream[self.pointer];
-                self.pointer += 1;
-                i += 1;
+    /// Replies to the request of compact filter headers with the hashes of the filters in the range
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileCreatingMessage`: It will appear when the filters could not be updated or hashed
+    fn reply_to_get_cfheaders_message(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let magic_numbers = header.magic_numbers;
+        let get_cfheaders = GetCFHeadersMessage::deserialize_message(&mut self.peer, header)?;
+
+        if get_cfheaders.filter_type != BASIC_FILTER_TYPE {
+            self.log_unserved_filter_request(CommandName::GetCFHeaders);
+            return Ok(());
+        }
+
+        let filter_hashes = match self.lock_filter_chain()? {
+            Some(filter_chain) => filter_chain
+                .get_filter_hashes(get_cfheaders.start_height as u64, &get_cfheaders.stop_hash),
+            None => Ok(None),
+        };
+
+        let (previous_filter_header, filter_hashes) = match filter_hashes {
+            Ok(Some(filter_hashes)) => filter_hashes,
+            Ok(None) => {
+                self.log_unserved_filter_request(CommandName::GetCFHeaders);
+                return Ok(());
             }
-            if i == 0 {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::WouldBlock,
-                    "Error reading the stream",
-                ));
+            Err(_) => {
+                return Err(ErrorNode::WhileCreatingMessage(
+                    "While hashing the filters to create the filter headers message".to_string(),
+                ))
             }
-            Ok(i)
-        }
+        };
+
+        let cfheaders = CFHeadersMessage {
+            filter_type: BASIC_FILTER_TYPE,
+            stop_hash: get_cfheaders.stop_hash,
+            previous_filter_header,
+            filter_hashes,
+        };
+        CFHeadersMessage::serialize_message(&mut self.peer, magic_numbers, &cfheaders)?;
+
+        Ok(())
     }
 
-    impl Write for Stream {
-        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-            let mut i = 0;
-            while i < buf.len() {
-                self.write_stream.push(buf[i]);
-                i += 1;
-            }
-            Ok(i)
-        }
+    /// Replies to the request of compact filter checkpoints with the filter headers at every interval
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileCreatingMessage`: It will appear when the filters could not be updated
+    fn reply_to_get_cfcheckpt_message(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let magic_numbers = header.magic_numbers;
+        let get_cfcheckpt = GetCFCheckptMessage::deserialize_message(&mut self.peer, header)?;
 
-        fn flush(&mut self) -> std::io::Result<()> {
-            Ok(())
+        if get_cfcheckpt.filter_type != BASIC_FILTER_TYPE {
+            self.log_unserved_filter_request(CommandName::GetCFCheckpt);
+            return Ok(());
         }
-    }
 
-    fn serialize_headers_message<W: Write>(
-        stream: &mut W,
-        magic_numbers: [u8; 4],
-        headers: Vec<BlockHeader>,
-    ) -> Result<(), ErrorSerialization> {
-        let headers_message = HeadersMessage { headers };
-        HeadersMessage::serialize_message(stream, magic_numbers, &headers_message)
-    }
+        let filter_headers = match self.lock_filter_chain()? {
+            Some(filter_chain) => filter_chain.get_checkpoints(&get_cfcheckpt.stop_hash),
+            None => None,
+        };
 
-    fn serialize_block_message<W: Write>(
-        stream: &mut W,
-        magic_numbers: [u8; 4],
-        block: Block,
-    ) -> Result<(), ErrorSerialization> {
-        let block_message = BlockMessage { block };
-        BlockMessage::serialize_message(stream, magic_numbers, &block_message)
-    }
+        let filter_headers = match filter_headers {
+            Some(filter_headers) => filter_headers,
+            None => {
+                self.log_unserved_filter_request(CommandName::GetCFCheckpt);
+                return Ok(());
+            }
+        };
 
-    fn serialize_tx_message<W: Write>(
-        stream: &mut W,
-        magic_numbers: [u8; 4],
-        transaction: Transaction,
-    ) -> Result<(), ErrorSerialization> {
-        let tx_message = TxMessage { transaction };
-        TxMessage::serialize_message(stream, magic_numbers, &tx_message)
-    }
+        let cfcheckpt = CFCheckptMessage {
+            filter_type: BASIC_FILTER_TYPE,
+            stop_hash: get_cfcheckpt.stop_hash,
+            filter_headers,
+        };
+        CFCheckptMessage::serialize_message(&mut self.peer, magic_numbers, &cfcheckpt)?;
 
-    fn serialize_inv_message<W: Write>(
-        stream: &mut W,
-        magic_numbers: [u8; 4],
-        inventory_vectors: Vec<InventoryVector>,
-    ) -> Result<(), ErrorSerialization> {
-        let inventory_message = InventoryMessage::new(inventory_vectors);
-        InventoryMessage::serialize_message(stream, magic_numbers, &inventory_message)
+        Ok(())
     }
 
-    fn serialize_ping_message<W: Write>(
-        stream: &mut W,
-        magic_numbers: [u8; 4],
-    ) -> Result<(), ErrorSerialization> {
-        let ping_message = PingMessage { nonce: 1234 };
-        PingMessage::serialize_message(stream, magic_numbers, &ping_message)
-    }
+    /// Returns the compact block filters updated with the last blocks of the blockchain, or None if we don't serve them
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileCreatingMessage`: It will appear when the blockchain or the filters could not be locked or updated
+    fn lock_filter_chain(&self) -> Result<Option<MutexGuard<'_, FilterChain>>, ErrorNode> {
+        let filter_chain = match &self.filter_chain {
+            Some(filter_chain) => filter_chain,
+            None => return Ok(None),
+        };
 
-    fn create_transaction(time: u32) -> Transaction {
-        let transaction_input =
-            TransactionInput::new(Outpoint::new([1; 32], 23), vec![1, 2, 3], 24);
+        let blockchain = match self.blockchain.lock() {
+            Ok(blockchain) => blockchain,
+            Err(_) => {
+                return Err(ErrorNode::WhileCreatingMessage(
+                    "While locking the blockchain to update the filters".to_string(),
+                ))
+            }
+        };
 
-        let transaction_output = TransactionOutput {
-            value: 10,
-            pk_script: vec![4, 5, 6],
+        let mut filter_chain = match filter_chain.lock() {
+            Ok(filter_chain) => filter_chain,
+            Err(_) => {
+                return Err(ErrorNode::WhileCreatingMessage(
+                    "While locking the filters of the blocks".to_string(),
+                ))
+            }
         };
 
-        Transaction {
-            version: 1,
-            tx_in: vec![transaction_input.clone()],
-            tx_out: vec![transaction_output.clone()],
-            time,
+        if filter_chain.update(&blockchain).is_err() {
+            return Err(ErrorNode::WhileCreatingMessage(
+                "While updating the filters of the blocks".to_string(),
+            ));
         }
+
+        Ok(Some(filter_chain))
     }
 
-    fn create_header(transaction_count: u64) -> BlockHeader {
-        BlockHeader::new(
-            BlockVersion::version(1),
-            [0; 32],
-            [0; 32],
-            0,
-            Compact256::from(u32::MAX),
-            0,
-            CompactSize::new(transaction_count),
-        )
+    /// Logs a request of compact filters that can't be answered, which is ignored
+    fn log_unserved_filter_request(&self, command_name: CommandName) {
+        let _ = self.logger.log_connection(format!(
+            "Peer {} sent {command_name} that can't be served, it will be ignored",
+            self.id
+        ));
     }
 
-    fn create_empty_block(transaction_count: u64) -> Block {
-        Block::new(create_header(transaction_count))
+    /// Returns the block with the given hash if it's in the blockchain and its transactions were not pruned
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileCreatingMessage`: It will appear when the blockchain could not be locked
+    fn get_block(&self, hash: &HashType) -> Result<Option<Block>, ErrorNode> {
+        let blockchain = match self.blockchain.lock() {
+            Ok(blockchain) => blockchain,
+            Err(_) => {
+                return Err(ErrorNode::WhileCreatingMessage(
+                    "While locking the blockchain to get a block".to_string(),
+                ))
+            }
+        };
+
+        if blockchain.is_pruned(hash) {
+            let _ = self.logger.log_connection(format!(
+                "Peer {} requested a pruned block, which is not served",
+                self.id
+            ));
+            return Ok(None);
+        }
+
+        Ok(blockchain.get_block_with_hash(hash))
     }
 
-    fn create_mock_blockchain() -> BlockChain {
-        let transaction_input = TransactionInput::new(
-            Outpoint::new([1; 32], 23),
-            "Prueba in".as_bytes().to_vec(),
-            24,
-        );
+    /// Receives the send compact message. If the peer asks for it, the new blocks are announced to it with a compact block
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    fn receive_send_cmpct(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let send_cmpct = SendCmpctMessage::deserialize_message(&mut self.peer, header)?;
 
-        let transaction_output = TransactionOutput {
-            value: 10,
-            pk_script: "Prueba out".as_bytes().to_vec(),
+        self.prefers_compact_blocks =
+            send_cmpct.announce && send_cmpct.version == COMPACT_BLOCKS_VERSION;
+
+        let _ = self.logger.log_connection(format!(
+            "Peer {} announce compact blocks: {}",
+            self.id, self.prefers_compact_blocks
+        ));
+
+        Ok(())
+    }
+
+    /// Receives a compact block and rebuilds it with the pending transactions, asking the peer for the missing ones
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to others threads
+    fn receive_compact_block(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let compact_block = CmpctBlockMessage::deserialize_message(&mut self.peer, header)?;
+
+        let available_transactions = match &self.mempool {
+            Some(mempool) => match mempool.lock() {
+                Ok(mempool) => mempool.pending_transactions().clone(),
+                Err(_) => return Err(ErrorNode::FailThread),
+            },
+            None => Vec::new(),
         };
 
-        let transaction = Transaction {
-            version: 1,
-            tx_in: vec![transaction_input.clone()],
-            tx_out: vec![transaction_output.clone()],
-            time: 0,
+        let partial_block = match PartialBlock::new(compact_block, &available_transactions) {
+            Ok(partial_block) => partial_block,
+            Err(_) => {
+                return Err(ErrorNode::WhileDeserializing(
+                    "While rebuilding a compact block".to_string(),
+                ))
+            }
         };
 
-        let empty_block = Block::new(BlockHeader::new(
-            BlockVersion::version(1),
-            [0; 32],
-            [0; 32],
-            0,
-            Compact256::from(u32::MAX),
-            0,
-            CompactSize::new(0),
+        let hash = match partial_block.get_hash() {
+            Ok(hash) => hash,
+            Err(_) => {
+                return Err(ErrorNode::WhileDeserializing(
+                    "While hashing the header of a compact block".to_string(),
+                ))
+            }
+        };
+
+        let missing_indexes = partial_block.missing_indexes();
+        if missing_indexes.is_empty() {
+            return self.receive_rebuilt_block(hash, partial_block);
+        }
+
+        let _ = self.logger.log_connection(format!(
+            "Asking for {} missing transactions of a compact block",
+            missing_indexes.len()
         ));
 
-        let mut block_with_transactions = empty_block.clone();
-        block_with_transactions
-            .append_transaction(transaction.clone())
+        let get_block_txn = GetBlockTxnMessage {
+            block_hash: hash,
+            indexes: missing_indexes,
+        };
+        GetBlockTxnMessage::serialize_message(&mut self.peer, self.magic_numbers, &get_block_txn)?;
+
+        if self.partial_blocks.len() >= MAX_PARTIAL_BLOCKS {
+            self.partial_blocks.pop_front();
+        }
+        self.partial_blocks.push_back((hash, partial_block));
+        Ok(())
+    }
+
+    /// Receives the missing transactions of a compact block and completes it
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to others threads
+    fn receive_block_transactions(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let block_txn = BlockTxnMessage::deserialize_message(&mut self.peer, header)?;
+
+        let position = self
+            .partial_blocks
+            .iter()
+            .position(|(hash, _)| *hash == block_txn.block_hash);
+
+        let mut partial_block =
+            match position.and_then(|position| self.partial_blocks.remove(position)) {
+                Some((_, partial_block)) => partial_block,
+                None => {
+                    let _ = self
+                        .logger
+                        .log_connection("Receiving transactions of an unknown block".to_string());
+                    return Ok(());
+                }
+            };
+
+        if partial_block.fill_missing(block_txn.transactions).is_err() {
+            return self.request_full_block(block_txn.block_hash);
+        }
+
+        self.receive_rebuilt_block(block_txn.block_hash, partial_block)
+    }
+
+    /// Sends the rebuilt block to others threads via the sender. If the transactions do not match the
+    /// merkle root, because of a collision of short ids, the full block is requested to the peer
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to others threads
+    fn receive_rebuilt_block(
+        &mut self,
+        hash: HashType,
+        partial_block: PartialBlock,
+    ) -> Result<(), ErrorNode> {
+        let block = match partial_block.into_block() {
+            Some(block) if block.proof_of_inclusion() => block,
+            _ => return self.request_full_block(hash),
+        };
+
+        let _ = self
+            .logger
+            .log_connection(format!("Rebuilt a compact block: {}", block));
+
+        if self
+            .sender
+            .send(MessageResponse::Block(block, self.id))
+            .is_err()
+        {
+            return Err(ErrorNode::WhileSendingMessage(
+                "Sending block back".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Asks the peer for the full block of the given hash
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    fn request_full_block(&mut self, hash: HashType) -> Result<(), ErrorNode> {
+        let _ = self
+            .logger
+            .log_connection("Could not rebuild a compact block, asking the full block".to_string());
+
+        let get_data_message = GetDataMessage::get_blocks(vec![hash]);
+        GetDataMessage::serialize_message(&mut self.peer, self.magic_numbers, &get_data_message)?;
+        Ok(())
+    }
+
+    /// Creates a response to a get block transactions message with the requested transactions of the block
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSerializing`: It will appear when there is an error in the serialization
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    ///  * `ErrorNode::WhileCreatingMessage`: It will appear when the blockchain could not be locked
+    fn reply_to_get_block_txn_message(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        let magic_numbers = header.magic_numbers;
+        let get_block_txn = GetBlockTxnMessage::deserialize_message(&mut self.peer, header)?;
+
+        let block = match self.get_block(&get_block_txn.block_hash)? {
+            Some(block) => block,
+            None => return Ok(()),
+        };
+
+        let mut transactions = Vec::new();
+        for index in get_block_txn.indexes {
+            match block.transactions.get(index as usize) {
+                Some(transaction) => transactions.push(transaction.clone()),
+                None => {
+                    let _ = self.logger.log_connection(format!(
+                        "Peer {} asked for a transaction out of the block",
+                        self.id
+                    ));
+                    return Ok(());
+                }
+            }
+        }
+
+        let block_txn = BlockTxnMessage {
+            block_hash: get_block_txn.block_hash,
+            transactions,
+        };
+        BlockTxnMessage::serialize_message(&mut self.peer, magic_numbers, &block_txn)?;
+        Ok(())
+    }
+
+    /// Queues the transaction to be announced to the peer in the next trickle, unless the peer already knows it
+    /// or it doesn't match the bloom filter of the peer
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when the id of the transaction could not be calculated
+    fn queue_transaction(&mut self, transaction: Transaction) -> Result<(), ErrorNode> {
+        let transaction_id = match transaction.get_tx_id() {
+            Ok(transaction_id) => transaction_id,
+            Err(_) => {
+                return Err(ErrorNode::WhileSendingMessage(
+                    "Getting the id of the transaction to announce".to_string(),
+                ))
+            }
+        };
+
+        if let Some(bloom_filter) = &mut self.bloom_filter {
+            if !bloom_filter.is_relevant_and_update(&transaction) {
+                return Ok(());
+            }
+        }
+
+        self.inventory_relay
+            .queue_transaction(transaction_id, transaction, Instant::now());
+        Ok(())
+    }
+
+    /// Announces to the peer the queued transactions with an inventory message, if it's time for the trickle
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to the peer
+    fn announce_transactions(&mut self) -> Result<(), ErrorNode> {
+        let announcements = self.inventory_relay.take_announcements(Instant::now());
+        if announcements.is_empty() {
+            return Ok(());
+        }
+
+        let _ = self.logger.log_connection(format!(
+            "Announcing {} transactions to peer {}",
+            announcements.len(),
+            self.id
+        ));
+
+        let inventory_vectors = announcements
+            .into_iter()
+            .map(|transaction_id| {
+                InventoryVector::new(TypeIdentifier::TransactionId, transaction_id)
+            })
+            .collect();
+        let inventory_message = InventoryMessage::new(inventory_vectors);
+
+        if InventoryMessage::serialize_message(
+            &mut self.peer,
+            self.magic_numbers,
+            &inventory_message,
+        )
+        .is_err()
+        {
+            return Err(ErrorNode::WhileSendingMessage(
+                "Announcing transactions to peers".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the transaction with the given id if it was announced to the peer or it's pending
+    ///
+    /// ### Error
+    ///  * `ErrorNode::FailThread`: It will appear when the pending transactions could not be locked
+    fn get_transaction(&self, transaction_id: &HashType) -> Result<Option<Transaction>, ErrorNode> {
+        match self
+            .inventory_relay
+            .get_announced_transaction(transaction_id)
+        {
+            Some(transaction) => Ok(Some(transaction.clone())),
+            None => self.get_pending_transaction(transaction_id),
+        }
+    }
+
+    /// Returns the pending transaction with the given id, if there is one
+    ///
+    /// ### Error
+    ///  * `ErrorNode::FailThread`: It will appear when the pending transactions could not be locked
+    fn get_pending_transaction(
+        &self,
+        transaction_id: &HashType,
+    ) -> Result<Option<Transaction>, ErrorNode> {
+        let mempool = match &self.mempool {
+            Some(mempool) => match mempool.lock() {
+                Ok(mempool) => mempool,
+                Err(_) => return Err(ErrorNode::FailThread),
+            },
+            None => return Ok(None),
+        };
+
+        Ok(mempool
+            .pending_transactions()
+            .iter()
+            .find(|transaction| {
+                transaction
+                    .get_tx_id()
+                    .is_ok_and(|id| id == *transaction_id)
+            })
+            .cloned())
+    }
+
+    /// Receives the send headers message, after it the new blocks are announced to the peer with a headers message
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileDeserialization`: It will appear when there is an error in the deserialization
+    fn receive_send_headers(&mut self, header: MessageHeader) -> Result<(), ErrorNode> {
+        SendHeadersMessage::deserialize_message(&mut self.peer, header)?;
+
+        let _ = self
+            .logger
+            .log_connection(format!("Peer {} prefers headers announcements", self.id));
+
+        self.prefers_headers = true;
+        Ok(())
+    }
+
+    /// Forgets the transactions created by us that are confirmed in the block, since they can't be rejected anymore
+    fn forget_confirmed_transactions(&mut self, block: &Block) {
+        for transaction in block.transactions.iter() {
+            if let Ok(transaction_id) = transaction.get_tx_id() {
+                self.own_transactions.remove(&transaction_id);
+            }
+        }
+    }
+
+    /// Announces a new block to the peer with a compact block or a headers message if it asked for it, or with an
+    /// inventory message otherwise. The full block is only sent if the peer request it with a get data message
+    ///
+    /// ### Error
+    ///  * `ErrorNode::WhileSendingMessage`: It will appear when there is an error while sending a message to the peer
+    fn announce_block(&mut self, block: Block) -> Result<(), ErrorNode> {
+        let result = if self.prefers_compact_blocks {
+            match CmpctBlockMessage::from_block(&block, generate_nonce()) {
+                Ok(compact_block) => CmpctBlockMessage::serialize_message(
+                    &mut self.peer,
+                    self.magic_numbers,
+                    &compact_block,
+                ),
+                Err(error) => Err(error),
+            }
+        } else if self.prefers_headers {
+            let headers_message = HeadersMessage {
+                headers: vec![block.header],
+            };
+
+            HeadersMessage::serialize_message(&mut self.peer, self.magic_numbers, &headers_message)
+        } else {
+            let hash = match block.header.get_hash256d() {
+                Ok(hash) => hash,
+                Err(_) => {
+                    return Err(ErrorNode::WhileSendingMessage(
+                        "Getting the hash of the block to announce".to_string(),
+                    ))
+                }
+            };
+
+            let inventory_message =
+                InventoryMessage::new(vec![InventoryVector::new(TypeIdentifier::Block, hash)]);
+
+            InventoryMessage::serialize_message(
+                &mut self.peer,
+                self.magic_numbers,
+                &inventory_message,
+            )
+        };
+
+        if result.is_err() {
+            return Err(ErrorNode::WhileSendingMessage(
+                "Announcing block to peers".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Generates the nonce used to calculate the short ids of a compact block
+fn generate_nonce() -> u64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_nanos() as u64,
+        Err(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::{
+        block_structure::{
+            block::Block, block_header::BlockHeader, block_version::BlockVersion,
+            compact256::Compact256, merkle_tree::MerkleTree, outpoint::Outpoint,
+            transaction::Transaction, transaction_input::TransactionInput,
+            transaction_output::TransactionOutput,
+        },
+        connections::{p2p_protocol::ProtocolVersionP2P, type_identifier::TypeIdentifier},
+        logs::logger,
+        messages::{compact_size::CompactSize, inventory_vector::InventoryVector, message},
+        node_structure::{bloom_update::BloomUpdate, connection_type::ConnectionType},
+        notifications::{notification::Notification, notifier::Notifier},
+        serialization::{
+            error_serialization::ErrorSerialization,
+            serializable_internal_order::SerializableInternalOrder,
+        },
+    };
+
+    use std::{
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        sync::mpsc::{channel, Sender},
+    };
+
+    #[derive(Clone)]
+    struct NotificationMock {}
+
+    impl Notifier for NotificationMock {
+        fn notify(&self, _notification: Notification) {}
+    }
+
+    struct Stream {
+        write_stream: Vec<u8>,
+        read_stream: Vec<u8>,
+        pointer: usize,
+    }
+
+    impl Stream {
+        pub fn new(read_stream: Vec<u8>) -> Stream {
+            Stream {
+                read_stream,
+                write_stream: Vec::new(),
+                pointer: 0,
+            }
+        }
+
+        pub fn get_write_stream(&self) -> Stream {
+            Stream {
+                read_stream: self.write_stream.clone(),
+                write_stream: Vec::new(),
+                pointer: 0,
+            }
+        }
+    }
+
+    impl Read for Stream {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let mut i = 0;
+            while i < buf.len() && self.pointer < self.read_stream.len() {
+                buf[i] = self.read_stream[self.pointer];
+                self.pointer += 1;
+                i += 1;
+            }
+            if i == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::WouldBlock,
+                    "Error reading the stream",
+                ));
+            }
+            Ok(i)
+        }
+    }
+
+    impl Write for Stream {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let mut i = 0;
+            while i < buf.len() {
+                self.write_stream.push(buf[i]);
+                i += 1;
+            }
+            Ok(i)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn serialize_headers_message<W: Write>(
+        stream: &mut W,
+        magic_numbers: [u8; 4],
+        headers: Vec<BlockHeader>,
+    ) -> Result<(), ErrorSerialization> {
+        let headers_message = HeadersMessage { headers };
+        HeadersMessage::serialize_message(stream, magic_numbers, &headers_message)
+    }
+
+    fn serialize_block_message<W: Write>(
+        stream: &mut W,
+        magic_numbers: [u8; 4],
+        block: Block,
+    ) -> Result<(), ErrorSerialization> {
+        let block_message = BlockMessage { block };
+        BlockMessage::serialize_message(stream, magic_numbers, &block_message)
+    }
+
+    fn serialize_tx_message<W: Write>(
+        stream: &mut W,
+        magic_numbers: [u8; 4],
+        transaction: Transaction,
+    ) -> Result<(), ErrorSerialization> {
+        let tx_message = TxMessage { transaction };
+        TxMessage::serialize_message(stream, magic_numbers, &tx_message)
+    }
+
+    fn serialize_inv_message<W: Write>(
+        stream: &mut W,
+        magic_numbers: [u8; 4],
+        inventory_vectors: Vec<InventoryVector>,
+    ) -> Result<(), ErrorSerialization> {
+        let inventory_message = InventoryMessage::new(inventory_vectors);
+        InventoryMessage::serialize_message(stream, magic_numbers, &inventory_message)
+    }
+
+    fn serialize_ping_message<W: Write>(
+        stream: &mut W,
+        magic_numbers: [u8; 4],
+    ) -> Result<(), ErrorSerialization> {
+        let ping_message = PingMessage { nonce: 1234 };
+        PingMessage::serialize_message(stream, magic_numbers, &ping_message)
+    }
+
+    fn create_transaction(time: u32) -> Transaction {
+        let transaction_input =
+            TransactionInput::new(Outpoint::new([1; 32], 23), vec![1, 2, 3], 24);
+
+        let transaction_output = TransactionOutput {
+            value: 10,
+            pk_script: vec![4, 5, 6],
+        };
+
+        Transaction {
+            version: 1,
+            tx_in: vec![transaction_input.clone()],
+            tx_out: vec![transaction_output.clone()],
+            time,
+        }
+    }
+
+    fn create_header(transaction_count: u64) -> BlockHeader {
+        BlockHeader::new(
+            BlockVersion::version(1),
+            [0; 32],
+            [0; 32],
+            0,
+            Compact256::from(u32::MAX),
+            0,
+            CompactSize::new(transaction_count),
+        )
+    }
+
+    fn create_empty_block(transaction_count: u64) -> Block {
+        Block::new(create_header(transaction_count))
+    }
+
+    fn create_mock_blockchain() -> BlockChain {
+        let transaction_input = TransactionInput::new(
+            Outpoint::new([1; 32], 23),
+            "Prueba in".as_bytes().to_vec(),
+            24,
+        );
+
+        let transaction_output = TransactionOutput {
+            value: 10,
+            pk_script: "Prueba out".as_bytes().to_vec(),
+        };
+
+        let transaction = Transaction {
+            version: 1,
+            tx_in: vec![transaction_input.clone()],
+            tx_out: vec![transaction_output.clone()],
+            time: 0,
+        };
+
+        let empty_block = Block::new(BlockHeader::new(
+            BlockVersion::version(1),
+            [0; 32],
+            [0; 32],
+            0,
+            Compact256::from(u32::MAX),
+            0,
+            CompactSize::new(0),
+        ));
+
+        let mut block_with_transactions = empty_block.clone();
+        block_with_transactions
+            .append_transaction(transaction.clone())
+            .unwrap();
+
+        let mut blockchain = BlockChain::new(empty_block).unwrap();
+
+        blockchain.update_block(block_with_transactions).unwrap();
+        blockchain
+    }
+
+    #[test]
+    fn test01_peer_manager_receives_transaction_successfully() {
+        let mut stream: Vec<u8> = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let transaction = create_transaction(0);
+
+        serialize_tx_message(&mut stream, magic_numbers.clone(), transaction.clone()).unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, receiver_message) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Client),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let _ = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+
+        assert_eq!(
+            MessageResponse::Transaction(
+                transaction,
+                ConnectionId::new(id_address, ConnectionType::Client)
+            ),
+            receiver_message.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn test02_peer_manager_receives_block_successfully() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let mut block = create_empty_block(3);
+        block.append_transaction(create_transaction(0)).unwrap();
+        block.append_transaction(create_transaction(1)).unwrap();
+        block.append_transaction(create_transaction(2)).unwrap();
+
+        serialize_block_message(&mut stream, magic_numbers.clone(), block.clone()).unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, receiver_message) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let _ = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+
+        assert_eq!(
+            MessageResponse::Block(block, ConnectionId::new(id_address, ConnectionType::Client)),
+            receiver_message.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn test03_peer_manager_receives_headers_successfully() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let first_header = create_header(4);
+        let first_header_hash = first_header.get_hash256d().unwrap();
+
+        let second_header = create_header(2);
+        let second_header_hash = second_header.get_hash256d().unwrap();
+
+        serialize_headers_message(
+            &mut stream,
+            magic_numbers.clone(),
+            vec![first_header.clone(), second_header.clone()],
+        )
+        .unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, _) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let (stream, _) = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+        let mut stream = stream.get_write_stream();
+
+        let header = message::deserialize_until_found(&mut stream, CommandName::GetData).unwrap();
+
+        assert_eq!(header.command_name, CommandName::GetData);
+
+        let get_data_message = GetDataMessage::deserialize_message(&mut stream, header).unwrap();
+
+        let inventory_vectors = vec![
+            InventoryVector::new(TypeIdentifier::Block, first_header_hash),
+            InventoryVector::new(TypeIdentifier::Block, second_header_hash),
+        ];
+
+        assert_eq!(inventory_vectors, get_data_message.inventory_vectors);
+    }
+
+    #[test]
+    fn test04_peer_manager_receives_inventory_message_successfully() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let block = create_empty_block(4);
+        let block_hash = block.header.get_hash256d().unwrap();
+
+        let transaction = create_transaction(0);
+        let transaction_id = transaction.get_tx_id().unwrap();
+
+        serialize_inv_message(
+            &mut stream,
+            magic_numbers.clone(),
+            vec![
+                InventoryVector::new(TypeIdentifier::Block, block_hash),
+                InventoryVector::new(TypeIdentifier::TransactionId, transaction_id),
+            ],
+        )
+        .unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, _) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+        let (stream, _) = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+        let mut stream = stream.get_write_stream();
+
+        let header = message::deserialize_until_found(&mut stream, CommandName::GetData).unwrap();
+
+        assert_eq!(header.command_name, CommandName::GetData);
+
+        let get_data_message = GetDataMessage::deserialize_message(&mut stream, header).unwrap();
+
+        let inventory_vectors = vec![
+            InventoryVector::new(TypeIdentifier::Block, block_hash),
+            InventoryVector::new(TypeIdentifier::TransactionId, transaction_id),
+        ];
+
+        assert_eq!(inventory_vectors, get_data_message.inventory_vectors);
+    }
+
+    #[test]
+    fn test05_peer_manager_announces_transaction_successfully() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let transaction = create_transaction(0);
+
+        serialize_ping_message(&mut stream, magic_numbers.clone()).unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, _) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        )
+        .with_trickle_delay(Duration::ZERO);
+
+        sender_transaction
+            .send(MessageToPeer::SendTransaction(transaction.clone(), None))
+            .unwrap();
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let (stream, _) = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+        let mut stream = stream.get_write_stream();
+
+        let header = message::deserialize_until_found(&mut stream, CommandName::Pong).unwrap();
+
+        assert_eq!(header.command_name, CommandName::Pong);
+
+        let _ = PongMessage::deserialize_message(&mut stream, header).unwrap();
+
+        let header = message::deserialize_until_found(&mut stream, CommandName::Inventory).unwrap();
+
+        assert_eq!(header.command_name, CommandName::Inventory);
+
+        let inventory_message = InventoryMessage::deserialize_message(&mut stream, header).unwrap();
+
+        assert_eq!(
+            vec![InventoryVector::new(
+                TypeIdentifier::TransactionId,
+                transaction.get_tx_id().unwrap()
+            )],
+            inventory_message.inventory_vectors
+        );
+    }
+
+    #[test]
+    fn test06_peer_manager_announces_block_with_inventory_message() {
+        let magic_numbers = [11, 17, 9, 7];
+
+        let block = create_empty_block(0);
+        let block_hash = block.header.get_hash256d().unwrap();
+
+        let stream = Stream::new(Vec::new());
+
+        let (sender_message, _) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+        let from_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+
+        sender_transaction
+            .send(MessageToPeer::SendBlock(
+                block,
+                Some(ConnectionId::new(from_address, ConnectionType::Peer)),
+            ))
+            .unwrap();
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let (stream, _) = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+        let mut stream = stream.get_write_stream();
+
+        let header = MessageHeader::deserialize_header(&mut stream).unwrap();
+
+        assert_eq!(header.command_name, CommandName::Inventory);
+
+        let inventory_message = InventoryMessage::deserialize_message(&mut stream, header).unwrap();
+
+        assert_eq!(
+            vec![InventoryVector::new(TypeIdentifier::Block, block_hash)],
+            inventory_message.inventory_vectors
+        );
+    }
+
+    #[test]
+    fn test07_peer_manager_announces_block_with_headers_after_send_headers() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let block = create_empty_block(0);
+
+        SendHeadersMessage::serialize_message(&mut stream, magic_numbers, &SendHeadersMessage)
+            .unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, _) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+        let from_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+
+        sender_transaction
+            .send(MessageToPeer::SendBlock(
+                block.clone(),
+                Some(ConnectionId::new(from_address, ConnectionType::Peer)),
+            ))
+            .unwrap();
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let (stream, _) = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+        let mut stream = stream.get_write_stream();
+
+        let header = MessageHeader::deserialize_header(&mut stream).unwrap();
+
+        assert_eq!(header.command_name, CommandName::Headers);
+
+        let headers_message = HeadersMessage::deserialize_message(&mut stream, header).unwrap();
+
+        assert_eq!(vec![block.header], headers_message.headers);
+    }
+
+    fn create_block_with_transactions() -> Block {
+        let transactions: Vec<Transaction> = (0..3).map(create_transaction).collect();
+
+        let mut header = create_header(transactions.len() as u64);
+        header.merkle_root_hash = MerkleTree::new(&transactions).unwrap().root;
+
+        let mut block = Block::new(header);
+        for transaction in transactions {
+            block.append_transaction(transaction).unwrap();
+        }
+        block
+    }
+
+    #[test]
+    fn test08_peer_manager_rebuilds_compact_block_with_pending_transactions() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let block = create_block_with_transactions();
+        let compact_block = CmpctBlockMessage::from_block(&block, 5).unwrap();
+
+        CmpctBlockMessage::serialize_message(&mut stream, magic_numbers, &compact_block).unwrap();
+
+        let stream = Stream::new(stream);
+
+        let mut mempool = UTXOSet::new(Vec::new());
+        mempool.append_pending_transaction(block.transactions[1].clone());
+        mempool.append_pending_transaction(block.transactions[2].clone());
+
+        let (sender_message, receiver_message) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        )
+        .with_mempool(Arc::new(Mutex::new(mempool)));
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let _ = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+
+        assert_eq!(
+            MessageResponse::Block(block, ConnectionId::new(id_address, ConnectionType::Peer)),
+            receiver_message.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn test09_peer_manager_asks_for_missing_transactions_of_compact_block() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let block = create_block_with_transactions();
+        let block_hash = block.header.get_hash256d().unwrap();
+        let compact_block = CmpctBlockMessage::from_block(&block, 5).unwrap();
+
+        CmpctBlockMessage::serialize_message(&mut stream, magic_numbers, &compact_block).unwrap();
+
+        let block_txn = BlockTxnMessage {
+            block_hash,
+            transactions: vec![block.transactions[1].clone(), block.transactions[2].clone()],
+        };
+        BlockTxnMessage::serialize_message(&mut stream, magic_numbers, &block_txn).unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, receiver_message) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let (stream, _) = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+        let mut stream = stream.get_write_stream();
+
+        let header =
+            message::deserialize_until_found(&mut stream, CommandName::GetBlockTxn).unwrap();
+        let get_block_txn = GetBlockTxnMessage::deserialize_message(&mut stream, header).unwrap();
+
+        assert_eq!(block_hash, get_block_txn.block_hash);
+        assert_eq!(vec![1, 2], get_block_txn.indexes);
+
+        assert_eq!(
+            MessageResponse::Block(block, ConnectionId::new(id_address, ConnectionType::Peer)),
+            receiver_message.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn test10_peer_manager_only_requests_unseen_transactions_and_serves_pending_ones() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let pending_transaction = create_transaction(0);
+        let pending_transaction_id = pending_transaction.get_tx_id().unwrap();
+        let unseen_transaction_id = create_transaction(1).get_tx_id().unwrap();
+
+        serialize_inv_message(
+            &mut stream,
+            magic_numbers,
+            vec![
+                InventoryVector::new(TypeIdentifier::TransactionId, pending_transaction_id),
+                InventoryVector::new(TypeIdentifier::TransactionId, unseen_transaction_id),
+            ],
+        )
+        .unwrap();
+
+        let get_data_message = GetDataMessage::new(vec![InventoryVector::new(
+            TypeIdentifier::TransactionId,
+            pending_transaction_id,
+        )]);
+        GetDataMessage::serialize_message(&mut stream, magic_numbers, &get_data_message).unwrap();
+
+        let stream = Stream::new(stream);
+
+        let mut mempool = UTXOSet::new(Vec::new());
+        mempool.append_pending_transaction(pending_transaction.clone());
+
+        let (sender_message, _) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        )
+        .with_mempool(Arc::new(Mutex::new(mempool)));
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let (stream, _) = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+        let mut stream = stream.get_write_stream();
+
+        let header = message::deserialize_until_found(&mut stream, CommandName::GetData).unwrap();
+        let get_data_message = GetDataMessage::deserialize_message(&mut stream, header).unwrap();
+
+        assert_eq!(
+            vec![InventoryVector::new(
+                TypeIdentifier::TransactionId,
+                unseen_transaction_id
+            )],
+            get_data_message.inventory_vectors
+        );
+
+        let header = message::deserialize_until_found(&mut stream, CommandName::Tx).unwrap();
+        let transaction_message = TxMessage::deserialize_message(&mut stream, header).unwrap();
+
+        assert_eq!(pending_transaction, transaction_message.transaction);
+    }
+
+    #[derive(Clone)]
+    struct NotificationSender {
+        sender: Sender<Notification>,
+    }
+
+    impl Notifier for NotificationSender {
+        fn notify(&self, notification: Notification) {
+            let _ = self.sender.send(notification);
+        }
+    }
+
+    #[test]
+    fn test11_peer_manager_notifies_reject_of_own_transaction() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let transaction = create_transaction(0);
+        let transaction_id = transaction.get_tx_id().unwrap();
+
+        let reject_message = RejectMessage::new(
+            CommandName::Tx,
+            RejectCode::InsufficientFee,
+            "min relay fee not met",
+            Some(transaction_id),
+        );
+        RejectMessage::serialize_message(&mut stream, magic_numbers, &reject_message).unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, _) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let (sender_notification, receiver_notification) = channel::<Notification>();
+        let notifier = NotificationSender {
+            sender: sender_notification,
+        };
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let mut peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+        peer_manager
+            .own_transactions
+            .insert(transaction_id, transaction.clone());
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let _ = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+
+        let (rejected_transaction, code, reason) = receiver_notification
+            .try_iter()
+            .find_map(|notification| match notification {
+                Notification::TransactionRejected(transaction, code, reason) => {
+                    Some((transaction, code, reason))
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(transaction, rejected_transaction);
+        assert_eq!(RejectCode::InsufficientFee, code);
+        assert_eq!("min relay fee not met", reason);
+    }
+
+    #[test]
+    fn test12_peer_manager_skips_unknown_message_and_keeps_the_connection() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let unknown_header = MessageHeader {
+            magic_numbers,
+            command_name: CommandName::Unknown([
+                b'x', b'y', b'z', b'z', b'y', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0', b'\0',
+            ]),
+            payload_size: 3,
+            checksum: [0; 4],
+        };
+        unknown_header.io_serialize(&mut stream).unwrap();
+        stream.extend_from_slice(&[1, 2, 3]);
+
+        let transaction = create_transaction(0);
+        serialize_tx_message(&mut stream, magic_numbers, transaction.clone()).unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, receiver_message) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain = create_mock_blockchain();
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Peer),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let _ = peer_manager
+            .connecting_to_peer(receiver_transaction)
             .unwrap();
 
-        let mut blockchain = BlockChain::new(empty_block).unwrap();
+        assert_eq!(
+            MessageResponse::Transaction(
+                transaction,
+                ConnectionId::new(id_address, ConnectionType::Peer)
+            ),
+            receiver_message.try_recv().unwrap()
+        );
+    }
 
-        blockchain.update_block(block_with_transactions).unwrap();
-        blockchain
+    fn create_mock_transaction() -> Transaction {
+        Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([1; 32], 23),
+                "Prueba in".as_bytes().to_vec(),
+                24,
+            )],
+            tx_out: vec![TransactionOutput {
+                value: 10,
+                pk_script: "Prueba out".as_bytes().to_vec(),
+            }],
+            time: 0,
+        }
     }
 
     #[test]
-    fn test01_peer_manager_receives_transaction_successfully() {
-        let mut stream: Vec<u8> = Vec::new();
+    fn test13_peer_manager_serves_merkle_block_with_the_matched_transactions() {
+        let mut stream = Vec::new();
         let magic_numbers = [11, 17, 9, 7];
 
-        let transaction = create_transaction(0);
+        let transaction = create_mock_transaction();
+        let mut bloom_filter = BloomFilter::new(1, 0.0001, 0, BloomUpdate::None);
+        bloom_filter.insert(&transaction.get_tx_id().unwrap());
 
-        serialize_tx_message(&mut stream, magic_numbers.clone(), transaction.clone()).unwrap();
+        FilterLoadMessage::serialize_message(
+            &mut stream,
+            magic_numbers,
+            &FilterLoadMessage { bloom_filter },
+        )
+        .unwrap();
+
+        let block_hash = create_header(0).get_hash256d().unwrap();
+        let get_data_message = GetDataMessage::new(vec![InventoryVector::new(
+            TypeIdentifier::FilteredBlock,
+            block_hash,
+        )]);
+        GetDataMessage::serialize_message(&mut stream, magic_numbers, &get_data_message).unwrap();
 
         let stream = Stream::new(stream);
 
@@ -652,34 +2625,42 @@ mod tests {
             magic_numbers,
             notifier,
             sender,
-        );
+        )
+        .with_bloom_filters(true);
 
         sender_transaction.send(MessageToPeer::Stop).unwrap();
 
-        let _ = peer_manager
+        let (stream, _) = peer_manager
             .connecting_to_peer(receiver_transaction)
             .unwrap();
+        let mut stream = stream.get_write_stream();
 
-        assert_eq!(
-            MessageResponse::Transaction(
-                transaction,
-                ConnectionId::new(id_address, ConnectionType::Client)
-            ),
-            receiver_message.try_recv().unwrap()
-        );
+        let header =
+            message::deserialize_until_found(&mut stream, CommandName::MerkleBlock).unwrap();
+        let merkle_block = MerkleBlockMessage::deserialize_message(&mut stream, header).unwrap();
+
+        let (_, matched_ids) = merkle_block.partial_merkle_tree.extract_matches().unwrap();
+        assert_eq!(vec![transaction.get_tx_id().unwrap()], matched_ids);
+
+        let header = message::deserialize_until_found(&mut stream, CommandName::Tx).unwrap();
+        let transaction_message = TxMessage::deserialize_message(&mut stream, header).unwrap();
+
+        assert_eq!(transaction, transaction_message.transaction);
+        assert!(receiver_message.try_recv().is_err());
     }
 
     #[test]
-    fn test02_peer_manager_receives_block_successfully() {
+    fn test14_peer_manager_punishes_filter_load_when_bloom_filters_are_disabled() {
         let mut stream = Vec::new();
         let magic_numbers = [11, 17, 9, 7];
 
-        let mut block = create_empty_block(3);
-        block.append_transaction(create_transaction(0)).unwrap();
-        block.append_transaction(create_transaction(1)).unwrap();
-        block.append_transaction(create_transaction(2)).unwrap();
-
-        serialize_block_message(&mut stream, magic_numbers.clone(), block.clone()).unwrap();
+        let bloom_filter = BloomFilter::new(1, 0.0001, 0, BloomUpdate::None);
+        FilterLoadMessage::serialize_message(
+            &mut stream,
+            magic_numbers,
+            &FilterLoadMessage { bloom_filter },
+        )
+        .unwrap();
 
         let stream = Stream::new(stream);
 
@@ -690,11 +2671,12 @@ mod tests {
         let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
 
         let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+        let connection_id = ConnectionId::new(id_address, ConnectionType::Client);
 
         let logger_text: Vec<u8> = Vec::new();
         let (sender, _) = logger::initialize_logger(logger_text, false);
         let peer_manager = PeerManager::new(
-            ConnectionId::new(id_address, ConnectionType::Peer),
+            connection_id,
             stream,
             sender_message,
             blockchain,
@@ -710,26 +2692,38 @@ mod tests {
             .unwrap();
 
         assert_eq!(
-            MessageResponse::Block(block, ConnectionId::new(id_address, ConnectionType::Client)),
+            MessageResponse::Misbehavior(Misbehavior::InvalidFilter, connection_id),
             receiver_message.try_recv().unwrap()
         );
     }
-
     #[test]
-    fn test03_peer_manager_receives_headers_successfully() {
+    fn test15_peer_manager_serves_compact_filters_and_their_headers() {
         let mut stream = Vec::new();
         let magic_numbers = [11, 17, 9, 7];
 
-        let first_header = create_header(4);
-        let first_header_hash = first_header.get_hash256d().unwrap();
-
-        let second_header = create_header(2);
-        let second_header_hash = second_header.get_hash256d().unwrap();
+        let blockchain = create_mock_blockchain();
+        let filter_chain = FilterChain::from_block_chain(&blockchain).unwrap();
+        let start_height = filter_chain.get_tip_height().unwrap() as u32;
+        let stop_hash = create_header(0).get_hash256d().unwrap();
 
-        serialize_headers_message(
+        GetCFiltersMessage::serialize_message(
             &mut stream,
-            magic_numbers.clone(),
-            vec![first_header.clone(), second_header.clone()],
+            magic_numbers,
+            &GetCFiltersMessage {
+                filter_type: BASIC_FILTER_TYPE,
+                start_height,
+                stop_hash,
+            },
+        )
+        .unwrap();
+        GetCFHeadersMessage::serialize_message(
+            &mut stream,
+            magic_numbers,
+            &GetCFHeadersMessage {
+                filter_type: BASIC_FILTER_TYPE,
+                start_height,
+                stop_hash,
+            },
         )
         .unwrap();
 
@@ -738,7 +2732,6 @@ mod tests {
         let (sender_message, _) = channel::<MessageResponse>();
         let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
         let notifier = NotificationMock {};
-        let blockchain = create_mock_blockchain();
         let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
 
         let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
@@ -746,14 +2739,15 @@ mod tests {
         let logger_text: Vec<u8> = Vec::new();
         let (sender, _) = logger::initialize_logger(logger_text, false);
         let peer_manager = PeerManager::new(
-            ConnectionId::new(id_address, ConnectionType::Peer),
+            ConnectionId::new(id_address, ConnectionType::Client),
             stream,
             sender_message,
             blockchain,
             magic_numbers,
             notifier,
             sender,
-        );
+        )
+        .with_filter_chain(Arc::new(Mutex::new(FilterChain::default())));
 
         sender_transaction.send(MessageToPeer::Stop).unwrap();
 
@@ -762,98 +2756,175 @@ mod tests {
             .unwrap();
         let mut stream = stream.get_write_stream();
 
-        let header = message::deserialize_until_found(&mut stream, CommandName::GetData).unwrap();
+        let expected_filter = filter_chain.get_filter(&stop_hash).unwrap();
 
-        assert_eq!(header.command_name, CommandName::GetData);
+        let header = message::deserialize_until_found(&mut stream, CommandName::CFilter).unwrap();
+        let cfilter = CFilterMessage::deserialize_message(&mut stream, header).unwrap();
 
-        let get_data_message = GetDataMessage::deserialize_message(&mut stream, header).unwrap();
+        assert_eq!(stop_hash, cfilter.block_hash);
+        assert_eq!(expected_filter.get_bytes(), cfilter.filter);
 
-        let inventory_vectors = vec![
-            InventoryVector::new(TypeIdentifier::Block, first_header_hash),
-            InventoryVector::new(TypeIdentifier::Block, second_header_hash),
-        ];
+        let header = message::deserialize_until_found(&mut stream, CommandName::CFHeaders).unwrap();
+        let cfheaders = CFHeadersMessage::deserialize_message(&mut stream, header).unwrap();
 
-        assert_eq!(inventory_vectors, get_data_message.inventory_vectors);
+        assert_eq!([0; 32], cfheaders.previous_filter_header);
+        assert_eq!(
+            vec![expected_filter.get_filter_hash().unwrap()],
+            cfheaders.filter_hashes
+        );
     }
 
     #[test]
-    fn test04_peer_manager_receives_inventory_message_successfully() {
+    fn test16_peer_manager_receives_filtered_block_with_its_matched_transactions() {
         let mut stream = Vec::new();
         let magic_numbers = [11, 17, 9, 7];
 
-        let block = create_empty_block(4);
+        let block = create_block_with_transactions();
         let block_hash = block.header.get_hash256d().unwrap();
-
-        let transaction = create_transaction(0);
-        let transaction_id = transaction.get_tx_id().unwrap();
+        let matched_transaction = block.transactions[1].clone();
 
         serialize_inv_message(
             &mut stream,
-            magic_numbers.clone(),
-            vec![
-                InventoryVector::new(TypeIdentifier::Block, block_hash),
-                InventoryVector::new(TypeIdentifier::TransactionId, transaction_id),
-            ],
+            magic_numbers,
+            vec![InventoryVector::new(TypeIdentifier::Block, block_hash)],
         )
         .unwrap();
 
+        let mut bloom_filter = BloomFilter::new(1, 0.0001, 0, BloomUpdate::None);
+        bloom_filter.insert(&matched_transaction.get_tx_id().unwrap());
+        let (merkle_block, _) = MerkleBlockMessage::from_block(&block, &mut bloom_filter).unwrap();
+        MerkleBlockMessage::serialize_message(&mut stream, magic_numbers, &merkle_block).unwrap();
+        serialize_tx_message(&mut stream, magic_numbers, matched_transaction.clone()).unwrap();
+
         let stream = Stream::new(stream);
 
-        let (sender_message, _) = channel::<MessageResponse>();
+        let (sender_message, receiver_message) = channel::<MessageResponse>();
         let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
         let notifier = NotificationMock {};
         let blockchain = create_mock_blockchain();
         let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
 
         let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+        let connection_id = ConnectionId::new(id_address, ConnectionType::Peer);
 
         let logger_text: Vec<u8> = Vec::new();
         let (sender, _) = logger::initialize_logger(logger_text, false);
         let peer_manager = PeerManager::new(
-            ConnectionId::new(id_address, ConnectionType::Peer),
+            connection_id,
             stream,
             sender_message,
             blockchain,
             magic_numbers,
             notifier,
             sender,
-        );
+        )
+        .with_filtered_blocks(true);
 
         sender_transaction.send(MessageToPeer::Stop).unwrap();
+
         let (stream, _) = peer_manager
             .connecting_to_peer(receiver_transaction)
             .unwrap();
         let mut stream = stream.get_write_stream();
 
         let header = message::deserialize_until_found(&mut stream, CommandName::GetData).unwrap();
+        let get_data_message = GetDataMessage::deserialize_message(&mut stream, header).unwrap();
 
-        assert_eq!(header.command_name, CommandName::GetData);
+        assert_eq!(
+            vec![InventoryVector::new(
+                TypeIdentifier::FilteredBlock,
+                block_hash
+            )],
+            get_data_message.inventory_vectors
+        );
 
-        let get_data_message = GetDataMessage::deserialize_message(&mut stream, header).unwrap();
+        let mut filtered_block = Block::new(block.header);
+        filtered_block
+            .append_transaction(matched_transaction)
+            .unwrap();
 
-        let inventory_vectors = vec![
-            InventoryVector::new(TypeIdentifier::Block, block_hash),
-            InventoryVector::new(TypeIdentifier::TransactionId, transaction_id),
-        ];
+        assert_eq!(
+            MessageResponse::FilteredBlock(filtered_block, connection_id),
+            receiver_message.try_recv().unwrap()
+        );
+        assert!(receiver_message.try_recv().is_err());
+    }
 
-        assert_eq!(inventory_vectors, get_data_message.inventory_vectors);
+    #[test]
+    fn test17_peer_manager_does_not_serve_pruned_blocks() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let block_hash = create_header(0).get_hash256d().unwrap();
+        let get_data_message = GetDataMessage::get_blocks(vec![block_hash]);
+        GetDataMessage::serialize_message(&mut stream, magic_numbers, &get_data_message).unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, receiver_message) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let mut blockchain = create_mock_blockchain().with_prune_depth(0);
+        assert_eq!(1, blockchain.prune().unwrap());
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Client),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let (stream, _) = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+        let mut stream = stream.get_write_stream();
+
+        assert!(message::deserialize_until_found(&mut stream, CommandName::Block).is_err());
+        assert!(receiver_message.try_recv().is_err());
     }
 
     #[test]
-    fn test05_peer_manager_sends_transaction_successfully() {
+    fn test18_peer_manager_replies_get_blocks_with_an_inventory_of_the_blocks() {
         let mut stream = Vec::new();
         let magic_numbers = [11, 17, 9, 7];
 
-        let transaction = create_transaction(0);
+        let first_hash = create_header(0).get_hash256d().unwrap();
+        let mut next_block = Block::new(BlockHeader::new(
+            BlockVersion::version(1),
+            first_hash,
+            [0; 32],
+            1,
+            Compact256::from(u32::MAX),
+            0,
+            CompactSize::new(0),
+        ));
+        next_block
+            .append_transaction(create_transaction(1))
+            .unwrap();
+        let next_hash = next_block.header.get_hash256d().unwrap();
 
-        serialize_ping_message(&mut stream, magic_numbers.clone()).unwrap();
+        let get_blocks_message =
+            GetBlocksMessage::new(ProtocolVersionP2P::V70015, vec![first_hash], [0; 32]);
+        GetBlocksMessage::serialize_message(&mut stream, magic_numbers, &get_blocks_message)
+            .unwrap();
 
         let stream = Stream::new(stream);
 
         let (sender_message, _) = channel::<MessageResponse>();
         let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
         let notifier = NotificationMock {};
-        let blockchain = create_mock_blockchain();
+        let mut blockchain = create_mock_blockchain();
+        blockchain.append_block(next_block).unwrap();
         let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(blockchain));
 
         let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
@@ -861,7 +2932,7 @@ mod tests {
         let logger_text: Vec<u8> = Vec::new();
         let (sender, _) = logger::initialize_logger(logger_text, false);
         let peer_manager = PeerManager::new(
-            ConnectionId::new(id_address, ConnectionType::Peer),
+            ConnectionId::new(id_address, ConnectionType::Client),
             stream,
             sender_message,
             blockchain,
@@ -870,9 +2941,63 @@ mod tests {
             sender,
         );
 
-        sender_transaction
-            .send(MessageToPeer::SendTransaction(transaction.clone(), None))
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let (stream, _) = peer_manager
+            .connecting_to_peer(receiver_transaction)
             .unwrap();
+        let mut stream = stream.get_write_stream();
+
+        let header = message::deserialize_until_found(&mut stream, CommandName::Inventory).unwrap();
+        let inventory_message = InventoryMessage::deserialize_message(&mut stream, header).unwrap();
+
+        assert_eq!(
+            vec![InventoryVector::new(TypeIdentifier::Block, next_hash)],
+            inventory_message.inventory_vectors
+        );
+    }
+
+    #[test]
+    fn test19_peer_manager_replies_not_found_for_the_missing_items() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
+
+        let missing_vectors = vec![
+            InventoryVector::new(
+                TypeIdentifier::TransactionId,
+                create_transaction(5).get_tx_id().unwrap(),
+            ),
+            InventoryVector::new(TypeIdentifier::Block, [3; 32]),
+            InventoryVector::new(
+                TypeIdentifier::FilteredBlock,
+                create_header(0).get_hash256d().unwrap(),
+            ),
+        ];
+        let get_data_message = GetDataMessage::new(missing_vectors.clone());
+        GetDataMessage::serialize_message(&mut stream, magic_numbers, &get_data_message).unwrap();
+
+        let stream = Stream::new(stream);
+
+        let (sender_message, _) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(create_mock_blockchain()));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            ConnectionId::new(id_address, ConnectionType::Client),
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        )
+        .with_mempool(Arc::new(Mutex::new(UTXOSet::new(Vec::new()))));
+
         sender_transaction.send(MessageToPeer::Stop).unwrap();
 
         let (stream, _) = peer_manager
@@ -880,18 +3005,70 @@ mod tests {
             .unwrap();
         let mut stream = stream.get_write_stream();
 
-        let header = message::deserialize_until_found(&mut stream, CommandName::Pong).unwrap();
+        let header = message::deserialize_until_found(&mut stream, CommandName::NotFound).unwrap();
+        let not_found_message = NotFoundMessage::deserialize_message(&mut stream, header).unwrap();
 
-        assert_eq!(header.command_name, CommandName::Pong);
+        assert_eq!(missing_vectors, not_found_message.inventory_vectors);
+    }
 
-        let _ = PongMessage::deserialize_message(&mut stream, header).unwrap();
+    #[test]
+    fn test20_peer_manager_forgets_the_oldest_compact_blocks_waiting_for_transactions() {
+        let mut stream = Vec::new();
+        let magic_numbers = [11, 17, 9, 7];
 
-        let header = message::deserialize_until_found(&mut stream, CommandName::Tx).unwrap();
+        let mut blocks = Vec::new();
+        for time in 0..=MAX_PARTIAL_BLOCKS as u32 {
+            let mut block = create_block_with_transactions();
+            block.header.time = time;
+            let compact_block = CmpctBlockMessage::from_block(&block, 5).unwrap();
+            CmpctBlockMessage::serialize_message(&mut stream, magic_numbers, &compact_block)
+                .unwrap();
+            blocks.push(block);
+        }
 
-        assert_eq!(header.command_name, CommandName::Tx);
+        for block in [blocks[0].clone(), blocks[MAX_PARTIAL_BLOCKS].clone()] {
+            let block_txn = BlockTxnMessage {
+                block_hash: block.header.get_hash256d().unwrap(),
+                transactions: vec![block.transactions[1].clone(), block.transactions[2].clone()],
+            };
+            BlockTxnMessage::serialize_message(&mut stream, magic_numbers, &block_txn).unwrap();
+        }
 
-        let transaction_message = TxMessage::deserialize_message(&mut stream, header).unwrap();
+        let stream = Stream::new(stream);
 
-        assert_eq!(transaction, transaction_message.transaction);
+        let (sender_message, receiver_message) = channel::<MessageResponse>();
+        let (sender_transaction, receiver_transaction) = channel::<MessageToPeer>();
+        let notifier = NotificationMock {};
+        let blockchain: Arc<Mutex<BlockChain>> = Arc::new(Mutex::new(create_mock_blockchain()));
+
+        let id_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8333);
+        let connection_id = ConnectionId::new(id_address, ConnectionType::Peer);
+
+        let logger_text: Vec<u8> = Vec::new();
+        let (sender, _) = logger::initialize_logger(logger_text, false);
+        let peer_manager = PeerManager::new(
+            connection_id,
+            stream,
+            sender_message,
+            blockchain,
+            magic_numbers,
+            notifier,
+            sender,
+        );
+
+        sender_transaction.send(MessageToPeer::Stop).unwrap();
+
+        let _ = peer_manager
+            .connecting_to_peer(receiver_transaction)
+            .unwrap();
+
+        let received_blocks: Vec<MessageResponse> = receiver_message.try_iter().collect();
+        assert_eq!(
+            vec![MessageResponse::Block(
+                blocks[MAX_PARTIAL_BLOCKS].clone(),
+                connection_id
+            )],
+            received_blocks
+        );
     }
 }
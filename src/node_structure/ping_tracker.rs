@@ -0,0 +1,132 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const PING_INTERVAL: Duration = Duration::from_secs(2 * 60);
+
+/// It keeps track of the pings sent to a peer, to know if the peer is still alive and its latency
+#[derive(Debug)]
+pub struct PingTracker {
+    timeout: Duration,
+    next_nonce: u64,
+    pending_ping: Option<(u64, Instant)>,
+    last_ping: Instant,
+    latency: Option<Duration>,
+}
+
+impl PingTracker {
+    /// Creates the tracker from the given time, waiting the timeout for the answer of each ping
+    pub fn new(timeout: Duration, now: Instant) -> Self {
+        let next_nonce = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_nanos() as u64,
+            Err(_) => 0,
+        };
+
+        PingTracker {
+            timeout,
+            next_nonce,
+            pending_ping: None,
+            last_ping: now,
+            latency: None,
+        }
+    }
+
+    /// Returns the time when the tracker has to be checked again, to send a ping or to see if the last one timed out
+    pub fn next_deadline(&self) -> Instant {
+        match self.pending_ping {
+            Some((_, sent)) => sent + self.timeout,
+            None => self.last_ping + PING_INTERVAL,
+        }
+    }
+
+    /// Returns true if there is no ping waiting for an answer and it's time to send a new one
+    pub fn should_send_ping(&self, now: Instant) -> bool {
+        self.pending_ping.is_none() && now >= self.last_ping + PING_INTERVAL
+    }
+
+    /// Registers that a ping is sent at the given time, returning the nonce to use in it
+    pub fn ping_sent(&mut self, now: Instant) -> u64 {
+        let nonce = self.next_nonce;
+        self.next_nonce = self.next_nonce.wrapping_add(1);
+
+        self.pending_ping = Some((nonce, now));
+        self.last_ping = now;
+        nonce
+    }
+
+    /// Registers the answer of a ping. Returns the round trip time if the nonce is the one of the pending ping
+    pub fn pong_received(&mut self, nonce: u64, now: Instant) -> Option<Duration> {
+        match self.pending_ping {
+            Some((pending_nonce, sent)) if pending_nonce == nonce => {
+                let latency = now.duration_since(sent);
+                self.pending_ping = None;
+                self.latency = Some(latency);
+                Some(latency)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if the pending ping was not answered within the timeout and false otherwise
+    pub fn timed_out(&self, now: Instant) -> bool {
+        match self.pending_ping {
+            Some((_, sent)) => now >= sent + self.timeout,
+            None => false,
+        }
+    }
+
+    /// Returns the round trip time of the last answered ping
+    pub fn get_latency(&self) -> Option<Duration> {
+        self.latency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_01_ping_is_sent_after_the_interval() {
+        let start = Instant::now();
+        let mut ping_tracker = PingTracker::new(Duration::from_secs(30), start);
+
+        assert!(!ping_tracker.should_send_ping(start));
+        assert_eq!(start + PING_INTERVAL, ping_tracker.next_deadline());
+
+        let now = start + PING_INTERVAL;
+        assert!(ping_tracker.should_send_ping(now));
+
+        ping_tracker.ping_sent(now);
+        assert!(!ping_tracker.should_send_ping(now));
+        assert_eq!(now + Duration::from_secs(30), ping_tracker.next_deadline());
+    }
+
+    #[test]
+    fn test_02_matching_pong_gives_the_latency() {
+        let start = Instant::now();
+        let mut ping_tracker = PingTracker::new(Duration::from_secs(30), start);
+
+        let nonce = ping_tracker.ping_sent(start);
+
+        let answer = start + Duration::from_millis(150);
+        assert_eq!(
+            None,
+            ping_tracker.pong_received(nonce.wrapping_add(1), answer)
+        );
+        assert_eq!(
+            Some(Duration::from_millis(150)),
+            ping_tracker.pong_received(nonce, answer)
+        );
+        assert_eq!(Some(Duration::from_millis(150)), ping_tracker.get_latency());
+        assert!(!ping_tracker.timed_out(start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_03_unanswered_ping_times_out() {
+        let start = Instant::now();
+        let mut ping_tracker = PingTracker::new(Duration::from_secs(30), start);
+
+        ping_tracker.ping_sent(start);
+
+        assert!(!ping_tracker.timed_out(start + Duration::from_secs(29)));
+        assert!(ping_tracker.timed_out(start + Duration::from_secs(30)));
+    }
+}
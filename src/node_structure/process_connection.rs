@@ -1,5 +1,5 @@
 use super::{
-    connection_event::ConnectionEvent, connection_id::ConnectionId,
+    ban_list::BanList, connection_event::ConnectionEvent, connection_id::ConnectionId,
     connection_type::ConnectionType, error_node::ErrorNode, handshake::Handshake,
     handshake_data::HandshakeData,
 };
@@ -12,10 +12,15 @@ use crate::{
     serialization::error_serialization::ErrorSerialization,
 };
 
+use chrono::Utc;
+
 use std::{
     net::SocketAddr,
     net::TcpStream,
-    sync::mpsc::{channel, Receiver, Sender},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
     thread::{self, JoinHandle},
     time::Duration,
 };
@@ -32,6 +37,8 @@ pub struct ProcessConnection<N: Notifier + Send + 'static> {
     sender_confirm_connection: SenderConfirm,
     receiver_potential_connections: ReceiverPotential,
 
+    ban_list: Arc<Mutex<BanList>>,
+
     notifier: N,
     logger: LoggerSender,
 }
@@ -41,6 +48,7 @@ impl<N: Notifier + Send + 'static> ProcessConnection<N> {
         connection_config: ConnectionConfig,
         sender_confirm_connection: SenderConfirm,
         receiver_potential_connections: ReceiverPotential,
+        ban_list: Arc<Mutex<BanList>>,
         notifier: N,
         logger: LoggerSender,
     ) -> Self {
@@ -61,6 +69,7 @@ impl<N: Notifier + Send + 'static> ProcessConnection<N> {
             handshake,
             sender_confirm_connection,
             receiver_potential_connections,
+            ban_list,
             notifier,
             logger,
         }
@@ -77,6 +86,10 @@ impl<N: Notifier + Send + 'static> ProcessConnection<N> {
         for connection_event in &self.receiver_potential_connections {
             match connection_event {
                 ConnectionEvent::PotentialPeer(socket_address) => {
+                    if self.is_banned(&socket_address)? {
+                        continue;
+                    }
+
                     let (sender, receiver) = channel::<Stop>();
 
                     let stream = match Self::create_stream(socket_address, self.logger.clone()) {
@@ -98,6 +111,10 @@ impl<N: Notifier + Send + 'static> ProcessConnection<N> {
                     pending_connection_handlers.push((handler, sender));
                 }
                 ConnectionEvent::PotentialClient(stream, socket_address) => {
+                    if self.is_banned(&socket_address)? {
+                        continue;
+                    }
+
                     let (sender, receiver) = channel::<Stop>();
 
                     let handler = self.handle_connection_event(
@@ -125,6 +142,26 @@ impl<N: Notifier + Send + 'static> ProcessConnection<N> {
         result
     }
 
+    /// Returns true if the address of the potential connection is banned and false otherwise
+    ///
+    /// ### Error
+    ///  * `ErrorNode::FailThread`: It will appear when thread is poisoned
+    fn is_banned(&self, socket_address: &SocketAddr) -> Result<bool, ErrorNode> {
+        let ban_list = match self.ban_list.lock() {
+            Ok(ban_list) => ban_list,
+            Err(_) => return Err(ErrorNode::FailThread),
+        };
+
+        let is_banned = ban_list.is_banned(&socket_address.ip(), Utc::now().timestamp() as u32);
+        if is_banned {
+            let _ = self.logger.log_connection(format!(
+                "Refusing connection with banned address {socket_address}"
+            ));
+        }
+
+        Ok(is_banned)
+    }
+
     /// Create a thread to handle the new potential connection to establish the handshake
     fn handle_connection_event(
         &self,
@@ -210,26 +247,27 @@ impl<N: Notifier + Send + 'static> ProcessConnection<N> {
     ) -> Result<bool, ErrorSerialization> {
         handshake.send_version_message(stream, local_socket, potential_socket)?;
 
-        loop {
+        let peer_version = loop {
             match Work::listen(stream, receiver) {
                 Work::Message(header) => {
-                    handshake.receive_version_message(stream, header, potential_socket)?;
-                    break;
+                    break handshake.receive_version_message(stream, header, potential_socket)?;
                 }
                 Work::Information(()) => continue,
                 Work::Stop => {
                     return Ok(false);
                 }
             }
-        }
+        };
 
+        handshake.send_sendaddrv2_message(stream, peer_version)?;
         handshake.send_verack_message(stream, potential_socket)?;
 
         loop {
             match Work::listen(stream, receiver) {
                 Work::Message(header) => {
-                    handshake.receive_verack_message(stream, header, potential_socket)?;
-                    break;
+                    if handshake.receive_verack_message(stream, header, potential_socket)? {
+                        break;
+                    }
                 }
                 Work::Information(()) => continue,
                 Work::Stop => {
@@ -239,6 +277,8 @@ impl<N: Notifier + Send + 'static> ProcessConnection<N> {
         }
 
         handshake.send_sendheaders_message(stream)?;
+        handshake.send_sendcmpct_message(stream)?;
+        handshake.send_getaddr_message(stream)?;
 
         Ok(true)
     }
@@ -256,26 +296,27 @@ impl<N: Notifier + Send + 'static> ProcessConnection<N> {
         handshake: &Handshake,
         receiver: &Receiver<Stop>,
     ) -> Result<bool, ErrorSerialization> {
-        loop {
+        let peer_version = loop {
             match Work::listen(stream, receiver) {
                 Work::Message(header) => {
-                    handshake.receive_version_message(stream, header, potential_socket)?;
-                    break;
+                    break handshake.receive_version_message(stream, header, potential_socket)?;
                 }
                 Work::Information(()) => continue,
                 Work::Stop => {
                     return Ok(false);
                 }
             }
-        }
+        };
 
         handshake.send_version_message(stream, local_socket, potential_socket)?;
+        handshake.send_sendaddrv2_message(stream, peer_version)?;
 
         loop {
             match Work::listen(stream, receiver) {
                 Work::Message(header) => {
-                    handshake.receive_verack_message(stream, header, potential_socket)?;
-                    break;
+                    if handshake.receive_verack_message(stream, header, potential_socket)? {
+                        break;
+                    }
                 }
                 Work::Information(()) => continue,
                 Work::Stop => {
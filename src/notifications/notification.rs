@@ -1,10 +1,13 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Duration};
 
 use crate::{
-    block_structure::{block::Block, hash::HashType, transaction::Transaction},
-    messages::command_name::CommandName,
-    node_structure::connection_id::ConnectionId,
-    wallet_structure::account::Account,
+    block_structure::{block::Block, hash::HashType, outpoint::Outpoint, transaction::Transaction},
+    messages::{command_name::CommandName, reject_code::RejectCode},
+    node_structure::{connection_id::ConnectionId, misbehavior::Misbehavior},
+    wallet_structure::{
+        account::Account, contact::Contact, output_record::OutputRecord,
+        transaction_record::TransactionRecord,
+    },
 };
 
 /// The different types of notifications that the notifier can send.
@@ -21,6 +24,15 @@ pub enum Notification {
     /// Notifies that we have to update a connection
     ConnectionUpdated(ConnectionId),
 
+    /// Notifies that a connection was lost.
+    ConnectionLost(ConnectionId),
+
+    /// Notifies the round trip time of the last ping answered by a peer.
+    UpdatedPeerLatency(ConnectionId, Duration),
+
+    /// Notifies that a peer was banned because of its misbehavior.
+    PeerBanned(SocketAddr, Misbehavior),
+
     /// Notifies that we have received a transaction for an account in the wallet.
     TransactionOfAccountReceived(Vec<Account>, Transaction),
 
@@ -33,6 +45,9 @@ pub enum Notification {
     /// Notifies that we have successfully sent a transaction.
     SuccessfullySentTransaction(Transaction),
 
+    /// Notifies that a peer rejected a transaction we sent, with the code and the reason.
+    TransactionRejected(Transaction, RejectCode, String),
+
     /// Notifies that we successfully obtained the merkle poof of inclusion.
     SuccessfulMerkleProof(Vec<HashType>, HashType),
 
@@ -63,8 +78,44 @@ pub enum Notification {
     /// Notifies that there is no account currently selected.
     AccountNotSelected,
 
-    /// Notifies the information of the transactions of an account.
-    AccountTransactions(Account, Vec<Transaction>),
+    /// Notifies the history of the transactions received and sent by an account.
+    AccountTransactions(Account, Vec<TransactionRecord>),
+
+    /// Notifies the unspent outputs of an account.
+    AccountOutputs(Account, Vec<OutputRecord>),
+
+    /// Notifies that the label of a transaction was changed.
+    TransactionLabeled(HashType, String),
+
+    /// Notifies that the label of an output was changed.
+    OutputLabeled(Outpoint, String),
+
+    /// Notifies that an output will not be selected automatically to be spent.
+    OutputFrozen(Outpoint),
+
+    /// Notifies that an output can be selected automatically to be spent again.
+    OutputUnfrozen(Outpoint),
+
+    /// Notifies that the history of transactions of an account was exported to a file.
+    SuccessfullyExportedTransactions(Account, String),
+
+    /// Notifies that there was a problem while exporting the history of transactions.
+    ProblemExportingTransactions(String),
+
+    /// Notifies the contacts saved in the address book.
+    AddressBookContacts(Vec<Contact>),
+
+    /// Notifies that a contact was added or updated in the address book.
+    SavedContact(Contact),
+
+    /// Notifies that a contact was removed from the address book.
+    RemovedContact(Contact),
+
+    /// Notifies that there is already a contact with the given name in the address book.
+    ContactAlreadyExists(String),
+
+    /// Notifies that there is no contact with the given name in the address book.
+    ContactNotFound(String),
 
     /// Notifies that we have entered an invalid address.
     InvalidAddressEnter,
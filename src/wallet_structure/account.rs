@@ -19,9 +19,11 @@ use crate::block_structure::{
     utxo_set::UTXOSet,
 };
 
+use crate::connections::network::Network;
+
 use std::{
     cmp::PartialEq,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::Display,
     io::{Read, Write},
 };
@@ -40,11 +42,12 @@ impl Account {
         name: &str,
         private_key_bytes: &PrivateKeyType,
         public_key_bytes: &PublicKeyType,
+        network: Network,
     ) -> Result<Account, ErrorWallet> {
         let account_name = name.to_string();
         let private_key = PrivateKey::new(private_key_bytes)?;
         let public_key = PublicKey::new(public_key_bytes);
-        let address = Address::from_public_key(&public_key)?;
+        let address = Address::from_public_key(&public_key, network)?;
 
         Ok(Account {
             account_name,
@@ -58,9 +61,10 @@ impl Account {
         name: &str,
         private_key: PrivateKey,
         public_key: PublicKey,
+        network: Network,
     ) -> Result<Account, ErrorWallet> {
         let account_name = name.to_string();
-        let address = Address::from_public_key(&public_key)?;
+        let address = Address::from_public_key(&public_key, network)?;
 
         Ok(Account {
             account_name,
@@ -70,6 +74,29 @@ impl Account {
         })
     }
 
+    /// Creates an account from a private key in the wallet import format of the given network
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotGeneratePrivateKey`: It will appear when the WIF is not valid or does not belong to the given network
+    ///  * `ErrorWallet::CannotCreateAddress`: It will appear when there was a problem hashing the public key
+    pub fn from_wif(
+        name: &str,
+        wif: &str,
+        public_key: PublicKey,
+        network: Network,
+    ) -> Result<Account, ErrorWallet> {
+        let private_key = PrivateKey::from_wif(wif, network)?;
+        Account::from_keys(name, private_key, public_key, network)
+    }
+
+    /// Returns the private key of the account in the wallet import format of the given network
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotGeneratePrivateKey`: It will appear when there was a problem hashing
+    pub fn get_wif(&self, network: Network) -> Result<String, ErrorWallet> {
+        self.private_key.to_wif(network)
+    }
+
     /// Returns true if the account owns the given transaction output (works for P2PKH) and false otherwise.
     pub fn verify_transaction_output_ownership(&self, txo: &TransactionOutput) -> bool {
         self.address.verify_transaction_ownership(txo)
@@ -90,7 +117,8 @@ impl Account {
         utxo_set.get_balance_in_tbtc(&self.address)
     }
 
-    /// Returns a transaction given the amount and to whom it is sent
+    /// Returns a transaction given the amount and to whom it is sent, choosing automatically which
+    /// outputs to spend without touching the frozen ones
     ///
     /// ### Error
     ///  * `ErrorWallet::CannotCreateNewTransaction`: It will appear when a transaction cannot be created
@@ -101,8 +129,13 @@ impl Account {
         amount: i64,
         fee: i64,
         utxo_set: &UTXOSet,
+        frozen_outputs: &HashSet<Outpoint>,
     ) -> Result<Transaction, ErrorWallet> {
-        let mut available_outputs = utxo_set.get_utxo_list_with_outpoints(Some(&self.address));
+        let mut available_outputs: Vec<(Outpoint, TransactionOutput)> = utxo_set
+            .get_utxo_list_with_outpoints(Some(&self.address))
+            .into_iter()
+            .filter(|(outpoint, _)| !frozen_outputs.contains(outpoint))
+            .collect();
         available_outputs.sort_by(|(_, a), (_, b)| b.value.cmp(&a.value));
 
         let mut input_amount = 0;
@@ -115,13 +148,65 @@ impl Account {
             }
         }
 
+        self.create_transaction_from_outputs(
+            to,
+            amount,
+            fee,
+            outputs_to_spend.into_iter().collect(),
+        )
+    }
+
+    /// Returns a transaction given the amount and to whom it is sent, spending only the selected outputs
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotCreateNewTransaction`: It will appear when a transaction cannot be created or a selected output is not available
+    ///  * `ErrorWallet::NotEnoughFunds`: It will appear when the selected outputs are not enough to create a transaction for the amount requested
+    pub fn create_transaction_with_selected_outputs(
+        &self,
+        to: Address,
+        amount: i64,
+        fee: i64,
+        utxo_set: &UTXOSet,
+        selected_outputs: &[Outpoint],
+    ) -> Result<Transaction, ErrorWallet> {
+        let available_outputs: HashMap<Outpoint, TransactionOutput> = utxo_set
+            .get_utxo_list_with_outpoints(Some(&self.address))
+            .into_iter()
+            .collect();
+
+        let mut outputs_to_spend: HashMap<Outpoint, TransactionOutput> = HashMap::new();
+        for outpoint in selected_outputs {
+            match available_outputs.get(outpoint) {
+                Some(output) => outputs_to_spend.insert(outpoint.clone(), output.clone()),
+                None => {
+                    return Err(ErrorWallet::CannotCreateNewTransaction(format!(
+                        "The output {outpoint} is not available to be spent"
+                    )))
+                }
+            };
+        }
+
+        self.create_transaction_from_outputs(to, amount, fee, outputs_to_spend)
+    }
+
+    /// Returns a transaction spending all the given outputs
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotCreateNewTransaction`: It will appear when a transaction cannot be created
+    ///  * `ErrorWallet::NotEnoughFunds`: It will appear when the outputs are not enough to create a transaction for the amount requested
+    fn create_transaction_from_outputs(
+        &self,
+        to: Address,
+        amount: i64,
+        fee: i64,
+        outputs_to_spend: HashMap<Outpoint, TransactionOutput>,
+    ) -> Result<Transaction, ErrorWallet> {
+        let input_amount: i64 = outputs_to_spend.values().map(|output| output.value).sum();
+
         if input_amount < (amount + fee) {
             return Err(ErrorWallet::NotEnoughFunds(format!("Not enough funds to create the transaction. Input amount: {}. Output amount: {}. Fee: {}", input_amount, amount, fee)));
         }
 
-        let outputs_to_spend: HashMap<Outpoint, TransactionOutput> =
-            outputs_to_spend.into_iter().collect();
-
         match Transaction::from_account_to_address(self, &outputs_to_spend, &to, amount, fee) {
             Ok(transaction) => Ok(transaction),
             Err(error) => Err(ErrorWallet::CannotCreateNewTransaction(format!(
@@ -187,6 +272,14 @@ impl Display for Account {
 mod tests {
     use super::*;
 
+    use crate::{
+        block_structure::{
+            block::Block, block_header::BlockHeader, block_version::BlockVersion,
+            compact256::Compact256, hash::HashType, transaction_input::TransactionInput,
+        },
+        messages::compact_size::CompactSize,
+    };
+
     #[test]
     fn test_1_correct_account_creation() {
         let priv_key_bytes: [u8; 32] = [
@@ -199,7 +292,8 @@ mod tests {
             0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
             0x35, 0x14, 0x92, 0x4A, 0x22,
         ];
-        let account = Account::new("test", &priv_key_bytes, &pubkey_bytes).unwrap();
+        let account =
+            Account::new("test", &priv_key_bytes, &pubkey_bytes, Network::Testnet3).unwrap();
         assert_eq!(account.account_name, "test");
         assert_eq!(account.public_key.as_bytes(), pubkey_bytes);
     }
@@ -220,7 +314,7 @@ mod tests {
         let account_name = "test".to_string();
         let private_key = PrivateKey::new(&priv_key_bytes).unwrap();
         let public_key = PublicKey::new(&pubkey_bytes);
-        let address = Address::from_public_key(&public_key).unwrap();
+        let address = Address::from_public_key(&public_key, Network::Testnet3).unwrap();
 
         let mut serialized_fields: Vec<u8> = Vec::new();
         (account_name.len() as u64)
@@ -231,7 +325,8 @@ mod tests {
         public_key.io_serialize(&mut serialized_fields).unwrap();
         address.io_serialize(&mut serialized_fields).unwrap();
 
-        let account = Account::new("test", &priv_key_bytes, &pubkey_bytes).unwrap();
+        let account =
+            Account::new("test", &priv_key_bytes, &pubkey_bytes, Network::Testnet3).unwrap();
 
         let mut serialized_account: Vec<u8> = Vec::new();
         account.io_serialize(&mut serialized_account).unwrap();
@@ -252,7 +347,8 @@ mod tests {
             0x35, 0x14, 0x92, 0x4A, 0x22,
         ];
 
-        let account = Account::new("test", &priv_key_bytes, &pubkey_bytes).unwrap();
+        let account =
+            Account::new("test", &priv_key_bytes, &pubkey_bytes, Network::Testnet3).unwrap();
 
         let mut serialized_transaction: Vec<u8> = Vec::new();
         account.io_serialize(&mut serialized_transaction).unwrap();
@@ -277,6 +373,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -310,6 +407,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -330,4 +428,182 @@ mod tests {
 
         assert!(account_old.verify_transaction_output_ownership(&transaction_output));
     }
+
+    fn create_utxo_set_with_outputs(account: &Account, values: &[i64]) -> (UTXOSet, HashType) {
+        let transaction = Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([1; 32], 0),
+                vec![1, 2, 3],
+                24,
+            )],
+            tx_out: values
+                .iter()
+                .map(|value| TransactionOutput {
+                    value: *value,
+                    pk_script: account.address.generate_script_pubkey_p2pkh(),
+                })
+                .collect(),
+            time: 0,
+        };
+        let transaction_id = transaction.get_tx_id().unwrap();
+
+        let mut block = Block::new(BlockHeader::new(
+            BlockVersion::version(1),
+            [0; 32],
+            [0; 32],
+            0,
+            Compact256::from(u32::MAX),
+            0,
+            CompactSize::new(1),
+        ));
+        block.append_transaction(transaction).unwrap();
+
+        (UTXOSet::new(vec![block]), transaction_id)
+    }
+
+    #[test]
+    fn test_06_frozen_outputs_are_not_spent_automatically() {
+        let account = Account::new(
+            "test",
+            &[
+                0x0A, 0x52, 0x65, 0x08, 0x2E, 0x24, 0x11, 0x5F, 0x77, 0x54, 0x0A, 0xB3, 0xB8, 0xC2,
+                0xB9, 0x20, 0x60, 0xAA, 0x30, 0xD6, 0xD2, 0xB8, 0x1A, 0x08, 0x5D, 0x71, 0xAB, 0x37,
+                0xED, 0xA7, 0x68, 0x91,
+            ],
+            &[
+                0x03, 0xBC, 0x6D, 0x45, 0xD2, 0x10, 0x1E, 0x91, 0x28, 0xDE, 0x14, 0xB5, 0xB6, 0x68,
+                0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
+                0x35, 0x14, 0x92, 0x4A, 0x22,
+            ],
+            Network::Testnet3,
+        )
+        .unwrap();
+        let (utxo_set, transaction_id) = create_utxo_set_with_outputs(&account, &[1000, 5000]);
+
+        let mut frozen_outputs = HashSet::new();
+        frozen_outputs.insert(Outpoint::new(transaction_id, 1));
+
+        let transaction = account
+            .create_transaction(
+                account.address.clone(),
+                800,
+                100,
+                &utxo_set,
+                &frozen_outputs,
+            )
+            .unwrap();
+        assert_eq!(1, transaction.tx_in.len());
+        assert_eq!(
+            Outpoint::new(transaction_id, 0),
+            transaction.tx_in[0].previous_output
+        );
+
+        assert!(matches!(
+            account.create_transaction(
+                account.address.clone(),
+                2000,
+                100,
+                &utxo_set,
+                &frozen_outputs
+            ),
+            Err(ErrorWallet::NotEnoughFunds(_))
+        ));
+    }
+
+    #[test]
+    fn test_07_transaction_spends_only_the_selected_outputs() {
+        let account = Account::new(
+            "test",
+            &[
+                0x0A, 0x52, 0x65, 0x08, 0x2E, 0x24, 0x11, 0x5F, 0x77, 0x54, 0x0A, 0xB3, 0xB8, 0xC2,
+                0xB9, 0x20, 0x60, 0xAA, 0x30, 0xD6, 0xD2, 0xB8, 0x1A, 0x08, 0x5D, 0x71, 0xAB, 0x37,
+                0xED, 0xA7, 0x68, 0x91,
+            ],
+            &[
+                0x03, 0xBC, 0x6D, 0x45, 0xD2, 0x10, 0x1E, 0x91, 0x28, 0xDE, 0x14, 0xB5, 0xB6, 0x68,
+                0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
+                0x35, 0x14, 0x92, 0x4A, 0x22,
+            ],
+            Network::Testnet3,
+        )
+        .unwrap();
+        let (utxo_set, transaction_id) =
+            create_utxo_set_with_outputs(&account, &[1000, 5000, 3000]);
+
+        let selected_outputs = vec![
+            Outpoint::new(transaction_id, 0),
+            Outpoint::new(transaction_id, 2),
+        ];
+        let transaction = account
+            .create_transaction_with_selected_outputs(
+                account.address.clone(),
+                3500,
+                100,
+                &utxo_set,
+                &selected_outputs,
+            )
+            .unwrap();
+
+        assert_eq!(2, transaction.tx_in.len());
+        for input in transaction.tx_in.iter() {
+            assert!(selected_outputs.contains(&input.previous_output));
+        }
+
+        assert!(matches!(
+            account.create_transaction_with_selected_outputs(
+                account.address.clone(),
+                4500,
+                100,
+                &utxo_set,
+                &selected_outputs,
+            ),
+            Err(ErrorWallet::NotEnoughFunds(_))
+        ));
+        assert!(matches!(
+            account.create_transaction_with_selected_outputs(
+                account.address.clone(),
+                100,
+                10,
+                &utxo_set,
+                &[Outpoint::new(transaction_id, 5)],
+            ),
+            Err(ErrorWallet::CannotCreateNewTransaction(_))
+        ));
+    }
+
+    #[test]
+    fn test_08_account_is_imported_from_the_wif_of_its_network() {
+        let priv_key_bytes: [u8; 32] = [
+            0x0a, 0x52, 0x65, 0x08, 0x2e, 0x24, 0x11, 0x5f, 0x77, 0x54, 0x0a, 0xb3, 0xb8, 0xc2,
+            0xb9, 0x20, 0x60, 0xaa, 0x30, 0xd6, 0xd2, 0xb8, 0x1a, 0x08, 0x5d, 0x71, 0xab, 0x37,
+            0xed, 0xa7, 0x68, 0x91,
+        ];
+        let pubkey_bytes: [u8; 33] = [
+            0x03, 0xBC, 0x6D, 0x45, 0xD2, 0x10, 0x1E, 0x91, 0x28, 0xDE, 0x14, 0xB5, 0xB6, 0x68,
+            0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
+            0x35, 0x14, 0x92, 0x4A, 0x22,
+        ];
+        let account =
+            Account::new("test", &priv_key_bytes, &pubkey_bytes, Network::Testnet3).unwrap();
+        let wif = account.get_wif(Network::Testnet3).unwrap();
+
+        let imported_account = Account::from_wif(
+            "test",
+            &wif,
+            PublicKey::new(&pubkey_bytes),
+            Network::Testnet3,
+        )
+        .unwrap();
+
+        assert_eq!(account.private_key, imported_account.private_key);
+        assert_eq!(account.address, imported_account.address);
+        assert!(Account::from_wif(
+            "test",
+            &wif,
+            PublicKey::new(&pubkey_bytes),
+            Network::Mainnet
+        )
+        .is_err());
+    }
 }
@@ -1,4 +1,7 @@
-use super::{error_wallet::ErrorWallet, public_key::PublicKey};
+use super::{
+    error_wallet::ErrorWallet,
+    public_key::{PublicKey, PublicKeyType, PUBLIC_KEY_SIZE},
+};
 
 use crate::serialization::{
     deserializable_fix_size::DeserializableFixSize,
@@ -9,7 +12,10 @@ use crate::serialization::{
     serializable_little_endian::SerializableLittleEndian,
 };
 
-use crate::block_structure::{hash::hash256d_reduce, transaction_output::TransactionOutput};
+use crate::{
+    block_structure::{hash::hash256d_reduce, transaction_output::TransactionOutput},
+    connections::network::Network,
+};
 
 use std::{
     convert::TryInto,
@@ -20,7 +26,10 @@ use std::{
 use bs58::decode;
 
 pub const ADDRESS_SIZE: usize = 25;
-pub const ADDRESS_TESTNET_VERSION_BYTE: u8 = 0x6f;
+
+const MIN_ADDRESS_LENGTH: usize = 26;
+
+const MAX_ADDRESS_LENGTH: usize = 34;
 
 pub type AddressType = [u8; ADDRESS_SIZE];
 
@@ -32,14 +41,14 @@ pub struct Address {
 }
 
 impl Address {
-    /// Creates an address object from a string with a Bitcoin address
+    /// Creates an address object from a string with a Bitcoin address of the given network
     ///
     /// ### Error
-    ///  * `ErrorWallet::CannotDecodeAddress`: It will appear when address for an account cannot be generated
-    pub fn new(address: &str) -> Result<Address, ErrorWallet> {
-        if address.len() != 34 {
+    ///  * `ErrorWallet::CannotDecodeAddress`: It will appear when address for an account cannot be generated or does not belong to the given network
+    pub fn new(address: &str, network: Network) -> Result<Address, ErrorWallet> {
+        if !(MIN_ADDRESS_LENGTH..=MAX_ADDRESS_LENGTH).contains(&address.len()) {
             return Err(ErrorWallet::CannotDecodeAddress(format!(
-                "Invalid address length, expected 34, got {}",
+                "Invalid address length, expected between {MIN_ADDRESS_LENGTH} and {MAX_ADDRESS_LENGTH}, got {}",
                 address.len()
             )));
         }
@@ -61,16 +70,25 @@ impl Address {
                 )))
             }
         };
+        if decoded_list[0] != network.get_address_version_byte() {
+            return Err(ErrorWallet::CannotDecodeAddress(format!(
+                "The address {} does not belong to the network {}",
+                address, network
+            )));
+        }
         Ok(Address {
             address_bytes: decoded_list,
             address_string: address.to_string(),
         })
     }
 
-    /// Generates an Address from a public key
+    /// Generates an Address of the given network from a public key
     /// ### Error
     ///  * `ErrorWallet::CannotCreateAccount`: It will appear when there was a problem hashing
-    pub fn from_public_key(public_key: &PublicKey) -> Result<Address, ErrorWallet> {
+    pub fn from_public_key(
+        public_key: &PublicKey,
+        network: Network,
+    ) -> Result<Address, ErrorWallet> {
         let hashed_pk = match public_key.get_hashed_160() {
             Ok(hashed_pk) => hashed_pk,
             Err(e) => {
@@ -80,9 +98,75 @@ impl Address {
                 )))
             }
         };
+        Self::from_hashed_public_key(&hashed_pk, network)
+    }
+
+    /// Generates an Address of the given network from the receiver of a P2PKH script pubkey
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotDecodeAddress`: It will appear when the script is not a P2PKH script
+    ///  * `ErrorWallet::CannotCreateAccount`: It will appear when there was a problem hashing
+    pub fn from_script_pubkey_p2pkh(
+        pk_script: &[u8],
+        network: Network,
+    ) -> Result<Address, ErrorWallet> {
+        if !Self::is_script_pubkey_p2pkh(pk_script) {
+            return Err(ErrorWallet::CannotDecodeAddress(
+                "The script pubkey is not P2PKH".to_string(),
+            ));
+        }
+        Self::from_hashed_public_key(&pk_script[3..23], network)
+    }
+
+    /// Generates an Address of the given network from the signer of a P2PKH signature script, that has the form `<sig> <pubkey>`
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotDecodeAddress`: It will appear when the script does not end with a compressed public key
+    ///  * `ErrorWallet::CannotCreateAccount`: It will appear when there was a problem hashing
+    pub fn from_signature_script_p2pkh(
+        signature_script: &[u8],
+        network: Network,
+    ) -> Result<Address, ErrorWallet> {
+        let signature_length = match signature_script.first() {
+            Some(signature_length) => *signature_length as usize,
+            None => {
+                return Err(ErrorWallet::CannotDecodeAddress(
+                    "The signature script is empty".to_string(),
+                ))
+            }
+        };
+
+        let public_key_position = signature_length + 1;
+        let public_key_bytes: PublicKeyType = match signature_script.get(public_key_position) {
+            Some(length) if *length as usize == PUBLIC_KEY_SIZE => {
+                match signature_script[public_key_position + 1..].try_into() {
+                    Ok(public_key_bytes) => public_key_bytes,
+                    Err(e) => {
+                        return Err(ErrorWallet::CannotDecodeAddress(format!(
+                            "Cannot extract the public key of the signature script, error : {:?}",
+                            e
+                        )))
+                    }
+                }
+            }
+            _ => {
+                return Err(ErrorWallet::CannotDecodeAddress(
+                    "The signature script is not P2PKH".to_string(),
+                ))
+            }
+        };
+
+        Self::from_public_key(&PublicKey::new(&public_key_bytes), network)
+    }
+
+    /// Generates an Address from the hash 160 of a public key, starting with the version byte of the network
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotCreateAccount`: It will appear when there was a problem hashing
+    fn from_hashed_public_key(hashed_pk: &[u8], network: Network) -> Result<Address, ErrorWallet> {
         let mut extended_hashed_pk = Vec::new();
-        extended_hashed_pk.push(ADDRESS_TESTNET_VERSION_BYTE);
-        extended_hashed_pk.extend_from_slice(&hashed_pk);
+        extended_hashed_pk.push(network.get_address_version_byte());
+        extended_hashed_pk.extend_from_slice(hashed_pk);
         let checksum = match hash256d_reduce(&extended_hashed_pk) {
             Ok(checksum) => checksum,
             Err(e) => {
@@ -102,6 +186,16 @@ impl Address {
         })
     }
 
+    /// Returns true if the script pubkey has the form of a P2PKH script
+    fn is_script_pubkey_p2pkh(pk_script: &[u8]) -> bool {
+        pk_script.len() == 25
+            && pk_script[0] == 0x76
+            && pk_script[1] == 0xa9
+            && pk_script[2] == 0x14
+            && pk_script[23] == 0x88
+            && pk_script[24] == 0xac
+    }
+
     /// Extracts the hashed public key from the address
     fn extract_hashed_pk(&self) -> &[u8] {
         &self.address_bytes[1..21] as &[u8]
@@ -118,15 +212,7 @@ impl Address {
     /// Returns true if the address owns the given transaction output (works for P2PKH) and false otherwise.
     pub fn verify_transaction_ownership(&self, txo: &TransactionOutput) -> bool {
         let pk_script = txo.pk_script.clone();
-        if pk_script.len() != 25 {
-            return false;
-        }
-        if pk_script[0] != 0x76
-            || pk_script[1] != 0xa9
-            || pk_script[2] != 0x14
-            || pk_script[23] != 0x88
-            || pk_script[24] != 0xac
-        {
+        if !Self::is_script_pubkey_p2pkh(&pk_script) {
             return false;
         }
         let hashed_pk = &pk_script[3..23];
@@ -173,7 +259,7 @@ mod tests {
             0x00, 0xf5, 0x4a, 0x58, 0x51, 0xe9, 0x37, 0x2b, 0x87, 0x81, 0x0a, 0x8e, 0x60, 0xcd,
             0xd2, 0xe7, 0xcf, 0xd8, 0x0b, 0x6e, 0x31, 0xc7, 0xf1, 0x8f, 0xe8,
         ];
-        let address = Address::new(&address).unwrap();
+        let address = Address::new(&address, Network::Mainnet).unwrap();
         assert!(address.address_string == "1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs");
         assert!(address.address_bytes == address_bytes);
     }
@@ -185,7 +271,7 @@ mod tests {
             0xf5, 0x4a, 0x58, 0x51, 0xe9, 0x37, 0x2b, 0x87, 0x81, 0x0a, 0x8e, 0x60, 0xcd, 0xd2,
             0xe7, 0xcf, 0xd8, 0x0b, 0x6e, 0x31,
         ];
-        let address = Address::new(&address).unwrap();
+        let address = Address::new(&address, Network::Mainnet).unwrap();
         assert!(address.extract_hashed_pk() == hashed_pk);
     }
 
@@ -197,14 +283,16 @@ mod tests {
             0x35, 0x14, 0x92, 0x4A, 0x22,
         ];
         let pubkey = PublicKey::new(&pubkey_bytes);
-        let address = Address::from_public_key(&pubkey).unwrap();
-        let actual_address = Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw").unwrap();
+        let address = Address::from_public_key(&pubkey, Network::Testnet3).unwrap();
+        let actual_address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
         assert_eq!(address, actual_address);
     }
 
     #[test]
     fn test_04_correct_verify_transaction_ownership() {
-        let address = Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw").unwrap();
+        let address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
 
         let transaction_bytes: Vec<u8> = vec![
             0x01, 0x00, 0x00, 0x00, 0x01, 0x20, 0x25, 0xEF, 0x69, 0x2C, 0xA9, 0x87, 0xB3, 0x9A,
@@ -223,4 +311,72 @@ mod tests {
 
         assert!(address.verify_transaction_ownership(&transaction_output));
     }
+
+    #[test]
+    fn test_05_correct_address_creation_from_script_pubkey() {
+        let address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
+        let script_pubkey = address.generate_script_pubkey_p2pkh();
+
+        assert_eq!(
+            Address::from_script_pubkey_p2pkh(&script_pubkey, Network::Testnet3).unwrap(),
+            address
+        );
+        assert!(Address::from_script_pubkey_p2pkh(&[0x00, 0x14], Network::Testnet3).is_err());
+    }
+
+    #[test]
+    fn test_06_correct_address_creation_from_signature_script() {
+        let pubkey_bytes: [u8; 33] = [
+            0x03, 0xBC, 0x6D, 0x45, 0xD2, 0x10, 0x1E, 0x91, 0x28, 0xDE, 0x14, 0xB5, 0xB6, 0x68,
+            0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
+            0x35, 0x14, 0x92, 0x4A, 0x22,
+        ];
+        let mut signature_script: Vec<u8> = vec![0x03, 0x01, 0x02, 0x03, 0x21];
+        signature_script.extend_from_slice(&pubkey_bytes);
+
+        let address =
+            Address::from_signature_script_p2pkh(&signature_script, Network::Testnet3).unwrap();
+        let actual_address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
+        assert_eq!(address, actual_address);
+        assert!(Address::from_signature_script_p2pkh(&[0x01, 0x02], Network::Testnet3).is_err());
+    }
+
+    #[test]
+    fn test_07_correct_mainnet_address_creation_from_pubkey() {
+        let pubkey_bytes: [u8; 33] = [
+            0x03, 0xBC, 0x6D, 0x45, 0xD2, 0x10, 0x1E, 0x91, 0x28, 0xDE, 0x14, 0xB5, 0xB6, 0x68,
+            0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
+            0x35, 0x14, 0x92, 0x4A, 0x22,
+        ];
+        let pubkey = PublicKey::new(&pubkey_bytes);
+
+        let mainnet_address = Address::from_public_key(&pubkey, Network::Mainnet).unwrap();
+        let regtest_address = Address::from_public_key(&pubkey, Network::Regtest).unwrap();
+
+        assert_eq!(
+            Address::new("17tPWSVaEua7ZPHrz6HksmtBwGT1wv1tFs", Network::Mainnet).unwrap(),
+            mainnet_address
+        );
+        assert_eq!(
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap(),
+            regtest_address
+        );
+        assert_eq!(
+            mainnet_address.generate_script_pubkey_p2pkh(),
+            regtest_address.generate_script_pubkey_p2pkh()
+        );
+    }
+
+    #[test]
+    fn test_08_address_is_only_accepted_in_its_network() {
+        let short_address = Address::new("1111111111111111111114oLvT2", Network::Mainnet).unwrap();
+        assert_eq!(short_address.extract_hashed_pk(), [0; 20]);
+
+        assert!(Address::new("1PMycacnJaSqwwJqjawXBErnLsZ7RkXUAs", Network::Testnet3).is_err());
+        assert!(Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Mainnet).is_err());
+        assert!(Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Regtest).is_ok());
+        assert!(Address::new(&"1".repeat(MIN_ADDRESS_LENGTH - 1), Network::Mainnet).is_err());
+    }
 }
@@ -0,0 +1,220 @@
+use super::{address::Address, contact::Contact, error_wallet::ErrorWallet};
+
+use crate::{
+    configurations::try_default::TryDefault,
+    connections::network::Network,
+    serialization::{
+        deserializable_internal_order::DeserializableInternalOrder,
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization,
+        serializable_internal_order::SerializableInternalOrder,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::io::{Read, Write};
+
+/// It's the collection of contacts known by the user, identified by their name
+#[derive(Debug, PartialEq)]
+pub struct AddressBook {
+    contacts: Vec<Contact>,
+}
+
+impl AddressBook {
+    pub fn new(contacts: Vec<Contact>) -> AddressBook {
+        AddressBook { contacts }
+    }
+
+    /// Appends a contact to the address book
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::ContactAlreadyExists`: It will appear when there is already a contact with the same name
+    pub fn add_contact(&mut self, contact: Contact) -> Result<(), ErrorWallet> {
+        if self.get_contact_with_name(&contact.name).is_some() {
+            return Err(ErrorWallet::ContactAlreadyExists(contact.name));
+        }
+
+        self.contacts.push(contact);
+        Ok(())
+    }
+
+    /// Replaces the contact with the same name as the one given
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::ContactNotFound`: It will appear when there is no contact with the given name
+    pub fn update_contact(&mut self, contact: Contact) -> Result<(), ErrorWallet> {
+        match self.contacts.iter_mut().find(|x| x.name == contact.name) {
+            Some(old_contact) => {
+                *old_contact = contact;
+                Ok(())
+            }
+            None => Err(ErrorWallet::ContactNotFound(contact.name)),
+        }
+    }
+
+    /// Removes the contact with the given name and returns it
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::ContactNotFound`: It will appear when there is no contact with the given name
+    pub fn remove_contact(&mut self, name: &str) -> Result<Contact, ErrorWallet> {
+        match self.contacts.iter().position(|x| x.name == name) {
+            Some(position) => Ok(self.contacts.remove(position)),
+            None => Err(ErrorWallet::ContactNotFound(name.to_string())),
+        }
+    }
+
+    /// Returns the contacts from the address book
+    pub fn get_contacts(&self) -> &Vec<Contact> {
+        &self.contacts
+    }
+
+    /// Returns a contact from the address book by its name
+    pub fn get_contact_with_name(&self, name: &str) -> Option<&Contact> {
+        self.contacts.iter().find(|x| x.name == name)
+    }
+
+    /// Returns the address represented by the given text, it can be an address of the given network or the name of a contact
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotDecodeAddress`: It will appear when the text is neither a valid address nor a contact name
+    pub fn get_address(
+        &self,
+        address_or_name: &str,
+        network: Network,
+    ) -> Result<Address, ErrorWallet> {
+        match Address::new(address_or_name, network) {
+            Ok(address) => Ok(address),
+            Err(error) => match self.get_contact_with_name(address_or_name) {
+                Some(contact) => Ok(contact.address.clone()),
+                None => Err(error),
+            },
+        }
+    }
+}
+
+impl TryDefault for AddressBook {
+    type Error = ErrorWallet;
+
+    fn try_default() -> Result<Self, Self::Error> {
+        Ok(AddressBook::new(Vec::new()))
+    }
+}
+
+impl SerializableInternalOrder for AddressBook {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        (self.contacts.len() as u64).le_serialize(stream)?;
+
+        for contact in &self.contacts {
+            contact.io_serialize(stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for AddressBook {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let contacts_len = u64::le_deserialize(stream)?;
+
+        let mut contacts: Vec<Contact> = Vec::new();
+        for _ in 0..contacts_len {
+            contacts.push(Contact::io_deserialize(stream)?);
+        }
+
+        Ok(AddressBook::new(contacts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_01_correct_address_book_serialization() {
+        let address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
+        let address_book = AddressBook::new(vec![
+            Contact::new("Alice", address.clone(), Some("Rent".to_string())),
+            Contact::new("Bob", address, None),
+        ]);
+
+        let mut serialized_address_book = Vec::new();
+        address_book
+            .io_serialize(&mut serialized_address_book)
+            .unwrap();
+
+        let deserialized_address_book =
+            AddressBook::io_deserialize(&mut serialized_address_book.as_slice()).unwrap();
+
+        assert_eq!(address_book, deserialized_address_book);
+    }
+
+    #[test]
+    fn test_02_cannot_add_two_contacts_with_the_same_name() {
+        let address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
+        let mut address_book = AddressBook::new(Vec::new());
+
+        address_book
+            .add_contact(Contact::new("Alice", address.clone(), None))
+            .unwrap();
+
+        assert_eq!(
+            Err(ErrorWallet::ContactAlreadyExists("Alice".to_string())),
+            address_book.add_contact(Contact::new("Alice", address, None))
+        );
+        assert_eq!(1, address_book.get_contacts().len());
+    }
+
+    #[test]
+    fn test_03_correct_contact_update_and_removal() {
+        let address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
+        let mut address_book = AddressBook::new(Vec::new());
+
+        address_book
+            .add_contact(Contact::new("Alice", address.clone(), None))
+            .unwrap();
+        address_book
+            .update_contact(Contact::new(
+                "Alice",
+                address.clone(),
+                Some("Rent".to_string()),
+            ))
+            .unwrap();
+
+        assert_eq!(
+            Some(&"Rent".to_string()),
+            address_book
+                .get_contact_with_name("Alice")
+                .unwrap()
+                .note
+                .as_ref()
+        );
+
+        let removed_contact = address_book.remove_contact("Alice").unwrap();
+        assert_eq!(address, removed_contact.address);
+        assert!(address_book.get_contacts().is_empty());
+        assert_eq!(
+            Err(ErrorWallet::ContactNotFound("Alice".to_string())),
+            address_book.remove_contact("Alice")
+        );
+    }
+
+    #[test]
+    fn test_04_address_from_contact_name_or_address() {
+        let address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
+        let address_book = AddressBook::new(vec![Contact::new("Alice", address.clone(), None)]);
+
+        assert_eq!(
+            Ok(address.clone()),
+            address_book.get_address("Alice", Network::Testnet3)
+        );
+        assert_eq!(
+            Ok(address),
+            address_book.get_address("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3)
+        );
+        assert!(address_book.get_address("Bob", Network::Testnet3).is_err());
+    }
+}
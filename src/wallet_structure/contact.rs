@@ -0,0 +1,123 @@
+use super::address::Address;
+
+use crate::serialization::{
+    deserializable_fix_size::DeserializableFixSize,
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::{
+    fmt::Display,
+    io::{Read, Write},
+};
+
+/// It's the representation of a known address with a name to identify it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contact {
+    pub name: String,
+    pub address: Address,
+    pub note: Option<String>,
+}
+
+impl Contact {
+    pub fn new(name: &str, address: Address, note: Option<String>) -> Contact {
+        let note = match note {
+            Some(note) if !note.is_empty() => Some(note),
+            _ => None,
+        };
+
+        Contact {
+            name: name.to_string(),
+            address,
+            note,
+        }
+    }
+}
+
+impl SerializableInternalOrder for Contact {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        (self.name.len() as u64).le_serialize(stream)?;
+        self.name.le_serialize(stream)?;
+
+        self.address.io_serialize(stream)?;
+
+        let note = match &self.note {
+            Some(note) => note.clone(),
+            None => "".to_string(),
+        };
+        (note.len() as u64).le_serialize(stream)?;
+        note.le_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for Contact {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let name_len = u64::le_deserialize(stream)? as usize;
+        let name = String::deserialize_fix_size(stream, name_len)?;
+
+        let address = Address::io_deserialize(stream)?;
+
+        let note_len = u64::le_deserialize(stream)? as usize;
+        let note = String::deserialize_fix_size(stream, note_len)?;
+
+        Ok(Contact::new(&name, address, Some(note)))
+    }
+}
+
+impl Display for Contact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.note {
+            Some(note) => write!(
+                f,
+                "Contact Name: {}\n    with address: {}\n    note: {}",
+                self.name, self.address, note
+            ),
+            None => write!(
+                f,
+                "Contact Name: {}\n    with address: {}",
+                self.name, self.address
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connections::network::Network;
+
+    #[test]
+    fn test_01_correct_contact_serialization() {
+        let address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
+        let contact = Contact::new("Alice", address, Some("Rent".to_string()));
+
+        let mut serialized_contact = Vec::new();
+        contact.io_serialize(&mut serialized_contact).unwrap();
+
+        let deserialized_contact =
+            Contact::io_deserialize(&mut serialized_contact.as_slice()).unwrap();
+
+        assert_eq!(contact, deserialized_contact);
+    }
+
+    #[test]
+    fn test_02_empty_note_is_not_stored() {
+        let address =
+            Address::new("mnQLoVaZ3w1NLVmUhfG8hh6WoG3iu7cnNw", Network::Testnet3).unwrap();
+        let contact = Contact::new("Alice", address, Some("".to_string()));
+
+        let mut serialized_contact = Vec::new();
+        contact.io_serialize(&mut serialized_contact).unwrap();
+
+        let deserialized_contact =
+            Contact::io_deserialize(&mut serialized_contact.as_slice()).unwrap();
+
+        assert_eq!(None, deserialized_contact.note);
+    }
+}
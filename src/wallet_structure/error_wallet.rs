@@ -21,4 +21,10 @@ pub enum ErrorWallet {
 
     /// It will appear when a problem appears when trying to create an address from a public key
     CannotCreateAddress(String),
+
+    /// It will appear when a contact with the same name is already in the address book
+    ContactAlreadyExists(String),
+
+    /// It will appear when there is no contact with the given name in the address book
+    ContactNotFound(String),
 }
@@ -3,6 +3,14 @@ pub mod wallet;
 pub mod account;
 
 pub mod address;
+pub mod address_book;
+pub mod contact;
 pub mod error_wallet;
 pub mod private_key;
 pub mod public_key;
+
+pub mod output_record;
+
+pub mod transaction_direction;
+pub mod transaction_history;
+pub mod transaction_record;
@@ -0,0 +1,43 @@
+use crate::block_structure::{outpoint::Outpoint, transaction_output::TransactionOutput};
+
+use std::fmt::Display;
+
+const FROM_SATOSHIS_TO_TBTC: f64 = 100_000_000.0;
+
+/// It's the representation of an unspent output owned by an account
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputRecord {
+    pub outpoint: Outpoint,
+    pub output: TransactionOutput,
+    pub confirmations: u64,
+    pub frozen: bool,
+    pub label: Option<String>,
+}
+
+impl OutputRecord {
+    /// Returns the value of the output in TBTC
+    pub fn get_value_in_tbtc(&self) -> f64 {
+        self.output.value as f64 / FROM_SATOSHIS_TO_TBTC
+    }
+}
+
+impl Display for OutputRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Output {}\n    Amount: {}\n    Confirmations: {}",
+            self.outpoint,
+            self.get_value_in_tbtc(),
+            self.confirmations,
+        )?;
+
+        if self.frozen {
+            write!(f, "\n    Frozen")?;
+        }
+
+        match &self.label {
+            Some(label) => write!(f, "\n    Label: {label}"),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use super::error_wallet::ErrorWallet;
 
+use crate::{block_structure::hash::hash256d_reduce, connections::network::Network};
+
 use crate::serialization::{
     deserializable_internal_order::DeserializableInternalOrder,
     error_serialization::ErrorSerialization,
@@ -16,6 +18,11 @@ use secp256k1::{Secp256k1, SecretKey};
 pub const PRIVATE_KEY_SIZE: usize = 32;
 pub type PrivateKeyType = [u8; PRIVATE_KEY_SIZE];
 
+/// The flag that follows the key in the wallet import format when its public key is compressed
+const WIF_COMPRESSED_FLAG: u8 = 0x01;
+
+const CHECKSUM_SIZE: usize = 4;
+
 /// It's the internal representation of a private key for an account
 #[derive(Debug, Clone, PartialEq)]
 pub struct PrivateKey {
@@ -41,6 +48,91 @@ impl PrivateKey {
         Ok(PrivateKey { key })
     }
 
+    /// Creates a private key object from a string with a WIF (compressed or uncompressed) of the given network
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotGeneratePrivateKey`: It will appear when the WIF cannot be decoded, its checksum is wrong or it does not belong to the given network
+    pub fn from_wif(wif: &str, network: Network) -> Result<PrivateKey, ErrorWallet> {
+        let decoded_wif = match bs58::decode(wif).into_vec() {
+            Ok(decoded_wif) => decoded_wif,
+            Err(e) => {
+                return Err(ErrorWallet::CannotGeneratePrivateKey(format!(
+                    "Cannot decode WIF {}, error : {:?}",
+                    wif, e
+                )))
+            }
+        };
+
+        let payload_size = decoded_wif.len().saturating_sub(CHECKSUM_SIZE);
+        let (payload, checksum) = decoded_wif.split_at(payload_size);
+        let is_valid_size = match payload.len() {
+            len if len == PRIVATE_KEY_SIZE + 1 => true,
+            len if len == PRIVATE_KEY_SIZE + 2 => payload[len - 1] == WIF_COMPRESSED_FLAG,
+            _ => false,
+        };
+        if !is_valid_size {
+            return Err(ErrorWallet::CannotGeneratePrivateKey(format!(
+                "Invalid WIF length, got {}",
+                decoded_wif.len()
+            )));
+        }
+
+        match hash256d_reduce(payload) {
+            Ok(expected_checksum) if expected_checksum == checksum => {}
+            _ => {
+                return Err(ErrorWallet::CannotGeneratePrivateKey(format!(
+                    "The checksum of the WIF {} is not valid",
+                    wif
+                )))
+            }
+        }
+
+        if payload[0] != network.get_wif_version_byte() {
+            return Err(ErrorWallet::CannotGeneratePrivateKey(format!(
+                "The WIF {} does not belong to the network {}",
+                wif, network
+            )));
+        }
+
+        let mut private_key_bytes: PrivateKeyType = [0; PRIVATE_KEY_SIZE];
+        private_key_bytes.copy_from_slice(&payload[1..PRIVATE_KEY_SIZE + 1]);
+        PrivateKey::new(&private_key_bytes)
+    }
+
+    /// Creates a private key object from a string with a WIF of the given network or with the key in hexadecimal
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotGeneratePrivateKey`: It will appear when the string is neither a WIF of the network nor a key in hexadecimal
+    pub fn from_string(value: &str, network: Network) -> Result<PrivateKey, ErrorWallet> {
+        match PrivateKey::from_wif(value, network) {
+            Ok(private_key) => Ok(private_key),
+            Err(_) => PrivateKey::try_from(value),
+        }
+    }
+
+    /// Returns the private key in the wallet import format of the given network, for a compressed public key
+    ///
+    /// ### Error
+    ///  * `ErrorWallet::CannotGeneratePrivateKey`: It will appear when there was a problem hashing
+    pub fn to_wif(&self, network: Network) -> Result<String, ErrorWallet> {
+        let mut payload = vec![network.get_wif_version_byte()];
+        payload.extend_from_slice(&self.as_bytes());
+        payload.push(WIF_COMPRESSED_FLAG);
+
+        let checksum = match hash256d_reduce(&payload) {
+            Ok(checksum) => checksum,
+            Err(e) => {
+                return Err(ErrorWallet::CannotGeneratePrivateKey(format!(
+                    "Cannot hash private key, error : {:?}",
+                    e
+                )))
+            }
+        };
+        payload.extend_from_slice(&checksum);
+
+        Ok(bs58::encode(payload).into_string())
+    }
+
     /// Returns the private key as a byte array
     fn as_bytes(&self) -> PrivateKeyType {
         self.key.secret_bytes()
@@ -125,4 +217,48 @@ mod tests {
         let signing_bytes = private_key.as_bytes();
         assert!(signing_bytes == private_key_bytes);
     }
+
+    #[test]
+    fn test_02_correct_key_creation_from_wif() {
+        let private_key = PrivateKey::try_from(
+            "0C28FCA386C7A227600B2FE50B7CAE11EC86D3BF1FBE471BE89827E19D72AA1D",
+        )
+        .unwrap();
+
+        assert_eq!(
+            private_key,
+            PrivateKey::from_wif(
+                "5HueCGU8rMjxEXxiPuD5BDku4MkFqeZyd4dZ1jvhTVqvbTLvyTJ",
+                Network::Mainnet
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "KwdMAjGmerYanjeui5SHS7JkmpZvVipYvB2LJGU1ZxJwYvP98617",
+            private_key.to_wif(Network::Mainnet).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_03_wif_of_another_network_is_rejected() {
+        let private_key = PrivateKey::try_from(
+            "0C28FCA386C7A227600B2FE50B7CAE11EC86D3BF1FBE471BE89827E19D72AA1D",
+        )
+        .unwrap();
+        let wif = private_key.to_wif(Network::Testnet3).unwrap();
+
+        assert!(PrivateKey::from_wif(&wif, Network::Mainnet).is_err());
+        assert_eq!(
+            private_key,
+            PrivateKey::from_string(&wif, Network::Signet).unwrap()
+        );
+        assert_eq!(
+            private_key,
+            PrivateKey::from_string(
+                "0C28FCA386C7A227600B2FE50B7CAE11EC86D3BF1FBE471BE89827E19D72AA1D",
+                Network::Mainnet
+            )
+            .unwrap()
+        );
+    }
 }
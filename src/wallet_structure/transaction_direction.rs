@@ -0,0 +1,53 @@
+use crate::serialization::{
+    deserializable_internal_order::DeserializableInternalOrder,
+    deserializable_little_endian::DeserializableLittleEndian,
+    error_serialization::ErrorSerialization,
+    serializable_internal_order::SerializableInternalOrder,
+    serializable_little_endian::SerializableLittleEndian,
+};
+
+use std::{
+    fmt::{Display, Formatter, Result},
+    io::{Read, Write},
+};
+
+const INCOMING: &str = "Incoming";
+const OUTGOING: &str = "Outgoing";
+
+/// Represents if a transaction gives funds to an account or spends funds from it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransactionDirection {
+    Incoming,
+    Outgoing,
+}
+
+impl SerializableInternalOrder for TransactionDirection {
+    fn io_serialize(&self, stream: &mut dyn Write) -> std::result::Result<(), ErrorSerialization> {
+        let value: u8 = match self {
+            TransactionDirection::Incoming => 0,
+            TransactionDirection::Outgoing => 1,
+        };
+        value.le_serialize(stream)
+    }
+}
+
+impl DeserializableInternalOrder for TransactionDirection {
+    fn io_deserialize(stream: &mut dyn Read) -> std::result::Result<Self, ErrorSerialization> {
+        match u8::le_deserialize(stream)? {
+            0 => Ok(TransactionDirection::Incoming),
+            1 => Ok(TransactionDirection::Outgoing),
+            value => Err(ErrorSerialization::ErrorInDeserialization(format!(
+                "Unknown transaction direction {value}"
+            ))),
+        }
+    }
+}
+
+impl Display for TransactionDirection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            TransactionDirection::Incoming => write!(f, "{INCOMING}"),
+            TransactionDirection::Outgoing => write!(f, "{OUTGOING}"),
+        }
+    }
+}
@@ -0,0 +1,661 @@
+use super::{
+    account::Account, address::Address, error_wallet::ErrorWallet,
+    transaction_direction::TransactionDirection, transaction_record::TransactionRecord,
+};
+
+use crate::block_structure::{
+    block::Block, block_chain::BlockChain, filter_chain::FilterChain, hash::HashType,
+    outpoint::Outpoint, transaction::Transaction, transaction_output::TransactionOutput,
+};
+
+use crate::{
+    configurations::try_default::TryDefault,
+    connections::network::Network,
+    serialization::{
+        deserializable_internal_order::DeserializableInternalOrder,
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization,
+        serializable_internal_order::SerializableInternalOrder,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+/// It's the index of the transactions, received and sent, of the accounts of the wallet
+#[derive(Debug, Clone)]
+pub struct TransactionHistory {
+    accounts: Vec<Account>,
+    owned_outputs: HashMap<Outpoint, TransactionOutput>,
+    records: Vec<TransactionRecord>,
+    tip_height: u64,
+    network: Network,
+}
+
+impl TransactionHistory {
+    /// Creates the history of the given accounts from the blocks in the block chain, scanning only the ones
+    /// whose filters may match the accounts if the filters are given. The addresses of the counterparties
+    /// are shown in the given network
+    pub fn new(
+        accounts: &[Account],
+        block_chain: &BlockChain,
+        filter_chain: Option<&FilterChain>,
+        network: Network,
+    ) -> TransactionHistory {
+        let mut transaction_history = TransactionHistory {
+            accounts: Vec::new(),
+            owned_outputs: HashMap::new(),
+            records: Vec::new(),
+            tip_height: block_chain.get_height(),
+            network,
+        };
+
+        for account in accounts {
+            transaction_history.track_account(account, block_chain, filter_chain);
+        }
+
+        transaction_history
+    }
+
+    /// Starts recording the transactions of the account, scanning the block chain if it was not tracked already.
+    /// If the filters of the blocks are given, only the blocks whose filters may match the account are scanned
+    pub fn track_account(
+        &mut self,
+        account: &Account,
+        block_chain: &BlockChain,
+        filter_chain: Option<&FilterChain>,
+    ) {
+        if self.is_tracking(account) {
+            return;
+        }
+
+        let scripts = vec![account.address.generate_script_pubkey_p2pkh()];
+        for (block, height) in block_chain.get_all_blocks_with_height() {
+            let may_match = match (filter_chain, block.header.get_hash256d()) {
+                (Some(filter_chain), Ok(block_hash)) => {
+                    filter_chain.may_match(&block_hash, &scripts)
+                }
+                _ => true,
+            };
+
+            if may_match {
+                self.update_account_with_block(account, &block, height);
+            }
+        }
+
+        self.accounts.push(account.clone());
+        self.tip_height = self.tip_height.max(block_chain.get_height());
+    }
+
+    /// Returns true if the transactions of the account are being recorded
+    pub fn is_tracking(&self, account: &Account) -> bool {
+        self.accounts
+            .iter()
+            .any(|tracked_account| tracked_account.address == account.address)
+    }
+
+    /// Updates the history of all the tracked accounts with the transactions of a new block
+    pub fn update_with_block(&mut self, block: &Block, height: u64) {
+        for account in self.accounts.clone().iter() {
+            self.update_account_with_block(account, block, height);
+        }
+
+        self.tip_height = self.tip_height.max(height);
+    }
+
+    /// Updates the height of the last block seen with the one of the block chain, so the confirmations are up to date
+    pub fn update_tip_height(&mut self, block_chain: &BlockChain) {
+        self.tip_height = self.tip_height.max(block_chain.get_height());
+    }
+
+    /// Returns the confirmations of a transaction recorded in the history, or zero if it is not in a block yet
+    pub fn get_confirmations(&self, transaction_id: &HashType) -> u64 {
+        match self
+            .records
+            .iter()
+            .find(|record| record.transaction_id == *transaction_id)
+        {
+            Some(record) => (self.tip_height + 1).saturating_sub(record.block_height),
+            None => 0,
+        }
+    }
+
+    /// Returns the records of the account with the confirmations up to the last block seen
+    pub fn get_account_records(&self, account: &Account) -> Vec<TransactionRecord> {
+        self.records
+            .iter()
+            .filter(|record| record.address == account.address)
+            .map(|record| {
+                let mut record = record.clone();
+                record.confirmations = (self.tip_height + 1).saturating_sub(record.block_height);
+                record
+            })
+            .collect()
+    }
+
+    /// Returns the height of the last block seen
+    pub fn get_tip_height(&self) -> u64 {
+        self.tip_height
+    }
+
+    /// Returns the network in which the addresses of the counterparties are shown
+    pub fn get_network(&self) -> Network {
+        self.network
+    }
+
+    /// Records the transactions of the block that involve the given account
+    fn update_account_with_block(&mut self, account: &Account, block: &Block, height: u64) {
+        for transaction in block.transactions.iter() {
+            let transaction_id = match transaction.get_tx_id() {
+                Ok(transaction_id) => transaction_id,
+                Err(_) => continue,
+            };
+
+            let received = self.add_owned_outputs(account, transaction, transaction_id);
+            let (spent, all_inputs_known) = self.get_spent_amount(account, transaction);
+
+            if received == 0 && spent == 0 {
+                continue;
+            }
+
+            if self.records.iter().any(|record| {
+                record.transaction_id == transaction_id && record.address == account.address
+            }) {
+                continue;
+            }
+
+            let (direction, fee, counterparty) = match spent > 0 {
+                true => {
+                    let fee = match all_inputs_known {
+                        true => self.get_fee(transaction),
+                        false => 0,
+                    };
+                    let counterparty = get_receiver(account, transaction, self.network);
+                    (TransactionDirection::Outgoing, fee, counterparty)
+                }
+                false => (
+                    TransactionDirection::Incoming,
+                    0,
+                    get_sender(transaction, self.network),
+                ),
+            };
+
+            self.records.push(TransactionRecord {
+                address: account.address.clone(),
+                transaction_id,
+                timestamp: block.header.time,
+                direction,
+                net_amount: received - spent,
+                fee,
+                block_height: height,
+                confirmations: 0,
+                counterparty,
+                label: None,
+            });
+        }
+    }
+
+    /// Saves the outputs of the transaction owned by the account and returns the amount received
+    fn add_owned_outputs(
+        &mut self,
+        account: &Account,
+        transaction: &Transaction,
+        transaction_id: HashType,
+    ) -> i64 {
+        let mut received: i64 = 0;
+        for (index, output) in transaction.tx_out.iter().enumerate() {
+            if account.verify_transaction_output_ownership(output) {
+                self.owned_outputs
+                    .insert(Outpoint::new(transaction_id, index as u32), output.clone());
+                received += output.value;
+            }
+        }
+        received
+    }
+
+    /// Returns the amount spent from the account and if every input of the transaction is known
+    fn get_spent_amount(&self, account: &Account, transaction: &Transaction) -> (i64, bool) {
+        let mut spent: i64 = 0;
+        let mut all_inputs_known = true;
+        for input in transaction.tx_in.iter() {
+            match self.owned_outputs.get(&input.previous_output) {
+                Some(output) => {
+                    if account.verify_transaction_output_ownership(output) {
+                        spent += output.value;
+                    }
+                }
+                None => all_inputs_known = false,
+            }
+        }
+        (spent, all_inputs_known)
+    }
+
+    /// Returns the fee of a transaction whose inputs are all known
+    fn get_fee(&self, transaction: &Transaction) -> i64 {
+        let mut input_amount: i64 = 0;
+        for input in transaction.tx_in.iter() {
+            if let Some(output) = self.owned_outputs.get(&input.previous_output) {
+                input_amount += output.value;
+            }
+        }
+
+        let output_amount: i64 = transaction.tx_out.iter().map(|output| output.value).sum();
+        input_amount - output_amount
+    }
+}
+
+impl TryDefault for TransactionHistory {
+    type Error = ErrorWallet;
+
+    fn try_default() -> Result<Self, Self::Error> {
+        Ok(TransactionHistory {
+            accounts: Vec::new(),
+            owned_outputs: HashMap::new(),
+            records: Vec::new(),
+            tip_height: 0,
+            network: Network::default(),
+        })
+    }
+}
+
+impl SerializableInternalOrder for TransactionHistory {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.network.io_serialize(stream)?;
+        self.tip_height.le_serialize(stream)?;
+
+        (self.accounts.len() as u64).le_serialize(stream)?;
+        for account in &self.accounts {
+            account.io_serialize(stream)?;
+        }
+
+        (self.owned_outputs.len() as u64).le_serialize(stream)?;
+        for (outpoint, output) in &self.owned_outputs {
+            outpoint.io_serialize(stream)?;
+            output.io_serialize(stream)?;
+        }
+
+        (self.records.len() as u64).le_serialize(stream)?;
+        for record in &self.records {
+            record.io_serialize(stream)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for TransactionHistory {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let network = Network::io_deserialize(stream)?;
+        let tip_height = u64::le_deserialize(stream)?;
+
+        let accounts_len = u64::le_deserialize(stream)?;
+        let mut accounts: Vec<Account> = Vec::new();
+        for _ in 0..accounts_len {
+            accounts.push(Account::io_deserialize(stream)?);
+        }
+
+        let owned_outputs_len = u64::le_deserialize(stream)?;
+        let mut owned_outputs: HashMap<Outpoint, TransactionOutput> = HashMap::new();
+        for _ in 0..owned_outputs_len {
+            let outpoint = Outpoint::io_deserialize(stream)?;
+            owned_outputs.insert(outpoint, TransactionOutput::io_deserialize(stream)?);
+        }
+
+        let records_len = u64::le_deserialize(stream)?;
+        let mut records: Vec<TransactionRecord> = Vec::new();
+        for _ in 0..records_len {
+            records.push(TransactionRecord::io_deserialize(stream)?);
+        }
+
+        Ok(TransactionHistory {
+            accounts,
+            owned_outputs,
+            records,
+            tip_height,
+            network,
+        })
+    }
+}
+
+/// Returns the address of the first receiver of the transaction that is not the account, if it can be decoded
+fn get_receiver(account: &Account, transaction: &Transaction, network: Network) -> Option<Address> {
+    transaction
+        .tx_out
+        .iter()
+        .filter(|output| !account.verify_transaction_output_ownership(output))
+        .find_map(|output| Address::from_script_pubkey_p2pkh(&output.pk_script, network).ok())
+}
+
+/// Returns the address of the first signer of the transaction, if it can be decoded
+fn get_sender(transaction: &Transaction, network: Network) -> Option<Address> {
+    transaction.tx_in.iter().find_map(|input| {
+        Address::from_signature_script_p2pkh(&input.signature_script, network).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        block_structure::{
+            block_header::BlockHeader, block_version::BlockVersion, compact256::Compact256,
+            transaction_input::TransactionInput,
+        },
+        messages::compact_size::CompactSize,
+    };
+
+    fn create_account() -> Account {
+        let private_key_bytes: [u8; 32] = [
+            0x0a, 0x52, 0x65, 0x08, 0x2e, 0x24, 0x11, 0x5f, 0x77, 0x54, 0x0a, 0xb3, 0xb8, 0xc2,
+            0xb9, 0x20, 0x60, 0xaa, 0x30, 0xd6, 0xd2, 0xb8, 0x1a, 0x08, 0x5d, 0x71, 0xab, 0x37,
+            0xed, 0xa7, 0x68, 0x91,
+        ];
+        let public_key_bytes: [u8; 33] = [
+            0x03, 0xBC, 0x6D, 0x45, 0xD2, 0x10, 0x1E, 0x91, 0x28, 0xDE, 0x14, 0xB5, 0xB6, 0x68,
+            0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
+            0x35, 0x14, 0x92, 0x4A, 0x22,
+        ];
+        Account::new(
+            "test",
+            &private_key_bytes,
+            &public_key_bytes,
+            Network::Testnet3,
+        )
+        .unwrap()
+    }
+
+    fn create_transaction(
+        previous_output: Outpoint,
+        outputs: Vec<TransactionOutput>,
+    ) -> Transaction {
+        Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(previous_output, vec![1, 2, 3], 24)],
+            tx_out: outputs,
+            time: 0,
+        }
+    }
+
+    fn create_block(previous_header: HashType, transactions: Vec<Transaction>, time: u32) -> Block {
+        let mut block = Block::new(BlockHeader::new(
+            BlockVersion::version(1),
+            previous_header,
+            [0; 32],
+            time,
+            Compact256::from(u32::MAX),
+            0,
+            CompactSize::new(transactions.len() as u64),
+        ));
+        for transaction in transactions {
+            block.append_transaction(transaction).unwrap();
+        }
+        block
+    }
+
+    #[test]
+    fn test_01_correct_record_of_incoming_transaction() {
+        let account = create_account();
+        let block_chain = BlockChain::new(create_block([0; 32], vec![], 0)).unwrap();
+        let mut transaction_history = TransactionHistory::new(
+            std::slice::from_ref(&account),
+            &block_chain,
+            None,
+            Network::Testnet3,
+        );
+
+        let transaction = create_transaction(
+            Outpoint::new([1; 32], 0),
+            vec![TransactionOutput {
+                value: 1000,
+                pk_script: account.address.generate_script_pubkey_p2pkh(),
+            }],
+        );
+        let block = create_block([0; 32], vec![transaction.clone()], 10);
+
+        transaction_history.update_with_block(&block, 1);
+
+        let records = transaction_history.get_account_records(&account);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].transaction_id, transaction.get_tx_id().unwrap());
+        assert_eq!(records[0].direction, TransactionDirection::Incoming);
+        assert_eq!(records[0].net_amount, 1000);
+        assert_eq!(records[0].fee, 0);
+        assert_eq!(records[0].timestamp, 10);
+        assert_eq!(records[0].block_height, 1);
+        assert_eq!(records[0].confirmations, 1);
+    }
+
+    #[test]
+    fn test_02_correct_record_of_outgoing_transaction() {
+        let account = create_account();
+        let block_chain = BlockChain::new(create_block([0; 32], vec![], 0)).unwrap();
+        let mut transaction_history = TransactionHistory::new(
+            std::slice::from_ref(&account),
+            &block_chain,
+            None,
+            Network::Testnet3,
+        );
+
+        let incoming_transaction = create_transaction(
+            Outpoint::new([1; 32], 0),
+            vec![TransactionOutput {
+                value: 1000,
+                pk_script: account.address.generate_script_pubkey_p2pkh(),
+            }],
+        );
+        let mut receiver_script: Vec<u8> = vec![0x76, 0xa9, 0x14];
+        receiver_script.extend_from_slice(&[0x11; 20]);
+        receiver_script.extend_from_slice(&[0x88, 0xac]);
+        let outgoing_transaction = create_transaction(
+            Outpoint::new(incoming_transaction.get_tx_id().unwrap(), 0),
+            vec![
+                TransactionOutput {
+                    value: 600,
+                    pk_script: receiver_script.clone(),
+                },
+                TransactionOutput {
+                    value: 300,
+                    pk_script: account.address.generate_script_pubkey_p2pkh(),
+                },
+            ],
+        );
+
+        transaction_history
+            .update_with_block(&create_block([0; 32], vec![incoming_transaction], 10), 1);
+        transaction_history.update_with_block(
+            &create_block([1; 32], vec![outgoing_transaction.clone()], 20),
+            2,
+        );
+
+        let records = transaction_history.get_account_records(&account);
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[1].transaction_id,
+            outgoing_transaction.get_tx_id().unwrap()
+        );
+        assert_eq!(records[1].direction, TransactionDirection::Outgoing);
+        assert_eq!(records[1].net_amount, -700);
+        assert_eq!(records[1].fee, 100);
+        assert_eq!(
+            records[1].counterparty,
+            Some(Address::from_script_pubkey_p2pkh(&receiver_script, Network::Testnet3).unwrap())
+        );
+        assert_eq!(records[0].confirmations, 2);
+        assert_eq!(records[1].confirmations, 1);
+    }
+
+    #[test]
+    fn test_03_same_block_is_not_recorded_twice() {
+        let account = create_account();
+        let block_chain = BlockChain::new(create_block([0; 32], vec![], 0)).unwrap();
+        let mut transaction_history = TransactionHistory::new(
+            std::slice::from_ref(&account),
+            &block_chain,
+            None,
+            Network::Testnet3,
+        );
+
+        let transaction = create_transaction(
+            Outpoint::new([1; 32], 0),
+            vec![TransactionOutput {
+                value: 1000,
+                pk_script: account.address.generate_script_pubkey_p2pkh(),
+            }],
+        );
+        let block = create_block([0; 32], vec![transaction], 10);
+
+        transaction_history.update_with_block(&block, 1);
+        transaction_history.update_with_block(&block, 1);
+
+        assert_eq!(transaction_history.get_account_records(&account).len(), 1);
+    }
+
+    #[test]
+    fn test_04_correct_tracking_of_account_from_block_chain() {
+        let account = create_account();
+
+        let transaction = create_transaction(
+            Outpoint::new([1; 32], 0),
+            vec![TransactionOutput {
+                value: 1000,
+                pk_script: account.address.generate_script_pubkey_p2pkh(),
+            }],
+        );
+        let first_block = create_block([0; 32], vec![], 0);
+        let block = create_block(
+            first_block.header.get_hash256d().unwrap(),
+            vec![transaction],
+            10,
+        );
+        let empty_block = create_block(block.header.get_hash256d().unwrap(), vec![], 20);
+
+        let mut block_chain = BlockChain::new(first_block).unwrap();
+        block_chain.append_block(block).unwrap();
+        block_chain.append_block(empty_block).unwrap();
+
+        let mut transaction_history =
+            TransactionHistory::new(&[], &block_chain, None, Network::Testnet3);
+        assert!(!transaction_history.is_tracking(&account));
+
+        transaction_history.track_account(&account, &block_chain, None);
+
+        let records = transaction_history.get_account_records(&account);
+        assert!(transaction_history.is_tracking(&account));
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].block_height, 1);
+        assert_eq!(records[0].confirmations, 2);
+    }
+
+    #[test]
+    fn test_05_correct_confirmations_of_a_transaction() {
+        let account = create_account();
+        let block_chain = BlockChain::new(create_block([0; 32], vec![], 0)).unwrap();
+        let mut transaction_history = TransactionHistory::new(
+            std::slice::from_ref(&account),
+            &block_chain,
+            None,
+            Network::Testnet3,
+        );
+
+        let transaction = create_transaction(
+            Outpoint::new([1; 32], 0),
+            vec![TransactionOutput {
+                value: 1000,
+                pk_script: account.address.generate_script_pubkey_p2pkh(),
+            }],
+        );
+        let transaction_id = transaction.get_tx_id().unwrap();
+
+        transaction_history.update_with_block(&create_block([0; 32], vec![transaction], 10), 1);
+        transaction_history.update_with_block(&create_block([2; 32], vec![], 20), 3);
+
+        assert_eq!(3, transaction_history.get_confirmations(&transaction_id));
+        assert_eq!(0, transaction_history.get_confirmations(&[7; 32]));
+    }
+
+    #[test]
+    fn test_06_correct_transaction_history_serialization() {
+        let account = create_account();
+        let block_chain = BlockChain::new(create_block([0; 32], vec![], 0)).unwrap();
+        let mut transaction_history = TransactionHistory::new(
+            std::slice::from_ref(&account),
+            &block_chain,
+            None,
+            Network::Signet,
+        );
+
+        let transaction = create_transaction(
+            Outpoint::new([1; 32], 0),
+            vec![TransactionOutput {
+                value: 1000,
+                pk_script: account.address.generate_script_pubkey_p2pkh(),
+            }],
+        );
+        transaction_history.update_with_block(&create_block([0; 32], vec![transaction], 10), 1);
+
+        let mut stream: Vec<u8> = Vec::new();
+        transaction_history.io_serialize(&mut stream).unwrap();
+        let deserialized_history =
+            TransactionHistory::io_deserialize(&mut stream.as_slice()).unwrap();
+
+        assert!(deserialized_history.is_tracking(&account));
+        assert_eq!(Network::Signet, deserialized_history.get_network());
+        assert_eq!(1, deserialized_history.get_tip_height());
+        assert_eq!(
+            transaction_history.get_account_records(&account),
+            deserialized_history.get_account_records(&account)
+        );
+    }
+
+    #[test]
+    fn test_07_correct_tracking_of_account_with_the_filters_of_the_blocks() {
+        let account = create_account();
+
+        let transaction = create_transaction(
+            Outpoint::new([1; 32], 0),
+            vec![TransactionOutput {
+                value: 1000,
+                pk_script: account.address.generate_script_pubkey_p2pkh(),
+            }],
+        );
+        let first_block = create_block([0; 32], vec![], 0);
+        let block = create_block(
+            first_block.header.get_hash256d().unwrap(),
+            vec![transaction.clone()],
+            10,
+        );
+        let other_block = create_block(
+            block.header.get_hash256d().unwrap(),
+            vec![create_transaction(
+                Outpoint::new([2; 32], 0),
+                vec![TransactionOutput {
+                    value: 500,
+                    pk_script: vec![0x51; 25],
+                }],
+            )],
+            20,
+        );
+
+        let mut block_chain = BlockChain::new(first_block).unwrap();
+        block_chain.append_block(block).unwrap();
+        block_chain.append_block(other_block).unwrap();
+        let filter_chain = FilterChain::from_block_chain(&block_chain).unwrap();
+
+        let transaction_history = TransactionHistory::new(
+            std::slice::from_ref(&account),
+            &block_chain,
+            Some(&filter_chain),
+            Network::Testnet3,
+        );
+
+        let records = transaction_history.get_account_records(&account);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].transaction_id, transaction.get_tx_id().unwrap());
+        assert_eq!(records[0].block_height, 1);
+        assert_eq!(records[0].confirmations, 2);
+    }
+}
@@ -0,0 +1,141 @@
+use super::{address::Address, transaction_direction::TransactionDirection};
+
+use crate::{
+    block_structure::hash::HashType,
+    serialization::{
+        deserializable_fix_size::DeserializableFixSize,
+        deserializable_internal_order::DeserializableInternalOrder,
+        deserializable_little_endian::DeserializableLittleEndian,
+        error_serialization::ErrorSerialization,
+        serializable_internal_order::SerializableInternalOrder,
+        serializable_little_endian::SerializableLittleEndian,
+    },
+};
+
+use std::{
+    fmt::Display,
+    io::{Read, Write},
+};
+
+const FROM_SATOSHIS_TO_TBTC: f64 = 100_000_000.0;
+
+/// It's the representation of a transaction in the history of an account
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionRecord {
+    pub address: Address,
+    pub transaction_id: HashType,
+    pub timestamp: u32,
+    pub direction: TransactionDirection,
+    pub net_amount: i64,
+    pub fee: i64,
+    pub block_height: u64,
+    pub confirmations: u64,
+    pub counterparty: Option<Address>,
+    pub label: Option<String>,
+}
+
+impl TransactionRecord {
+    /// Returns the amount that the transaction added or removed from the account in TBTC
+    pub fn get_net_amount_in_tbtc(&self) -> f64 {
+        self.net_amount as f64 / FROM_SATOSHIS_TO_TBTC
+    }
+
+    /// Returns the fee paid by the transaction in TBTC
+    pub fn get_fee_in_tbtc(&self) -> f64 {
+        self.fee as f64 / FROM_SATOSHIS_TO_TBTC
+    }
+
+    /// Returns the transaction id in the order that is shown to the user
+    pub fn get_transaction_id_string(&self) -> String {
+        let mut transaction_id_string = "".to_string();
+        for byte in self.transaction_id.iter().rev() {
+            transaction_id_string.push_str(&format!("{:02x}", byte));
+        }
+        transaction_id_string
+    }
+}
+
+impl SerializableInternalOrder for TransactionRecord {
+    fn io_serialize(&self, stream: &mut dyn Write) -> Result<(), ErrorSerialization> {
+        self.address.io_serialize(stream)?;
+        self.transaction_id.io_serialize(stream)?;
+        self.timestamp.le_serialize(stream)?;
+        self.direction.io_serialize(stream)?;
+        self.net_amount.le_serialize(stream)?;
+        self.fee.le_serialize(stream)?;
+        self.block_height.le_serialize(stream)?;
+
+        match &self.counterparty {
+            Some(counterparty) => {
+                true.le_serialize(stream)?;
+                counterparty.io_serialize(stream)?;
+            }
+            None => false.le_serialize(stream)?,
+        }
+
+        let label = match &self.label {
+            Some(label) => label.clone(),
+            None => "".to_string(),
+        };
+        (label.len() as u64).le_serialize(stream)?;
+        label.le_serialize(stream)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializableInternalOrder for TransactionRecord {
+    fn io_deserialize(stream: &mut dyn Read) -> Result<Self, ErrorSerialization> {
+        let address = Address::io_deserialize(stream)?;
+        let transaction_id = HashType::io_deserialize(stream)?;
+        let timestamp = u32::le_deserialize(stream)?;
+        let direction = TransactionDirection::io_deserialize(stream)?;
+        let net_amount = i64::le_deserialize(stream)?;
+        let fee = i64::le_deserialize(stream)?;
+        let block_height = u64::le_deserialize(stream)?;
+
+        let counterparty = match bool::le_deserialize(stream)? {
+            true => Some(Address::io_deserialize(stream)?),
+            false => None,
+        };
+
+        let label_len = u64::le_deserialize(stream)? as usize;
+        let label = match String::deserialize_fix_size(stream, label_len)? {
+            label if label.is_empty() => None,
+            label => Some(label),
+        };
+
+        Ok(TransactionRecord {
+            address,
+            transaction_id,
+            timestamp,
+            direction,
+            net_amount,
+            fee,
+            block_height,
+            confirmations: 0,
+            counterparty,
+            label,
+        })
+    }
+}
+
+impl Display for TransactionRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}\n    Amount: {}\n    Fee: {}\n    Height: {}\n    Confirmations: {}",
+            self.direction,
+            self.get_transaction_id_string(),
+            self.get_net_amount_in_tbtc(),
+            self.get_fee_in_tbtc(),
+            self.block_height,
+            self.confirmations,
+        )?;
+
+        match &self.label {
+            Some(label) => write!(f, "\n    Label: {label}"),
+            None => Ok(()),
+        }
+    }
+}
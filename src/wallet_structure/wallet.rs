@@ -1,8 +1,10 @@
 use super::{account::Account, error_wallet::ErrorWallet};
 
 use crate::{
+    block_structure::{hash::HashType, outpoint::Outpoint, utxo_set::UTXOSet},
     configurations::try_default::TryDefault,
     serialization::{
+        deserializable_fix_size::DeserializableFixSize,
         deserializable_internal_order::DeserializableInternalOrder,
         deserializable_little_endian::DeserializableLittleEndian,
         error_serialization::ErrorSerialization,
@@ -11,13 +13,19 @@ use crate::{
     },
 };
 
-use std::io::{Read, Write};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Read, Write},
+};
 
 /// It's the internal representation of a wallet
 #[derive(Debug, PartialEq)]
 pub struct Wallet {
     selected_account: Option<Account>,
     accounts: Vec<Account>,
+    transaction_labels: HashMap<HashType, String>,
+    output_labels: HashMap<Outpoint, String>,
+    frozen_outputs: HashSet<Outpoint>,
 }
 
 impl Wallet {
@@ -25,6 +33,9 @@ impl Wallet {
         Wallet {
             selected_account: accounts.first().cloned(),
             accounts,
+            transaction_labels: HashMap::new(),
+            output_labels: HashMap::new(),
+            frozen_outputs: HashSet::new(),
         }
     }
 
@@ -68,6 +79,76 @@ impl Wallet {
     pub fn get_account_with_name(&self, name: &str) -> Option<&Account> {
         self.accounts.iter().find(|x| x.account_name == name)
     }
+
+    /// Changes the label of a transaction, an empty label removes it
+    pub fn set_transaction_label(&mut self, transaction_id: HashType, label: &str) {
+        match label.is_empty() {
+            true => self.transaction_labels.remove(&transaction_id),
+            false => self
+                .transaction_labels
+                .insert(transaction_id, label.to_string()),
+        };
+    }
+
+    /// Returns the label of a transaction if it has one
+    pub fn get_transaction_label(&self, transaction_id: &HashType) -> Option<&String> {
+        self.transaction_labels.get(transaction_id)
+    }
+
+    /// Changes the label of an output, an empty label removes it
+    pub fn set_output_label(&mut self, outpoint: Outpoint, label: &str) {
+        match label.is_empty() {
+            true => self.output_labels.remove(&outpoint),
+            false => self.output_labels.insert(outpoint, label.to_string()),
+        };
+    }
+
+    /// Returns the label of an output if it has one
+    pub fn get_output_label(&self, outpoint: &Outpoint) -> Option<&String> {
+        self.output_labels.get(outpoint)
+    }
+
+    /// Marks an output so it is never chosen when selecting the outputs to spend automatically
+    pub fn freeze_output(&mut self, outpoint: Outpoint) {
+        self.frozen_outputs.insert(outpoint);
+    }
+
+    /// Lets an output be chosen again when selecting the outputs to spend automatically
+    pub fn unfreeze_output(&mut self, outpoint: &Outpoint) {
+        self.frozen_outputs.remove(outpoint);
+    }
+
+    /// Returns true if the output is frozen and false otherwise
+    pub fn is_output_frozen(&self, outpoint: &Outpoint) -> bool {
+        self.frozen_outputs.contains(outpoint)
+    }
+
+    /// Returns the outputs that are frozen
+    pub fn get_frozen_outputs(&self) -> &HashSet<Outpoint> {
+        &self.frozen_outputs
+    }
+
+    /// Returns the data the scripts paying the accounts push: their public keys and the hashes of them
+    pub fn get_script_data(&self) -> Vec<Vec<u8>> {
+        let mut script_data: Vec<Vec<u8>> = Vec::new();
+        for account in self.accounts.iter() {
+            script_data.push(account.public_key.as_bytes().to_vec());
+            if let Ok(hashed_public_key) = account.public_key.get_hashed_160() {
+                script_data.push(hashed_public_key.to_vec());
+            }
+        }
+
+        script_data
+    }
+
+    /// Returns the outpoints of the outputs of the accounts that have not been spent yet
+    pub fn get_outpoints(&self, utxo_set: &UTXOSet) -> Vec<Outpoint> {
+        self.accounts
+            .iter()
+            .flat_map(|account| utxo_set.get_utxo_list_with_outpoints(Some(&account.address)))
+            .map(|(outpoint, _)| outpoint)
+            .collect()
+    }
 }
 
 impl TryDefault for Wallet {
@@ -86,6 +167,25 @@ impl SerializableInternalOrder for Wallet {
             account.io_serialize(stream)?;
         }
 
+        (self.transaction_labels.len() as u64).le_serialize(stream)?;
+        for (transaction_id, label) in &self.transaction_labels {
+            transaction_id.io_serialize(stream)?;
+            (label.len() as u64).le_serialize(stream)?;
+            label.le_serialize(stream)?;
+        }
+
+        (self.output_labels.len() as u64).le_serialize(stream)?;
+        for (outpoint, label) in &self.output_labels {
+            outpoint.io_serialize(stream)?;
+            (label.len() as u64).le_serialize(stream)?;
+            label.le_serialize(stream)?;
+        }
+
+        (self.frozen_outputs.len() as u64).le_serialize(stream)?;
+        for outpoint in &self.frozen_outputs {
+            outpoint.io_serialize(stream)?;
+        }
+
         Ok(())
     }
 }
@@ -99,7 +199,31 @@ impl DeserializableInternalOrder for Wallet {
             accounts.push(Account::io_deserialize(stream)?);
         }
 
-        Ok(Wallet::new(accounts))
+        let mut wallet = Wallet::new(accounts);
+
+        // Wallets saved by older versions end before the labels or the frozen outputs
+        let transaction_labels_len = u64::le_deserialize(stream).unwrap_or(0);
+        for _ in 0..transaction_labels_len {
+            let transaction_id = HashType::io_deserialize(stream)?;
+            let label_len = u64::le_deserialize(stream)? as usize;
+            let label = String::deserialize_fix_size(stream, label_len)?;
+            wallet.set_transaction_label(transaction_id, &label);
+        }
+
+        let output_labels_len = u64::le_deserialize(stream).unwrap_or(0);
+        for _ in 0..output_labels_len {
+            let outpoint = Outpoint::io_deserialize(stream)?;
+            let label_len = u64::le_deserialize(stream)? as usize;
+            let label = String::deserialize_fix_size(stream, label_len)?;
+            wallet.set_output_label(outpoint, &label);
+        }
+
+        let frozen_outputs_len = u64::le_deserialize(stream).unwrap_or(0);
+        for _ in 0..frozen_outputs_len {
+            wallet.freeze_output(Outpoint::io_deserialize(stream)?);
+        }
+
+        Ok(wallet)
     }
 }
 
@@ -107,6 +231,14 @@ impl DeserializableInternalOrder for Wallet {
 mod tests {
     use super::*;
 
+    use crate::block_structure::{
+        block::Block, block_header::BlockHeader, block_version, compact256::Compact256,
+        transaction::Transaction, transaction_input::TransactionInput,
+        transaction_output::TransactionOutput,
+    };
+    use crate::connections::network::Network;
+    use crate::messages::compact_size::CompactSize;
+
     #[test]
     fn test_01_correct_wallet_creation() {
         let account_old = Account::new(
@@ -121,6 +253,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -136,6 +269,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -158,6 +292,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -173,6 +308,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -185,6 +321,9 @@ mod tests {
             .unwrap();
         account_old.io_serialize(&mut serialized_fields).unwrap();
         account_new.io_serialize(&mut serialized_fields).unwrap();
+        0u64.le_serialize(&mut serialized_fields).unwrap();
+        0u64.le_serialize(&mut serialized_fields).unwrap();
+        0u64.le_serialize(&mut serialized_fields).unwrap();
 
         let mut serialized_wallet = Vec::new();
         wallet.io_serialize(&mut serialized_wallet).unwrap();
@@ -206,6 +345,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -221,6 +361,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -250,6 +391,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -265,6 +407,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -290,6 +433,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -305,6 +449,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -330,6 +475,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -345,6 +491,7 @@ mod tests {
                 0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
                 0x35, 0x14, 0x92, 0x4A, 0x22,
             ],
+            Network::Testnet3,
         )
         .unwrap();
 
@@ -355,4 +502,142 @@ mod tests {
 
         assert!(wallet.get_account_with_name("Old").is_none());
     }
+
+    #[test]
+    fn test_07_labels_survive_wallet_serialization() {
+        let account = Account::new(
+            "Old",
+            &[
+                0x0A, 0x52, 0x65, 0x08, 0x2E, 0x24, 0x11, 0x5F, 0x77, 0x54, 0x0A, 0xB3, 0xB8, 0xC2,
+                0xB9, 0x20, 0x60, 0xAA, 0x30, 0xD6, 0xD2, 0xB8, 0x1A, 0x08, 0x5D, 0x71, 0xAB, 0x37,
+                0xED, 0xA7, 0x68, 0x91,
+            ],
+            &[
+                0x03, 0xBC, 0x6D, 0x45, 0xD2, 0x10, 0x1E, 0x91, 0x28, 0xDE, 0x14, 0xB5, 0xB6, 0x68,
+                0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
+                0x35, 0x14, 0x92, 0x4A, 0x22,
+            ],
+            Network::Testnet3,
+        )
+        .unwrap();
+
+        let mut wallet = Wallet::new(vec![account]);
+        wallet.set_transaction_label([1; 32], "rent");
+        wallet.set_transaction_label([2; 32], "faucet");
+        wallet.set_output_label(Outpoint::new([1; 32], 0), "savings");
+        wallet.freeze_output(Outpoint::new([2; 32], 1));
+
+        let mut serialized_wallet = Vec::new();
+        wallet.io_serialize(&mut serialized_wallet).unwrap();
+
+        let deserialized_wallet =
+            Wallet::io_deserialize(&mut serialized_wallet.as_slice()).unwrap();
+
+        assert_eq!(wallet, deserialized_wallet);
+        assert_eq!(
+            Some(&"rent".to_string()),
+            deserialized_wallet.get_transaction_label(&[1; 32])
+        );
+        assert_eq!(
+            Some(&"savings".to_string()),
+            deserialized_wallet.get_output_label(&Outpoint::new([1; 32], 0))
+        );
+        assert!(deserialized_wallet.is_output_frozen(&Outpoint::new([2; 32], 1)));
+    }
+
+    #[test]
+    fn test_08_empty_label_removes_it() {
+        let mut wallet = Wallet::new(Vec::new());
+        wallet.set_transaction_label([1; 32], "rent");
+        wallet.set_transaction_label([1; 32], "");
+
+        assert!(wallet.get_transaction_label(&[1; 32]).is_none());
+    }
+
+    #[test]
+    fn test_09_wallet_without_labels_can_be_loaded() {
+        let mut serialized_wallet = Vec::new();
+        0u64.le_serialize(&mut serialized_wallet).unwrap();
+
+        let deserialized_wallet =
+            Wallet::io_deserialize(&mut serialized_wallet.as_slice()).unwrap();
+
+        assert_eq!(Wallet::new(Vec::new()), deserialized_wallet);
+    }
+
+    #[test]
+    fn test_10_correct_freezing_of_outputs() {
+        let mut wallet = Wallet::new(Vec::new());
+        let outpoint = Outpoint::new([3; 32], 2);
+
+        wallet.freeze_output(outpoint.clone());
+        assert!(wallet.is_output_frozen(&outpoint));
+        assert_eq!(1, wallet.get_frozen_outputs().len());
+
+        wallet.unfreeze_output(&outpoint);
+        assert!(!wallet.is_output_frozen(&outpoint));
+        assert!(wallet.get_frozen_outputs().is_empty());
+    }
+
+    #[test]
+    fn test_11_script_data_and_outpoints_of_the_accounts() {
+        let account = Account::new(
+            "Filtered",
+            &[
+                0x0A, 0x52, 0x65, 0x08, 0x2E, 0x24, 0x11, 0x5F, 0x77, 0x54, 0x0A, 0xB3, 0xB8, 0xC2,
+                0xB9, 0x20, 0x60, 0xAA, 0x30, 0xD6, 0xD2, 0xB8, 0x1A, 0x08, 0x5D, 0x71, 0xAB, 0x37,
+                0xED, 0xA7, 0x68, 0x91,
+            ],
+            &[
+                0x03, 0xBC, 0x6D, 0x45, 0xD2, 0x10, 0x1E, 0x91, 0x28, 0xDE, 0x14, 0xB5, 0xB6, 0x68,
+                0x83, 0xD6, 0x9C, 0xF1, 0xC3, 0x1A, 0x50, 0xB9, 0x6F, 0xEA, 0x2D, 0xAD, 0x4E, 0xD2,
+                0x35, 0x14, 0x92, 0x4A, 0x22,
+            ],
+            Network::Testnet3,
+        )
+        .unwrap();
+
+        let transaction = Transaction {
+            version: 1,
+            tx_in: vec![TransactionInput::new(
+                Outpoint::new([1; 32], 0),
+                vec![0x01, 0x02],
+                0xffffffff,
+            )],
+            tx_out: vec![TransactionOutput::new(
+                10,
+                account.address.generate_script_pubkey_p2pkh(),
+            )],
+            time: 0,
+        };
+
+        let mut block = Block::new(BlockHeader::new(
+            block_version::BlockVersion::version(1),
+            [0; 32],
+            [0; 32],
+            0,
+            Compact256::from(u32::MAX),
+            0,
+            CompactSize::new(1),
+        ));
+        block.append_transaction(transaction.clone()).unwrap();
+        let utxo_set = UTXOSet::new(vec![block]);
+
+        let wallet = Wallet::new(vec![account.clone()]);
+        assert_eq!(
+            vec![
+                account.public_key.as_bytes().to_vec(),
+                account.public_key.get_hashed_160().unwrap().to_vec(),
+            ],
+            wallet.get_script_data()
+        );
+        assert_eq!(
+            vec![Outpoint::new(transaction.get_tx_id().unwrap(), 0)],
+            wallet.get_outpoints(&utxo_set)
+        );
+
+        let empty_wallet = Wallet::new(Vec::new());
+        assert!(empty_wallet.get_script_data().is_empty());
+        assert!(empty_wallet.get_outpoints(&utxo_set).is_empty());
+    }
 }
@@ -4,6 +4,7 @@ use cargosos_bitcoin::{
         compact256::Compact256, hash::HashType, outpoint::Outpoint, transaction::Transaction,
         transaction_input::TransactionInput, transaction_output::TransactionOutput,
     },
+    connections::network::Network,
     messages::compact_size::CompactSize,
 };
 
@@ -36,7 +37,9 @@ pub fn create_header(previous_header: HashType, transaction_count: u64) -> Block
 }
 
 pub fn create_genesis_block() -> Block {
-    Block::new(BlockHeader::generate_genesis_block_header())
+    Block::new(BlockHeader::generate_genesis_block_header(
+        Network::Testnet3,
+    ))
 }
 
 pub fn create_block(previous_header: HashType, transaction_count: u64) -> Block {
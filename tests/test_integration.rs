@@ -9,15 +9,19 @@ mod test_integration {
         block_structure::{
             block::Block, block_chain::BlockChain, hash::HashType, merkle_tree::MerkleTree,
         },
-        connections::{p2p_protocol::ProtocolVersionP2P, supported_services::SupportedServices},
+        connections::{
+            p2p_protocol::ProtocolVersionP2P, supported_services::SupportedServices,
+            type_identifier::TypeIdentifier,
+        },
         logs::logger,
         messages::{
             bitfield_services::BitfieldServices,
             command_name::CommandName,
             get_headers_message::GetHeadersMessage,
+            inventory_message::InventoryMessage,
+            inventory_vector::InventoryVector,
             message::{self, Message},
             send_headers_message::SendHeadersMessage,
-            tx_message::TxMessage,
             verack_message::VerackMessage,
             version_message::VersionMessage,
         },
@@ -33,6 +37,7 @@ mod test_integration {
     use std::{
         net::{IpAddr, Ipv4Addr, SocketAddr},
         sync::{mpsc::channel, Arc, Mutex},
+        time::Duration,
     };
 
     fn read_message<M: Message>(stream: &mut Stream, message_type: CommandName) -> M {
@@ -206,7 +211,8 @@ mod test_integration {
             magic_numbers,
             notifier,
             sender,
-        );
+        )
+        .with_trickle_delay(Duration::ZERO);
 
         sender_transaction
             .send(MessageToPeer::SendTransaction(
@@ -241,8 +247,15 @@ mod test_integration {
             vec![first_block_header_hash, first_block_header_hash]
         );
 
-        let transaction_message = read_message::<TxMessage>(&mut stream, CommandName::Tx);
+        let inventory_message =
+            read_message::<InventoryMessage>(&mut stream, CommandName::Inventory);
 
-        assert_eq!(transaction_message.transaction, send_transaction);
+        assert_eq!(
+            inventory_message.inventory_vectors,
+            vec![InventoryVector::new(
+                TypeIdentifier::TransactionId,
+                send_transaction.get_tx_id().unwrap()
+            )]
+        );
     }
 }